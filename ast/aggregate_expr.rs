@@ -1,16 +1,66 @@
 use kernel::DataType;
 use serde::{Deserialize, Serialize};
 
-use crate::Column;
+use crate::{Column, OrderBy};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct AggregateExpr {
     pub function: AggregateFunction,
     pub distinct: bool,
+    /// IGNORE NULLS skips NULL inputs, even for functions that would otherwise respect them.
+    pub ignore_nulls: bool,
+    /// HAVING MAX/MIN restricts the aggregate to the rows where a column takes its maximum/minimum value.
+    pub having: Option<HavingModifier>,
+    /// ORDER BY determines the order in which the input values are aggregated.
+    pub order_by: Vec<OrderBy>,
+    /// LIMIT aggregates only the first values, in order_by order.
+    pub limit: Option<usize>,
     pub input: Column,
     pub output: Column,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum HavingModifier {
+    Max(Column),
+    Min(Column),
+}
+
+impl AggregateExpr {
+    /// An aggregate without any modifiers.
+    pub fn new(function: AggregateFunction, input: Column, output: Column) -> Self {
+        Self {
+            function,
+            distinct: false,
+            ignore_nulls: false,
+            having: None,
+            order_by: vec![],
+            limit: None,
+            input,
+            output,
+        }
+    }
+
+    /// Every column the aggregate reads from its input: the input, followed by the having column and the order-by columns.
+    pub fn references(&self) -> Vec<Column> {
+        let mut columns = vec![self.input.clone()];
+        if let Some(having) = &self.having {
+            columns.push(having.column().clone());
+        }
+        for o in &self.order_by {
+            columns.push(o.column.clone());
+        }
+        columns
+    }
+}
+
+impl HavingModifier {
+    pub fn column(&self) -> &Column {
+        match self {
+            HavingModifier::Max(column) | HavingModifier::Min(column) => column,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum AggregateFunction {
     AnyValue,
@@ -20,6 +70,29 @@ pub enum AggregateFunction {
     Max,
     Min,
     Sum,
+    /// Concatenates its input strings in no particular order.
+    StringAgg,
+    /// Estimates the number of distinct values using a HyperLogLog sketch.
+    ApproxCountDistinct,
+    /// Partial phase of ApproxCountDistinct: the HyperLogLog sketch of its input, serialized as bytes.
+    HllCountInit,
+    /// Final phase of ApproxCountDistinct: merges sketches produced by HllCountInit and estimates their cardinality.
+    HllCountMerge,
+    /// VAR_SAMP and VARIANCE. Like the other variance functions, the final phase accepts either values or the output of MomentsInit.
+    VarSamp,
+    VarPop,
+    /// STDDEV_SAMP and STDDEV.
+    StddevSamp,
+    StddevPop,
+    /// Partial phase of the variance functions: the count, mean and sum of squared differences from the mean of its input, serialized as a string.
+    MomentsInit,
+    /// Collects its input values, including NULLs, into an array.
+    ArrayAgg,
+    /// Concatenates its input arrays, skipping NULL arrays. Also the final phase of ArrayAgg.
+    ArrayConcatAgg,
+    /// The minimum, the maximum and the quantiles that divide its input into this many parts.
+    /// The final phase accepts either values or the arrays of values produced by ArrayAgg.
+    ApproxQuantiles(usize),
 }
 
 impl AggregateFunction {
@@ -33,6 +106,17 @@ impl AggregateFunction {
             "ZetaSQL:max" => AggregateFunction::Max,
             "ZetaSQL:min" => AggregateFunction::Min,
             "ZetaSQL:sum" => AggregateFunction::Sum,
+            "ZetaSQL:string_agg" => AggregateFunction::StringAgg,
+            "ZetaSQL:approx_count_distinct" => AggregateFunction::ApproxCountDistinct,
+            "ZetaSQL:approx_quantiles" => {
+                panic!("approx_quantiles should be converted along with its number argument")
+            }
+            "ZetaSQL:array_agg" => AggregateFunction::ArrayAgg,
+            "ZetaSQL:array_concat_agg" => AggregateFunction::ArrayConcatAgg,
+            "ZetaSQL:var_samp" | "ZetaSQL:variance" => AggregateFunction::VarSamp,
+            "ZetaSQL:var_pop" => AggregateFunction::VarPop,
+            "ZetaSQL:stddev_samp" | "ZetaSQL:stddev" => AggregateFunction::StddevSamp,
+            "ZetaSQL:stddev_pop" => AggregateFunction::StddevPop,
             _ => panic!("{} is not supported", name),
         }
     }
@@ -42,9 +126,72 @@ impl AggregateFunction {
             AggregateFunction::AnyValue
             | AggregateFunction::Max
             | AggregateFunction::Min
-            | AggregateFunction::Sum => column_type.clone(),
-            AggregateFunction::Count => DataType::I64,
+            | AggregateFunction::Sum
+            | AggregateFunction::ArrayConcatAgg => column_type.clone(),
+            AggregateFunction::Count
+            | AggregateFunction::ApproxCountDistinct
+            | AggregateFunction::HllCountMerge => DataType::I64,
             AggregateFunction::LogicalAnd | AggregateFunction::LogicalOr => DataType::Bool,
+            AggregateFunction::StringAgg | AggregateFunction::MomentsInit => DataType::String,
+            AggregateFunction::HllCountInit => DataType::Bytes,
+            AggregateFunction::VarSamp
+            | AggregateFunction::VarPop
+            | AggregateFunction::StddevSamp
+            | AggregateFunction::StddevPop => DataType::F64,
+            AggregateFunction::ArrayAgg | AggregateFunction::ApproxQuantiles(_) => {
+                DataType::Array(Box::new(column_type))
+            }
+        }
+    }
+
+    /// Does the function return the same result if its input contains duplicates, so DISTINCT has no effect?
+    pub fn ignores_duplicates(&self) -> bool {
+        matches!(
+            self,
+            AggregateFunction::AnyValue
+                | AggregateFunction::LogicalAnd
+                | AggregateFunction::LogicalOr
+                | AggregateFunction::Max
+                | AggregateFunction::Min
+                | AggregateFunction::ApproxCountDistinct
+        )
+    }
+
+    /// Split an aggregate into a partial phase, which runs independently on each worker,
+    /// and a final phase, which combines the outputs of the partial phase.
+    pub fn split(&self) -> (AggregateFunction, AggregateFunction) {
+        match self {
+            // Workers with no input produce NULL, so the final phase must skip NULLs.
+            AggregateFunction::AnyValue => (AggregateFunction::AnyValue, AggregateFunction::Max),
+            AggregateFunction::Count => (AggregateFunction::Count, AggregateFunction::Sum),
+            AggregateFunction::LogicalAnd => {
+                (AggregateFunction::LogicalAnd, AggregateFunction::LogicalAnd)
+            }
+            AggregateFunction::LogicalOr => {
+                (AggregateFunction::LogicalOr, AggregateFunction::LogicalOr)
+            }
+            AggregateFunction::Max => (AggregateFunction::Max, AggregateFunction::Max),
+            AggregateFunction::Min => (AggregateFunction::Min, AggregateFunction::Min),
+            AggregateFunction::Sum => (AggregateFunction::Sum, AggregateFunction::Sum),
+            AggregateFunction::StringAgg => {
+                (AggregateFunction::StringAgg, AggregateFunction::StringAgg)
+            }
+            AggregateFunction::ApproxCountDistinct => (
+                AggregateFunction::HllCountInit,
+                AggregateFunction::HllCountMerge,
+            ),
+            AggregateFunction::VarSamp
+            | AggregateFunction::VarPop
+            | AggregateFunction::StddevSamp
+            | AggregateFunction::StddevPop => (AggregateFunction::MomentsInit, *self),
+            AggregateFunction::ArrayAgg | AggregateFunction::ArrayConcatAgg => {
+                (*self, AggregateFunction::ArrayConcatAgg)
+            }
+            // Every worker sends its values, so the quantiles are computed exactly.
+            AggregateFunction::ApproxQuantiles(_) => (AggregateFunction::ArrayAgg, *self),
+            AggregateFunction::HllCountInit
+            | AggregateFunction::HllCountMerge
+            | AggregateFunction::MomentsInit => panic!("{:?} is already split", self),
         }
     }
 }
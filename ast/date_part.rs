@@ -1,3 +1,5 @@
+use std::fmt;
+
 use chrono::Weekday;
 use serde::{Deserialize, Serialize};
 
@@ -59,6 +61,34 @@ impl DatePart {
     }
 }
 
+impl fmt::Display for DatePart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatePart::Nanosecond => write!(f, "NANOSECOND"),
+            DatePart::Microsecond => write!(f, "MICROSECOND"),
+            DatePart::Millisecond => write!(f, "MILLISECOND"),
+            DatePart::Second => write!(f, "SECOND"),
+            DatePart::Minute => write!(f, "MINUTE"),
+            DatePart::Hour => write!(f, "HOUR"),
+            DatePart::DayOfWeek => write!(f, "DAYOFWEEK"),
+            DatePart::Day => write!(f, "DAY"),
+            DatePart::DayOfYear => write!(f, "DAYOFYEAR"),
+            DatePart::Week(Weekday::Sun) => write!(f, "WEEK"),
+            DatePart::Week(Weekday::Mon) => write!(f, "WEEK(MONDAY)"),
+            DatePart::Week(Weekday::Tue) => write!(f, "WEEK(TUESDAY)"),
+            DatePart::Week(Weekday::Wed) => write!(f, "WEEK(WEDNESDAY)"),
+            DatePart::Week(Weekday::Thu) => write!(f, "WEEK(THURSDAY)"),
+            DatePart::Week(Weekday::Fri) => write!(f, "WEEK(FRIDAY)"),
+            DatePart::Week(Weekday::Sat) => write!(f, "WEEK(SATURDAY)"),
+            DatePart::IsoWeek => write!(f, "ISOWEEK"),
+            DatePart::Month => write!(f, "MONTH"),
+            DatePart::Quarter => write!(f, "QUARTER"),
+            DatePart::Year => write!(f, "YEAR"),
+            DatePart::IsoYear => write!(f, "ISOYEAR"),
+        }
+    }
+}
+
 impl From<i32> for DatePart {
     fn from(i: i32) -> Self {
         match i {
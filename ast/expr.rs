@@ -8,7 +8,7 @@ use std::{
 };
 use zetasql::TableRefProto;
 
-use crate::{AggregateExpr, Column, Index, Procedure, Scalar, Value};
+use crate::{AggregateExpr, Column, HavingModifier, Index, Procedure, Scalar, Value};
 
 // Expr plan nodes combine inputs in a Plan tree.
 #[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -23,6 +23,16 @@ pub enum Expr {
         predicates: Vec<Scalar>,
         projects: Vec<Column>,
         table: Table,
+        sample: Option<Sample>,
+    },
+    // LogicalIndexGet { index, limit } reads a table in index order until limit rows pass the predicates.
+    // It may return more than limit rows, so it is only used beneath a LogicalSort and a LogicalLimit.
+    LogicalIndexGet {
+        predicates: Vec<Scalar>,
+        projects: Vec<Column>,
+        index: Index,
+        table: Table,
+        limit: usize,
     },
     // LogicalFilter { predicates } implements the WHERE/HAVING clauses.
     LogicalFilter {
@@ -95,12 +105,27 @@ pub enum Expr {
         left: Box<Expr>,
         right: Box<Expr>,
     },
+    // LogicalIterate { name, columns } implements WITH RECURSIVE _ AS (SELECT _ UNION ALL SELECT _).
+    // It produces the rows of base, and then repeatedly produces the rows of step,
+    // which reads the rows produced by the previous iteration using LogicalGetWith { name },
+    // until an iteration produces no rows.
+    LogicalIterate {
+        name: String,
+        // Matches 1:1 position-wise with LogicalGetWith.columns.
+        columns: Vec<Column>,
+        // The query fails if it hasn't finished after this many iterations.
+        recursion_limit: usize,
+        base: Box<Expr>,
+        step: Box<Expr>,
+    },
     // LogicalInsert { table, columns } implements the INSERT operation.
     LogicalInsert {
         table: Table,
         input: Box<Expr>,
         /// [(query_output_column, table_column), ..]
         columns: Vec<(Column, String)>,
+        /// The hash of the distribution key of the table, which decides the worker each row is stored on.
+        partition_by: Option<Column>,
     },
     // LogicalValues { columns, values } implements VALUES expressions.
     LogicalValues {
@@ -127,21 +152,80 @@ pub enum Expr {
         name: Name,
     },
     // LogicalCreateTable implements the CREATE TABLE operation.
+    // Temp tables are only visible to the session that created them, so they are registered by the planner instead of the metadata tables.
     LogicalCreateTable {
         name: Name,
         columns: Vec<(String, DataType)>,
+        constraints: Vec<Constraint>,
+        temp: bool,
+        // OPTIONS(name = value, ..), which are checked by the planner.
+        options: Vec<(String, Scalar)>,
+    },
+    // LogicalCreateTableAs implements the CREATE TABLE ... AS SELECT and CREATE MATERIALIZED VIEW operations.
+    // The query can't be planned as an INSERT until the table exists,
+    // so the coordinator runs the create and insert statements one after the other,
+    // and runs the drop statement if the insert fails, so a failed statement doesn't leave an empty table behind.
+    LogicalCreateTableAs {
+        create: String,
+        insert: String,
+        drop: String,
     },
     // LogicalCreateIndex implements the CREATE INDEX operation.
     LogicalCreateIndex {
         name: Name,
         table: Table,
         columns: Vec<String>,
+        descending: Vec<bool>,
+        options: Vec<(String, Scalar)>,
     },
-    // LogicalDrop implements the DROP DATABASE/TABLE/INDEX operation.
+    // LogicalCreateSequence implements the CREATE SEQUENCE operation.
+    LogicalCreateSequence {
+        name: Name,
+        start: i64,
+        increment: i64,
+    },
+    // LogicalDrop implements the DROP DATABASE/TABLE/INDEX/VIEW/FUNCTION/SEQUENCE operation and ALTER TABLE DROP COLUMN.
     LogicalDrop {
         object: ObjectType,
         name: Name,
     },
+    // LogicalAddColumn implements the ALTER TABLE ADD COLUMN operation.
+    // The existing rows are rewritten by a separate LogicalUpdate.
+    LogicalAddColumn {
+        name: Name,
+        column: (String, DataType),
+        // The SQL of the DEFAULT expression.
+        default: Option<String>,
+        not_null: bool,
+        if_not_exists: bool,
+    },
+    // LogicalRename implements the RENAME TABLE/INDEX/COLUMN and ALTER TABLE RENAME TO operations.
+    LogicalRename {
+        object: ObjectType,
+        name: Name,
+        new_name: Name,
+    },
+    // LogicalCreateView implements the CREATE VIEW and CREATE MATERIALIZED VIEW operations.
+    LogicalCreateView {
+        name: Name,
+        columns: Vec<(String, DataType)>,
+        sql: String,
+        materialized: bool,
+    },
+    // LogicalCreateFunction implements the CREATE FUNCTION and CREATE TABLE FUNCTION operations.
+    LogicalCreateFunction {
+        name: Name,
+        arguments: Vec<(String, DataType)>,
+        // The return type of a scalar function, or None for a table-valued function.
+        return_type: Option<DataType>,
+        // The output columns of a table-valued function.
+        columns: Vec<(String, DataType)>,
+        sql: String,
+    },
+    // LogicalRefresh implements ALTER MATERIALIZED VIEW ... SET OPTIONS (refresh = true).
+    LogicalRefresh {
+        name: Name,
+    },
     LogicalScript {
         stmts: Vec<Expr>,
     },
@@ -160,6 +244,7 @@ pub enum Expr {
         projects: Vec<Column>,
         predicates: Vec<Scalar>,
         table: Table,
+        sample: Option<Sample>,
     },
     IndexScan {
         include_existing: bool,
@@ -170,6 +255,13 @@ pub enum Expr {
         table: Table,
         input: Box<Expr>,
     },
+    IndexOrderScan {
+        projects: Vec<Column>,
+        predicates: Vec<Scalar>,
+        index: Index,
+        table: Table,
+        limit: usize,
+    },
     Filter {
         predicates: Vec<Scalar>,
         input: Box<Expr>,
@@ -217,6 +309,13 @@ pub enum Expr {
         aggregate: Vec<AggregateExpr>,
         input: Box<Expr>,
     },
+    /// PartialAggregate runs the first phase of a two-phase aggregation independently on each worker,
+    /// reducing the data that must be sent to the SimpleAggregate or GroupByAggregate that finishes it.
+    PartialAggregate {
+        group_by: Vec<Column>,
+        aggregate: Vec<AggregateExpr>,
+        input: Box<Expr>,
+    },
     Limit {
         limit: usize,
         offset: usize,
@@ -230,6 +329,16 @@ pub enum Expr {
         left: Box<Expr>,
         right: Box<Expr>,
     },
+    Iterate {
+        name: String,
+        // Matches 1:1 position-wise with GetTempTable.columns.
+        columns: Vec<Column>,
+        recursion_limit: usize,
+        // The stage that checks whether every worker has finished iterating.
+        stage: Option<i32>,
+        base: Box<Expr>,
+        step: Box<Expr>,
+    },
     /// Broadcast the build side of a join to every node.
     Broadcast {
         stage: Option<i32>,
@@ -253,6 +362,7 @@ pub enum Expr {
         input: Box<Expr>,
         /// [(query_output_column, table_column), ..]
         columns: Vec<(Column, String)>,
+        partition_by: Option<Column>,
     },
     Values {
         columns: Vec<Column>,
@@ -284,6 +394,7 @@ impl Expr {
             | Expr::LogicalWith { .. }
             | Expr::LogicalCreateTempTable { .. }
             | Expr::LogicalUnion { .. }
+            | Expr::LogicalIterate { .. }
             | Expr::LogicalFilter { .. }
             | Expr::LogicalOut { .. }
             | Expr::LogicalMap { .. }
@@ -296,11 +407,19 @@ impl Expr {
             | Expr::LogicalDelete { .. }
             | Expr::LogicalSingleGet
             | Expr::LogicalGet { .. }
+            | Expr::LogicalIndexGet { .. }
             | Expr::LogicalGetWith { .. }
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::LogicalRewrite { .. }
             | Expr::LogicalScript { .. }
             | Expr::LogicalCall { .. }
@@ -308,6 +427,7 @@ impl Expr {
             Expr::Leaf { .. }
             | Expr::TableFreeScan { .. }
             | Expr::SeqScan { .. }
+            | Expr::IndexOrderScan { .. }
             | Expr::IndexScan { .. }
             | Expr::Filter { .. }
             | Expr::Out { .. }
@@ -318,9 +438,11 @@ impl Expr {
             | Expr::GetTempTable { .. }
             | Expr::SimpleAggregate { .. }
             | Expr::GroupByAggregate { .. }
+            | Expr::PartialAggregate { .. }
             | Expr::Limit { .. }
             | Expr::Sort { .. }
             | Expr::Union { .. }
+            | Expr::Iterate { .. }
             | Expr::Broadcast { .. }
             | Expr::Exchange { .. }
             | Expr::Gather { .. }
@@ -346,9 +468,11 @@ impl Expr {
             | Expr::LogicalDependentJoin { .. }
             | Expr::LogicalWith { .. }
             | Expr::LogicalUnion { .. }
+            | Expr::LogicalIterate { .. }
             | Expr::NestedLoop { .. }
             | Expr::HashJoin { .. }
-            | Expr::Union { .. } => 2,
+            | Expr::Union { .. }
+            | Expr::Iterate { .. } => 2,
             Expr::LogicalFilter { .. }
             | Expr::LogicalOut { .. }
             | Expr::LogicalMap { .. }
@@ -365,6 +489,7 @@ impl Expr {
             | Expr::IndexScan { .. }
             | Expr::SimpleAggregate { .. }
             | Expr::GroupByAggregate { .. }
+            | Expr::PartialAggregate { .. }
             | Expr::Limit { .. }
             | Expr::Sort { .. }
             | Expr::Broadcast { .. }
@@ -382,13 +507,22 @@ impl Expr {
             Expr::Leaf { .. }
             | Expr::LogicalSingleGet
             | Expr::LogicalGet { .. }
+            | Expr::LogicalIndexGet { .. }
             | Expr::LogicalGetWith { .. }
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::TableFreeScan { .. }
             | Expr::SeqScan { .. }
+            | Expr::IndexOrderScan { .. }
             | Expr::GetTempTable { .. }
             | Expr::LogicalRewrite { .. } => 0,
             Expr::LogicalScript { stmts } | Expr::Script { stmts } => stmts.len(),
@@ -403,9 +537,9 @@ impl Expr {
 
     pub fn attributes(&self) -> HashSet<Column> {
         match self {
-            Expr::LogicalGet { projects, .. } | Expr::LogicalOut { projects, .. } => {
-                projects.iter().map(|c| c.clone()).collect()
-            }
+            Expr::LogicalGet { projects, .. }
+            | Expr::LogicalIndexGet { projects, .. }
+            | Expr::LogicalOut { projects, .. } => projects.iter().map(|c| c.clone()).collect(),
             Expr::LogicalMap {
                 include_existing,
                 projects,
@@ -479,9 +613,9 @@ impl Expr {
                 set.extend(right.attributes());
                 set
             }
-            Expr::LogicalGetWith { columns, .. } | Expr::LogicalValues { columns, .. } => {
-                columns.iter().map(|c| c.clone()).collect()
-            }
+            Expr::LogicalGetWith { columns, .. }
+            | Expr::LogicalIterate { columns, .. }
+            | Expr::LogicalValues { columns, .. } => columns.iter().map(|c| c.clone()).collect(),
             Expr::LogicalAggregate {
                 group_by,
                 aggregate,
@@ -502,12 +636,20 @@ impl Expr {
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTempTable { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::LogicalScript { .. }
             | Expr::LogicalRewrite { .. } => HashSet::new(),
             Expr::TableFreeScan { .. }
             | Expr::SeqScan { .. }
+            | Expr::IndexOrderScan { .. }
             | Expr::IndexScan { .. }
             | Expr::Filter { .. }
             | Expr::Out { .. }
@@ -518,9 +660,11 @@ impl Expr {
             | Expr::GetTempTable { .. }
             | Expr::SimpleAggregate { .. }
             | Expr::GroupByAggregate { .. }
+            | Expr::PartialAggregate { .. }
             | Expr::Limit { .. }
             | Expr::Sort { .. }
             | Expr::Union { .. }
+            | Expr::Iterate { .. }
             | Expr::Broadcast { .. }
             | Expr::Exchange { .. }
             | Expr::Gather { .. }
@@ -544,7 +688,9 @@ impl Expr {
 
     fn collect_references(&self, set: &mut HashSet<Column>) {
         match self {
-            Expr::LogicalGet { predicates, .. } | Expr::LogicalFilter { predicates, .. } => {
+            Expr::LogicalGet { predicates, .. }
+            | Expr::LogicalIndexGet { predicates, .. }
+            | Expr::LogicalFilter { predicates, .. } => {
                 for p in predicates {
                     p.collect_references(set);
                 }
@@ -573,8 +719,8 @@ impl Expr {
                 ..
             } => {
                 set.extend(group_by.clone());
-                for AggregateExpr { input, .. } in aggregate {
-                    set.insert(input.clone());
+                for a in aggregate {
+                    set.extend(a.references());
                 }
             }
             Expr::LogicalSort { order_by, .. } => {
@@ -598,19 +744,28 @@ impl Expr {
             | Expr::LogicalOut { .. }
             | Expr::LogicalLimit { .. }
             | Expr::LogicalUnion { .. }
+            | Expr::LogicalIterate { .. }
             | Expr::LogicalInsert { .. }
             | Expr::LogicalUpdate { .. }
             | Expr::LogicalDelete { .. }
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTempTable { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::LogicalScript { .. }
             | Expr::LogicalRewrite { .. }
             | Expr::LogicalExplain { .. } => {}
             Expr::TableFreeScan { .. }
             | Expr::SeqScan { .. }
+            | Expr::IndexOrderScan { .. }
             | Expr::IndexScan { .. }
             | Expr::Filter { .. }
             | Expr::Out { .. }
@@ -621,9 +776,11 @@ impl Expr {
             | Expr::GetTempTable { .. }
             | Expr::SimpleAggregate { .. }
             | Expr::GroupByAggregate { .. }
+            | Expr::PartialAggregate { .. }
             | Expr::Limit { .. }
             | Expr::Sort { .. }
             | Expr::Union { .. }
+            | Expr::Iterate { .. }
             | Expr::Broadcast { .. }
             | Expr::Exchange { .. }
             | Expr::Gather { .. }
@@ -645,9 +802,11 @@ impl Expr {
     pub fn replace(&mut self, params: &HashMap<String, Value>) {
         match self {
             Expr::LogicalGet { predicates, .. }
+            | Expr::LogicalIndexGet { predicates, .. }
             | Expr::LogicalFilter { predicates, .. }
             | Expr::LogicalDependentJoin { predicates, .. }
             | Expr::SeqScan { predicates, .. }
+            | Expr::IndexOrderScan { predicates, .. }
             | Expr::Filter { predicates, .. } => {
                 for scalar in predicates {
                     scalar.replace(params)
@@ -686,13 +845,21 @@ impl Expr {
             | Expr::LogicalLimit { .. }
             | Expr::LogicalSort { .. }
             | Expr::LogicalUnion { .. }
+            | Expr::LogicalIterate { .. }
             | Expr::LogicalInsert { .. }
             | Expr::LogicalUpdate { .. }
             | Expr::LogicalDelete { .. }
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::LogicalScript { .. }
             | Expr::LogicalCall { .. }
             | Expr::LogicalExplain { .. }
@@ -705,9 +872,11 @@ impl Expr {
             | Expr::GetTempTable { .. }
             | Expr::SimpleAggregate { .. }
             | Expr::GroupByAggregate { .. }
+            | Expr::PartialAggregate { .. }
             | Expr::Limit { .. }
             | Expr::Sort { .. }
             | Expr::Union { .. }
+            | Expr::Iterate { .. }
             | Expr::Broadcast { .. }
             | Expr::Exchange { .. }
             | Expr::Gather { .. }
@@ -736,6 +905,11 @@ impl Expr {
                 projects,
                 predicates,
                 ..
+            }
+            | Expr::LogicalIndexGet {
+                projects,
+                predicates,
+                ..
             } => {
                 *projects = projects.iter().map(subst_c).collect();
                 *predicates = predicates.iter().map(subst_x).collect();
@@ -762,7 +936,8 @@ impl Expr {
             }
             Expr::LogicalWith { columns, .. }
             | Expr::LogicalCreateTempTable { columns, .. }
-            | Expr::LogicalGetWith { columns, .. } => {
+            | Expr::LogicalGetWith { columns, .. }
+            | Expr::LogicalIterate { columns, .. } => {
                 *columns = columns.iter().map(subst_c).collect();
             }
             Expr::LogicalAggregate {
@@ -777,11 +952,22 @@ impl Expr {
                         |AggregateExpr {
                              function,
                              distinct,
+                             ignore_nulls,
+                             having,
+                             order_by,
+                             limit,
                              input,
                              output,
                          }| AggregateExpr {
                             function: *function,
                             distinct: *distinct,
+                            ignore_nulls: *ignore_nulls,
+                            having: having.as_ref().map(|having| match having {
+                                HavingModifier::Max(column) => HavingModifier::Max(subst_c(column)),
+                                HavingModifier::Min(column) => HavingModifier::Min(subst_c(column)),
+                            }),
+                            order_by: order_by.iter().map(subst_o).collect(),
+                            limit: *limit,
                             input: subst_c(input),
                             output: subst_c(output),
                         },
@@ -813,14 +999,22 @@ impl Expr {
             | Expr::LogicalInsert { .. }
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::LogicalScript { .. }
             | Expr::LogicalCall { .. }
             | Expr::LogicalExplain { .. }
             | Expr::LogicalRewrite { .. } => {}
             Expr::TableFreeScan { .. }
             | Expr::SeqScan { .. }
+            | Expr::IndexOrderScan { .. }
             | Expr::IndexScan { .. }
             | Expr::Filter { .. }
             | Expr::Out { .. }
@@ -831,9 +1025,11 @@ impl Expr {
             | Expr::GetTempTable { .. }
             | Expr::SimpleAggregate { .. }
             | Expr::GroupByAggregate { .. }
+            | Expr::PartialAggregate { .. }
             | Expr::Limit { .. }
             | Expr::Sort { .. }
             | Expr::Union { .. }
+            | Expr::Iterate { .. }
             | Expr::Broadcast { .. }
             | Expr::Exchange { .. }
             | Expr::Gather { .. }
@@ -869,13 +1065,13 @@ impl Expr {
             | Exchange { input, .. }
             | Gather { input, .. }
             | Delete { input, .. } => input.schema(),
-            SeqScan { projects, .. } => projects
+            SeqScan { projects, .. } | IndexOrderScan { projects, .. } => projects
                 .iter()
-                .map(|c| (c.canonical_name(), c.data_type))
+                .map(|c| (c.canonical_name(), c.data_type.clone()))
                 .collect(),
             Out { projects, .. } => projects
                 .iter()
-                .map(|c| (c.name.clone(), c.data_type))
+                .map(|c| (c.name.clone(), c.data_type.clone()))
                 .collect(),
             IndexScan {
                 include_existing,
@@ -885,7 +1081,7 @@ impl Expr {
             } => {
                 let mut fields: Vec<_> = projects
                     .iter()
-                    .map(|c| (c.canonical_name(), c.data_type))
+                    .map(|c| (c.canonical_name(), c.data_type.clone()))
                     .collect();
                 if *include_existing {
                     fields.extend_from_slice(&input.schema());
@@ -899,7 +1095,7 @@ impl Expr {
             } => {
                 let mut fields: Vec<_> = projects
                     .iter()
-                    .map(|(_, c)| (c.canonical_name(), c.data_type))
+                    .map(|(_, c)| (c.canonical_name(), c.data_type.clone()))
                     .collect();
                 if *include_existing {
                     fields.extend_from_slice(&input.schema());
@@ -913,7 +1109,7 @@ impl Expr {
                 fields.extend_from_slice(&left.schema());
                 fields.extend_from_slice(&right.schema());
                 if let Join::Mark(column, _) = join {
-                    fields.push((column.canonical_name(), column.data_type))
+                    fields.push((column.canonical_name(), column.data_type.clone()))
                 }
                 fields
             }
@@ -924,18 +1120,18 @@ impl Expr {
                 fields.extend_from_slice(&left.schema());
                 fields.extend_from_slice(&right.schema());
                 if let Join::Mark(column, _) = join {
-                    fields.push((column.canonical_name(), column.data_type))
+                    fields.push((column.canonical_name(), column.data_type.clone()))
                 }
                 fields
             }
-            GetTempTable { columns, .. } => columns
+            GetTempTable { columns, .. } | Iterate { columns, .. } => columns
                 .iter()
-                .map(|column| (column.canonical_name(), column.data_type))
+                .map(|column| (column.canonical_name(), column.data_type.clone()))
                 .collect(),
             SimpleAggregate { aggregate, .. } => {
                 let mut fields = vec![];
                 for a in aggregate {
-                    fields.push((a.output.canonical_name(), a.output.data_type));
+                    fields.push((a.output.canonical_name(), a.output.data_type.clone()));
                 }
                 fields
             }
@@ -943,19 +1139,24 @@ impl Expr {
                 group_by,
                 aggregate,
                 ..
+            }
+            | PartialAggregate {
+                group_by,
+                aggregate,
+                ..
             } => {
                 let mut fields = vec![];
                 for column in group_by {
-                    fields.push((column.canonical_name(), column.data_type));
+                    fields.push((column.canonical_name(), column.data_type.clone()));
                 }
                 for a in aggregate {
-                    fields.push((a.output.canonical_name(), a.output.data_type));
+                    fields.push((a.output.canonical_name(), a.output.data_type.clone()));
                 }
                 fields
             }
             Values { columns, .. } => columns
                 .iter()
-                .map(|column| (column.canonical_name(), column.data_type))
+                .map(|column| (column.canonical_name(), column.data_type.clone()))
                 .collect(),
             Script { stmts, .. } => stmts.last().unwrap().schema(),
             Explain { .. } => vec![("plan".to_string(), DataType::String)],
@@ -963,6 +1164,7 @@ impl Expr {
             Leaf { .. }
             | LogicalSingleGet { .. }
             | LogicalGet { .. }
+            | LogicalIndexGet { .. }
             | LogicalFilter { .. }
             | LogicalOut { .. }
             | LogicalMap { .. }
@@ -975,14 +1177,22 @@ impl Expr {
             | LogicalLimit { .. }
             | LogicalSort { .. }
             | LogicalUnion { .. }
+            | LogicalIterate { .. }
             | LogicalInsert { .. }
             | LogicalValues { .. }
             | LogicalUpdate { .. }
             | LogicalDelete { .. }
             | LogicalCreateDatabase { .. }
             | LogicalCreateTable { .. }
+            | LogicalCreateTableAs { .. }
             | LogicalCreateIndex { .. }
+            | LogicalCreateSequence { .. }
             | LogicalDrop { .. }
+            | LogicalAddColumn { .. }
+            | LogicalRename { .. }
+            | LogicalCreateView { .. }
+            | LogicalCreateFunction { .. }
+            | LogicalRefresh { .. }
             | LogicalScript { .. }
             | LogicalCall { .. }
             | LogicalExplain { .. }
@@ -1007,9 +1217,19 @@ impl std::ops::Index<usize> for Expr {
             }
             | Expr::LogicalWith { left, right, .. }
             | Expr::LogicalUnion { left, right, .. }
+            | Expr::LogicalIterate {
+                base: left,
+                step: right,
+                ..
+            }
             | Expr::NestedLoop { left, right, .. }
             | Expr::HashJoin { left, right, .. }
-            | Expr::Union { left, right } => match index {
+            | Expr::Union { left, right }
+            | Expr::Iterate {
+                base: left,
+                step: right,
+                ..
+            } => match index {
                 0 => left,
                 1 => right,
                 _ => panic!("{} is out of bounds [0,2)", index),
@@ -1030,6 +1250,7 @@ impl std::ops::Index<usize> for Expr {
             | Expr::IndexScan { input, .. }
             | Expr::SimpleAggregate { input, .. }
             | Expr::GroupByAggregate { input, .. }
+            | Expr::PartialAggregate { input, .. }
             | Expr::Limit { input, .. }
             | Expr::Sort { input, .. }
             | Expr::Broadcast { input, .. }
@@ -1050,13 +1271,22 @@ impl std::ops::Index<usize> for Expr {
             Expr::Leaf { .. }
             | Expr::LogicalSingleGet { .. }
             | Expr::LogicalGet { .. }
+            | Expr::LogicalIndexGet { .. }
             | Expr::LogicalGetWith { .. }
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::TableFreeScan { .. }
             | Expr::SeqScan { .. }
+            | Expr::IndexOrderScan { .. }
             | Expr::GetTempTable { .. }
             | Expr::LogicalRewrite { .. } => panic!("{} has no inputs", self.name()),
             Expr::LogicalScript { stmts } | Expr::Script { stmts } => &stmts[index],
@@ -1075,9 +1305,19 @@ impl std::ops::IndexMut<usize> for Expr {
             }
             | Expr::LogicalWith { left, right, .. }
             | Expr::LogicalUnion { left, right, .. }
+            | Expr::LogicalIterate {
+                base: left,
+                step: right,
+                ..
+            }
             | Expr::NestedLoop { left, right, .. }
             | Expr::HashJoin { left, right, .. }
-            | Expr::Union { left, right } => match index {
+            | Expr::Union { left, right }
+            | Expr::Iterate {
+                base: left,
+                step: right,
+                ..
+            } => match index {
                 0 => left.as_mut(),
                 1 => right.as_mut(),
                 _ => panic!("{} is out of bounds [0,2)", index),
@@ -1098,6 +1338,7 @@ impl std::ops::IndexMut<usize> for Expr {
             | Expr::IndexScan { input, .. }
             | Expr::SimpleAggregate { input, .. }
             | Expr::GroupByAggregate { input, .. }
+            | Expr::PartialAggregate { input, .. }
             | Expr::Limit { input, .. }
             | Expr::Sort { input, .. }
             | Expr::Broadcast { input, .. }
@@ -1118,13 +1359,22 @@ impl std::ops::IndexMut<usize> for Expr {
             Expr::Leaf { .. }
             | Expr::LogicalSingleGet { .. }
             | Expr::LogicalGet { .. }
+            | Expr::LogicalIndexGet { .. }
             | Expr::LogicalGetWith { .. }
             | Expr::LogicalCreateDatabase { .. }
             | Expr::LogicalCreateTable { .. }
+            | Expr::LogicalCreateTableAs { .. }
             | Expr::LogicalCreateIndex { .. }
+            | Expr::LogicalCreateSequence { .. }
             | Expr::LogicalDrop { .. }
+            | Expr::LogicalAddColumn { .. }
+            | Expr::LogicalRename { .. }
+            | Expr::LogicalCreateView { .. }
+            | Expr::LogicalCreateFunction { .. }
+            | Expr::LogicalRefresh { .. }
             | Expr::TableFreeScan { .. }
             | Expr::SeqScan { .. }
+            | Expr::IndexOrderScan { .. }
             | Expr::GetTempTable { .. }
             | Expr::LogicalRewrite { .. } => panic!("{} has no inputs", self.name()),
             Expr::LogicalScript { stmts } | Expr::Script { stmts } => &mut stmts[index],
@@ -1221,6 +1471,61 @@ impl fmt::Display for Table {
     }
 }
 
+/// TABLESAMPLE method (percent PERCENT) [REPEATABLE (seed)] reads a random subset of a table.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Sample {
+    pub method: SampleMethod,
+    pub percent: f64,
+    pub repeatable: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SampleMethod {
+    // SYSTEM keeps or skips whole pages, so it only reads the pages it keeps.
+    System,
+    // BERNOULLI keeps or skips each row, so it reads the whole table but the sample is less clumpy.
+    Bernoulli,
+}
+
+impl Sample {
+    /// The probability that a page or row is kept.
+    pub fn fraction(&self) -> f64 {
+        self.percent / 100.0
+    }
+}
+
+impl PartialEq for Sample {
+    fn eq(&self, other: &Self) -> bool {
+        self.method == other.method
+            && self.percent.to_bits() == other.percent.to_bits()
+            && self.repeatable == other.repeatable
+    }
+}
+
+impl Eq for Sample {}
+
+impl Hash for Sample {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.method.hash(state);
+        self.percent.to_bits().hash(state);
+        self.repeatable.hash(state);
+    }
+}
+
+impl fmt::Display for Sample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let method = match self.method {
+            SampleMethod::System => "SYSTEM",
+            SampleMethod::Bernoulli => "BERNOULLI",
+        };
+        write!(f, "TABLESAMPLE {} ({} PERCENT)", method, self.percent)?;
+        if let Some(seed) = self.repeatable {
+            write!(f, " REPEATABLE ({})", seed)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Name {
     pub catalog_id: i64,
@@ -1233,12 +1538,32 @@ impl fmt::Display for Name {
     }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Constraint {
+    // NotNull(column) rejects rows where column is NULL.
+    NotNull(String),
+    // Default(column, sql) fills in column when it is omitted from INSERT or set to DEFAULT.
+    // The SQL is stored in the catalog and analyzed again by each statement that uses it, like the SQL of a view.
+    Default(String, String),
+    // Check(name, sql) rejects rows where the predicate is FALSE. Columns are matched by name.
+    Check(String, String),
+    // ForeignKey(name, columns, table, referenced_columns) rejects rows whose columns are all non-NULL and don't match a row of table.
+    ForeignKey(String, Vec<String>, Table, Vec<String>),
+    // PrimaryKey(columns) rejects rows whose columns match another row. Foreign keys must reference the primary key.
+    PrimaryKey(Vec<String>),
+    // Identity(column) fills in column from a sequence that is created with the table, when it is omitted from INSERT.
+    Identity(String),
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum ObjectType {
     Database,
     Table,
     Index,
     Column,
+    View,
+    Function,
+    Sequence,
 }
 
 impl ObjectType {
@@ -1248,6 +1573,9 @@ impl ObjectType {
             "table" => ObjectType::Table,
             "index" => ObjectType::Index,
             "column" => ObjectType::Column,
+            "view" | "materialized view" => ObjectType::View,
+            "function" | "table function" => ObjectType::Function,
+            "sequence" => ObjectType::Sequence,
             _ => panic!("{}", name),
         }
     }
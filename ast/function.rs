@@ -13,15 +13,20 @@ pub enum F {
     NextCatalogID,
     NextTableID,
     NextIndexID,
+    NextSequenceID,
+    GenerateUuid,
+    Rand,
     Coalesce(Vec<Scalar>),
     ConcatString(Vec<Scalar>),
     Hash(Vec<Scalar>),
+    FormatString(Vec<Scalar>),
     Greatest(Vec<Scalar>),
     Least(Vec<Scalar>),
     AbsDouble(Scalar),
     AbsInt64(Scalar),
     AcosDouble(Scalar),
     AcoshDouble(Scalar),
+    ArrayLength(Scalar),
     AsinDouble(Scalar),
     AsinhDouble(Scalar),
     AtanDouble(Scalar),
@@ -32,13 +37,16 @@ pub enum F {
     ChrString(Scalar),
     CosDouble(Scalar),
     CoshDouble(Scalar),
-    DateFromTimestamp(Scalar),
+    DateFromTimestamp(Scalar, Option<Scalar>),
     DateFromUnixDate(Scalar),
     DecimalLogarithmDouble(Scalar),
     Error(Scalar),
     ExpDouble(Scalar),
-    ExtractDateFromTimestamp(Scalar),
+    ExtractDateFromTimestamp(Scalar, Option<Scalar>),
+    FarmFingerprintString(Scalar),
     FloorDouble(Scalar),
+    FromBase64(Scalar),
+    FromHex(Scalar),
     IsFalse(Scalar),
     IsInf(Scalar),
     IsNan(Scalar),
@@ -46,22 +54,31 @@ pub enum F {
     IsTrue(Scalar),
     LengthString(Scalar),
     LowerString(Scalar),
+    Md5Bytes(Scalar),
+    Md5String(Scalar),
     NaturalLogarithmDouble(Scalar),
+    NextVal(Scalar),
     Not(Scalar),
+    ParseJson(Scalar),
     ReverseString(Scalar),
     RoundDouble(Scalar),
+    Sha256Bytes(Scalar),
+    Sha256String(Scalar),
     SignDouble(Scalar),
     SignInt64(Scalar),
     SinDouble(Scalar),
     SinhDouble(Scalar),
+    SoundexString(Scalar),
     SqrtDouble(Scalar),
     StringFromDate(Scalar),
-    StringFromTimestamp(Scalar),
+    StringFromTimestamp(Scalar, Option<Scalar>),
     TanDouble(Scalar),
     TanhDouble(Scalar),
-    TimestampFromDate(Scalar),
-    TimestampFromString(Scalar),
+    TimestampFromDate(Scalar, Option<Scalar>),
+    TimestampFromString(Scalar, Option<Scalar>),
     TimestampFromUnixMicrosInt64(Scalar),
+    ToBase64(Scalar),
+    ToHex(Scalar),
     TruncDouble(Scalar),
     UnaryMinusDouble(Scalar),
     UnaryMinusInt64(Scalar),
@@ -71,23 +88,34 @@ pub enum F {
     UpperString(Scalar),
     DateTruncDate(Scalar, DatePart),
     ExtractFromDate(Scalar, DatePart),
-    ExtractFromTimestamp(Scalar, DatePart),
-    TimestampTrunc(Scalar, DatePart),
+    ExtractFromTimestamp(Scalar, DatePart, Option<Scalar>),
+    TimestampTrunc(Scalar, DatePart, Option<Scalar>),
     In(Scalar, Vec<Scalar>),
     AddDouble(Scalar, Scalar),
     AddInt64(Scalar, Scalar),
     And(Scalar, Scalar),
+    ArrayAtOffset(Scalar, Scalar),
+    ArrayAtOrdinal(Scalar, Scalar),
+    ArrayToString(Scalar, Scalar, Option<Scalar>),
     Atan2Double(Scalar, Scalar),
     DivideDouble(Scalar, Scalar),
     DivInt64(Scalar, Scalar),
     EndsWithString(Scalar, Scalar),
     Equal(Scalar, Scalar),
     FormatDate(Scalar, Scalar),
-    FormatTimestamp(Scalar, Scalar),
+    FormatTimestamp(Scalar, Scalar, Option<Scalar>),
     Greater(Scalar, Scalar),
     GreaterOrEqual(Scalar, Scalar),
+    IeeeDivideDouble(Scalar, Scalar),
     Ifnull(Scalar, Scalar),
+    InitcapString(Scalar, Option<Scalar>),
     Is(Scalar, Scalar),
+    JsonQuery(Scalar, Scalar),
+    JsonQueryJson(Scalar, Scalar),
+    JsonSubscriptInt64(Scalar, Scalar),
+    JsonSubscriptString(Scalar, Scalar),
+    JsonValue(Scalar, Option<Scalar>),
+    JsonValueJson(Scalar, Option<Scalar>),
     LeftString(Scalar, Scalar),
     Less(Scalar, Scalar),
     LessOrEqual(Scalar, Scalar),
@@ -100,19 +128,26 @@ pub enum F {
     Nullif(Scalar, Scalar),
     Or(Scalar, Scalar),
     ParseDate(Scalar, Scalar),
-    ParseTimestamp(Scalar, Scalar),
+    ParseTimestamp(Scalar, Scalar, Option<Scalar>),
     PowDouble(Scalar, Scalar),
     RegexpContainsString(Scalar, Scalar),
+    RegexpExtractAllString(Scalar, Scalar),
     RegexpExtractString(Scalar, Scalar),
     RepeatString(Scalar, Scalar),
     RightString(Scalar, Scalar),
     RoundWithDigitsDouble(Scalar, Scalar),
     RtrimString(Scalar, Option<Scalar>),
+    SafeArrayAtOffset(Scalar, Scalar),
+    SafeArrayAtOrdinal(Scalar, Scalar),
+    SafeDivideDouble(Scalar, Scalar),
+    SplitBytes(Scalar, Scalar),
+    SplitString(Scalar, Scalar),
     StartsWithString(Scalar, Scalar),
     StringLike(Scalar, Scalar),
     StrposString(Scalar, Scalar),
     SubtractDouble(Scalar, Scalar),
     SubtractInt64(Scalar, Scalar),
+    ToJsonString(Scalar, Option<Scalar>),
     TrimString(Scalar, Option<Scalar>),
     TruncWithDigitsDouble(Scalar, Scalar),
     DateAddDate(Scalar, Scalar, DatePart),
@@ -129,6 +164,7 @@ pub enum F {
     ReplaceString(Scalar, Scalar, Scalar),
     RpadString(Scalar, Scalar, Scalar),
     SubstrString(Scalar, Scalar, Option<Scalar>),
+    TranslateString(Scalar, Scalar, Scalar),
     CaseNoValue(Vec<(Scalar, Scalar)>, Scalar),
     CaseWithValue(Scalar, Vec<(Scalar, Scalar)>, Scalar),
 }
@@ -139,13 +175,17 @@ impl F {
             F::CurrentDate
             | F::CurrentTimestamp
             | F::Xid
+            | F::GenerateUuid
+            | F::Rand
             | F::NextCatalogID
             | F::NextTableID
-            | F::NextIndexID => 0,
+            | F::NextIndexID
+            | F::NextSequenceID => 0,
             F::AbsDouble(_)
             | F::AbsInt64(_)
             | F::AcosDouble(_)
             | F::AcoshDouble(_)
+            | F::ArrayLength(_)
             | F::AsinDouble(_)
             | F::AsinhDouble(_)
             | F::AtanDouble(_)
@@ -156,13 +196,16 @@ impl F {
             | F::ChrString(_)
             | F::CosDouble(_)
             | F::CoshDouble(_)
-            | F::DateFromTimestamp(_)
+            | F::DateFromTimestamp(_, None)
             | F::DateFromUnixDate(_)
             | F::DecimalLogarithmDouble(_)
             | F::Error(_)
             | F::ExpDouble(_)
-            | F::ExtractDateFromTimestamp(_)
+            | F::ExtractDateFromTimestamp(_, None)
+            | F::FarmFingerprintString(_)
             | F::FloorDouble(_)
+            | F::FromBase64(_)
+            | F::FromHex(_)
             | F::IsFalse(_)
             | F::IsInf(_)
             | F::IsNan(_)
@@ -170,22 +213,30 @@ impl F {
             | F::IsTrue(_)
             | F::LengthString(_)
             | F::LowerString(_)
+            | F::Md5Bytes(_)
+            | F::Md5String(_)
             | F::NaturalLogarithmDouble(_)
+            | F::NextVal(_)
             | F::Not(_)
             | F::ReverseString(_)
             | F::RoundDouble(_)
+            | F::Sha256Bytes(_)
+            | F::Sha256String(_)
             | F::SignDouble(_)
             | F::SignInt64(_)
             | F::SinDouble(_)
             | F::SinhDouble(_)
+            | F::SoundexString(_)
             | F::SqrtDouble(_)
             | F::StringFromDate(_)
-            | F::StringFromTimestamp(_)
+            | F::StringFromTimestamp(_, None)
             | F::TanDouble(_)
             | F::TanhDouble(_)
-            | F::TimestampFromDate(_)
-            | F::TimestampFromString(_)
+            | F::TimestampFromDate(_, None)
+            | F::TimestampFromString(_, None)
             | F::TimestampFromUnixMicrosInt64(_)
+            | F::ToBase64(_)
+            | F::ToHex(_)
             | F::TruncDouble(_)
             | F::UnaryMinusDouble(_)
             | F::UnaryMinusInt64(_)
@@ -195,25 +246,42 @@ impl F {
             | F::UpperString(_)
             | F::DateTruncDate(_, _)
             | F::ExtractFromDate(_, _)
-            | F::ExtractFromTimestamp(_, _)
+            | F::ExtractFromTimestamp(_, _, None)
             | F::TrimString(_, None)
-            | F::TimestampTrunc(_, _)
+            | F::TimestampTrunc(_, _, None)
+            | F::InitcapString(_, None)
+            | F::JsonValue(_, None)
+            | F::JsonValueJson(_, None)
+            | F::ParseJson(_)
+            | F::ToJsonString(_, None)
             | F::LtrimString(_, None)
             | F::RtrimString(_, None) => 1,
             F::AddDouble(_, _)
             | F::AddInt64(_, _)
             | F::And(_, _)
+            | F::ArrayAtOffset(_, _)
+            | F::ArrayAtOrdinal(_, _)
+            | F::ArrayToString(_, _, None)
             | F::Atan2Double(_, _)
             | F::DivideDouble(_, _)
             | F::DivInt64(_, _)
             | F::EndsWithString(_, _)
             | F::Equal(_, _)
             | F::FormatDate(_, _)
-            | F::FormatTimestamp(_, _)
+            | F::FormatTimestamp(_, _, None)
             | F::Greater(_, _)
             | F::GreaterOrEqual(_, _)
+            | F::IeeeDivideDouble(_, _)
             | F::Ifnull(_, _)
+            | F::InitcapString(_, Some(_))
             | F::Is(_, _)
+            | F::JsonQuery(_, _)
+            | F::JsonQueryJson(_, _)
+            | F::JsonSubscriptInt64(_, _)
+            | F::JsonSubscriptString(_, _)
+            | F::JsonValue(_, Some(_))
+            | F::JsonValueJson(_, Some(_))
+            | F::ToJsonString(_, Some(_))
             | F::LeftString(_, _)
             | F::Less(_, _)
             | F::LessOrEqual(_, _)
@@ -226,14 +294,20 @@ impl F {
             | F::Nullif(_, _)
             | F::Or(_, _)
             | F::ParseDate(_, _)
-            | F::ParseTimestamp(_, _)
+            | F::ParseTimestamp(_, _, None)
             | F::PowDouble(_, _)
             | F::RegexpContainsString(_, _)
+            | F::RegexpExtractAllString(_, _)
             | F::RegexpExtractString(_, _)
             | F::RepeatString(_, _)
             | F::RightString(_, _)
             | F::RoundWithDigitsDouble(_, _)
             | F::RtrimString(_, Some(_))
+            | F::SafeArrayAtOffset(_, _)
+            | F::SafeArrayAtOrdinal(_, _)
+            | F::SafeDivideDouble(_, _)
+            | F::SplitBytes(_, _)
+            | F::SplitString(_, _)
             | F::StartsWithString(_, _)
             | F::StringLike(_, _)
             | F::StrposString(_, _)
@@ -247,6 +321,13 @@ impl F {
             | F::TimestampAdd(_, _, _)
             | F::TimestampDiff(_, _, _)
             | F::TimestampSub(_, _, _)
+            | F::DateFromTimestamp(_, Some(_))
+            | F::ExtractDateFromTimestamp(_, Some(_))
+            | F::StringFromTimestamp(_, Some(_))
+            | F::TimestampFromDate(_, Some(_))
+            | F::TimestampFromString(_, Some(_))
+            | F::ExtractFromTimestamp(_, _, Some(_))
+            | F::TimestampTrunc(_, _, Some(_))
             | F::SubstrString(_, _, None) => 2,
             F::Between(_, _, _)
             | F::DateFromYearMonthDay(_, _, _)
@@ -255,10 +336,15 @@ impl F {
             | F::RegexpReplaceString(_, _, _)
             | F::ReplaceString(_, _, _)
             | F::RpadString(_, _, _)
+            | F::TranslateString(_, _, _)
+            | F::FormatTimestamp(_, _, Some(_))
+            | F::ArrayToString(_, _, Some(_))
+            | F::ParseTimestamp(_, _, Some(_))
             | F::SubstrString(_, _, Some(_)) => 3,
             F::Coalesce(varargs)
             | F::ConcatString(varargs)
             | F::Hash(varargs)
+            | F::FormatString(varargs)
             | F::Greatest(varargs)
             | F::Least(varargs) => varargs.len(),
             F::In(_, varargs) => varargs.len() + 1,
@@ -276,13 +362,17 @@ impl std::ops::Index<usize> for F {
             F::CurrentDate
             | F::CurrentTimestamp
             | F::Xid
+            | F::GenerateUuid
+            | F::Rand
             | F::NextCatalogID
             | F::NextTableID
-            | F::NextIndexID => panic!("{}", index),
+            | F::NextIndexID
+            | F::NextSequenceID => panic!("{}", index),
             F::AbsDouble(a)
             | F::AbsInt64(a)
             | F::AcosDouble(a)
             | F::AcoshDouble(a)
+            | F::ArrayLength(a)
             | F::AsinDouble(a)
             | F::AsinhDouble(a)
             | F::AtanDouble(a)
@@ -293,13 +383,16 @@ impl std::ops::Index<usize> for F {
             | F::ChrString(a)
             | F::CosDouble(a)
             | F::CoshDouble(a)
-            | F::DateFromTimestamp(a)
+            | F::DateFromTimestamp(a, None)
             | F::DateFromUnixDate(a)
             | F::DecimalLogarithmDouble(a)
             | F::Error(a)
             | F::ExpDouble(a)
-            | F::ExtractDateFromTimestamp(a)
+            | F::ExtractDateFromTimestamp(a, None)
+            | F::FarmFingerprintString(a)
             | F::FloorDouble(a)
+            | F::FromBase64(a)
+            | F::FromHex(a)
             | F::IsFalse(a)
             | F::IsInf(a)
             | F::IsNan(a)
@@ -307,22 +400,30 @@ impl std::ops::Index<usize> for F {
             | F::IsTrue(a)
             | F::LengthString(a)
             | F::LowerString(a)
+            | F::Md5Bytes(a)
+            | F::Md5String(a)
             | F::NaturalLogarithmDouble(a)
+            | F::NextVal(a)
             | F::Not(a)
             | F::ReverseString(a)
             | F::RoundDouble(a)
+            | F::Sha256Bytes(a)
+            | F::Sha256String(a)
             | F::SignDouble(a)
             | F::SignInt64(a)
             | F::SinDouble(a)
             | F::SinhDouble(a)
+            | F::SoundexString(a)
             | F::SqrtDouble(a)
             | F::StringFromDate(a)
-            | F::StringFromTimestamp(a)
+            | F::StringFromTimestamp(a, None)
             | F::TanDouble(a)
             | F::TanhDouble(a)
-            | F::TimestampFromDate(a)
-            | F::TimestampFromString(a)
+            | F::TimestampFromDate(a, None)
+            | F::TimestampFromString(a, None)
             | F::TimestampFromUnixMicrosInt64(a)
+            | F::ToBase64(a)
+            | F::ToHex(a)
             | F::TruncDouble(a)
             | F::UnaryMinusDouble(a)
             | F::UnaryMinusInt64(a)
@@ -332,9 +433,14 @@ impl std::ops::Index<usize> for F {
             | F::UpperString(a)
             | F::DateTruncDate(a, _)
             | F::ExtractFromDate(a, _)
-            | F::ExtractFromTimestamp(a, _)
+            | F::ExtractFromTimestamp(a, _, None)
             | F::TrimString(a, None)
-            | F::TimestampTrunc(a, _)
+            | F::TimestampTrunc(a, _, None)
+            | F::InitcapString(a, None)
+            | F::JsonValue(a, None)
+            | F::JsonValueJson(a, None)
+            | F::ParseJson(a)
+            | F::ToJsonString(a, None)
             | F::LtrimString(a, None)
             | F::RtrimString(a, None) => match index {
                 0 => a,
@@ -343,17 +449,29 @@ impl std::ops::Index<usize> for F {
             F::AddDouble(a, b)
             | F::AddInt64(a, b)
             | F::And(a, b)
+            | F::ArrayAtOffset(a, b)
+            | F::ArrayAtOrdinal(a, b)
+            | F::ArrayToString(a, b, None)
             | F::Atan2Double(a, b)
             | F::DivideDouble(a, b)
             | F::DivInt64(a, b)
             | F::EndsWithString(a, b)
             | F::Equal(a, b)
             | F::FormatDate(a, b)
-            | F::FormatTimestamp(a, b)
+            | F::FormatTimestamp(a, b, None)
             | F::Greater(a, b)
             | F::GreaterOrEqual(a, b)
+            | F::IeeeDivideDouble(a, b)
             | F::Ifnull(a, b)
+            | F::InitcapString(a, Some(b))
             | F::Is(a, b)
+            | F::JsonQuery(a, b)
+            | F::JsonQueryJson(a, b)
+            | F::JsonSubscriptInt64(a, b)
+            | F::JsonSubscriptString(a, b)
+            | F::JsonValue(a, Some(b))
+            | F::JsonValueJson(a, Some(b))
+            | F::ToJsonString(a, Some(b))
             | F::LeftString(a, b)
             | F::Less(a, b)
             | F::LessOrEqual(a, b)
@@ -366,14 +484,20 @@ impl std::ops::Index<usize> for F {
             | F::Nullif(a, b)
             | F::Or(a, b)
             | F::ParseDate(a, b)
-            | F::ParseTimestamp(a, b)
+            | F::ParseTimestamp(a, b, None)
             | F::PowDouble(a, b)
             | F::RegexpContainsString(a, b)
+            | F::RegexpExtractAllString(a, b)
             | F::RegexpExtractString(a, b)
             | F::RepeatString(a, b)
             | F::RightString(a, b)
             | F::RoundWithDigitsDouble(a, b)
             | F::RtrimString(a, Some(b))
+            | F::SafeArrayAtOffset(a, b)
+            | F::SafeArrayAtOrdinal(a, b)
+            | F::SafeDivideDouble(a, b)
+            | F::SplitBytes(a, b)
+            | F::SplitString(a, b)
             | F::StartsWithString(a, b)
             | F::StringLike(a, b)
             | F::StrposString(a, b)
@@ -387,6 +511,13 @@ impl std::ops::Index<usize> for F {
             | F::TimestampAdd(a, b, _)
             | F::TimestampDiff(a, b, _)
             | F::TimestampSub(a, b, _)
+            | F::DateFromTimestamp(a, Some(b))
+            | F::ExtractDateFromTimestamp(a, Some(b))
+            | F::StringFromTimestamp(a, Some(b))
+            | F::TimestampFromDate(a, Some(b))
+            | F::TimestampFromString(a, Some(b))
+            | F::ExtractFromTimestamp(a, _, Some(b))
+            | F::TimestampTrunc(a, _, Some(b))
             | F::SubstrString(a, b, None) => match index {
                 0 => a,
                 1 => b,
@@ -399,6 +530,10 @@ impl std::ops::Index<usize> for F {
             | F::RegexpReplaceString(a, b, c)
             | F::ReplaceString(a, b, c)
             | F::RpadString(a, b, c)
+            | F::TranslateString(a, b, c)
+            | F::FormatTimestamp(a, b, Some(c))
+            | F::ArrayToString(a, b, Some(c))
+            | F::ParseTimestamp(a, b, Some(c))
             | F::SubstrString(a, b, Some(c)) => match index {
                 0 => a,
                 1 => b,
@@ -408,6 +543,7 @@ impl std::ops::Index<usize> for F {
             F::Coalesce(varargs)
             | F::ConcatString(varargs)
             | F::Hash(varargs)
+            | F::FormatString(varargs)
             | F::Greatest(varargs)
             | F::Least(varargs) => &varargs[index],
             F::In(a, varargs) => match index {
@@ -454,13 +590,17 @@ impl std::ops::IndexMut<usize> for F {
             F::CurrentDate
             | F::CurrentTimestamp
             | F::Xid
+            | F::GenerateUuid
+            | F::Rand
             | F::NextCatalogID
             | F::NextTableID
-            | F::NextIndexID => panic!("{}", index),
+            | F::NextIndexID
+            | F::NextSequenceID => panic!("{}", index),
             F::AbsDouble(a)
             | F::AbsInt64(a)
             | F::AcosDouble(a)
             | F::AcoshDouble(a)
+            | F::ArrayLength(a)
             | F::AsinDouble(a)
             | F::AsinhDouble(a)
             | F::AtanDouble(a)
@@ -471,13 +611,16 @@ impl std::ops::IndexMut<usize> for F {
             | F::ChrString(a)
             | F::CosDouble(a)
             | F::CoshDouble(a)
-            | F::DateFromTimestamp(a)
+            | F::DateFromTimestamp(a, None)
             | F::DateFromUnixDate(a)
             | F::DecimalLogarithmDouble(a)
             | F::Error(a)
             | F::ExpDouble(a)
-            | F::ExtractDateFromTimestamp(a)
+            | F::ExtractDateFromTimestamp(a, None)
+            | F::FarmFingerprintString(a)
             | F::FloorDouble(a)
+            | F::FromBase64(a)
+            | F::FromHex(a)
             | F::IsFalse(a)
             | F::IsInf(a)
             | F::IsNan(a)
@@ -485,22 +628,30 @@ impl std::ops::IndexMut<usize> for F {
             | F::IsTrue(a)
             | F::LengthString(a)
             | F::LowerString(a)
+            | F::Md5Bytes(a)
+            | F::Md5String(a)
             | F::NaturalLogarithmDouble(a)
+            | F::NextVal(a)
             | F::Not(a)
             | F::ReverseString(a)
             | F::RoundDouble(a)
+            | F::Sha256Bytes(a)
+            | F::Sha256String(a)
             | F::SignDouble(a)
             | F::SignInt64(a)
             | F::SinDouble(a)
             | F::SinhDouble(a)
+            | F::SoundexString(a)
             | F::SqrtDouble(a)
             | F::StringFromDate(a)
-            | F::StringFromTimestamp(a)
+            | F::StringFromTimestamp(a, None)
             | F::TanDouble(a)
             | F::TanhDouble(a)
-            | F::TimestampFromDate(a)
-            | F::TimestampFromString(a)
+            | F::TimestampFromDate(a, None)
+            | F::TimestampFromString(a, None)
             | F::TimestampFromUnixMicrosInt64(a)
+            | F::ToBase64(a)
+            | F::ToHex(a)
             | F::TruncDouble(a)
             | F::UnaryMinusDouble(a)
             | F::UnaryMinusInt64(a)
@@ -510,9 +661,14 @@ impl std::ops::IndexMut<usize> for F {
             | F::UpperString(a)
             | F::DateTruncDate(a, _)
             | F::ExtractFromDate(a, _)
-            | F::ExtractFromTimestamp(a, _)
+            | F::ExtractFromTimestamp(a, _, None)
             | F::TrimString(a, None)
-            | F::TimestampTrunc(a, _)
+            | F::TimestampTrunc(a, _, None)
+            | F::InitcapString(a, None)
+            | F::JsonValue(a, None)
+            | F::JsonValueJson(a, None)
+            | F::ParseJson(a)
+            | F::ToJsonString(a, None)
             | F::LtrimString(a, None)
             | F::RtrimString(a, None) => match index {
                 0 => a,
@@ -521,17 +677,29 @@ impl std::ops::IndexMut<usize> for F {
             F::AddDouble(a, b)
             | F::AddInt64(a, b)
             | F::And(a, b)
+            | F::ArrayAtOffset(a, b)
+            | F::ArrayAtOrdinal(a, b)
+            | F::ArrayToString(a, b, None)
             | F::Atan2Double(a, b)
             | F::DivideDouble(a, b)
             | F::DivInt64(a, b)
             | F::EndsWithString(a, b)
             | F::Equal(a, b)
             | F::FormatDate(a, b)
-            | F::FormatTimestamp(a, b)
+            | F::FormatTimestamp(a, b, None)
             | F::Greater(a, b)
             | F::GreaterOrEqual(a, b)
+            | F::IeeeDivideDouble(a, b)
             | F::Ifnull(a, b)
+            | F::InitcapString(a, Some(b))
             | F::Is(a, b)
+            | F::JsonQuery(a, b)
+            | F::JsonQueryJson(a, b)
+            | F::JsonSubscriptInt64(a, b)
+            | F::JsonSubscriptString(a, b)
+            | F::JsonValue(a, Some(b))
+            | F::JsonValueJson(a, Some(b))
+            | F::ToJsonString(a, Some(b))
             | F::LeftString(a, b)
             | F::Less(a, b)
             | F::LessOrEqual(a, b)
@@ -544,14 +712,20 @@ impl std::ops::IndexMut<usize> for F {
             | F::Nullif(a, b)
             | F::Or(a, b)
             | F::ParseDate(a, b)
-            | F::ParseTimestamp(a, b)
+            | F::ParseTimestamp(a, b, None)
             | F::PowDouble(a, b)
             | F::RegexpContainsString(a, b)
+            | F::RegexpExtractAllString(a, b)
             | F::RegexpExtractString(a, b)
             | F::RepeatString(a, b)
             | F::RightString(a, b)
             | F::RoundWithDigitsDouble(a, b)
             | F::RtrimString(a, Some(b))
+            | F::SafeArrayAtOffset(a, b)
+            | F::SafeArrayAtOrdinal(a, b)
+            | F::SafeDivideDouble(a, b)
+            | F::SplitBytes(a, b)
+            | F::SplitString(a, b)
             | F::StartsWithString(a, b)
             | F::StringLike(a, b)
             | F::StrposString(a, b)
@@ -565,6 +739,13 @@ impl std::ops::IndexMut<usize> for F {
             | F::TimestampAdd(a, b, _)
             | F::TimestampDiff(a, b, _)
             | F::TimestampSub(a, b, _)
+            | F::DateFromTimestamp(a, Some(b))
+            | F::ExtractDateFromTimestamp(a, Some(b))
+            | F::StringFromTimestamp(a, Some(b))
+            | F::TimestampFromDate(a, Some(b))
+            | F::TimestampFromString(a, Some(b))
+            | F::ExtractFromTimestamp(a, _, Some(b))
+            | F::TimestampTrunc(a, _, Some(b))
             | F::SubstrString(a, b, None) => match index {
                 0 => a,
                 1 => b,
@@ -577,6 +758,10 @@ impl std::ops::IndexMut<usize> for F {
             | F::RegexpReplaceString(a, b, c)
             | F::ReplaceString(a, b, c)
             | F::RpadString(a, b, c)
+            | F::TranslateString(a, b, c)
+            | F::FormatTimestamp(a, b, Some(c))
+            | F::ArrayToString(a, b, Some(c))
+            | F::ParseTimestamp(a, b, Some(c))
             | F::SubstrString(a, b, Some(c)) => match index {
                 0 => a,
                 1 => b,
@@ -586,6 +771,7 @@ impl std::ops::IndexMut<usize> for F {
             F::Coalesce(varargs)
             | F::ConcatString(varargs)
             | F::Hash(varargs)
+            | F::FormatString(varargs)
             | F::Greatest(varargs)
             | F::Least(varargs) => &mut varargs[index],
             F::In(a, varargs) => match index {
@@ -644,6 +830,33 @@ fn unary_date_part(mut args: Vec<Scalar>, f: impl Fn(Scalar, DatePart) -> F) ->
     f(a, b)
 }
 
+/// Timestamp functions take an optional time zone as their last argument.
+fn unary_time_zone(mut args: Vec<Scalar>, f: impl Fn(Scalar, Option<Scalar>) -> F) -> F {
+    assert!(args.len() == 1 || args.len() == 2);
+    let b = if args.len() == 2 { args.pop() } else { None };
+    let a = args.pop().unwrap();
+    f(a, b)
+}
+
+fn unary_date_part_time_zone(
+    mut args: Vec<Scalar>,
+    f: impl Fn(Scalar, DatePart, Option<Scalar>) -> F,
+) -> F {
+    assert!(args.len() == 2 || args.len() == 3);
+    let c = if args.len() == 3 { args.pop() } else { None };
+    let b = DatePart::from_scalar(args.pop().unwrap());
+    let a = args.pop().unwrap();
+    f(a, b, c)
+}
+
+/// JSON_VALUE and JSON_EXTRACT_SCALAR take an optional JSONPath, which defaults to the whole document.
+fn unary_path(mut args: Vec<Scalar>, f: impl Fn(Scalar, Option<Scalar>) -> F) -> F {
+    assert!(args.len() == 1 || args.len() == 2);
+    let b = if args.len() == 2 { args.pop() } else { None };
+    let a = args.pop().unwrap();
+    f(a, b)
+}
+
 fn unary_vararg(mut args: Vec<Scalar>, f: impl Fn(Scalar, Vec<Scalar>) -> F) -> F {
     assert!(args.len() >= 2);
     f(args.remove(0), args)
@@ -656,6 +869,14 @@ fn binary(mut args: Vec<Scalar>, f: impl Fn(Scalar, Scalar) -> F) -> F {
     f(a, b)
 }
 
+fn binary_time_zone(mut args: Vec<Scalar>, f: impl Fn(Scalar, Scalar, Option<Scalar>) -> F) -> F {
+    assert!(args.len() == 2 || args.len() == 3);
+    let c = if args.len() == 3 { args.pop() } else { None };
+    let b = args.pop().unwrap();
+    let a = args.pop().unwrap();
+    f(a, b, c)
+}
+
 fn reduce(mut args: Vec<Scalar>, f: impl Fn(Scalar, Scalar) -> F) -> F {
     assert!(args.len() >= 2);
     let b = args.pop().unwrap();
@@ -725,6 +946,8 @@ impl F {
                 F::CaseWithValue(value, cases, default)
             }
             "ZetaSQL:$divide" => binary(args, |a, b| F::DivideDouble(a, b)),
+            "ZetaSQL:ieee_divide" => binary(args, |a, b| F::IeeeDivideDouble(a, b)),
+            "ZetaSQL:safe_divide" => binary(args, |a, b| F::SafeDivideDouble(a, b)),
             "ZetaSQL:$greater" => binary(args, |a, b| F::Greater(a, b)),
             "ZetaSQL:$greater_or_equal" => binary(args, |a, b| F::GreaterOrEqual(a, b)),
             "ZetaSQL:$less" => binary(args, |a, b| F::Less(a, b)),
@@ -776,6 +999,7 @@ impl F {
             "ZetaSQL:rtrim" if args.len() == 2 => binary(args, |a, b| F::RtrimString(a, Some(b))),
             "ZetaSQL:replace" => ternary(args, |a, b, c| F::ReplaceString(a, b, c)),
             "ZetaSQL:regexp_extract" => binary(args, |a, b| F::RegexpExtractString(a, b)),
+            "ZetaSQL:regexp_extract_all" => binary(args, |a, b| F::RegexpExtractAllString(a, b)),
             "ZetaSQL:regexp_replace" => ternary(args, |a, b, c| F::RegexpReplaceString(a, b, c)),
             "ZetaSQL:byte_length" => unary(args, |a| F::ByteLengthString(a)),
             "ZetaSQL:char_length" => unary(args, |a| F::CharLengthString(a)),
@@ -793,11 +1017,88 @@ impl F {
             "ZetaSQL:repeat" => binary(args, |a, b| F::RepeatString(a, b)),
             "ZetaSQL:reverse" => unary(args, |a| F::ReverseString(a)),
             "ZetaSQL:chr" => unary(args, |a| F::ChrString(a)),
+            "ZetaSQL:format" => F::FormatString(args),
+            "ZetaSQL:initcap" if args.len() == 1 => unary(args, |a| F::InitcapString(a, None)),
+            "ZetaSQL:initcap" if args.len() == 2 => {
+                binary(args, |a, b| F::InitcapString(a, Some(b)))
+            }
+            "ZetaSQL:translate" => ternary(args, |a, b, c| F::TranslateString(a, b, c)),
+            "ZetaSQL:soundex" => unary(args, |a| F::SoundexString(a)),
+            "ZetaSQL:farm_fingerprint" => unary(args, |a| F::FarmFingerprintString(a)),
+            "ZetaSQL:md5" if first_argument == Some(DataType::Bytes) => {
+                unary(args, |a| F::Md5Bytes(a))
+            }
+            "ZetaSQL:md5" => unary(args, |a| F::Md5String(a)),
+            "ZetaSQL:sha256" if first_argument == Some(DataType::Bytes) => {
+                unary(args, |a| F::Sha256Bytes(a))
+            }
+            "ZetaSQL:sha256" => unary(args, |a| F::Sha256String(a)),
+            "ZetaSQL:to_hex" => unary(args, |a| F::ToHex(a)),
+            "ZetaSQL:from_hex" => unary(args, |a| F::FromHex(a)),
+            "ZetaSQL:to_base64" => unary(args, |a| F::ToBase64(a)),
+            "ZetaSQL:from_base64" => unary(args, |a| F::FromBase64(a)),
+            "ZetaSQL:split" if args.len() == 1 && first_argument == Some(DataType::Bytes) => {
+                unary(args, |a| {
+                    F::SplitBytes(a, Scalar::Literal(Value::Bytes(Some(b",".to_vec()))))
+                })
+            }
+            "ZetaSQL:split" if args.len() == 1 => unary(args, |a| {
+                F::SplitString(a, Scalar::Literal(Value::String(Some(",".to_string()))))
+            }),
+            "ZetaSQL:split" if first_argument == Some(DataType::Bytes) => {
+                binary(args, |a, b| F::SplitBytes(a, b))
+            }
+            "ZetaSQL:split" => binary(args, |a, b| F::SplitString(a, b)),
+            "ZetaSQL:array_length" => unary(args, |a| F::ArrayLength(a)),
+            "ZetaSQL:array_to_string" if args.len() == 2 => {
+                binary(args, |a, b| F::ArrayToString(a, b, None))
+            }
+            "ZetaSQL:array_to_string" if args.len() == 3 => {
+                ternary(args, |a, b, c| F::ArrayToString(a, b, Some(c)))
+            }
+            "ZetaSQL:$array_at_offset" => binary(args, |a, b| F::ArrayAtOffset(a, b)),
+            "ZetaSQL:$array_at_ordinal" => binary(args, |a, b| F::ArrayAtOrdinal(a, b)),
+            "ZetaSQL:$safe_array_at_offset" => binary(args, |a, b| F::SafeArrayAtOffset(a, b)),
+            "ZetaSQL:$safe_array_at_ordinal" => binary(args, |a, b| F::SafeArrayAtOrdinal(a, b)),
+            "ZetaSQL:json_query" | "ZetaSQL:json_extract"
+                if first_argument == Some(DataType::Json) =>
+            {
+                binary(args, |a, b| F::JsonQueryJson(a, b))
+            }
+            "ZetaSQL:json_query" | "ZetaSQL:json_extract" => {
+                binary(args, |a, b| F::JsonQuery(a, b))
+            }
+            "ZetaSQL:json_value" | "ZetaSQL:json_extract_scalar"
+                if first_argument == Some(DataType::Json) =>
+            {
+                unary_path(args, |a, b| F::JsonValueJson(a, b))
+            }
+            "ZetaSQL:json_value" | "ZetaSQL:json_extract_scalar" => {
+                unary_path(args, |a, b| F::JsonValue(a, b))
+            }
+            "ZetaSQL:$subscript"
+                if DataType::from(signature.argument[1].r#type.as_ref().unwrap())
+                    == DataType::I64 =>
+            {
+                binary(args, |a, b| F::JsonSubscriptInt64(a, b))
+            }
+            "ZetaSQL:$subscript" => binary(args, |a, b| F::JsonSubscriptString(a, b)),
+            "ZetaSQL:parse_json" => unary(args, |a| F::ParseJson(a)),
+            "ZetaSQL:to_json_string" if args.len() == 1 => {
+                unary(args, |a| F::ToJsonString(a, None))
+            }
+            "ZetaSQL:to_json_string" if args.len() == 2 => {
+                binary(args, |a, b| F::ToJsonString(a, Some(b)))
+            }
+            "ZetaSQL:generate_uuid" => thunk(args, || F::GenerateUuid),
             "ZetaSQL:if" => ternary(args, |a, b, c| F::If(a, b, c)),
             "ZetaSQL:coalesce" => F::Coalesce(args),
             "ZetaSQL:ifnull" => binary(args, |a, b| F::Ifnull(a, b)),
             "ZetaSQL:nullif" => binary(args, |a, b| F::Nullif(a, b)),
-            "ZetaSQL:current_date" => thunk(args, || F::CurrentDate),
+            "ZetaSQL:current_date" if args.len() == 0 => thunk(args, || F::CurrentDate),
+            "ZetaSQL:current_date" if args.len() == 1 => unary(args, |a| {
+                F::DateFromTimestamp(Scalar::Call(Box::new(F::CurrentTimestamp)), Some(a))
+            }),
             "ZetaSQL:current_timestamp" => thunk(args, || F::CurrentTimestamp),
             "ZetaSQL:date_add" => {
                 binary_date_part(args, |a, b, date_part| F::DateAddDate(a, b, date_part))
@@ -820,12 +1121,9 @@ impl F {
             "ZetaSQL:date_trunc" => {
                 unary_date_part(args, |a, date_part| F::DateTruncDate(a, date_part))
             }
-            "ZetaSQL:timestamp_trunc" if args.len() == 2 => {
-                unary_date_part(args, |a, date_part| F::TimestampTrunc(a, date_part))
-            }
-            "ZetaSQL:timestamp_trunc" if args.len() == 3 => {
-                panic!("TIMESTAMP_TRUNC with time zone is not supported")
-            }
+            "ZetaSQL:timestamp_trunc" => unary_date_part_time_zone(args, |a, date_part, b| {
+                F::TimestampTrunc(a, date_part, b)
+            }),
             "ZetaSQL:date_from_unix_date" => unary(args, |a| F::DateFromUnixDate(a)),
             "ZetaSQL:timestamp_from_unix_micros" => {
                 unary(args, |a| F::TimestampFromUnixMicrosInt64(a))
@@ -834,44 +1132,48 @@ impl F {
             "ZetaSQL:unix_micros" => unary(args, |a| F::UnixMicrosFromTimestamp(a)),
             "ZetaSQL:unix_millis" => unary(args, |a| F::UnixMillisFromTimestamp(a)),
             "ZetaSQL:date" if first_argument == Some(DataType::Timestamp) => {
-                unary(args, |a| F::DateFromTimestamp(a))
+                unary_time_zone(args, |a, b| F::DateFromTimestamp(a, b))
             }
+            "ZetaSQL:date" if first_argument == Some(DataType::Date) => {
+                unary(args, |a| F::DateTruncDate(a, DatePart::Day))
+            }
+            "ZetaSQL:date" if first_argument == Some(DataType::String) => unary(args, |a| {
+                F::ParseDate(Scalar::Literal(Value::String(Some("%F".to_string()))), a)
+            }),
             "ZetaSQL:date" if signature.argument.len() == 3 => {
                 ternary(args, |a, b, c| F::DateFromYearMonthDay(a, b, c))
             }
             "ZetaSQL:timestamp" if first_argument == Some(DataType::String) => {
-                unary(args, |a| F::TimestampFromString(a))
+                unary_time_zone(args, |a, b| F::TimestampFromString(a, b))
             }
             "ZetaSQL:timestamp" if first_argument == Some(DataType::Date) => {
-                unary(args, |a| F::TimestampFromDate(a))
+                unary_time_zone(args, |a, b| F::TimestampFromDate(a, b))
             }
             "ZetaSQL:string" if first_argument == Some(DataType::Date) => {
                 unary(args, |a| F::StringFromDate(a))
             }
             "ZetaSQL:string" if first_argument == Some(DataType::Timestamp) => {
-                unary(args, |a| F::StringFromTimestamp(a))
+                unary_time_zone(args, |a, b| F::StringFromTimestamp(a, b))
             }
             "ZetaSQL:$extract" if first_argument == Some(DataType::Date) => {
                 unary_date_part(args, |a, date_part| F::ExtractFromDate(a, date_part))
             }
             "ZetaSQL:$extract" if first_argument == Some(DataType::Timestamp) => {
-                unary_date_part(args, |a, date_part| F::ExtractFromTimestamp(a, date_part))
-            }
-            "ZetaSQL:$extract_date" if args.len() == 1 => {
-                unary(args, |a| F::ExtractDateFromTimestamp(a))
+                unary_date_part_time_zone(args, |a, date_part, b| {
+                    F::ExtractFromTimestamp(a, date_part, b)
+                })
             }
-            "ZetaSQL:$extract_date" if args.len() == 2 => {
-                panic!("EXTRACT from date with time zone is not supported")
+            "ZetaSQL:$extract_date" => {
+                unary_time_zone(args, |a, b| F::ExtractDateFromTimestamp(a, b))
             }
             "ZetaSQL:format_date" => binary(args, |a, b| F::FormatDate(a, b)),
-            "ZetaSQL:format_timestamp" if args.len() == 2 => {
-                binary(args, |a, b| F::FormatTimestamp(a, b))
-            }
-            "ZetaSQL:format_timestamp" if args.len() == 3 => {
-                panic!("FORMAT_TIMESTAMP with time zone is not supported")
+            "ZetaSQL:format_timestamp" => {
+                binary_time_zone(args, |a, b, c| F::FormatTimestamp(a, b, c))
             }
             "ZetaSQL:parse_date" => binary(args, |a, b| F::ParseDate(a, b)),
-            "ZetaSQL:parse_timestamp" => binary(args, |a, b| F::ParseTimestamp(a, b)),
+            "ZetaSQL:parse_timestamp" => {
+                binary_time_zone(args, |a, b, c| F::ParseTimestamp(a, b, c))
+            }
             "ZetaSQL:abs" if returns == DataType::I64 => unary(args, |a| F::AbsInt64(a)),
             "ZetaSQL:abs" if returns == DataType::F64 => unary(args, |a| F::AbsDouble(a)),
             "ZetaSQL:sign" if returns == DataType::I64 => unary(args, |a| F::SignInt64(a)),
@@ -912,10 +1214,13 @@ impl F {
             "ZetaSQL:atan" => unary(args, |a| F::AtanDouble(a)),
             "ZetaSQL:atanh" => unary(args, |a| F::AtanhDouble(a)),
             "ZetaSQL:atan2" => binary(args, |a, b| F::Atan2Double(a, b)),
+            "ZetaSQL:rand" => thunk(args, || F::Rand),
             "ZetaSQL:error" => unary(args, |a| F::Error(a)),
             "System:next_catalog_id" => thunk(args, || F::NextCatalogID),
             "System:next_table_id" => thunk(args, || F::NextTableID),
             "System:next_index_id" => thunk(args, || F::NextIndexID),
+            "System:next_sequence_id" => thunk(args, || F::NextSequenceID),
+            "System:xid" => thunk(args, || F::Xid),
             other => panic!("{} is not a known function name", other),
         }
     }
@@ -928,15 +1233,20 @@ impl F {
             F::NextCatalogID => "NextCatalogID",
             F::NextTableID => "NextTableID",
             F::NextIndexID => "NextIndexID",
+            F::NextSequenceID => "NextSequenceID",
+            F::GenerateUuid => "GenerateUuid",
+            F::Rand => "Rand",
             F::Coalesce(_) => "Coalesce",
             F::ConcatString(_) => "ConcatString",
             F::Hash(_) => "Hash",
+            F::FormatString(_) => "FormatString",
             F::Greatest(_) => "Greatest",
             F::Least(_) => "Least",
             F::AbsDouble(_) => "AbsDouble",
             F::AbsInt64(_) => "AbsInt64",
             F::AcosDouble(_) => "AcosDouble",
             F::AcoshDouble(_) => "AcoshDouble",
+            F::ArrayLength(_) => "ArrayLength",
             F::AsinDouble(_) => "AsinDouble",
             F::AsinhDouble(_) => "AsinhDouble",
             F::AtanDouble(_) => "AtanDouble",
@@ -947,13 +1257,16 @@ impl F {
             F::ChrString(_) => "ChrString",
             F::CosDouble(_) => "CosDouble",
             F::CoshDouble(_) => "CoshDouble",
-            F::DateFromTimestamp(_) => "DateFromTimestamp",
+            F::DateFromTimestamp(_, _) => "DateFromTimestamp",
             F::DateFromUnixDate(_) => "DateFromUnixDate",
             F::DecimalLogarithmDouble(_) => "DecimalLogarithmDouble",
             F::Error(_) => "ExpDouble",
             F::ExpDouble(_) => "ExpDouble",
-            F::ExtractDateFromTimestamp(_) => "ExtractDateFromTimestamp",
+            F::ExtractDateFromTimestamp(_, _) => "ExtractDateFromTimestamp",
+            F::FarmFingerprintString(_) => "FarmFingerprintString",
             F::FloorDouble(_) => "FloorDouble",
+            F::FromBase64(_) => "FromBase64",
+            F::FromHex(_) => "FromHex",
             F::IsFalse(_) => "IsFalse",
             F::IsInf(_) => "IsInf",
             F::IsNan(_) => "IsNan",
@@ -961,22 +1274,31 @@ impl F {
             F::IsTrue(_) => "IsTrue",
             F::LengthString(_) => "LengthString",
             F::LowerString(_) => "LowerString",
+            F::Md5Bytes(_) => "Md5Bytes",
+            F::Md5String(_) => "Md5String",
             F::NaturalLogarithmDouble(_) => "NaturalLogarithmDouble",
+            F::NextVal(_) => "NextVal",
             F::Not(_) => "Not",
+            F::ParseJson(_) => "ParseJson",
             F::ReverseString(_) => "ReverseString",
             F::RoundDouble(_) => "RoundDouble",
+            F::Sha256Bytes(_) => "Sha256Bytes",
+            F::Sha256String(_) => "Sha256String",
             F::SignDouble(_) => "SignDouble",
             F::SignInt64(_) => "SignInt64",
             F::SinDouble(_) => "SinDouble",
             F::SinhDouble(_) => "SinhDouble",
+            F::SoundexString(_) => "SoundexString",
             F::SqrtDouble(_) => "SqrtDouble",
             F::StringFromDate(_) => "StringFromDate",
-            F::StringFromTimestamp(_) => "StringFromTimestamp",
+            F::StringFromTimestamp(_, _) => "StringFromTimestamp",
             F::TanDouble(_) => "TanDouble",
             F::TanhDouble(_) => "TanhDouble",
-            F::TimestampFromDate(_) => "TimestampFromDate",
-            F::TimestampFromString(_) => "TimestampFromString",
+            F::TimestampFromDate(_, _) => "TimestampFromDate",
+            F::TimestampFromString(_, _) => "TimestampFromString",
             F::TimestampFromUnixMicrosInt64(_) => "TimestampFromUnixMicrosInt64",
+            F::ToBase64(_) => "ToBase64",
+            F::ToHex(_) => "ToHex",
             F::TruncDouble(_) => "TruncDouble",
             F::UnaryMinusDouble(_) => "UnaryMinusDouble",
             F::UnaryMinusInt64(_) => "UnaryMinusInt64",
@@ -986,23 +1308,34 @@ impl F {
             F::UpperString(_) => "UpperString",
             F::DateTruncDate(_, _) => "DateTruncDate",
             F::ExtractFromDate(_, _) => "ExtractFromDate",
-            F::ExtractFromTimestamp(_, _) => "ExtractFromTimestamp",
-            F::TimestampTrunc(_, _) => "TimestampTrunc",
+            F::ExtractFromTimestamp(_, _, _) => "ExtractFromTimestamp",
+            F::TimestampTrunc(_, _, _) => "TimestampTrunc",
             F::In(_, _) => "In",
             F::AddDouble(_, _) => "AddDouble",
             F::AddInt64(_, _) => "AddInt64",
             F::And(_, _) => "And",
+            F::ArrayAtOffset(_, _) => "ArrayAtOffset",
+            F::ArrayAtOrdinal(_, _) => "ArrayAtOrdinal",
+            F::ArrayToString(_, _, _) => "ArrayToString",
             F::Atan2Double(_, _) => "Atan2Double",
             F::DivideDouble(_, _) => "DivideDouble",
             F::DivInt64(_, _) => "DivInt64",
             F::EndsWithString(_, _) => "EndsWithString",
             F::Equal(_, _) => "Equal",
             F::FormatDate(_, _) => "FormatDate",
-            F::FormatTimestamp(_, _) => "FormatTimestamp",
+            F::FormatTimestamp(_, _, _) => "FormatTimestamp",
             F::Greater(_, _) => "Greater",
             F::GreaterOrEqual(_, _) => "GreaterOrEqual",
+            F::IeeeDivideDouble(_, _) => "IeeeDivideDouble",
             F::Ifnull(_, _) => "Ifnull",
+            F::InitcapString(_, _) => "InitcapString",
             F::Is(_, _) => "Is",
+            F::JsonQuery(_, _) => "JsonQuery",
+            F::JsonQueryJson(_, _) => "JsonQueryJson",
+            F::JsonSubscriptInt64(_, _) => "JsonSubscriptInt64",
+            F::JsonSubscriptString(_, _) => "JsonSubscriptString",
+            F::JsonValue(_, _) => "JsonValue",
+            F::JsonValueJson(_, _) => "JsonValueJson",
             F::LeftString(_, _) => "LeftString",
             F::Less(_, _) => "Less",
             F::LessOrEqual(_, _) => "LessOrEqual",
@@ -1015,19 +1348,26 @@ impl F {
             F::Nullif(_, _) => "Nullif",
             F::Or(_, _) => "Or",
             F::ParseDate(_, _) => "ParseDate",
-            F::ParseTimestamp(_, _) => "ParseTimestamp",
+            F::ParseTimestamp(_, _, _) => "ParseTimestamp",
             F::PowDouble(_, _) => "PowDouble",
             F::RegexpContainsString(_, _) => "RegexpContainsString",
+            F::RegexpExtractAllString(_, _) => "RegexpExtractAllString",
             F::RegexpExtractString(_, _) => "RegexpExtractString",
             F::RepeatString(_, _) => "RepeatString",
             F::RightString(_, _) => "RightString",
             F::RoundWithDigitsDouble(_, _) => "RoundWithDigitsDouble",
             F::RtrimString(_, _) => "RtrimString",
+            F::SafeArrayAtOffset(_, _) => "SafeArrayAtOffset",
+            F::SafeArrayAtOrdinal(_, _) => "SafeArrayAtOrdinal",
+            F::SafeDivideDouble(_, _) => "SafeDivideDouble",
+            F::SplitBytes(_, _) => "SplitBytes",
+            F::SplitString(_, _) => "SplitString",
             F::StartsWithString(_, _) => "StartsWithString",
             F::StringLike(_, _) => "StringLike",
             F::StrposString(_, _) => "StrposString",
             F::SubtractDouble(_, _) => "SubtractDouble",
             F::SubtractInt64(_, _) => "SubtractInt64",
+            F::ToJsonString(_, _) => "ToJsonString",
             F::TrimString(_, _) => "TrimString",
             F::TruncWithDigitsDouble(_, _) => "TruncWithDigitsDouble",
             F::DateAddDate(_, _, _) => "DateAddDate",
@@ -1045,6 +1385,7 @@ impl F {
             F::ReplaceString(_, _, _) => "ReplaceString",
             F::RpadString(_, _, _) => "RpadString",
             F::SubstrString(_, _, _) => "SubstrString",
+            F::TranslateString(_, _, _) => "TranslateString",
             F::CaseWithValue(_, _, _) => "CaseWithValue",
         }
     }
@@ -1054,12 +1395,16 @@ impl F {
             F::CurrentDate
             | F::CurrentTimestamp
             | F::Xid
+            | F::GenerateUuid
+            | F::Rand
             | F::NextCatalogID
             | F::NextTableID
-            | F::NextIndexID => vec![],
+            | F::NextIndexID
+            | F::NextSequenceID => vec![],
             F::Coalesce(varargs)
             | F::ConcatString(varargs)
             | F::Hash(varargs)
+            | F::FormatString(varargs)
             | F::Greatest(varargs)
             | F::Least(varargs) => {
                 let mut arguments = vec![];
@@ -1072,6 +1417,7 @@ impl F {
             | F::AbsInt64(a)
             | F::AcosDouble(a)
             | F::AcoshDouble(a)
+            | F::ArrayLength(a)
             | F::AsinDouble(a)
             | F::AsinhDouble(a)
             | F::AtanDouble(a)
@@ -1082,13 +1428,16 @@ impl F {
             | F::ChrString(a)
             | F::CosDouble(a)
             | F::CoshDouble(a)
-            | F::DateFromTimestamp(a)
+            | F::DateFromTimestamp(a, None)
             | F::DateFromUnixDate(a)
             | F::DecimalLogarithmDouble(a)
             | F::Error(a)
             | F::ExpDouble(a)
-            | F::ExtractDateFromTimestamp(a)
+            | F::ExtractDateFromTimestamp(a, None)
+            | F::FarmFingerprintString(a)
             | F::FloorDouble(a)
+            | F::FromBase64(a)
+            | F::FromHex(a)
             | F::IsFalse(a)
             | F::IsInf(a)
             | F::IsNan(a)
@@ -1096,22 +1445,30 @@ impl F {
             | F::IsTrue(a)
             | F::LengthString(a)
             | F::LowerString(a)
+            | F::Md5Bytes(a)
+            | F::Md5String(a)
             | F::NaturalLogarithmDouble(a)
+            | F::NextVal(a)
             | F::Not(a)
             | F::ReverseString(a)
             | F::RoundDouble(a)
+            | F::Sha256Bytes(a)
+            | F::Sha256String(a)
             | F::SignDouble(a)
             | F::SignInt64(a)
             | F::SinDouble(a)
             | F::SinhDouble(a)
+            | F::SoundexString(a)
             | F::SqrtDouble(a)
             | F::StringFromDate(a)
-            | F::StringFromTimestamp(a)
+            | F::StringFromTimestamp(a, None)
             | F::TanDouble(a)
             | F::TanhDouble(a)
-            | F::TimestampFromDate(a)
-            | F::TimestampFromString(a)
+            | F::TimestampFromDate(a, None)
+            | F::TimestampFromString(a, None)
             | F::TimestampFromUnixMicrosInt64(a)
+            | F::ToBase64(a)
+            | F::ToHex(a)
             | F::TruncDouble(a)
             | F::UnaryMinusDouble(a)
             | F::UnaryMinusInt64(a)
@@ -1121,8 +1478,13 @@ impl F {
             | F::UpperString(a)
             | F::DateTruncDate(a, _)
             | F::ExtractFromDate(a, _)
-            | F::ExtractFromTimestamp(a, _)
-            | F::TimestampTrunc(a, _)
+            | F::ExtractFromTimestamp(a, _, None)
+            | F::TimestampTrunc(a, _, None)
+            | F::InitcapString(a, None)
+            | F::JsonValue(a, None)
+            | F::JsonValueJson(a, None)
+            | F::ParseJson(a)
+            | F::ToJsonString(a, None)
             | F::LtrimString(a, None)
             | F::RtrimString(a, None)
             | F::TrimString(a, None) => vec![a],
@@ -1136,17 +1498,29 @@ impl F {
             F::AddDouble(a, b)
             | F::AddInt64(a, b)
             | F::And(a, b)
+            | F::ArrayAtOffset(a, b)
+            | F::ArrayAtOrdinal(a, b)
+            | F::ArrayToString(a, b, None)
             | F::Atan2Double(a, b)
             | F::DivideDouble(a, b)
             | F::DivInt64(a, b)
             | F::EndsWithString(a, b)
             | F::Equal(a, b)
             | F::FormatDate(a, b)
-            | F::FormatTimestamp(a, b)
+            | F::FormatTimestamp(a, b, None)
             | F::Greater(a, b)
             | F::GreaterOrEqual(a, b)
+            | F::IeeeDivideDouble(a, b)
             | F::Ifnull(a, b)
+            | F::InitcapString(a, Some(b))
             | F::Is(a, b)
+            | F::JsonQuery(a, b)
+            | F::JsonQueryJson(a, b)
+            | F::JsonSubscriptInt64(a, b)
+            | F::JsonSubscriptString(a, b)
+            | F::JsonValue(a, Some(b))
+            | F::JsonValueJson(a, Some(b))
+            | F::ToJsonString(a, Some(b))
             | F::LeftString(a, b)
             | F::Less(a, b)
             | F::LessOrEqual(a, b)
@@ -1159,14 +1533,20 @@ impl F {
             | F::Nullif(a, b)
             | F::Or(a, b)
             | F::ParseDate(a, b)
-            | F::ParseTimestamp(a, b)
+            | F::ParseTimestamp(a, b, None)
             | F::PowDouble(a, b)
             | F::RegexpContainsString(a, b)
+            | F::RegexpExtractAllString(a, b)
             | F::RegexpExtractString(a, b)
             | F::RepeatString(a, b)
             | F::RightString(a, b)
             | F::RoundWithDigitsDouble(a, b)
             | F::RtrimString(a, Some(b))
+            | F::SafeArrayAtOffset(a, b)
+            | F::SafeArrayAtOrdinal(a, b)
+            | F::SafeDivideDouble(a, b)
+            | F::SplitBytes(a, b)
+            | F::SplitString(a, b)
             | F::StartsWithString(a, b)
             | F::StringLike(a, b)
             | F::StrposString(a, b)
@@ -1180,6 +1560,13 @@ impl F {
             | F::TimestampAdd(a, b, _)
             | F::TimestampDiff(a, b, _)
             | F::TimestampSub(a, b, _)
+            | F::DateFromTimestamp(a, Some(b))
+            | F::ExtractDateFromTimestamp(a, Some(b))
+            | F::StringFromTimestamp(a, Some(b))
+            | F::TimestampFromDate(a, Some(b))
+            | F::TimestampFromString(a, Some(b))
+            | F::ExtractFromTimestamp(a, _, Some(b))
+            | F::TimestampTrunc(a, _, Some(b))
             | F::SubstrString(a, b, None) => vec![a, b],
             F::Between(a, b, c)
             | F::DateFromYearMonthDay(a, b, c)
@@ -1188,6 +1575,10 @@ impl F {
             | F::RegexpReplaceString(a, b, c)
             | F::ReplaceString(a, b, c)
             | F::RpadString(a, b, c)
+            | F::TranslateString(a, b, c)
+            | F::FormatTimestamp(a, b, Some(c))
+            | F::ArrayToString(a, b, Some(c))
+            | F::ParseTimestamp(a, b, Some(c))
             | F::SubstrString(a, b, Some(c)) => vec![a, b, c],
             F::CaseNoValue(cases, default) => {
                 let mut arguments = vec![];
@@ -1220,6 +1611,17 @@ impl F {
             F::Coalesce(varargs) | F::Greatest(varargs) | F::Least(varargs) => {
                 varargs[0].data_type()
             }
+            F::ArrayAtOffset(array, _)
+            | F::ArrayAtOrdinal(array, _)
+            | F::SafeArrayAtOffset(array, _)
+            | F::SafeArrayAtOrdinal(array, _) => match array.data_type() {
+                DataType::Array(element_type) => *element_type,
+                other => panic!("{} is not an ARRAY", other),
+            },
+            F::RegexpExtractAllString { .. } | F::SplitString { .. } => {
+                DataType::Array(Box::new(DataType::String))
+            }
+            F::SplitBytes { .. } => DataType::Array(Box::new(DataType::Bytes)),
             F::And { .. }
             | F::Between { .. }
             | F::EndsWithString { .. }
@@ -1267,12 +1669,15 @@ impl F {
             | F::DivideDouble { .. }
             | F::ExpDouble { .. }
             | F::FloorDouble { .. }
+            | F::IeeeDivideDouble { .. }
             | F::LogarithmDouble { .. }
             | F::MultiplyDouble { .. }
             | F::NaturalLogarithmDouble { .. }
             | F::PowDouble { .. }
+            | F::Rand
             | F::RoundDouble { .. }
             | F::RoundWithDigitsDouble { .. }
+            | F::SafeDivideDouble { .. }
             | F::SignDouble { .. }
             | F::SinDouble { .. }
             | F::SinhDouble { .. }
@@ -1285,12 +1690,14 @@ impl F {
             | F::UnaryMinusDouble { .. } => DataType::F64,
             F::AbsInt64 { .. }
             | F::AddInt64 { .. }
+            | F::ArrayLength { .. }
             | F::ByteLengthString { .. }
             | F::CharLengthString { .. }
             | F::DateDiffDate { .. }
             | F::DivInt64 { .. }
             | F::ExtractFromDate { .. }
             | F::ExtractFromTimestamp { .. }
+            | F::FarmFingerprintString { .. }
             | F::Hash { .. }
             | F::LengthString { .. }
             | F::ModInt64 { .. }
@@ -1306,11 +1713,20 @@ impl F {
             | F::Xid { .. }
             | F::NextCatalogID
             | F::NextTableID
-            | F::NextIndexID => DataType::I64,
+            | F::NextIndexID
+            | F::NextSequenceID
+            | F::NextVal { .. } => DataType::I64,
             F::ChrString { .. }
+            | F::ArrayToString { .. }
             | F::ConcatString { .. }
             | F::FormatDate { .. }
+            | F::FormatString { .. }
             | F::FormatTimestamp { .. }
+            | F::GenerateUuid
+            | F::InitcapString { .. }
+            | F::JsonQuery { .. }
+            | F::JsonValue { .. }
+            | F::JsonValueJson { .. }
             | F::LeftString { .. }
             | F::LowerString { .. }
             | F::LpadString { .. }
@@ -1323,10 +1739,15 @@ impl F {
             | F::RightString { .. }
             | F::RpadString { .. }
             | F::RtrimString { .. }
+            | F::SoundexString { .. }
             | F::StringFromDate { .. }
             | F::StringFromTimestamp { .. }
             | F::SubstrString { .. }
+            | F::ToJsonString { .. }
+            | F::TranslateString { .. }
             | F::TrimString { .. }
+            | F::ToBase64 { .. }
+            | F::ToHex { .. }
             | F::UpperString { .. } => DataType::String,
             F::CurrentTimestamp { .. }
             | F::ParseTimestamp { .. }
@@ -1336,6 +1757,16 @@ impl F {
             | F::TimestampFromUnixMicrosInt64 { .. }
             | F::TimestampSub { .. }
             | F::TimestampTrunc { .. } => DataType::Timestamp,
+            F::JsonQueryJson { .. }
+            | F::JsonSubscriptInt64 { .. }
+            | F::JsonSubscriptString { .. }
+            | F::ParseJson { .. } => DataType::Json,
+            F::FromBase64 { .. }
+            | F::FromHex { .. }
+            | F::Md5Bytes { .. }
+            | F::Md5String { .. }
+            | F::Sha256Bytes { .. }
+            | F::Sha256String { .. } => DataType::Bytes,
         }
     }
 
@@ -1344,12 +1775,16 @@ impl F {
             F::CurrentDate
             | F::CurrentTimestamp
             | F::Xid
+            | F::GenerateUuid
+            | F::Rand
             | F::NextCatalogID
             | F::NextTableID
-            | F::NextIndexID => self,
+            | F::NextIndexID
+            | F::NextSequenceID => self,
             F::Coalesce(mut varargs) => F::Coalesce(varargs.drain(..).map(f).collect()),
             F::ConcatString(mut varargs) => F::ConcatString(varargs.drain(..).map(f).collect()),
             F::Hash(mut varargs) => F::Hash(varargs.drain(..).map(f).collect()),
+            F::FormatString(mut varargs) => F::FormatString(varargs.drain(..).map(f).collect()),
             F::Greatest(mut varargs) => F::Greatest(varargs.drain(..).map(f).collect()),
             F::Least(mut varargs) => F::Least(varargs.drain(..).map(f).collect()),
             F::In(a, mut varargs) => F::In(f(a), varargs.drain(..).map(f).collect()),
@@ -1366,6 +1801,7 @@ impl F {
             F::AbsInt64(a) => F::AbsInt64(f(a)),
             F::AcosDouble(a) => F::AcosDouble(f(a)),
             F::AcoshDouble(a) => F::AcoshDouble(f(a)),
+            F::ArrayLength(a) => F::ArrayLength(f(a)),
             F::AsinDouble(a) => F::AsinDouble(f(a)),
             F::AsinhDouble(a) => F::AsinhDouble(f(a)),
             F::AtanDouble(a) => F::AtanDouble(f(a)),
@@ -1376,13 +1812,16 @@ impl F {
             F::ChrString(a) => F::ChrString(f(a)),
             F::CosDouble(a) => F::CosDouble(f(a)),
             F::CoshDouble(a) => F::CoshDouble(f(a)),
-            F::DateFromTimestamp(a) => F::DateFromTimestamp(f(a)),
+            F::DateFromTimestamp(a, b) => F::DateFromTimestamp(f(a), b.map(f)),
             F::DateFromUnixDate(a) => F::DateFromUnixDate(f(a)),
             F::DecimalLogarithmDouble(a) => F::DecimalLogarithmDouble(f(a)),
             F::Error(a) => F::Error(f(a)),
             F::ExpDouble(a) => F::ExpDouble(f(a)),
-            F::ExtractDateFromTimestamp(a) => F::ExtractDateFromTimestamp(f(a)),
+            F::ExtractDateFromTimestamp(a, b) => F::ExtractDateFromTimestamp(f(a), b.map(f)),
+            F::FarmFingerprintString(a) => F::FarmFingerprintString(f(a)),
             F::FloorDouble(a) => F::FloorDouble(f(a)),
+            F::FromBase64(a) => F::FromBase64(f(a)),
+            F::FromHex(a) => F::FromHex(f(a)),
             F::IsFalse(a) => F::IsFalse(f(a)),
             F::IsInf(a) => F::IsInf(f(a)),
             F::IsNan(a) => F::IsNan(f(a)),
@@ -1390,22 +1829,31 @@ impl F {
             F::IsTrue(a) => F::IsTrue(f(a)),
             F::LengthString(a) => F::LengthString(f(a)),
             F::LowerString(a) => F::LowerString(f(a)),
+            F::Md5Bytes(a) => F::Md5Bytes(f(a)),
+            F::Md5String(a) => F::Md5String(f(a)),
             F::NaturalLogarithmDouble(a) => F::NaturalLogarithmDouble(f(a)),
+            F::NextVal(a) => F::NextVal(f(a)),
             F::Not(a) => F::Not(f(a)),
+            F::ParseJson(a) => F::ParseJson(f(a)),
             F::ReverseString(a) => F::ReverseString(f(a)),
             F::RoundDouble(a) => F::RoundDouble(f(a)),
+            F::Sha256Bytes(a) => F::Sha256Bytes(f(a)),
+            F::Sha256String(a) => F::Sha256String(f(a)),
             F::SignDouble(a) => F::SignDouble(f(a)),
             F::SignInt64(a) => F::SignInt64(f(a)),
             F::SinDouble(a) => F::SinDouble(f(a)),
             F::SinhDouble(a) => F::SinhDouble(f(a)),
+            F::SoundexString(a) => F::SoundexString(f(a)),
             F::SqrtDouble(a) => F::SqrtDouble(f(a)),
             F::StringFromDate(a) => F::StringFromDate(f(a)),
-            F::StringFromTimestamp(a) => F::StringFromTimestamp(f(a)),
+            F::StringFromTimestamp(a, b) => F::StringFromTimestamp(f(a), b.map(f)),
             F::TanDouble(a) => F::TanDouble(f(a)),
             F::TanhDouble(a) => F::TanhDouble(f(a)),
-            F::TimestampFromDate(a) => F::TimestampFromDate(f(a)),
-            F::TimestampFromString(a) => F::TimestampFromString(f(a)),
+            F::TimestampFromDate(a, b) => F::TimestampFromDate(f(a), b.map(f)),
+            F::TimestampFromString(a, b) => F::TimestampFromString(f(a), b.map(f)),
             F::TimestampFromUnixMicrosInt64(a) => F::TimestampFromUnixMicrosInt64(f(a)),
+            F::ToBase64(a) => F::ToBase64(f(a)),
+            F::ToHex(a) => F::ToHex(f(a)),
             F::TruncDouble(a) => F::TruncDouble(f(a)),
             F::UnaryMinusDouble(a) => F::UnaryMinusDouble(f(a)),
             F::UnaryMinusInt64(a) => F::UnaryMinusInt64(f(a)),
@@ -1415,22 +1863,35 @@ impl F {
             F::UpperString(a) => F::UpperString(f(a)),
             F::DateTruncDate(a, date_part) => F::DateTruncDate(f(a), date_part),
             F::ExtractFromDate(a, date_part) => F::ExtractFromDate(f(a), date_part),
-            F::ExtractFromTimestamp(a, date_part) => F::ExtractFromTimestamp(f(a), date_part),
-            F::TimestampTrunc(a, date_part) => F::TimestampTrunc(f(a), date_part),
+            F::ExtractFromTimestamp(a, date_part, b) => {
+                F::ExtractFromTimestamp(f(a), date_part, b.map(f))
+            }
+            F::TimestampTrunc(a, date_part, b) => F::TimestampTrunc(f(a), date_part, b.map(f)),
             F::AddDouble(a, b) => F::AddDouble(f(a), f(b)),
             F::AddInt64(a, b) => F::AddInt64(f(a), f(b)),
             F::And(a, b) => F::And(f(a), f(b)),
+            F::ArrayAtOffset(a, b) => F::ArrayAtOffset(f(a), f(b)),
+            F::ArrayAtOrdinal(a, b) => F::ArrayAtOrdinal(f(a), f(b)),
+            F::ArrayToString(a, b, c) => F::ArrayToString(f(a), f(b), c.map(f)),
             F::Atan2Double(a, b) => F::Atan2Double(f(a), f(b)),
             F::DivideDouble(a, b) => F::DivideDouble(f(a), f(b)),
             F::DivInt64(a, b) => F::DivInt64(f(a), f(b)),
             F::EndsWithString(a, b) => F::EndsWithString(f(a), f(b)),
             F::Equal(a, b) => F::Equal(f(a), f(b)),
             F::FormatDate(a, b) => F::FormatDate(f(a), f(b)),
-            F::FormatTimestamp(a, b) => F::FormatTimestamp(f(a), f(b)),
+            F::FormatTimestamp(a, b, c) => F::FormatTimestamp(f(a), f(b), c.map(f)),
             F::Greater(a, b) => F::Greater(f(a), f(b)),
             F::GreaterOrEqual(a, b) => F::GreaterOrEqual(f(a), f(b)),
+            F::IeeeDivideDouble(a, b) => F::IeeeDivideDouble(f(a), f(b)),
             F::Ifnull(a, b) => F::Ifnull(f(a), f(b)),
+            F::InitcapString(a, b) => F::InitcapString(f(a), b.map(f)),
             F::Is(a, b) => F::Is(f(a), f(b)),
+            F::JsonQuery(a, b) => F::JsonQuery(f(a), f(b)),
+            F::JsonQueryJson(a, b) => F::JsonQueryJson(f(a), f(b)),
+            F::JsonSubscriptInt64(a, b) => F::JsonSubscriptInt64(f(a), f(b)),
+            F::JsonSubscriptString(a, b) => F::JsonSubscriptString(f(a), f(b)),
+            F::JsonValue(a, b) => F::JsonValue(f(a), b.map(f)),
+            F::JsonValueJson(a, b) => F::JsonValueJson(f(a), b.map(f)),
             F::LeftString(a, b) => F::LeftString(f(a), f(b)),
             F::Less(a, b) => F::Less(f(a), f(b)),
             F::LessOrEqual(a, b) => F::LessOrEqual(f(a), f(b)),
@@ -1443,19 +1904,26 @@ impl F {
             F::Nullif(a, b) => F::Nullif(f(a), f(b)),
             F::Or(a, b) => F::Or(f(a), f(b)),
             F::ParseDate(a, b) => F::ParseDate(f(a), f(b)),
-            F::ParseTimestamp(a, b) => F::ParseTimestamp(f(a), f(b)),
+            F::ParseTimestamp(a, b, c) => F::ParseTimestamp(f(a), f(b), c.map(f)),
             F::PowDouble(a, b) => F::PowDouble(f(a), f(b)),
             F::RegexpContainsString(a, b) => F::RegexpContainsString(f(a), f(b)),
+            F::RegexpExtractAllString(a, b) => F::RegexpExtractAllString(f(a), f(b)),
             F::RegexpExtractString(a, b) => F::RegexpExtractString(f(a), f(b)),
             F::RepeatString(a, b) => F::RepeatString(f(a), f(b)),
             F::RightString(a, b) => F::RightString(f(a), f(b)),
             F::RoundWithDigitsDouble(a, b) => F::RoundWithDigitsDouble(f(a), f(b)),
             F::RtrimString(a, b) => F::RtrimString(f(a), b.map(f)),
+            F::SafeArrayAtOffset(a, b) => F::SafeArrayAtOffset(f(a), f(b)),
+            F::SafeArrayAtOrdinal(a, b) => F::SafeArrayAtOrdinal(f(a), f(b)),
+            F::SafeDivideDouble(a, b) => F::SafeDivideDouble(f(a), f(b)),
+            F::SplitBytes(a, b) => F::SplitBytes(f(a), f(b)),
+            F::SplitString(a, b) => F::SplitString(f(a), f(b)),
             F::StartsWithString(a, b) => F::StartsWithString(f(a), f(b)),
             F::StringLike(a, b) => F::StringLike(f(a), f(b)),
             F::StrposString(a, b) => F::StrposString(f(a), f(b)),
             F::SubtractDouble(a, b) => F::SubtractDouble(f(a), f(b)),
             F::SubtractInt64(a, b) => F::SubtractInt64(f(a), f(b)),
+            F::ToJsonString(a, b) => F::ToJsonString(f(a), b.map(f)),
             F::TrimString(a, b) => F::TrimString(f(a), b.map(f)),
             F::TruncWithDigitsDouble(a, b) => F::TruncWithDigitsDouble(f(a), f(b)),
             F::DateAddDate(a, b, date_part) => F::DateAddDate(f(a), f(b), date_part),
@@ -1472,6 +1940,7 @@ impl F {
             F::ReplaceString(a, b, c) => F::ReplaceString(f(a), f(b), f(c)),
             F::RpadString(a, b, c) => F::RpadString(f(a), f(b), f(c)),
             F::SubstrString(a, b, c) => F::SubstrString(f(a), f(b), c.map(f)),
+            F::TranslateString(a, b, c) => F::TranslateString(f(a), f(b), f(c)),
         }
     }
 }
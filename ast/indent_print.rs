@@ -1,6 +1,9 @@
 use std::fmt::{Debug, Display, Formatter};
 
-use crate::{column::Column, AggregateExpr, AggregateFunction, Expr, Index, Join, Scalar, F};
+use crate::{
+    column::Column, AggregateExpr, AggregateFunction, Constraint, Expr, HavingModifier, Index,
+    Join, Scalar, F,
+};
 
 pub trait IndentPrint {
     fn indent_print(&self, f: &mut Formatter<'_>, indent: usize) -> std::fmt::Result;
@@ -31,10 +34,16 @@ impl IndentPrint for Expr {
             Expr::Leaf { gid } => write!(f, "@{}", gid),
             Expr::LogicalSingleGet | Expr::TableFreeScan { .. } => write!(f, "{}", self.name()),
             Expr::LogicalGet {
-                predicates, table, ..
+                predicates,
+                table,
+                sample,
+                ..
             }
             | Expr::SeqScan {
-                predicates, table, ..
+                predicates,
+                table,
+                sample,
+                ..
             } => {
                 let predicates = visible_predicates(predicates);
                 if !predicates.is_empty() {
@@ -42,6 +51,38 @@ impl IndentPrint for Expr {
                     newline(f, indent)?;
                 }
                 write!(f, "{} {}", self.name(), table.name)?;
+                if let Some(sample) = sample {
+                    write!(f, " {}", sample)?;
+                }
+                Ok(())
+            }
+            Expr::LogicalIndexGet {
+                predicates,
+                index,
+                table,
+                limit,
+                ..
+            }
+            | Expr::IndexOrderScan {
+                predicates,
+                index,
+                table,
+                limit,
+                ..
+            } => {
+                let predicates = visible_predicates(predicates);
+                if !predicates.is_empty() {
+                    write!(f, "Filter* {}", join_scalars(&predicates))?;
+                    newline(f, indent)?;
+                }
+                write!(
+                    f,
+                    "{} {}({}) {}",
+                    self.name(),
+                    table.name,
+                    join_index_order(index),
+                    limit
+                )?;
                 Ok(())
             }
             Expr::LogicalFilter { predicates, input } | Expr::Filter { predicates, input } => {
@@ -130,6 +171,29 @@ impl IndentPrint for Expr {
                 newline(f, indent)?;
                 input.indent_print(f, indent + 1)
             }
+            Expr::LogicalIterate {
+                name,
+                columns,
+                base,
+                step,
+                ..
+            }
+            | Expr::Iterate {
+                name,
+                columns,
+                base,
+                step,
+                ..
+            } => {
+                write!(f, "{} {}", self.name(), name)?;
+                for c in columns {
+                    write!(f, " {} {}", c.name, c.data_type)?;
+                }
+                newline(f, indent)?;
+                base.indent_print(f, indent + 1)?;
+                newline(f, indent)?;
+                step.indent_print(f, indent + 1)
+            }
             Expr::LogicalGetWith { name, .. } | Expr::GetTempTable { name, .. } => {
                 write!(f, "{} {}", self.name(), name)
             }
@@ -143,23 +207,18 @@ impl IndentPrint for Expr {
                 aggregate,
                 input,
                 ..
+            }
+            | Expr::PartialAggregate {
+                group_by,
+                aggregate,
+                input,
             } => {
                 write!(f, "{}", self.name())?;
                 for column in group_by {
                     write!(f, " {}", column)?;
                 }
-                for AggregateExpr {
-                    function,
-                    distinct,
-                    input,
-                    output,
-                } in aggregate
-                {
-                    if *distinct {
-                        write!(f, " {}:({} Distinct {})", output, function, input)?;
-                    } else {
-                        write!(f, " {}:({} {})", output, function, input)?;
-                    }
+                for a in aggregate {
+                    write!(f, " {}", a)?;
                 }
                 newline(f, indent)?;
                 input.indent_print(f, indent + 1)
@@ -168,18 +227,8 @@ impl IndentPrint for Expr {
                 aggregate, input, ..
             } => {
                 write!(f, "{}", self.name())?;
-                for AggregateExpr {
-                    function,
-                    distinct,
-                    input,
-                    output,
-                } in aggregate
-                {
-                    if *distinct {
-                        write!(f, " {}:({} Distinct {})", output, function, input)?;
-                    } else {
-                        write!(f, " {}:({} {})", output, function, input)?;
-                    }
+                for a in aggregate {
+                    write!(f, " {}", a)?;
                 }
                 newline(f, indent)?;
                 input.indent_print(f, indent + 1)
@@ -240,8 +289,22 @@ impl IndentPrint for Expr {
                 input.indent_print(f, indent + 1)?;
                 Ok(())
             }
-            Expr::LogicalInsert { table, input, .. } | Expr::Insert { table, input, .. } => {
+            Expr::LogicalInsert {
+                table,
+                input,
+                partition_by,
+                ..
+            }
+            | Expr::Insert {
+                table,
+                input,
+                partition_by,
+                ..
+            } => {
                 write!(f, "{} {}", self.name(), table.name)?;
+                if let Some(partition_by) = partition_by {
+                    write!(f, " {}", partition_by)?;
+                }
                 newline(f, indent)?;
                 input.indent_print(f, indent + 1)
             }
@@ -282,29 +345,125 @@ impl IndentPrint for Expr {
             Expr::LogicalCreateDatabase { name, .. } => {
                 write!(f, "{} {}", self.name(), name.path.join("."))
             }
-            Expr::LogicalCreateTable { name, columns, .. } => {
+            Expr::LogicalCreateTable {
+                name,
+                columns,
+                constraints,
+                temp,
+                options,
+            } => {
                 write!(f, "{} {}", self.name(), name)?;
+                if *temp {
+                    write!(f, " temp")?;
+                }
                 for (name, data_type) in columns {
                     write!(f, " {}:{}", name, data_type)?;
                 }
+                for constraint in constraints {
+                    match constraint {
+                        Constraint::NotNull(column) => write!(f, " {}:not_null", column)?,
+                        Constraint::Default(column, value) => {
+                            write!(f, " {}:default({})", column, value)?
+                        }
+                        Constraint::Check(name, check) => write!(f, " {}:check({})", name, check)?,
+                        Constraint::ForeignKey(name, columns, table, referenced_columns) => write!(
+                            f,
+                            " {}:foreign_key({}):{}({})",
+                            name,
+                            columns.join(", "),
+                            table,
+                            referenced_columns.join(", ")
+                        )?,
+                        Constraint::PrimaryKey(columns) => {
+                            write!(f, " primary_key({})", columns.join(", "))?
+                        }
+                        Constraint::Identity(column) => write!(f, " {}:identity", column)?,
+                    }
+                }
+                for (name, value) in options {
+                    write!(f, " {}={}", name, value)?;
+                }
                 Ok(())
             }
+            Expr::LogicalCreateTableAs {
+                create,
+                insert,
+                drop,
+            } => write!(f, "{} {:?} {:?} {:?}", self.name(), create, insert, drop),
             Expr::LogicalCreateIndex {
                 name,
                 table,
                 columns,
+                options,
                 ..
-            } => write!(
-                f,
-                "{} {} {} {}",
-                self.name(),
+            } => {
+                write!(
+                    f,
+                    "{} {} {} {}",
+                    self.name(),
+                    name,
+                    table,
+                    columns.join(" ")
+                )?;
+                for (name, value) in options {
+                    write!(f, " {}={}", name, value)?;
+                }
+                Ok(())
+            }
+            Expr::LogicalCreateSequence {
                 name,
-                table,
-                columns.join(" ")
-            ),
+                start,
+                increment,
+            } => write!(f, "{} {} {} {}", self.name(), name, start, increment),
             Expr::LogicalDrop { object, name } => {
                 write!(f, "{} {:?} {}", self.name(), object, name)
             }
+            Expr::LogicalAddColumn { name, column, .. } => {
+                write!(f, "{} {} {}:{}", self.name(), name, column.0, column.1)
+            }
+            Expr::LogicalRename {
+                object,
+                name,
+                new_name,
+            } => write!(f, "{} {:?} {} {}", self.name(), object, name, new_name),
+            Expr::LogicalCreateView {
+                name,
+                columns,
+                materialized,
+                ..
+            } => {
+                write!(f, "{} {}", self.name(), name)?;
+                if *materialized {
+                    write!(f, " materialized")?;
+                }
+                for (name, data_type) in columns {
+                    write!(f, " {}:{}", name, data_type)?;
+                }
+                Ok(())
+            }
+            Expr::LogicalCreateFunction {
+                name,
+                arguments,
+                return_type,
+                columns,
+                ..
+            } => {
+                write!(f, "{} {}", self.name(), name)?;
+                for (name, data_type) in arguments {
+                    write!(f, " {}:{}", name, data_type)?;
+                }
+                match return_type {
+                    Some(data_type) => write!(f, " -> {}", data_type)?,
+                    None => {
+                        write!(f, " -> table")?;
+                        for (name, data_type) in columns {
+                            write!(f, " {}:{}", name, data_type)?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Expr::LogicalRefresh { name } => write!(f, "{} {}", self.name(), name),
             Expr::LogicalRewrite { sql } => write!(f, "{} {:?}", self.name(), sql),
             Expr::IndexScan {
                 predicates,
@@ -372,6 +531,7 @@ impl Expr {
             Expr::Leaf { .. } => "Leaf",
             Expr::LogicalSingleGet { .. } => "LogicalSingleGet",
             Expr::LogicalGet { .. } => "LogicalGet",
+            Expr::LogicalIndexGet { .. } => "LogicalIndexGet",
             Expr::LogicalFilter { .. } => "LogicalFilter",
             Expr::LogicalOut { .. } => "LogicalOut",
             Expr::LogicalMap { .. } => "LogicalMap",
@@ -384,20 +544,29 @@ impl Expr {
             Expr::LogicalLimit { .. } => "LogicalLimit",
             Expr::LogicalSort { .. } => "LogicalSort",
             Expr::LogicalUnion { .. } => "LogicalUnion",
+            Expr::LogicalIterate { .. } => "LogicalIterate",
             Expr::LogicalInsert { .. } => "LogicalInsert",
             Expr::LogicalValues { .. } => "LogicalValues",
             Expr::LogicalUpdate { .. } => "LogicalUpdate",
             Expr::LogicalDelete { .. } => "LogicalDelete",
             Expr::LogicalCreateDatabase { .. } => "LogicalCreateDatabase",
             Expr::LogicalCreateTable { .. } => "LogicalCreateTable",
+            Expr::LogicalCreateTableAs { .. } => "LogicalCreateTableAs",
             Expr::LogicalCreateIndex { .. } => "LogicalCreateIndex",
+            Expr::LogicalCreateSequence { .. } => "LogicalCreateSequence",
             Expr::LogicalDrop { .. } => "LogicalDrop",
+            Expr::LogicalAddColumn { .. } => "LogicalAddColumn",
+            Expr::LogicalRename { .. } => "LogicalRename",
+            Expr::LogicalCreateView { .. } => "LogicalCreateView",
+            Expr::LogicalCreateFunction { .. } => "LogicalCreateFunction",
+            Expr::LogicalRefresh { .. } => "LogicalRefresh",
             Expr::LogicalScript { .. } => "LogicalScript",
             Expr::LogicalCall { .. } => "LogicalCall",
             Expr::LogicalExplain { .. } => "LogicalExplain",
             Expr::LogicalRewrite { .. } => "LogicalRewrite",
             Expr::TableFreeScan { .. } => "TableFreeScan",
             Expr::SeqScan { .. } => "SeqScan",
+            Expr::IndexOrderScan { .. } => "IndexOrderScan",
             Expr::IndexScan { .. } => "IndexScan",
             Expr::Filter { .. } => "Filter",
             Expr::Out { .. } => "Out",
@@ -408,9 +577,11 @@ impl Expr {
             Expr::GetTempTable { .. } => "GetTempTable",
             Expr::SimpleAggregate { .. } => "SimpleAggregate",
             Expr::GroupByAggregate { .. } => "GroupByAggregate",
+            Expr::PartialAggregate { .. } => "PartialAggregate",
             Expr::Limit { .. } => "Limit",
             Expr::Sort { .. } => "Sort",
             Expr::Union { .. } => "Union",
+            Expr::Iterate { .. } => "Iterate",
             Expr::Broadcast { .. } => "Broadcast",
             Expr::Exchange { .. } => "Exchange",
             Expr::Gather { .. } => "Gather",
@@ -451,23 +622,56 @@ impl Display for Scalar {
             Scalar::Literal(value) => write!(f, "{}", value),
             Scalar::Parameter(name, _) => write!(f, "@{}", name),
             Scalar::Column(column) => write!(f, "{}", column),
-            Scalar::Call(function) => {
-                if function.arguments().is_empty() {
-                    write!(f, "({})", function.name())
-                } else {
-                    let arguments: Vec<String> = function
-                        .arguments()
-                        .iter()
-                        .map(|argument| argument.to_string())
-                        .collect();
-                    write!(f, "({} {})", function.name(), arguments.join(" "))
-                }
-            }
+            Scalar::Call(function) => write_call(f, function.name(), function),
             Scalar::Cast(value, data_type) => write!(f, "(Cast {} {})", value, data_type),
+            Scalar::SafeCall(function) => {
+                write_call(f, &format!("SAFE.{}", function.name()), function)
+            }
+            Scalar::SafeCast(value, data_type) => {
+                write!(f, "(SafeCast {} {})", value, data_type)
+            }
         }
     }
 }
 
+fn write_call(f: &mut Formatter<'_>, name: &str, function: &F) -> std::fmt::Result {
+    if function.arguments().is_empty() {
+        write!(f, "({})", name)
+    } else {
+        let arguments: Vec<String> = function
+            .arguments()
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        write!(f, "({} {})", name, arguments.join(" "))
+    }
+}
+
+impl Display for AggregateExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:({}", self.output, self.function)?;
+        if self.distinct {
+            write!(f, " Distinct")?;
+        }
+        write!(f, " {}", self.input)?;
+        if self.ignore_nulls {
+            write!(f, " IgnoreNulls")?;
+        }
+        match &self.having {
+            Some(HavingModifier::Max(column)) => write!(f, " HavingMax {}", column)?,
+            Some(HavingModifier::Min(column)) => write!(f, " HavingMin {}", column)?,
+            None => {}
+        }
+        for o in &self.order_by {
+            write!(f, " OrderBy {}", o)?;
+        }
+        if let Some(limit) = self.limit {
+            write!(f, " Limit {}", limit)?;
+        }
+        write!(f, ")")
+    }
+}
+
 impl Display for AggregateFunction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -478,6 +682,18 @@ impl Display for AggregateFunction {
             AggregateFunction::Max => write!(f, "Max"),
             AggregateFunction::Min => write!(f, "Min"),
             AggregateFunction::Sum => write!(f, "Sum"),
+            AggregateFunction::StringAgg => write!(f, "StringAgg"),
+            AggregateFunction::ApproxCountDistinct => write!(f, "ApproxCountDistinct"),
+            AggregateFunction::HllCountInit => write!(f, "HllCountInit"),
+            AggregateFunction::HllCountMerge => write!(f, "HllCountMerge"),
+            AggregateFunction::VarSamp => write!(f, "VarSamp"),
+            AggregateFunction::VarPop => write!(f, "VarPop"),
+            AggregateFunction::StddevSamp => write!(f, "StddevSamp"),
+            AggregateFunction::StddevPop => write!(f, "StddevPop"),
+            AggregateFunction::MomentsInit => write!(f, "MomentsInit"),
+            AggregateFunction::ArrayAgg => write!(f, "ArrayAgg"),
+            AggregateFunction::ArrayConcatAgg => write!(f, "ArrayConcatAgg"),
+            AggregateFunction::ApproxQuantiles(number) => write!(f, "ApproxQuantiles({})", number),
         }
     }
 }
@@ -518,6 +734,18 @@ fn join_index_lookups(index: &Index, lookup: &Vec<Scalar>) -> String {
     strings.join(" ")
 }
 
+fn join_index_order(index: &Index) -> String {
+    let mut strings = vec![];
+    for i in 0..index.columns.len() {
+        if index.descending[i] {
+            strings.push(format!("{} DESC", index.columns[i]));
+        } else {
+            strings.push(index.columns[i].clone());
+        }
+    }
+    strings.join(" ")
+}
+
 /// Hide (LessOrEqual $xmin (Xid)) (Less (Xid) $xmax)
 fn visible_predicates(predicates: &Vec<Scalar>) -> Vec<Scalar> {
     predicates
@@ -1,4 +1,4 @@
-use crate::{Scalar, F};
+use crate::{Column, OrderBy, Scalar, F};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -6,6 +6,8 @@ pub struct Index {
     pub index_id: i64,
     pub table_id: i64,
     pub columns: Vec<String>,
+    // descending[i] is true if columns[i] is stored in descending order.
+    pub descending: Vec<bool>,
 }
 
 impl Index {
@@ -36,4 +38,17 @@ impl Index {
             None
         }
     }
+
+    /// Check if scanning the index produces the table columns in projects in the order given by order_by.
+    pub fn sorts(&self, order_by: &Vec<OrderBy>, projects: &Vec<Column>) -> bool {
+        order_by.len() <= self.columns.len()
+            && order_by.iter().enumerate().all(|(i, o)| {
+                o.descending == self.descending[i]
+                    && projects.iter().any(|c| {
+                        c == &o.column
+                            && c.name == self.columns[i]
+                            && c.table.as_ref().map(|t| t.id) == Some(self.table_id)
+                    })
+            })
+    }
 }
@@ -1,21 +1,32 @@
 use std::{collections::HashSet, fmt};
 
+use kernel::Error;
 use serde::{Deserialize, Serialize};
 
-use crate::{Column, Scalar};
+use crate::{Column, Scalar, Table};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Procedure {
     CreateCatalog,
-    CreateTable,
+    // CreateTable(page_size) allocates the heap of a new table, and returns its table id.
+    CreateTable(Scalar),
     CreateIndex,
-    Assert(Scalar, String),
+    CreateSequence(Scalar, Scalar),
+    Truncate(Table),
+    // Assert(test, error) fails the statement with error unless test is true.
+    Assert(Scalar, Error),
 }
 
 impl Procedure {
     pub(crate) fn collect_references(&self, set: &mut HashSet<Column>) {
         match self {
-            Procedure::CreateCatalog | Procedure::CreateTable | Procedure::CreateIndex => {}
+            Procedure::CreateCatalog | Procedure::CreateIndex => {}
+            Procedure::CreateTable(page_size) => page_size.collect_references(set),
+            Procedure::CreateSequence(start, increment) => {
+                start.collect_references(set);
+                increment.collect_references(set);
+            }
+            Procedure::Truncate(_) => {}
             Procedure::Assert(x, _) => x.collect_references(set),
         }
     }
@@ -25,9 +36,13 @@ impl fmt::Display for Procedure {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Procedure::CreateCatalog => write!(f, "create_catalog"),
-            Procedure::CreateTable => write!(f, "create_table"),
+            Procedure::CreateTable(page_size) => write!(f, "create_table {}", page_size),
             Procedure::CreateIndex => write!(f, "create_index"),
-            Procedure::Assert(test, description) => write!(f, "assert {} {}", test, description),
+            Procedure::CreateSequence(start, increment) => {
+                write!(f, "create_sequence {} {}", start, increment)
+            }
+            Procedure::Truncate(table) => write!(f, "truncate {}", table),
+            Procedure::Assert(test, error) => write!(f, "assert {} {}", test, error),
         }
     }
 }
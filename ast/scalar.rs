@@ -12,6 +12,10 @@ pub enum Scalar {
     Column(Column),
     Call(Box<F>),
     Cast(Box<Scalar>, DataType),
+    /// SAFE.function(...), which returns NULL instead of an error.
+    SafeCall(Box<F>),
+    /// SAFE_CAST(... AS type), which returns NULL instead of an error.
+    SafeCast(Box<Scalar>, DataType),
 }
 
 impl Scalar {
@@ -20,16 +24,16 @@ impl Scalar {
             Scalar::Literal(value) => value.data_type().clone(),
             Scalar::Parameter(_, data_type) => data_type.clone(),
             Scalar::Column(column) => column.data_type.clone(),
-            Scalar::Call(function) => function.returns().clone(),
-            Scalar::Cast(_, data_type) => data_type.clone(),
+            Scalar::Call(function) | Scalar::SafeCall(function) => function.returns().clone(),
+            Scalar::Cast(_, data_type) | Scalar::SafeCast(_, data_type) => data_type.clone(),
         }
     }
 
     pub fn len(&self) -> usize {
         match self {
             Scalar::Literal(_) | Scalar::Column(_) | Scalar::Parameter(_, _) => 0,
-            Scalar::Call(f) => f.len(),
-            Scalar::Cast(_, _) => 1,
+            Scalar::Call(f) | Scalar::SafeCall(f) => f.len(),
+            Scalar::Cast(_, _) | Scalar::SafeCast(_, _) => 1,
         }
     }
 
@@ -45,12 +49,14 @@ impl Scalar {
             Scalar::Column(column) => {
                 free.insert(column.clone());
             }
-            Scalar::Call(function) => {
+            Scalar::Call(function) | Scalar::SafeCall(function) => {
                 for scalar in function.arguments() {
                     scalar.collect_references(free)
                 }
             }
-            Scalar::Cast(scalar, _) => scalar.collect_references(free),
+            Scalar::Cast(scalar, _) | Scalar::SafeCast(scalar, _) => {
+                scalar.collect_references(free)
+            }
         }
     }
 
@@ -59,6 +65,8 @@ impl Scalar {
             Scalar::Column(c) if map.contains_key(&c) => Scalar::Column(map[&c].clone()),
             Scalar::Call(f) => Scalar::Call(Box::new(f.map(|scalar| scalar.subst(map)))),
             Scalar::Cast(x, t) => Scalar::Cast(Box::new(x.subst(map)), t),
+            Scalar::SafeCall(f) => Scalar::SafeCall(Box::new(f.map(|scalar| scalar.subst(map)))),
+            Scalar::SafeCast(x, t) => Scalar::SafeCast(Box::new(x.subst(map)), t),
             _ => self,
         }
     }
@@ -70,6 +78,12 @@ impl Scalar {
             Scalar::Cast(uncast, data_type) => {
                 Scalar::Cast(Box::new(uncast.inline(expr, column)), data_type.clone())
             }
+            Scalar::SafeCall(f) => {
+                Scalar::SafeCall(Box::new(f.map(|scalar| scalar.inline(expr, column))))
+            }
+            Scalar::SafeCast(uncast, data_type) => {
+                Scalar::SafeCast(Box::new(uncast.inline(expr, column)), data_type.clone())
+            }
             _ => self.clone(),
         }
     }
@@ -98,8 +112,8 @@ impl std::ops::Index<usize> for Scalar {
     fn index(&self, index: usize) -> &Self::Output {
         match self {
             Scalar::Literal(_) | Scalar::Column(_) | Scalar::Parameter(_, _) => panic!("{}", index),
-            Scalar::Call(f) => &f[index],
-            Scalar::Cast(x, _) => {
+            Scalar::Call(f) | Scalar::SafeCall(f) => &f[index],
+            Scalar::Cast(x, _) | Scalar::SafeCast(x, _) => {
                 if index == 0 {
                     x.as_ref()
                 } else {
@@ -114,8 +128,8 @@ impl std::ops::IndexMut<usize> for Scalar {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         match self {
             Scalar::Literal(_) | Scalar::Column(_) | Scalar::Parameter(_, _) => panic!("{}", index),
-            Scalar::Call(f) => &mut f[index],
-            Scalar::Cast(x, _) => {
+            Scalar::Call(f) | Scalar::SafeCall(f) => &mut f[index],
+            Scalar::Cast(x, _) | Scalar::SafeCast(x, _) => {
                 if index == 0 {
                     x.as_mut()
                 } else {
@@ -1,6 +1,6 @@
 use chrono::*;
 use kernel::*;
-use rpc::{parameter::Any, Parameter};
+use rpc::{parameter::Any, Parameter, ParameterArray};
 use serde::{Deserialize, Serialize};
 use std::{fmt, hash};
 
@@ -12,6 +12,10 @@ pub enum Value {
     Date(Option<i32>),
     Timestamp(Option<i64>),
     String(Option<String>),
+    Bytes(Option<Vec<u8>>),
+    Json(Option<String>),
+    /// An ARRAY, with the type of its elements.
+    Array(DataType, Option<Vec<Value>>),
     EnumValue(i32),
 }
 
@@ -24,6 +28,16 @@ impl Value {
             AnyArray::Date(array) => Value::Date(array.get(0)),
             AnyArray::Timestamp(array) => Value::Timestamp(array.get(0)),
             AnyArray::String(array) => Value::String(array.get(0).map(|s| s.to_string())),
+            AnyArray::Bytes(array) => Value::Bytes(array.get(0)),
+            AnyArray::Json(array) => Value::Json(array.get(0)),
+            AnyArray::Array(array) => Value::Array(
+                array.element_type(),
+                array.get(0).map(|elements| {
+                    (0..elements.len())
+                        .map(|i| Value::from(&elements.slice(i..i + 1)))
+                        .collect()
+                }),
+            ),
         }
     }
 
@@ -42,6 +56,18 @@ impl Value {
             Any::Date(value) => Value::Date(maybe(value, parameter.is_null)),
             Any::Timestamp(value) => Value::Timestamp(maybe(value, parameter.is_null)),
             Any::String(value) => Value::String(maybe(value, parameter.is_null)),
+            Any::Bytes(value) => Value::Bytes(maybe(value, parameter.is_null)),
+            Any::Json(value) => Value::Json(maybe(
+                normalize_json(&value).unwrap_or_else(|message| panic!("{}", message)),
+                parameter.is_null,
+            )),
+            Any::Array(value) => Value::Array(
+                DataType::from(value.element_type.as_str()),
+                maybe(
+                    value.elements.iter().map(Value::from_proto).collect(),
+                    parameter.is_null,
+                ),
+            ),
         }
     }
 
@@ -71,6 +97,25 @@ impl Value {
                 is_null: value.is_none(),
                 any: Some(Any::String(value.clone().unwrap_or("".to_string()))),
             },
+            Value::Bytes(value) => Parameter {
+                is_null: value.is_none(),
+                any: Some(Any::Bytes(value.clone().unwrap_or_default())),
+            },
+            Value::Json(value) => Parameter {
+                is_null: value.is_none(),
+                any: Some(Any::Json(value.clone().unwrap_or("null".to_string()))),
+            },
+            Value::Array(element_type, value) => Parameter {
+                is_null: value.is_none(),
+                any: Some(Any::Array(ParameterArray {
+                    element_type: element_type.to_string(),
+                    elements: value
+                        .iter()
+                        .flatten()
+                        .map(|element| element.into_proto())
+                        .collect(),
+                })),
+            },
             Value::EnumValue(_) => unimplemented!(),
         }
     }
@@ -83,6 +128,9 @@ impl Value {
             DataType::Date => Value::Date(None),
             DataType::Timestamp => Value::Timestamp(None),
             DataType::String => Value::String(None),
+            DataType::Bytes => Value::Bytes(None),
+            DataType::Json => Value::Json(None),
+            DataType::Array(element_type) => Value::Array(*element_type, None),
         }
     }
 
@@ -98,6 +146,24 @@ impl Value {
             Value::String(value) => AnyArray::String(
                 StringArray::from_str_options(vec![value.as_ref().map(|s| s.as_str())]).repeat(len),
             ),
+            Value::Bytes(value) => {
+                AnyArray::Bytes(BytesArray::from_options(vec![value.clone()]).repeat(len))
+            }
+            Value::Json(value) => {
+                AnyArray::Json(JsonArray::from_options(vec![value.clone()]).repeat(len))
+            }
+            Value::Array(element_type, value) => {
+                let elements = value.as_ref().map(|value| {
+                    let mut elements = AnyArray::with_capacity(element_type.clone(), value.len());
+                    for element in value {
+                        elements.extend(&element.repeat(1));
+                    }
+                    elements
+                });
+                let mut array = ArrayArray::with_capacity(element_type.clone(), 1);
+                array.push(elements.as_ref());
+                AnyArray::Array(array.repeat(len))
+            }
             Value::EnumValue(_) => panic!("Enum values have no array representation"),
         }
     }
@@ -110,6 +176,9 @@ impl Value {
             Value::Date(_) => DataType::Date,
             Value::Timestamp(_) => DataType::Timestamp,
             Value::String(_) => DataType::String,
+            Value::Bytes(_) => DataType::Bytes,
+            Value::Json(_) => DataType::Json,
+            Value::Array(element_type, _) => DataType::Array(Box::new(element_type.clone())),
             Value::EnumValue(_) => panic!("Enum values have no array representation"),
         }
     }
@@ -160,6 +229,28 @@ impl fmt::Display for Value {
                     write!(f, "null")
                 }
             }
+            Value::Bytes(value) => {
+                if let Some(value) = value {
+                    write!(f, "{}", bytes_literal(value))
+                } else {
+                    write!(f, "null")
+                }
+            }
+            Value::Json(value) => {
+                if let Some(value) = value {
+                    write!(f, "JSON {:?}", value)
+                } else {
+                    write!(f, "null")
+                }
+            }
+            Value::Array(_, value) => {
+                if let Some(value) = value {
+                    let elements: Vec<String> = value.iter().map(|e| e.to_string()).collect();
+                    write!(f, "[{}]", elements.join(", "))
+                } else {
+                    write!(f, "null")
+                }
+            }
             Value::EnumValue(value) => write!(f, "{}", value),
         }
     }
@@ -176,6 +267,11 @@ impl PartialEq for Value {
             (Value::Date(left), Value::Date(right)) => *left == *right,
             (Value::Timestamp(left), Value::Timestamp(right)) => *left == *right,
             (Value::String(left), Value::String(right)) => *left == *right,
+            (Value::Bytes(left), Value::Bytes(right)) => *left == *right,
+            (Value::Json(left), Value::Json(right)) => *left == *right,
+            (Value::Array(left_type, left), Value::Array(right_type, right)) => {
+                left_type == right_type && *left == *right
+            }
             (_, _) => false,
         }
     }
@@ -193,6 +289,9 @@ impl hash::Hash for Value {
             Value::Date(value) => value.hash(state),
             Value::Timestamp(value) => value.hash(state),
             Value::String(value) => value.hash(state),
+            Value::Bytes(value) => value.hash(state),
+            Value::Json(value) => value.hash(state),
+            Value::Array(_, value) => value.hash(state),
             Value::EnumValue(value) => value.hash(state),
         }
     }
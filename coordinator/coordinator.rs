@@ -1,15 +1,19 @@
 use std::{
+    collections::{HashMap, HashSet},
+    panic::AssertUnwindSafe,
     sync::{
         atomic::{AtomicI64, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     thread,
 };
 
 use ast::{Expr, Value};
-use kernel::{Next, RecordBatch};
+use kernel::{panic_message, Array, DataType, Error, Next, RecordBatch};
+use planner::Statement;
 use rpc::{
-    coordinator_server::Coordinator, CheckRequest, CheckResponse, QueryRequest, QueryResponse,
+    coordinator_server::Coordinator, to_status, CheckRequest, CheckResponse, EndSessionRequest,
+    EndSessionResponse, QueryRequest, QueryResponse, StartSessionRequest, StartSessionResponse,
     StatementResponse, TraceRequest, TraceResponse,
 };
 use tonic::{async_trait, Request, Response, Status};
@@ -17,6 +21,30 @@ use tonic::{async_trait, Request, Response, Status};
 #[derive(Clone, Default)]
 pub struct CoordinatorNode {
     txn: Arc<AtomicI64>,
+    // The number of sessions started so far, which is the id of the next session.
+    sessions: Arc<AtomicI64>,
+    // The sessions that were started and have not ended yet.
+    live_sessions: Arc<Mutex<HashSet<i64>>>,
+}
+
+impl CoordinatorNode {
+    /// Sessions are issued by StartSession and retired by EndSession, so the sessions of different clients never share temp tables,
+    /// and a session that has ended can't create temp tables that would never be dropped.
+    fn check_session(&self, session: Option<i64>) -> Result<(), Status> {
+        match session {
+            Some(session) if !self.live_sessions.lock().unwrap().contains(&session) => {
+                Err(invalid_session(session))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn invalid_session(session: i64) -> Status {
+    to_status(Error::InvalidSession(format!(
+        "Session {} was not started or has ended",
+        session
+    )))
 }
 
 #[async_trait]
@@ -30,12 +58,13 @@ impl Coordinator for CoordinatorNode {
         request: Request<QueryRequest>,
     ) -> Result<Response<QueryResponse>, Status> {
         let request = request.into_inner();
+        self.check_session(request.session)?;
         let txn = request
             .txn
             .unwrap_or_else(|| self.txn.fetch_add(1, Ordering::Relaxed));
         let (sender, receiver) = tokio::sync::oneshot::channel();
-        thread::spawn(move || sender.send(submit(request, txn)).unwrap());
-        let batch = receiver.await.unwrap()?;
+        thread::spawn(move || sender.send(catch_panic(|| submit(request, txn))).unwrap());
+        let (batch, _) = receiver.await.unwrap()?;
         Ok(Response::new(QueryResponse {
             txn,
             record_batch: bincode::serialize(&batch).unwrap(),
@@ -47,13 +76,17 @@ impl Coordinator for CoordinatorNode {
         request: Request<QueryRequest>,
     ) -> Result<Response<StatementResponse>, Status> {
         let request = request.into_inner();
+        self.check_session(request.session)?;
         let txn = request
             .txn
             .unwrap_or_else(|| self.txn.fetch_add(1, Ordering::Relaxed));
         let (sender, receiver) = tokio::sync::oneshot::channel();
-        thread::spawn(move || sender.send(submit(request, txn)).unwrap());
-        let _batch = receiver.await.unwrap()?;
-        Ok(Response::new(StatementResponse { txn }))
+        thread::spawn(move || sender.send(catch_panic(|| submit(request, txn))).unwrap());
+        let (_, rows_affected) = receiver.await.unwrap()?;
+        Ok(Response::new(StatementResponse {
+            txn,
+            rows_affected: Some(rows_affected),
+        }))
     }
 
     async fn trace(
@@ -68,39 +101,130 @@ impl Coordinator for CoordinatorNode {
         }
         Ok(Response::new(TraceResponse { stages }))
     }
+
+    async fn start_session(
+        &self,
+        _: Request<StartSessionRequest>,
+    ) -> Result<Response<StartSessionResponse>, Status> {
+        let session = self.sessions.fetch_add(1, Ordering::Relaxed);
+        self.live_sessions.lock().unwrap().insert(session);
+        Ok(Response::new(StartSessionResponse { session }))
+    }
+
+    async fn end_session(
+        &self,
+        request: Request<EndSessionRequest>,
+    ) -> Result<Response<EndSessionResponse>, Status> {
+        let request = request.into_inner();
+        // Retire the session first, so new requests of the session are rejected before its temp tables are dropped.
+        if !self.live_sessions.lock().unwrap().remove(&request.session) {
+            return Err(invalid_session(request.session));
+        }
+        let txn = self.txn.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        thread::spawn(move || {
+            sender
+                .send(catch_panic(|| end_session(request.session, txn)))
+                .unwrap()
+        });
+        receiver.await.unwrap()?;
+        Ok(Response::new(EndSessionResponse {}))
+    }
+}
+
+/// Report a panic in the planner or the coordinator to the client as an internal error, instead of dropping the request.
+fn catch_panic<T>(f: impl FnOnce() -> Result<T, Status>) -> Result<T, Status> {
+    match std::panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => result,
+        Err(payload) => Err(to_status(Error::Internal(panic_message(payload)))),
+    }
+}
+
+/// Drop the temp tables of a session that has ended.
+fn end_session(session: i64, txn: i64) -> Result<(), Status> {
+    let _session = log::session(txn, 0, None);
+    if let Some(expr) = planner::end_session(session) {
+        execute(&expr, txn)?;
+    }
+    Ok(())
 }
 
-fn submit(request: QueryRequest, txn: i64) -> Result<RecordBatch, Status> {
+/// Run a statement or script, returning its result and the number of rows it inserted, updated or deleted.
+fn submit(request: QueryRequest, txn: i64) -> Result<(RecordBatch, i64), Status> {
     let _session = log::session(txn, 0, None);
     let _span = log::enter(&request.sql);
+    // Scripts with variables or control flow are run one statement at a time. The script of each request is cached.
+    let script = planner::parse_script(&request.sql).map_err(to_status)?;
+    if !script.iter().all(Statement::is_sql) {
+        let params = request
+            .params
+            .iter()
+            .map(|(name, parameter)| (name.clone(), Value::from_proto(parameter)))
+            .collect();
+        return crate::script::run(script, params, request.catalog_id, request.session, txn);
+    }
     let params = request
         .params
         .iter()
         .map(|(name, parameter)| (name.clone(), Value::from_proto(parameter).data_type()))
         .collect();
-    let mut expr = planner::plan(request.sql, params, request.catalog_id, txn)
-        .map_err(Status::invalid_argument)?;
+    let mut expr = planner::plan(
+        request.sql.clone(),
+        params,
+        &HashMap::new(),
+        request.catalog_id,
+        request.session,
+        txn,
+    )
+    .map_err(to_status)?;
     let params = request
         .params
         .iter()
         .map(|(name, parameter)| (name.clone(), Value::from_proto(parameter)))
         .collect();
+    // CREATE TABLE ... AS SELECT and CREATE MATERIALIZED VIEW are planned again as two statements, once the table exists.
+    // They are run as a script of one statement, which drops the table again if the insert fails.
+    if let Expr::LogicalCreateTableAs { .. } = expr {
+        let script = vec![Statement::Sql(request.sql)];
+        return crate::script::run(script, params, request.catalog_id, request.session, txn);
+    }
+    if let Expr::LogicalCreateTable { temp: true, .. } = expr {
+        return create_temp_table(expr, request.session, txn);
+    }
     expr.replace(&params);
     execute(&expr, txn)
 }
 
+/// Create the heap of a temp table, and register the table for the session only once the heap exists.
+pub(crate) fn create_temp_table(
+    expr: Expr,
+    session: Option<i64>,
+    txn: i64,
+) -> Result<(RecordBatch, i64), Status> {
+    let (expr, table) = planner::create_temp_table(session, expr).map_err(to_status)?;
+    let (mut batch, _) = execute(&expr, txn)?;
+    let (_, table_id) = batch.columns.remove(0);
+    let table_id = table_id.as_i64().get(0).unwrap();
+    planner::register_temp_table(session.unwrap(), table, table_id).map_err(to_status)?;
+    // Like other DDL statements, CREATE TEMP TABLE returns no rows.
+    Ok((
+        RecordBatch::empty(vec![("$dummy".to_string(), DataType::Bool)]),
+        0,
+    ))
+}
+
 #[log::trace]
-fn execute(expr: &Expr, txn: i64) -> Result<RecordBatch, Status> {
+pub(crate) fn execute(expr: &Expr, txn: i64) -> Result<(RecordBatch, i64), Status> {
     let schema = expr.schema();
     let mut stream = remote_execution::gather(expr, txn, 0);
     let mut batches = vec![];
     loop {
         match stream.next() {
             Next::Page(batch) => batches.push(batch),
-            Next::Error(message) => return Err(Status::internal(message)),
+            Next::Error(error) => return Err(to_status(error)),
             Next::End => break,
         }
     }
     let batch = RecordBatch::cat(batches).unwrap_or_else(|| RecordBatch::empty(schema));
-    Ok(batch)
+    Ok((batch, stream.rows_affected()))
 }
@@ -1,4 +1,5 @@
 mod coordinator;
+mod script;
 
 pub use crate::coordinator::CoordinatorNode;
 
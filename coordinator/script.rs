@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use ast::{Expr, Value};
+use kernel::{DataType, Error, RecordBatch};
+use planner::Statement;
+use rpc::to_status;
+use tonic::Status;
+
+/// Run a procedural script on the coordinator.
+/// Each SQL statement and expression in the script is planned and executed separately, with the variables of the script bound like query parameters.
+/// Returns the result of the last SQL statement and the number of rows affected by all the statements.
+pub fn run(
+    statements: Vec<Statement>,
+    params: HashMap<String, Value>,
+    catalog_id: i64,
+    session: Option<i64>,
+    txn: i64,
+) -> Result<(RecordBatch, i64), Status> {
+    let mut script = Script {
+        catalog_id,
+        session,
+        txn,
+        params,
+        scopes: vec![],
+        result: None,
+        rows_affected: 0,
+    };
+    match script.block(&statements)? {
+        Flow::Next => {}
+        Flow::Break | Flow::Continue => {
+            return Err(to_status(Error::Syntax(
+                "BREAK and CONTINUE are only allowed inside a loop".to_string(),
+            )))
+        }
+    }
+    let result = script
+        .result
+        .unwrap_or_else(|| RecordBatch::empty(vec![("$dummy".to_string(), DataType::Bool)]));
+    Ok((result, script.rows_affected))
+}
+
+struct Script {
+    catalog_id: i64,
+    session: Option<i64>,
+    txn: i64,
+    // The values of the query parameters and the variables that are currently declared.
+    params: HashMap<String, Value>,
+    // The variables declared in each enclosing block, which are referenced by name instead of like @x.
+    scopes: Vec<Vec<String>>,
+    // The result of the last SQL statement.
+    result: Option<RecordBatch>,
+    // The number of rows inserted, updated or deleted by the statements executed so far.
+    rows_affected: i64,
+}
+
+// The maximum number of iterations of a WHILE or LOOP, so a script that never leaves a loop doesn't run forever.
+const MAX_ITERATIONS: usize = 10_000;
+
+enum Flow {
+    Next,
+    Break,
+    Continue,
+}
+
+impl Script {
+    fn block(&mut self, statements: &Vec<Statement>) -> Result<Flow, Status> {
+        self.scopes.push(vec![]);
+        let flow = self.statements(statements);
+        for name in self.scopes.pop().unwrap() {
+            self.params.remove(&name);
+        }
+        flow
+    }
+
+    fn statements(&mut self, statements: &Vec<Statement>) -> Result<Flow, Status> {
+        for statement in statements {
+            match self.statement(statement)? {
+                Flow::Next => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<Flow, Status> {
+        match statement {
+            Statement::Sql(sql) => {
+                self.result = Some(self.query(sql)?);
+            }
+            Statement::CreateSequence { .. } | Statement::DropSequence { .. } => {
+                let expr = planner::plan_statement(statement, self.catalog_id);
+                self.result = Some(self.execute(expr)?);
+            }
+            Statement::Declare { names, value } => {
+                let value = self.eval(value)?;
+                for name in names {
+                    // Variable names are case-insensitive.
+                    let key = name.to_lowercase();
+                    if self.params.contains_key(&key) {
+                        return Err(to_status(Error::Syntax(format!(
+                            "Variable {} is already declared",
+                            name
+                        ))));
+                    }
+                    self.params.insert(key.clone(), value.clone());
+                    self.scopes.last_mut().unwrap().push(key);
+                }
+            }
+            Statement::Set { name, value } => {
+                let key = name.to_lowercase();
+                let data_type = match self.params.get(&key) {
+                    Some(current) if self.is_variable(&key) => current.data_type(),
+                    _ => {
+                        return Err(to_status(Error::Syntax(format!(
+                            "Variable {} is not declared",
+                            name
+                        ))))
+                    }
+                };
+                let value = self.eval(&format!("CAST(({}) AS {})", value, data_type))?;
+                self.params.insert(key, value);
+            }
+            Statement::If {
+                branches,
+                otherwise,
+            } => {
+                for (condition, body) in branches {
+                    if self.condition(condition)? {
+                        return self.block(body);
+                    }
+                }
+                return self.block(otherwise);
+            }
+            Statement::While { condition, body } => {
+                let mut iterations = 0;
+                while self.condition(condition)? {
+                    iterations = self.iteration("WHILE", iterations)?;
+                    if let Flow::Break = self.block(body)? {
+                        break;
+                    }
+                }
+            }
+            Statement::Loop { body } => {
+                let mut iterations = 0;
+                loop {
+                    iterations = self.iteration("LOOP", iterations)?;
+                    if let Flow::Break = self.block(body)? {
+                        break;
+                    }
+                }
+            }
+            Statement::Block { body } => return self.block(body),
+            Statement::Break => return Ok(Flow::Break),
+            Statement::Continue => return Ok(Flow::Continue),
+            Statement::Raise { message } => {
+                let message = match message {
+                    Some(message) => match self.eval(&format!("CAST(({}) AS STRING)", message))? {
+                        Value::String(Some(message)) => message,
+                        _ => "NULL".to_string(),
+                    },
+                    None => "RAISE".to_string(),
+                };
+                return Err(to_status(Error::Raise(message)));
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn iteration(&self, statement: &str, iterations: usize) -> Result<usize, Status> {
+        if iterations == MAX_ITERATIONS {
+            return Err(to_status(Error::DataException(format!(
+                "{} exceeded the limit of {} iterations",
+                statement, MAX_ITERATIONS
+            ))));
+        }
+        Ok(iterations + 1)
+    }
+
+    fn is_variable(&self, name: &String) -> bool {
+        self.scopes.iter().any(|scope| scope.contains(name))
+    }
+
+    fn condition(&mut self, condition: &String) -> Result<bool, Status> {
+        match self.eval(condition)? {
+            Value::Bool(value) => Ok(value.unwrap_or(false)),
+            other => Err(to_status(Error::Syntax(format!(
+                "Condition {} has type {} but BOOL was expected",
+                condition,
+                other.data_type()
+            )))),
+        }
+    }
+
+    fn eval(&mut self, expr: &str) -> Result<Value, Status> {
+        let batch = self.query(&format!("select {}", expr))?;
+        let (_, column) = &batch.columns[0];
+        Ok(Value::from(column))
+    }
+
+    fn query(&mut self, sql: &str) -> Result<RecordBatch, Status> {
+        let _span = log::enter(sql);
+        let (variables, params) = self
+            .params
+            .iter()
+            .map(|(name, value)| (name.clone(), value.data_type()))
+            .partition(|(name, _)| self.is_variable(name));
+        let expr = planner::plan(
+            sql.to_string(),
+            params,
+            &variables,
+            self.catalog_id,
+            self.session,
+            self.txn,
+        )
+        .map_err(to_status)?;
+        if let Expr::LogicalCreateTableAs {
+            create,
+            insert,
+            drop,
+        } = expr
+        {
+            self.query(&create)?;
+            return match self.query(&insert) {
+                Ok(batch) => Ok(batch),
+                Err(status) => {
+                    self.query(&drop)?;
+                    Err(status)
+                }
+            };
+        }
+        if let Expr::LogicalCreateTable { temp: true, .. } = expr {
+            let (batch, _) = crate::coordinator::create_temp_table(expr, self.session, self.txn)?;
+            return Ok(batch);
+        }
+        self.execute(expr)
+    }
+
+    fn execute(&mut self, mut expr: Expr) -> Result<RecordBatch, Status> {
+        expr.replace(&self.params);
+        let (batch, rows_affected) = crate::coordinator::execute(&expr, self.txn)?;
+        self.rows_affected += rows_affected;
+        Ok(batch)
+    }
+}
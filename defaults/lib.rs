@@ -47,6 +47,22 @@ pub fn enabled_language_features() -> Vec<i32> {
         LanguageFeature::FeatureV13OmitInsertColumnList as i32,
         LanguageFeature::FeatureV13ConcatMixedTypes as i32,
         LanguageFeature::FeatureV11WithOnSubquery as i32,
+        LanguageFeature::FeatureCreateTableNotNull as i32,
+        LanguageFeature::FeatureCheckConstraint as i32,
+        LanguageFeature::FeatureForeignKeys as i32,
+        LanguageFeature::FeatureV13ColumnDefaultValue as i32,
+        LanguageFeature::FeatureV13WithRecursive as i32,
+        LanguageFeature::FeatureGroupByRollup as i32,
+        LanguageFeature::FeatureV11OrderByInAggregate as i32,
+        LanguageFeature::FeatureV11LimitInAggregate as i32,
+        LanguageFeature::FeatureV11HavingInAggregate as i32,
+        LanguageFeature::FeatureV11NullHandlingModifierInAggregate as i32,
+        LanguageFeature::FeatureTableValuedFunctions as i32,
+        LanguageFeature::FeatureCreateTableFunction as i32,
+        LanguageFeature::FeatureV13DmlReturning as i32,
+        LanguageFeature::FeatureV12SafeFunctionCall as i32,
+        LanguageFeature::FeatureJsonType as i32,
+        LanguageFeature::FeatureTablesample as i32,
     ]
 }
 
@@ -61,9 +77,14 @@ pub fn supported_statement_kinds() -> Vec<i32> {
         ResolvedNodeKind::ResolvedSubqueryExpr as i32,
         ResolvedNodeKind::ResolvedSingleRowScan as i32,
         ResolvedNodeKind::ResolvedTableScan as i32,
+        ResolvedNodeKind::ResolvedTvfscan as i32,
+        ResolvedNodeKind::ResolvedFunctionArgument as i32,
+        ResolvedNodeKind::ResolvedExpressionColumn as i32,
         ResolvedNodeKind::ResolvedJoinScan as i32,
         ResolvedNodeKind::ResolvedFilterScan as i32,
         ResolvedNodeKind::ResolvedAggregateScan as i32,
+        ResolvedNodeKind::ResolvedGroupingSet as i32,
+        ResolvedNodeKind::ResolvedAggregateHavingModifier as i32,
         ResolvedNodeKind::ResolvedSetOperationItem as i32,
         ResolvedNodeKind::ResolvedSetOperationScan as i32,
         ResolvedNodeKind::ResolvedOrderByScan as i32,
@@ -72,6 +93,9 @@ pub fn supported_statement_kinds() -> Vec<i32> {
         ResolvedNodeKind::ResolvedComputedColumn as i32,
         ResolvedNodeKind::ResolvedOrderByItem as i32,
         ResolvedNodeKind::ResolvedColumnDefinition as i32,
+        ResolvedNodeKind::ResolvedColumnAnnotations as i32,
+        ResolvedNodeKind::ResolvedCheckConstraint as i32,
+        ResolvedNodeKind::ResolvedForeignKey as i32,
         ResolvedNodeKind::ResolvedOutputColumn as i32,
         ResolvedNodeKind::ResolvedProjectScan as i32,
         ResolvedNodeKind::ResolvedQueryStmt as i32,
@@ -80,15 +104,41 @@ pub fn supported_statement_kinds() -> Vec<i32> {
         ResolvedNodeKind::ResolvedCreateIndexStmt as i32,
         ResolvedNodeKind::ResolvedCreateSchemaStmt as i32,
         ResolvedNodeKind::ResolvedCreateTableStmt as i32,
+        ResolvedNodeKind::ResolvedCreateTableAsSelectStmt as i32,
+        ResolvedNodeKind::ResolvedCreateViewStmt as i32,
+        ResolvedNodeKind::ResolvedCreateMaterializedViewStmt as i32,
+        ResolvedNodeKind::ResolvedDropMaterializedViewStmt as i32,
+        ResolvedNodeKind::ResolvedCreateFunctionStmt as i32,
+        ResolvedNodeKind::ResolvedCreateTableFunctionStmt as i32,
+        ResolvedNodeKind::ResolvedArgumentDef as i32,
+        ResolvedNodeKind::ResolvedArgumentList as i32,
+        ResolvedNodeKind::ResolvedArgumentRef as i32,
+        ResolvedNodeKind::ResolvedDropFunctionStmt as i32,
+        ResolvedNodeKind::ResolvedDropTableFunctionStmt as i32,
+        ResolvedNodeKind::ResolvedAlterMaterializedViewStmt as i32,
+        ResolvedNodeKind::ResolvedSetOptionsAction as i32,
+        ResolvedNodeKind::ResolvedOption as i32,
         ResolvedNodeKind::ResolvedDropStmt as i32,
+        ResolvedNodeKind::ResolvedAlterTableStmt as i32,
+        ResolvedNodeKind::ResolvedAddColumnAction as i32,
+        ResolvedNodeKind::ResolvedDropColumnAction as i32,
+        ResolvedNodeKind::ResolvedRenameToAction as i32,
+        ResolvedNodeKind::ResolvedRenameStmt as i32,
         ResolvedNodeKind::ResolvedWithScan as i32,
+        ResolvedNodeKind::ResolvedRecursiveScan as i32,
+        ResolvedNodeKind::ResolvedRecursiveRefScan as i32,
         ResolvedNodeKind::ResolvedWithEntry as i32,
         ResolvedNodeKind::ResolvedDmlvalue as i32,
+        ResolvedNodeKind::ResolvedDmldefault as i32,
         ResolvedNodeKind::ResolvedInsertRow as i32,
         ResolvedNodeKind::ResolvedInsertStmt as i32,
         ResolvedNodeKind::ResolvedDeleteStmt as i32,
         ResolvedNodeKind::ResolvedUpdateItem as i32,
         ResolvedNodeKind::ResolvedUpdateStmt as i32,
+        ResolvedNodeKind::ResolvedMergeWhen as i32,
+        ResolvedNodeKind::ResolvedMergeStmt as i32,
+        ResolvedNodeKind::ResolvedTruncateStmt as i32,
+        ResolvedNodeKind::ResolvedReturningClause as i32,
         ResolvedNodeKind::ResolvedCallStmt as i32,
         ResolvedNodeKind::ResolvedAggregateHavingModifier as i32,
         ResolvedNodeKind::ResolvedExplainStmt as i32,
@@ -105,6 +155,8 @@ pub fn enabled_functions() -> Vec<i32> {
         FunctionSignatureId::FnCaseNoValue as i32,          // $case_no_value
         FunctionSignatureId::FnCaseWithValue as i32,        // $case_with_value
         FunctionSignatureId::FnDivideDouble as i32,         // $divide
+        FunctionSignatureId::FnIeeeDivideDouble as i32,     // ieee_divide
+        FunctionSignatureId::FnSafeDivideDouble as i32,     // safe_divide
         FunctionSignatureId::FnGreater as i32,              // $greater
         FunctionSignatureId::FnGreaterOrEqual as i32,       // $greater_or_equal
         FunctionSignatureId::FnLess as i32,                 // $less
@@ -139,6 +191,7 @@ pub fn enabled_functions() -> Vec<i32> {
         FunctionSignatureId::FnRtrimString as i32,  // rtrim(string[, string]) -> string
         FunctionSignatureId::FnReplaceString as i32, // replace(string, string, string) -> string
         FunctionSignatureId::FnRegexpExtractString as i32, // regexp_extract(string, string) -> string
+        FunctionSignatureId::FnRegexpExtractAllString as i32, // regexp_extract_all(string, string) -> array<string>
         FunctionSignatureId::FnRegexpReplaceString as i32, // regexp_replace(string, string, string) -> string
         FunctionSignatureId::FnByteLengthString as i32,    // byte_length(string) -> int64
         FunctionSignatureId::FnCharLengthString as i32,    // char_length(string) -> int64
@@ -150,6 +203,39 @@ pub fn enabled_functions() -> Vec<i32> {
         FunctionSignatureId::FnRepeatString as i32, // repeat(string, int64) -> string
         FunctionSignatureId::FnReverseString as i32, // reverse(string) -> string
         FunctionSignatureId::FnChrString as i32,  // chr(int64) -> string
+        FunctionSignatureId::FnFormatString as i32, // format(string, repeated any) -> string
+        FunctionSignatureId::FnInitcapString as i32, // initcap(string[, string]) -> string
+        FunctionSignatureId::FnTranslateString as i32, // translate(string, string, string) -> string
+        FunctionSignatureId::FnSoundexString as i32, // soundex(string) -> string
+        FunctionSignatureId::FnFarmFingerprintString as i32, // farm_fingerprint(string) -> int64
+        FunctionSignatureId::FnMd5Bytes as i32,   // md5(bytes) -> bytes
+        FunctionSignatureId::FnMd5String as i32,  // md5(string) -> bytes
+        FunctionSignatureId::FnSha256Bytes as i32, // sha256(bytes) -> bytes
+        FunctionSignatureId::FnSha256String as i32, // sha256(string) -> bytes
+        FunctionSignatureId::FnToHex as i32,      // to_hex(bytes) -> string
+        FunctionSignatureId::FnFromHex as i32,    // from_hex(string) -> bytes
+        FunctionSignatureId::FnToBase64 as i32,   // to_base64(bytes) -> string
+        FunctionSignatureId::FnFromBase64 as i32, // from_base64(string) -> bytes
+        FunctionSignatureId::FnSplitString as i32, // split(string[, string]) -> array<string>
+        FunctionSignatureId::FnSplitBytes as i32, // split(bytes[, bytes]) -> array<bytes>
+        FunctionSignatureId::FnArrayLength as i32, // array_length(array) -> int64
+        FunctionSignatureId::FnArrayToString as i32, // array_to_string(array<string>, string[, string]) -> string
+        FunctionSignatureId::FnArrayAtOffset as i32, // array[offset(int64)]
+        FunctionSignatureId::FnArrayAtOrdinal as i32, // array[ordinal(int64)]
+        FunctionSignatureId::FnSafeArrayAtOffset as i32, // array[safe_offset(int64)]
+        FunctionSignatureId::FnSafeArrayAtOrdinal as i32, // array[safe_ordinal(int64)]
+        FunctionSignatureId::FnJsonExtract as i32, // json_extract(string, string) -> string
+        FunctionSignatureId::FnJsonExtractJson as i32, // json_extract(json, string) -> json
+        FunctionSignatureId::FnJsonExtractScalar as i32, // json_extract_scalar(string, string) -> string
+        FunctionSignatureId::FnJsonExtractScalarJson as i32, // json_extract_scalar(json, string) -> string
+        FunctionSignatureId::FnJsonQuery as i32,  // json_query(string, string) -> string
+        FunctionSignatureId::FnJsonQueryJson as i32, // json_query(json, string) -> json
+        FunctionSignatureId::FnJsonValue as i32,  // json_value(string[, string]) -> string
+        FunctionSignatureId::FnJsonValueJson as i32, // json_value(json[, string]) -> string
+        FunctionSignatureId::FnJsonSubscriptInt64 as i32, // $subscript(json, int64) -> json
+        FunctionSignatureId::FnJsonSubscriptString as i32, // $subscript(json, string) -> json
+        FunctionSignatureId::FnParseJson as i32,  // parse_json(string) -> json
+        FunctionSignatureId::FnToJsonString as i32, // to_json_string(any[, bool]) -> string
         FunctionSignatureId::FnIf as i32,         // if
         FunctionSignatureId::FnCoalesce as i32,   // coalesce
         FunctionSignatureId::FnIfnull as i32,     // ifnull
@@ -222,6 +308,7 @@ pub fn enabled_functions() -> Vec<i32> {
         FunctionSignatureId::FnLogicalAnd as i32, // logical_and
         FunctionSignatureId::FnLogicalOr as i32,  // logical_or
         FunctionSignatureId::FnRand as i32,       // rand() -> double
+        FunctionSignatureId::FnGenerateUuid as i32, // generate_uuid() -> string
         FunctionSignatureId::FnError as i32,      // error
         // Aggregate functions
         FunctionSignatureId::FnCountStar as i32,
@@ -235,5 +322,17 @@ pub fn enabled_functions() -> Vec<i32> {
         FunctionSignatureId::FnSumDouble as i32,
         FunctionSignatureId::FnLogicalAnd as i32,
         FunctionSignatureId::FnLogicalOr as i32,
+        FunctionSignatureId::FnCountif as i32,
+        FunctionSignatureId::FnStringAggString as i32,
+        FunctionSignatureId::FnStringAggDelimString as i32,
+        FunctionSignatureId::FnStddevPop as i32,
+        FunctionSignatureId::FnStddevSamp as i32, // stddev and stddev_samp
+        FunctionSignatureId::FnVarPop as i32,
+        FunctionSignatureId::FnVarSamp as i32, // variance and var_samp
+        FunctionSignatureId::FnApproxCountDistinct as i32,
+        FunctionSignatureId::FnApproxQuantiles as i32,
+        FunctionSignatureId::FnArrayAgg as i32,
+        FunctionSignatureId::FnArrayConcatAgg as i32,
+        // PERCENTILE_CONT and PERCENTILE_DISC are analytic functions, which the planner doesn't support.
     ]
 }
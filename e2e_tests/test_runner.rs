@@ -137,6 +137,7 @@ impl TestRunner {
                 .collect(),
             catalog_id: self.catalog_id,
             txn: None,
+            session: None,
         };
         rpc::runtime()
             .block_on(self.client.lock().unwrap().query(request))
@@ -1,19 +1,22 @@
 use std::{
+    cmp::Ordering,
     collections::{HashMap, HashSet},
-    hash::{BuildHasherDefault, Hash, Hasher},
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
 };
 
-use ast::{AggregateExpr, AggregateFunction, Value};
+use ast::{AggregateExpr, AggregateFunction, HavingModifier, Value};
+use hyperloglogplus::*;
 use kernel::*;
+use serde::{Deserialize, Serialize};
 
 pub struct SimpleAggregate {
-    aggregate_slots: Vec<Acc>,
+    aggregate_slots: Vec<Slot>,
 }
 
 pub struct GroupByAggregate {
     group_by_batches: Vec<Batch>,
-    aggregate_slots: HashMap<Key, Vec<Acc>, BuildKeyHasher>,
-    aggregate_slot_template: Vec<Acc>,
+    aggregate_slots: HashMap<Key, Vec<Slot>, BuildKeyHasher>,
+    aggregate_slot_template: Vec<Slot>,
 }
 
 struct Batch {
@@ -27,6 +30,30 @@ struct Key {
     tuple: u32,
 }
 
+/// Slot applies the IGNORE NULLS, HAVING MAX/MIN, ORDER BY and LIMIT modifiers to an accumulator.
+/// Each update receives the columns of AggregateExpr::references, so the input is always column 0.
+#[derive(Clone)]
+enum Slot {
+    Plain(Acc),
+    IgnoreNulls(Box<Slot>),
+    Having {
+        max: bool,
+        column: usize,
+        best: Value,
+        inner: Box<Slot>,
+        template: Box<Slot>,
+    },
+    Ordered {
+        order_by: Vec<usize>,
+        descending: Vec<bool>,
+        limit: Option<usize>,
+        distinct: bool,
+        keys: Vec<AnyArray>,
+        values: AnyArray,
+        template: Acc,
+    },
+}
+
 #[derive(Clone)]
 enum Acc {
     AnyValue(Value),
@@ -38,6 +65,16 @@ enum Acc {
     Min(Value),
     Sum(Value),
     SumDistinct(Distinct),
+    StringAgg(Option<String>),
+    StringAggDistinct(Distinct),
+    ApproxCountDistinct(Sketch),
+    HllCountInit(Sketch),
+    HllCountMerge(Sketch),
+    Variance(AggregateFunction, Moments),
+    MomentsInit(Moments),
+    ArrayAgg(AnyArray),
+    ArrayConcatAgg(DataType, Option<AnyArray>),
+    ApproxQuantiles(usize, AnyArray),
 }
 
 #[derive(Clone)]
@@ -48,28 +85,66 @@ enum Distinct {
     Date(HashSet<i32>),
     Timestamp(HashSet<i64>),
     String(HashSet<String>),
+    Bytes(HashSet<Vec<u8>>),
+}
+
+/// Sketch estimates the number of distinct values in a column using HyperLogLog++.
+/// Sketches built on different workers can be merged, which is how APPROX_COUNT_DISTINCT runs distributed.
+#[derive(Clone)]
+struct Sketch {
+    hll: HyperLogLogPlus<u64, BuildTwoXHasher>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct BuildTwoXHasher;
+
+/// Moments accumulates the count, mean and sum of squared differences from the mean of a column, using Welford's algorithm.
+/// Unlike the sum of squares, this doesn't lose precision when the variance is small relative to the mean.
+/// Moments accumulated on different workers can be merged, which is how VARIANCE and STDDEV run distributed.
+#[derive(Clone, Default)]
+struct Moments {
+    count: i64,
+    mean: f64,
+    m2: f64,
+}
+
+/// Precision 14 gives a relative error of about 1%, the same as BigQuery's default.
+const SKETCH_PRECISION: u8 = 14;
+
+/// Find the columns that each aggregate reads, in the order of AggregateExpr::references.
+pub fn find_columns(aggregate_fns: &Vec<AggregateExpr>, batch: &RecordBatch) -> Vec<Vec<AnyArray>> {
+    aggregate_fns
+        .iter()
+        .map(|a| {
+            a.references()
+                .iter()
+                .map(|c| batch.find_always(&c.canonical_name()).clone())
+                .collect()
+        })
+        .collect()
 }
 
 impl SimpleAggregate {
     pub fn new(aggregate_fns: &Vec<AggregateExpr>) -> Self {
         Self {
-            aggregate_slots: aggregate_fns.iter().map(|a| Acc::new(a)).collect(),
+            aggregate_slots: aggregate_fns.iter().map(|a| Slot::new(a)).collect(),
         }
     }
 
     /// Insert a batch of rows into the hash table.
-    pub fn insert(&mut self, aggregate: Vec<AnyArray>) {
-        let len = aggregate.first().unwrap().len();
+    pub fn insert(&mut self, aggregate: Vec<Vec<AnyArray>>) -> Result<(), Error> {
+        let len = aggregate.first().unwrap()[0].len();
         for tuple in 0..len as u32 {
             for i in 0..self.aggregate_slots.len() {
-                self.aggregate_slots[i].update(&aggregate[i], tuple);
+                self.aggregate_slots[i].update(&aggregate[i], tuple)?;
             }
         }
+        Ok(())
     }
 
     /// Return the results we've accumulated so far.
-    pub fn finish(&self) -> Vec<AnyArray> {
-        self.aggregate_slots.iter().map(Acc::finish).collect()
+    pub fn finish(&self) -> Result<Vec<AnyArray>, Error> {
+        self.aggregate_slots.iter().map(Slot::finish).collect()
     }
 }
 
@@ -78,13 +153,20 @@ impl GroupByAggregate {
         Self {
             group_by_batches: vec![],
             aggregate_slots: HashMap::default(),
-            aggregate_slot_template: aggregate_fns.iter().map(|a| Acc::new(a)).collect(),
+            aggregate_slot_template: aggregate_fns.iter().map(|a| Slot::new(a)).collect(),
         }
     }
 
     /// Insert a batch of rows into the hash table.
-    pub fn insert(&mut self, group_by: Vec<AnyArray>, aggregate: Vec<AnyArray>) {
-        let len = group_by.first().or(aggregate.first()).unwrap().len();
+    pub fn insert(
+        &mut self,
+        group_by: Vec<AnyArray>,
+        aggregate: Vec<Vec<AnyArray>>,
+    ) -> Result<(), Error> {
+        let len = match group_by.first() {
+            Some(column) => column.len(),
+            None => aggregate.first().unwrap()[0].len(),
+        };
         // Add batch to the universe of tuples that we know about.
         let hash = if group_by.is_empty() {
             I64Array::zeros(len)
@@ -107,13 +189,14 @@ impl GroupByAggregate {
                 .entry(key)
                 .or_insert_with(|| aggregate_slot_template.clone());
             for i in 0..accs.len() {
-                accs[i].update(&aggregate[i], tuple);
+                accs[i].update(&aggregate[i], tuple)?;
             }
         }
+        Ok(())
     }
 
     /// Return the results we've accumulated so far.
-    pub fn finish(&self) -> Vec<AnyArray> {
+    pub fn finish(&self) -> Result<Vec<AnyArray>, Error> {
         let num_rows = self.aggregate_slots.len();
         let mut group_by_builders: Vec<AnyArray> = self.group_by_batches[0]
             .group_by
@@ -131,13 +214,13 @@ impl GroupByAggregate {
                 push(&mut group_by_builders[i], &group_by[i], key.tuple as usize);
             }
             for i in 0..aggregate.len() {
-                aggregate[i].append(&mut aggregate_builders[i])
+                aggregate[i].append(&mut aggregate_builders[i])?
             }
         }
         let mut columns = vec![];
         columns.extend(group_by_builders);
         columns.extend(aggregate_builders);
-        columns
+        Ok(columns)
     }
 
     /// Are the group-by columns in row1 equal to the group-by columns in row2?
@@ -178,6 +261,11 @@ impl GroupByAggregate {
                         return false;
                     }
                 }
+                (AnyArray::Bytes(column1), AnyArray::Bytes(column2)) => {
+                    if column1.get_bytes(tuple1) != column2.get_bytes(tuple2) {
+                        return false;
+                    }
+                }
                 (column1, column2) => panic!(
                     "expected {:?} but found {:?}",
                     column1.data_type(),
@@ -236,32 +324,217 @@ impl PartialEq for Key {
 
 impl Eq for Key {}
 
+impl Slot {
+    fn new(a: &AggregateExpr) -> Self {
+        let mut slot = if a.order_by.is_empty() && a.limit.is_none() {
+            Slot::Plain(Acc::new(a))
+        } else {
+            // Values are buffered and fed to the accumulator in order, so DISTINCT is applied while feeding.
+            let mut template = a.clone();
+            template.distinct = false;
+            Slot::Ordered {
+                order_by: (0..a.order_by.len())
+                    .map(|i| i + 1 + a.having.iter().count())
+                    .collect(),
+                descending: a.order_by.iter().map(|o| o.descending).collect(),
+                limit: a.limit,
+                distinct: a.distinct,
+                keys: a
+                    .order_by
+                    .iter()
+                    .map(|o| AnyArray::new(o.column.data_type.clone()))
+                    .collect(),
+                values: AnyArray::new(a.input.data_type.clone()),
+                template: Acc::new(&template),
+            }
+        };
+        if let Some(having) = &a.having {
+            let max = matches!(having, HavingModifier::Max(_));
+            slot = Slot::Having {
+                max,
+                column: 1,
+                best: Value::null(having.column().data_type.clone()),
+                inner: Box::new(slot.clone()),
+                template: Box::new(slot),
+            }
+        }
+        if a.ignore_nulls {
+            slot = Slot::IgnoreNulls(Box::new(slot))
+        }
+        slot
+    }
+
+    fn update(&mut self, columns: &Vec<AnyArray>, tuple: u32) -> Result<(), Error> {
+        match self {
+            Slot::Plain(acc) => acc.update(&columns[0], tuple)?,
+            Slot::IgnoreNulls(inner) => {
+                if !is_null(&columns[0], tuple as usize) {
+                    inner.update(columns, tuple)?
+                }
+            }
+            Slot::Having {
+                max,
+                column,
+                best,
+                inner,
+                template,
+            } => {
+                let ordering = match compare(&columns[*column], tuple as usize, best) {
+                    Some(ordering) if *max => ordering,
+                    Some(ordering) => ordering.reverse(),
+                    // Rows where the having column is NULL are ignored.
+                    None => return Ok(()),
+                };
+                match ordering {
+                    Ordering::Greater => {
+                        // A new best value discards everything accumulated so far.
+                        *best = Value::from(
+                            &columns[*column].slice(tuple as usize..tuple as usize + 1),
+                        );
+                        *inner = template.clone();
+                        inner.update(columns, tuple)?
+                    }
+                    Ordering::Equal => inner.update(columns, tuple)?,
+                    Ordering::Less => {}
+                }
+            }
+            Slot::Ordered {
+                order_by,
+                keys,
+                values,
+                ..
+            } => {
+                for i in 0..order_by.len() {
+                    push(&mut keys[i], &columns[order_by[i]], tuple as usize);
+                }
+                push(values, &columns[0], tuple as usize);
+            }
+        }
+        Ok(())
+    }
+
+    fn append(&self, builder: &mut AnyArray) -> Result<(), Error> {
+        match self {
+            Slot::Plain(acc) => acc.append(builder),
+            Slot::IgnoreNulls(inner) | Slot::Having { inner, .. } => inner.append(builder),
+            Slot::Ordered {
+                descending,
+                limit,
+                distinct,
+                keys,
+                values,
+                template,
+                ..
+            } => {
+                let sorted = if keys.is_empty() {
+                    values.clone()
+                } else {
+                    let keys = keys
+                        .iter()
+                        .enumerate()
+                        .map(|(i, key)| (i.to_string(), key.clone()))
+                        .collect();
+                    values.gather(&RecordBatch::new(keys).sort(descending.clone()))
+                };
+                // NULLs are skipped before LIMIT is applied, unless the aggregate collects them.
+                let mut acc = template.clone();
+                let mut count = 0;
+                let mut previous: Option<usize> = None;
+                for i in 0..sorted.len() {
+                    if Some(count) == *limit {
+                        break;
+                    }
+                    if is_null(&sorted, i) && template.ignores_nulls() {
+                        continue;
+                    }
+                    // DISTINCT requires the ORDER BY to match the input, so duplicates are adjacent.
+                    if *distinct && previous.map(|j| sorted.cmp(j, i)) == Some(Ordering::Equal) {
+                        continue;
+                    }
+                    acc.update(&sorted, i as u32)?;
+                    count += 1;
+                    previous = Some(i);
+                }
+                acc.append(builder)
+            }
+        }
+    }
+
+    fn finish(&self) -> Result<AnyArray, Error> {
+        let mut array = AnyArray::with_capacity(self.data_type(), 1);
+        self.append(&mut array)?;
+        Ok(array)
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Slot::Plain(acc) | Slot::Ordered { template: acc, .. } => acc.data_type(),
+            Slot::IgnoreNulls(inner) | Slot::Having { inner, .. } => inner.data_type(),
+        }
+    }
+}
+
 impl Acc {
     fn new(a: &AggregateExpr) -> Self {
         match &a.function {
-            AggregateFunction::AnyValue => Self::AnyValue(Value::null(a.input.data_type)),
+            AggregateFunction::AnyValue => Self::AnyValue(Value::null(a.input.data_type.clone())),
             AggregateFunction::Count => {
                 if a.distinct {
-                    Self::CountDistinct(Distinct::new(a.input.data_type))
+                    Self::CountDistinct(Distinct::new(a.input.data_type.clone()))
                 } else {
                     Self::Count(0)
                 }
             }
             AggregateFunction::LogicalAnd => Self::LogicalAnd(None),
             AggregateFunction::LogicalOr => Self::LogicalOr(None),
-            AggregateFunction::Max => Self::Max(Value::null(a.input.data_type)),
-            AggregateFunction::Min => Self::Min(Value::null(a.input.data_type)),
+            AggregateFunction::Max => Self::Max(Value::null(a.input.data_type.clone())),
+            AggregateFunction::Min => Self::Min(Value::null(a.input.data_type.clone())),
             AggregateFunction::Sum => {
                 if a.distinct {
-                    Self::SumDistinct(Distinct::new(a.input.data_type))
+                    Self::SumDistinct(Distinct::new(a.input.data_type.clone()))
                 } else {
-                    Self::Sum(Value::null(a.input.data_type))
+                    Self::Sum(Value::null(a.input.data_type.clone()))
+                }
+            }
+            AggregateFunction::StringAgg => {
+                if a.distinct {
+                    Self::StringAggDistinct(Distinct::new(a.input.data_type.clone()))
+                } else {
+                    Self::StringAgg(None)
+                }
+            }
+            AggregateFunction::ApproxCountDistinct => Self::ApproxCountDistinct(Sketch::new()),
+            AggregateFunction::HllCountInit => Self::HllCountInit(Sketch::new()),
+            AggregateFunction::HllCountMerge => Self::HllCountMerge(Sketch::new()),
+            AggregateFunction::VarSamp
+            | AggregateFunction::VarPop
+            | AggregateFunction::StddevSamp
+            | AggregateFunction::StddevPop => Self::Variance(a.function, Moments::default()),
+            AggregateFunction::MomentsInit => Self::MomentsInit(Moments::default()),
+            AggregateFunction::ArrayAgg => Self::ArrayAgg(AnyArray::new(a.input.data_type.clone())),
+            AggregateFunction::ArrayConcatAgg => match &a.input.data_type {
+                DataType::Array(element_type) => {
+                    Self::ArrayConcatAgg(element_type.as_ref().clone(), None)
                 }
+                other => panic!("expected array but found {:?}", other),
+            },
+            AggregateFunction::ApproxQuantiles(number) => {
+                // The final phase receives the arrays of values collected by the partial phase.
+                let element_type = match &a.input.data_type {
+                    DataType::Array(element_type) => element_type.as_ref().clone(),
+                    other => other.clone(),
+                };
+                Self::ApproxQuantiles(*number, AnyArray::new(element_type))
             }
         }
     }
 
-    fn update(&mut self, column: &AnyArray, tuple: u32) {
+    /// Does the aggregate skip NULL inputs? ArrayAgg is the only one that collects them.
+    fn ignores_nulls(&self) -> bool {
+        !matches!(self, Acc::ArrayAgg(_))
+    }
+
+    fn update(&mut self, column: &AnyArray, tuple: u32) -> Result<(), Error> {
         match (self, column) {
             (Acc::AnyValue(Value::Bool(value)), AnyArray::Bool(column)) => {
                 *value = column.get(tuple as usize)
@@ -281,6 +554,12 @@ impl Acc {
             (Acc::AnyValue(Value::String(value)), AnyArray::String(column)) => {
                 *value = column.get(tuple as usize).map(|s| s.to_string())
             }
+            (Acc::AnyValue(Value::Json(value)), AnyArray::Json(column)) => {
+                *value = column.get(tuple as usize)
+            }
+            (Acc::AnyValue(Value::Bytes(value)), AnyArray::Bytes(column)) => {
+                *value = column.get(tuple as usize)
+            }
             (Acc::Count(value), AnyArray::Bool(column)) => {
                 if column.get(tuple as usize).is_some() {
                     *value += 1
@@ -311,6 +590,16 @@ impl Acc {
                     *value += 1
                 }
             }
+            (Acc::Count(value), AnyArray::Json(column)) => {
+                if column.get_str(tuple as usize).is_some() {
+                    *value += 1
+                }
+            }
+            (Acc::Count(value), AnyArray::Bytes(column)) => {
+                if column.get_bytes(tuple as usize).is_some() {
+                    *value += 1
+                }
+            }
             (Acc::CountDistinct(Distinct::Bool(hash_set)), AnyArray::Bool(column)) => {
                 for i in 0..column.len() {
                     if let Some(next) = column.get(i) {
@@ -353,6 +642,13 @@ impl Acc {
                     }
                 }
             }
+            (Acc::CountDistinct(Distinct::Bytes(hash_set)), AnyArray::Bytes(column)) => {
+                for i in 0..column.len() {
+                    if let Some(next) = column.get(i) {
+                        hash_set.insert(next);
+                    }
+                }
+            }
             (Acc::LogicalAnd(value), AnyArray::Bool(column)) => {
                 let prev = value.unwrap_or(true);
                 if let Some(next) = column.get(tuple as usize) {
@@ -404,6 +700,15 @@ impl Acc {
                     }
                 }
             }
+            (Acc::Max(Value::Bytes(value)), AnyArray::Bytes(column)) => {
+                if let Some(next) = column.get_bytes(tuple as usize) {
+                    if let Some(prev) = value {
+                        *value = Some(next.max(prev).to_vec())
+                    } else {
+                        *value = Some(next.to_vec())
+                    }
+                }
+            }
             (Acc::Min(Value::Bool(value)), AnyArray::Bool(column)) => {
                 if let Some(next) = column.get(tuple as usize) {
                     let prev = value.unwrap_or(true);
@@ -443,6 +748,15 @@ impl Acc {
                     }
                 }
             }
+            (Acc::Min(Value::Bytes(value)), AnyArray::Bytes(column)) => {
+                if let Some(next) = column.get_bytes(tuple as usize) {
+                    if let Some(prev) = value {
+                        *value = Some(next.min(prev).to_vec())
+                    } else {
+                        *value = Some(next.to_vec())
+                    }
+                }
+            }
             (Acc::Sum(Value::I64(value)), AnyArray::I64(column)) => {
                 if let Some(next) = column.get(tuple as usize) {
                     *value = Some(value.unwrap_or(0) + next)
@@ -467,11 +781,63 @@ impl Acc {
                     }
                 }
             }
+            (Acc::StringAgg(value), AnyArray::String(column)) => {
+                if let Some(next) = column.get_str(tuple as usize) {
+                    value.get_or_insert_with(String::new).push_str(next)
+                }
+            }
+            (Acc::StringAggDistinct(Distinct::String(hash_set)), AnyArray::String(column)) => {
+                if let Some(next) = column.get_str(tuple as usize) {
+                    hash_set.insert(next.to_string());
+                }
+            }
+            (Acc::ApproxCountDistinct(sketch), column) | (Acc::HllCountInit(sketch), column) => {
+                sketch.insert(column, tuple as usize)
+            }
+            (Acc::HllCountMerge(sketch), AnyArray::Bytes(column)) => {
+                if let Some(next) = column.get_bytes(tuple as usize) {
+                    sketch.merge(&Sketch::decode(next)?)?
+                }
+            }
+            (Acc::Variance(_, moments), AnyArray::F64(column))
+            | (Acc::MomentsInit(moments), AnyArray::F64(column)) => {
+                if let Some(next) = column.get(tuple as usize) {
+                    moments.insert(next)
+                }
+            }
+            (Acc::Variance(_, moments), AnyArray::String(column)) => {
+                if let Some(next) = column.get_str(tuple as usize) {
+                    moments.merge(&Moments::decode(next)?)
+                }
+            }
+            (Acc::ArrayAgg(values), column) => push(values, column, tuple as usize),
+            (Acc::ArrayConcatAgg(element_type, values), AnyArray::Array(column)) => {
+                if let Some(next) = column.get(tuple as usize) {
+                    values
+                        .get_or_insert_with(|| AnyArray::new(element_type.clone()))
+                        .extend(&next)
+                }
+            }
+            (Acc::ApproxQuantiles(_, values), AnyArray::Array(column)) => {
+                if let Some(next) = column.get(tuple as usize) {
+                    for i in 0..next.len() {
+                        if !is_null(&next, i) {
+                            push(values, &next, i)
+                        }
+                    }
+                }
+            }
+            (Acc::ApproxQuantiles(_, values), column) => {
+                if !is_null(column, tuple as usize) {
+                    push(values, column, tuple as usize)
+                }
+            }
             (_, _) => panic!("unmatched aggregate / column"),
         }
+        Ok(())
     }
 
-    fn append(&self, builder: &mut AnyArray) {
+    fn append(&self, builder: &mut AnyArray) -> Result<(), Error> {
         match self {
             Acc::Count(value) => {
                 if let AnyArray::I64(builder) = builder {
@@ -508,6 +874,10 @@ impl Acc {
                             builder.push(None);
                         }
                     }
+                    (Value::Json(value), AnyArray::Json(builder)) => builder.push(value.clone()),
+                    (Value::Bytes(value), AnyArray::Bytes(builder)) => {
+                        builder.push_bytes(value.as_deref())
+                    }
                     (value, builder) => panic!(
                         "expected {:?} but found {:?}",
                         value.data_type(),
@@ -531,7 +901,7 @@ impl Acc {
                     if hash_set.is_empty() {
                         builder.push(None);
                     } else {
-                    let mut total = 0.0;
+                        let mut total = 0.0;
                         for next in hash_set {
                             total += as_f64(*next);
                         }
@@ -544,13 +914,108 @@ impl Acc {
                     builder.data_type()
                 ),
             },
+            Acc::StringAgg(value) => {
+                if let AnyArray::String(builder) = builder {
+                    builder.push_str(value.as_deref())
+                } else {
+                    panic!("expected string but found {:?}", builder.data_type())
+                }
+            }
+            Acc::StringAggDistinct(distinct) => match (distinct, builder) {
+                (Distinct::String(hash_set), AnyArray::String(builder)) => {
+                    if hash_set.is_empty() {
+                        builder.push(None);
+                    } else {
+                        let mut total = String::new();
+                        for next in hash_set {
+                            total.push_str(next);
+                        }
+                        builder.push_str(Some(&total))
+                    }
+                }
+                (value, builder) => panic!(
+                    "expected {:?} but found {:?}",
+                    value.data_type(),
+                    builder.data_type()
+                ),
+            },
+            Acc::ApproxCountDistinct(sketch) | Acc::HllCountMerge(sketch) => {
+                if let AnyArray::I64(builder) = builder {
+                    builder.push(Some(sketch.count()))
+                } else {
+                    panic!("expected i64 but found {:?}", builder.data_type())
+                }
+            }
+            Acc::HllCountInit(sketch) => {
+                if let AnyArray::Bytes(builder) = builder {
+                    builder.push_bytes(Some(&sketch.encode()?))
+                } else {
+                    panic!("expected bytes but found {:?}", builder.data_type())
+                }
+            }
+            Acc::Variance(function, moments) => {
+                let population = matches!(
+                    function,
+                    AggregateFunction::VarPop | AggregateFunction::StddevPop
+                );
+                let mut value = moments.variance(population);
+                if matches!(
+                    function,
+                    AggregateFunction::StddevSamp | AggregateFunction::StddevPop
+                ) {
+                    value = value.map(f64::sqrt)
+                }
+                if let AnyArray::F64(builder) = builder {
+                    builder.push(value)
+                } else {
+                    panic!("expected f64 but found {:?}", builder.data_type())
+                }
+            }
+            Acc::MomentsInit(moments) => {
+                if let AnyArray::String(builder) = builder {
+                    builder.push_str(Some(&moments.encode()))
+                } else {
+                    panic!("expected string but found {:?}", builder.data_type())
+                }
+            }
+            Acc::ArrayAgg(values) => {
+                // ARRAY_AGG of no rows is NULL, not an empty array.
+                if let AnyArray::Array(builder) = builder {
+                    if values.len() == 0 {
+                        builder.push(None)
+                    } else {
+                        builder.push(Some(values))
+                    }
+                } else {
+                    panic!("expected array but found {:?}", builder.data_type())
+                }
+            }
+            Acc::ArrayConcatAgg(_, values) => {
+                if let AnyArray::Array(builder) = builder {
+                    builder.push(values.as_ref())
+                } else {
+                    panic!("expected array but found {:?}", builder.data_type())
+                }
+            }
+            Acc::ApproxQuantiles(number, values) => {
+                if let AnyArray::Array(builder) = builder {
+                    if values.len() == 0 {
+                        builder.push(None)
+                    } else {
+                        // The minimum, the maximum, and number - 1 values evenly spaced between them.
+                        let sorted = values.gather(&values.sort());
+                        let last = sorted.len() - 1;
+                        let quantiles = I32Array::from_values(
+                            (0..=*number).map(|i| (i * last / number) as i32).collect(),
+                        );
+                        builder.push(Some(&sorted.gather(&quantiles)))
+                    }
+                } else {
+                    panic!("expected array but found {:?}", builder.data_type())
+                }
+            }
         }
-    }
-
-    fn finish(&self) -> AnyArray {
-        let mut array = AnyArray::with_capacity(self.data_type(), 1);
-        self.append(&mut array);
-        array
+        Ok(())
     }
 
     fn data_type(&self) -> DataType {
@@ -561,6 +1026,14 @@ impl Acc {
                 value.data_type()
             }
             Acc::SumDistinct(value) => value.data_type(),
+            Acc::StringAgg(_) | Acc::StringAggDistinct(_) | Acc::MomentsInit(_) => DataType::String,
+            Acc::HllCountInit(_) => DataType::Bytes,
+            Acc::ApproxCountDistinct(_) | Acc::HllCountMerge(_) => DataType::I64,
+            Acc::Variance(_, _) => DataType::F64,
+            Acc::ArrayAgg(values) | Acc::ApproxQuantiles(_, values) => {
+                DataType::Array(Box::new(values.data_type()))
+            }
+            Acc::ArrayConcatAgg(element_type, _) => DataType::Array(Box::new(element_type.clone())),
         }
     }
 }
@@ -574,6 +1047,11 @@ impl Distinct {
             DataType::Date => Distinct::Date(HashSet::default()),
             DataType::Timestamp => Distinct::Timestamp(HashSet::default()),
             DataType::String => Distinct::String(HashSet::default()),
+            DataType::Bytes => Distinct::Bytes(HashSet::default()),
+            DataType::Json => panic!("JSON values are not comparable, so they can't be DISTINCT"),
+            DataType::Array(_) => {
+                panic!("ARRAY values are not groupable, so they can't be DISTINCT")
+            }
         }
     }
 
@@ -585,6 +1063,7 @@ impl Distinct {
             Distinct::Date(_) => DataType::Date,
             Distinct::Timestamp(_) => DataType::Timestamp,
             Distinct::String(_) => DataType::String,
+            Distinct::Bytes(_) => DataType::Bytes,
         }
     }
 
@@ -596,7 +1075,184 @@ impl Distinct {
             Distinct::Date(hash_set) => hash_set.len() as i64,
             Distinct::Timestamp(hash_set) => hash_set.len() as i64,
             Distinct::String(hash_set) => hash_set.len() as i64,
+            Distinct::Bytes(hash_set) => hash_set.len() as i64,
+        }
+    }
+}
+
+impl Sketch {
+    fn new() -> Self {
+        Self {
+            hll: HyperLogLogPlus::new(SKETCH_PRECISION, BuildTwoXHasher).unwrap(),
+        }
+    }
+
+    fn insert(&mut self, column: &AnyArray, tuple: usize) {
+        let next = match column {
+            AnyArray::Bool(column) => column.get(tuple).map(|value| value as u64),
+            AnyArray::I64(column) => column.get(tuple).map(|value| value as u64),
+            AnyArray::F64(column) => column.get(tuple).map(as_u64),
+            AnyArray::Date(column) => column.get(tuple).map(|value| value as u64),
+            AnyArray::Timestamp(column) => column.get(tuple).map(|value| value as u64),
+            AnyArray::String(column) => column.get_str(tuple).map(|value| {
+                let mut hasher = BuildTwoXHasher.build_hasher();
+                hasher.write(value.as_bytes());
+                hasher.finish()
+            }),
+            AnyArray::Json(column) => column.get_str(tuple).map(|value| {
+                let mut hasher = BuildTwoXHasher.build_hasher();
+                hasher.write(value.as_bytes());
+                hasher.finish()
+            }),
+            AnyArray::Bytes(column) => column.get_bytes(tuple).map(|value| {
+                let mut hasher = BuildTwoXHasher.build_hasher();
+                hasher.write(value);
+                hasher.finish()
+            }),
+            AnyArray::Array(_) => {
+                panic!("ARRAY values are not groupable, so they can't be counted")
+            }
+        };
+        if let Some(next) = next {
+            self.hll.add(&next)
+        }
+    }
+
+    fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        self.hll
+            .merge(&other.hll)
+            .map_err(|e| Error::Internal(format!("Cannot merge HyperLogLog sketches: {}", e)))
+    }
+
+    fn count(&self) -> i64 {
+        self.hll.clone().count().round() as i64
+    }
+
+    /// Serialize the sketch, so it can be passed between stages as an ordinary bytes column.
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        bincode::serialize(&self.hll)
+            .map_err(|e| Error::Internal(format!("Cannot encode HyperLogLog sketch: {}", e)))
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let hll = bincode::deserialize(bytes)
+            .map_err(|e| Error::Internal(format!("Invalid HyperLogLog sketch: {}", e)))?;
+        Ok(Self { hll })
+    }
+}
+
+impl Moments {
+    fn insert(&mut self, next: f64) {
+        self.count += 1;
+        let delta = next - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (next - self.mean);
+    }
+
+    /// Combine the moments of two sets of values, using the parallel form of Welford's algorithm.
+    fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        self.m2 +=
+            other.m2 + delta * delta * (self.count as f64) * (other.count as f64) / (count as f64);
+        self.mean += delta * (other.count as f64) / (count as f64);
+        self.count = count;
+    }
+
+    /// The population variance is undefined for 0 values, the sample variance for 0 or 1 values.
+    fn variance(&self, population: bool) -> Option<f64> {
+        let denominator = if population {
+            self.count
+        } else {
+            self.count - 1
+        };
+        if denominator <= 0 {
+            None
+        } else {
+            Some(self.m2 / denominator as f64)
+        }
+    }
+
+    /// Serialize the moments as a string, so they can be passed between stages as an ordinary string column.
+    /// The floats are written as their bits, so they don't lose precision.
+    fn encode(&self) -> String {
+        format!(
+            "{}:{:x}:{:x}",
+            self.count,
+            self.mean.to_bits(),
+            self.m2.to_bits()
+        )
+    }
+
+    fn decode(encoded: &str) -> Result<Self, Error> {
+        let invalid = || Error::Internal(format!("Invalid variance moments {:?}", encoded));
+        let parts: Vec<&str> = encoded.split(':').collect();
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        let float = |part: &str| u64::from_str_radix(part, 16).map(f64::from_bits);
+        Ok(Self {
+            count: parts[0].parse().map_err(|_| invalid())?,
+            mean: float(parts[1]).map_err(|_| invalid())?,
+            m2: float(parts[2]).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl BuildHasher for BuildTwoXHasher {
+    type Hasher = twox_hash::Xxh3Hash64;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        twox_hash::Xxh3Hash64::default()
+    }
+}
+
+fn is_null(column: &AnyArray, tuple: usize) -> bool {
+    match column {
+        AnyArray::Bool(column) => column.get(tuple).is_none(),
+        AnyArray::I64(column) => column.get(tuple).is_none(),
+        AnyArray::F64(column) => column.get(tuple).is_none(),
+        AnyArray::Date(column) => column.get(tuple).is_none(),
+        AnyArray::Timestamp(column) => column.get(tuple).is_none(),
+        AnyArray::String(column) => column.get_str(tuple).is_none(),
+        AnyArray::Json(column) => column.get_str(tuple).is_none(),
+        AnyArray::Bytes(column) => column.get_bytes(tuple).is_none(),
+        AnyArray::Array(column) => column.get(tuple).is_none(),
+    }
+}
+
+/// Compare column[tuple] to value, returning None if column[tuple] is NULL.
+/// Every non-NULL value is greater than NULL.
+fn compare(column: &AnyArray, tuple: usize, value: &Value) -> Option<Ordering> {
+    fn compare_options<T: PartialOrd>(next: Option<T>, value: Option<T>) -> Option<Ordering> {
+        match (next, value) {
+            (None, _) => None,
+            (Some(_), None) => Some(Ordering::Greater),
+            (Some(next), Some(value)) => next.partial_cmp(&value),
+        }
+    }
+    match (column, value) {
+        (AnyArray::Bool(column), Value::Bool(value)) => compare_options(column.get(tuple), *value),
+        (AnyArray::I64(column), Value::I64(value)) => compare_options(column.get(tuple), *value),
+        (AnyArray::F64(column), Value::F64(value)) => compare_options(column.get(tuple), *value),
+        (AnyArray::Date(column), Value::Date(value)) => compare_options(column.get(tuple), *value),
+        (AnyArray::Timestamp(column), Value::Timestamp(value)) => {
+            compare_options(column.get(tuple), *value)
+        }
+        (AnyArray::String(column), Value::String(value)) => {
+            compare_options(column.get_str(tuple), value.as_deref())
+        }
+        (AnyArray::Bytes(column), Value::Bytes(value)) => {
+            compare_options(column.get_bytes(tuple), value.as_deref())
         }
+        (column, value) => panic!(
+            "expected {:?} but found {:?}",
+            value.data_type(),
+            column.data_type()
+        ),
     }
 }
 
@@ -616,6 +1272,9 @@ fn push(into: &mut AnyArray, from: &AnyArray, i: usize) {
         (AnyArray::Date(into), AnyArray::Date(from)) => into.push(from.get(i)),
         (AnyArray::Timestamp(into), AnyArray::Timestamp(from)) => into.push(from.get(i)),
         (AnyArray::String(into), AnyArray::String(from)) => into.push(from.get(i)),
+        (AnyArray::Json(into), AnyArray::Json(from)) => into.push(from.get(i)),
+        (AnyArray::Bytes(into), AnyArray::Bytes(from)) => into.push_bytes(from.get_bytes(i)),
+        (AnyArray::Array(into), AnyArray::Array(from)) => into.push(from.get(i).as_ref()),
         (into, from) => panic!("{} does not match {}", into.data_type(), from.data_type()),
     }
 }
@@ -1,19 +1,27 @@
+use std::convert::TryFrom;
 use std::sync::Mutex;
 
 use ast::*;
+use chrono::format::{Item, Parsed, StrftimeItems};
 use chrono::*;
+use globals::Global;
 use kernel::*;
+use md5::Md5;
+use rand::Rng;
 use regex::{Captures, Regex};
+use sha2::{Digest, Sha256};
 use storage::Storage;
 
+use crate::json::*;
 use crate::map::ArrayExt;
+use crate::time_zone::{Zone, DEFAULT_ZONE};
 
 pub(crate) fn all(
     predicates: &Vec<Scalar>,
     input: &RecordBatch,
     storage: &Mutex<Storage>,
     txn: i64,
-) -> Result<BoolArray, String> {
+) -> Result<BoolArray, Error> {
     let mut mask = BoolArray::trues(input.len());
     for p in predicates {
         mask = eval(p, &input, storage, txn)?.as_bool().and(&mask);
@@ -26,7 +34,7 @@ pub(crate) fn eval(
     input: &RecordBatch,
     storage: &Mutex<Storage>,
     txn: i64,
-) -> Result<AnyArray, String> {
+) -> Result<AnyArray, Error> {
     let a = match scalar {
         Scalar::Literal(value) => value.repeat(input.len()),
         Scalar::Parameter(name, _) => panic!("@{} should have been removed before execution", name),
@@ -35,19 +43,67 @@ pub(crate) fn eval(
             input.find_always(&find).clone()
         }
         Scalar::Call(function) => eval_function(function.as_ref(), input, storage, txn)?,
-        Scalar::Cast(scalar, data_type) => eval(scalar, input, storage, txn)?.cast(*data_type),
+        Scalar::Cast(scalar, data_type) => {
+            eval(scalar, input, storage, txn)?.cast(data_type.clone())?
+        }
+        Scalar::SafeCall(function) => eval_safe(function.as_ref(), input, storage, txn)?,
+        Scalar::SafeCast(scalar, data_type) => {
+            eval(scalar, input, storage, txn)?.safe_cast(data_type.clone())
+        }
     };
     Ok(a)
 }
 
+/// SAFE.function(...) returns NULL in the rows where the function fails. Errors in the arguments are
+/// not caught by SAFE, and the arguments are evaluated once, so RAND or NEXTVAL in the arguments
+/// aren't run again for the rows that are retried. Errors are rare, so we only apply the function to
+/// one row at a time after the whole batch has failed, except for NEXTVAL, which may fail after
+/// using up values.
+fn eval_safe(
+    function: &F,
+    input: &RecordBatch,
+    storage: &Mutex<Storage>,
+    txn: i64,
+) -> Result<AnyArray, Error> {
+    // Replace each argument with a column that holds its value.
+    let mut function = function.clone();
+    let mut columns = vec![];
+    for i in 0..function.len() {
+        let column = Column::fresh(&format!("$argument{}", i), function[i].data_type());
+        columns.push((
+            column.canonical_name(),
+            eval(&function[i], input, storage, txn)?,
+        ));
+        function[i] = Scalar::Column(column);
+    }
+    let input = if columns.is_empty() {
+        input.clone()
+    } else {
+        RecordBatch::new(columns)
+    };
+    if !matches!(function, F::NextVal(_)) {
+        if let Ok(output) = eval_function(&function, &input, storage, txn) {
+            return Ok(output);
+        }
+    }
+    let mut output = AnyArray::with_capacity(function.returns(), input.len());
+    for i in 0..input.len() {
+        match eval_function(&function, &input.slice(i..i + 1), storage, txn) {
+            Ok(row) => output.extend(&row),
+            Err(_) => output.extend(&AnyArray::nulls(function.returns(), 1)),
+        }
+    }
+    Ok(output)
+}
+
 fn eval_function(
     function: &F,
     input: &RecordBatch,
     storage: &Mutex<Storage>,
     txn: i64,
-) -> Result<AnyArray, String> {
+) -> Result<AnyArray, Error> {
     let e = |scalar| eval(scalar, input, storage, txn);
-    let es = |scalars: &Vec<Scalar>| -> Result<Vec<AnyArray>, String> {
+    let es = |scalars: &Vec<Scalar>| -> Result<Vec<AnyArray>, Error> {
         let mut arrays = vec![];
         for scalar in scalars {
             arrays.push(eval(scalar, input, storage, txn)?)
@@ -78,6 +134,27 @@ fn eval_function(
                 .repeat(input.len())
                 .as_any(),
         ),
+        F::NextSequenceID => Ok(I64Array::from_values(vec![storage
+            .lock()
+            .unwrap()
+            .next_sequence_id()])
+        .repeat(input.len())
+        .as_any()),
+        F::GenerateUuid => {
+            let mut output = StringArray::with_capacity(input.len());
+            for _ in 0..input.len() {
+                output.push(Some(generate_uuid()));
+            }
+            Ok(output.as_any())
+        }
+        F::Rand => {
+            let mut rng = rand::thread_rng();
+            let mut output = F64Array::with_capacity(input.len());
+            for _ in 0..input.len() {
+                output.push(Some(rng.gen()));
+            }
+            Ok(output.as_any())
+        }
         F::Coalesce(varargs) => {
             let mut tail = e(varargs.last().unwrap())?;
             for head in &varargs[..varargs.len() - 1] {
@@ -93,12 +170,32 @@ fn eval_function(
             Ok(StringArray::concat(strings).as_any())
         }
         F::Hash(varargs) => Ok(AnyArray::I64(I64Array::hash_all(&es(varargs)?))),
+        F::FormatString(varargs) => {
+            let format = e(&varargs[0])?.as_string();
+            let mut args = vec![];
+            for arg in &varargs[1..] {
+                args.push(e(arg)?);
+            }
+            let mut output = StringArray::with_capacity(input.len());
+            for row in 0..input.len() {
+                match format.get_str(row) {
+                    Some(format) => output.push(format_string(format, &args, row)?),
+                    None => output.push(None),
+                }
+            }
+            Ok(output.as_any())
+        }
         F::Greatest(varargs) => Ok(AnyArray::greatest(es(varargs)?)),
         F::Least(varargs) => Ok(AnyArray::least(es(varargs)?)),
         F::AbsDouble(a) => e(a)?.as_f64().map(f64::abs),
-        F::AbsInt64(a) => e(a)?.as_i64().map(i64::abs),
+        F::AbsInt64(a) => e(a)?.as_i64().map(|a: i64| {
+            a.checked_abs()
+                .map(Some)
+                .ok_or_else(|| Error::DataException(format!("int64 overflow: ABS({})", a)))
+        }),
         F::AcosDouble(a) => e(a)?.as_f64().map(f64::acos),
         F::AcoshDouble(a) => e(a)?.as_f64().map(f64::acosh),
+        F::ArrayLength(a) => Ok(array_length(&e(a)?.as_array())),
         F::AsinDouble(a) => e(a)?.as_f64().map(f64::asin),
         F::AsinhDouble(a) => e(a)?.as_f64().map(f64::asinh),
         F::AtanDouble(a) => e(a)?.as_f64().map(f64::atan),
@@ -109,48 +206,114 @@ fn eval_function(
         F::ChrString(a) => e(a)?.as_i64().map(chr),
         F::CosDouble(a) => e(a)?.as_f64().map(f64::cos),
         F::CoshDouble(a) => e(a)?.as_f64().map(f64::cosh),
-        F::DateFromTimestamp(a) => e(a)?.as_timestamp().map(date_from_timestamp),
+        F::DateFromTimestamp(a, None) | F::ExtractDateFromTimestamp(a, None) => e(a)?
+            .as_timestamp()
+            .map(|a| date_from_timestamp(a, DEFAULT_ZONE)),
+        F::DateFromTimestamp(a, Some(b)) | F::ExtractDateFromTimestamp(a, Some(b)) => e(a)?
+            .as_timestamp()
+            .bi_map(&e(b)?.as_string(), |a, b: &str| {
+                Zone::parse(b).map(|zone| Some(date_from_timestamp(a, zone)))
+            }),
+        F::FarmFingerprintString(a) => e(a)?
+            .as_string()
+            .map(|a: &str| farmhash::fingerprint64(a.as_bytes()) as i64),
         F::DateFromUnixDate(a) => e(a)?.as_i64().map(date_from_unix_date),
         F::DecimalLogarithmDouble(a) => e(a)?.as_f64().map(f64::log10),
         F::Error(a) => {
             let message = e(a)?.as_string().get(0).clone().unwrap_or("".to_string());
-            return Err(message);
+            return Err(Error::Raise(message));
+        }
+        F::NextVal(a) => {
+            let ids = e(a)?.as_i64();
+            let worker = globals::WORKER.get() as i64;
+            let workers: i64 = std::env::var("WORKER_COUNT").unwrap().parse().unwrap();
+            let mut storage = storage.lock().unwrap();
+            let mut values = Vec::with_capacity(ids.len());
+            for i in 0..ids.len() {
+                let id = ids.get(i).unwrap();
+                match storage.next_value(id, worker, workers, txn) {
+                    Some(value) => values.push(value),
+                    None => {
+                        return Err(Error::DataException(format!(
+                            "Sequence {} has run out of values",
+                            id
+                        )))
+                    }
+                }
+            }
+            Ok(I64Array::from_values(values).as_any())
         }
         F::ExpDouble(a) => e(a)?.as_f64().map(f64::exp),
-        F::ExtractDateFromTimestamp(a) => e(a)?.as_timestamp().map(date_from_timestamp),
         F::FloorDouble(a) => e(a)?.as_f64().map(f64::floor),
+        F::FromBase64(a) => e(a)?.as_string().map(from_base64),
+        F::FromHex(a) => e(a)?.as_string().map(from_hex),
         F::IsFalse(a) => e(a)?
             .as_bool()
-            .map(|a: Option<bool>| Ok(Some(a == Some(false)))),
+            .map(|a: Option<bool>| Some(a == Some(false))),
         F::IsInf(a) => e(a)?.as_f64().map(f64::is_infinite),
         F::IsNan(a) => e(a)?.as_f64().map(f64::is_nan),
         F::IsNull(a) => Ok(e(a)?.is_null().as_any()),
-        F::IsTrue(a) => e(a)?
-            .as_bool()
-            .map(|a: Option<bool>| Ok(Some(a == Some(true)))),
+        F::IsTrue(a) => e(a)?.as_bool().map(|a: Option<bool>| Some(a == Some(true))),
         F::LengthString(a) => e(a)?.as_string().map(|a: &str| a.chars().count() as i64),
         F::LowerString(a) => e(a)?.as_string().map(|a: &str| a.to_lowercase()),
+        F::Md5Bytes(a) => e(a)?.as_bytes().map(|a: &[u8]| Md5::digest(a).to_vec()),
+        F::Md5String(a) => e(a)?
+            .as_string()
+            .map(|a: &str| Md5::digest(a.as_bytes()).to_vec()),
         F::NaturalLogarithmDouble(a) => e(a)?.as_f64().map(f64::ln),
         F::Not(a) => e(a)?.as_bool().map(|a: bool| !a),
+        F::ParseJson(a) => e(a)?.cast(DataType::Json),
         F::ReverseString(a) => e(a)?
             .as_string()
-            .map(|a: Option<&str>| Ok(a.map(|a| a.chars().rev().collect::<String>()))),
+            .map(|a: Option<&str>| a.map(|a| a.chars().rev().collect::<String>())),
         F::RoundDouble(a) => e(a)?.as_f64().map(f64::round),
+        F::Sha256Bytes(a) => e(a)?.as_bytes().map(|a: &[u8]| Sha256::digest(a).to_vec()),
+        F::Sha256String(a) => e(a)?
+            .as_string()
+            .map(|a: &str| Sha256::digest(a.as_bytes()).to_vec()),
         F::SignDouble(a) => e(a)?.as_f64().map(f64::signum),
         F::SignInt64(a) => e(a)?.as_i64().map(i64::signum),
         F::SinDouble(a) => e(a)?.as_f64().map(f64::sin),
         F::SinhDouble(a) => e(a)?.as_f64().map(f64::sinh),
+        F::SoundexString(a) => e(a)?.as_string().map(soundex),
         F::SqrtDouble(a) => e(a)?.as_f64().map(f64::sqrt),
         F::StringFromDate(a) => e(a)?.as_date().map(string_from_date),
-        F::StringFromTimestamp(a) => e(a)?.as_timestamp().map(string_from_timestamp),
+        F::StringFromTimestamp(a, None) => e(a)?
+            .as_timestamp()
+            .map(|a| string_from_timestamp(a, DEFAULT_ZONE)),
+        F::StringFromTimestamp(a, Some(b)) => e(a)?
+            .as_timestamp()
+            .bi_map(&e(b)?.as_string(), |a, b: &str| {
+                Zone::parse(b).map(|zone| Some(string_from_timestamp(a, zone)))
+            }),
         F::TanDouble(a) => e(a)?.as_f64().map(f64::tan),
         F::TanhDouble(a) => e(a)?.as_f64().map(f64::tanh),
-        F::TimestampFromDate(a) => e(a)?.as_date().map(timestamp_from_date),
-        F::TimestampFromString(a) => e(a)?.as_string().map(timestamp_from_string),
+        F::TimestampFromDate(a, None) => e(a)?
+            .as_date()
+            .map(|a| timestamp_from_date(a, DEFAULT_ZONE)),
+        F::TimestampFromDate(a, Some(b)) => {
+            e(a)?.as_date().bi_map(&e(b)?.as_string(), |a, b: &str| {
+                Zone::parse(b).map(|zone| Some(timestamp_from_date(a, zone)))
+            })
+        }
+        F::TimestampFromString(a, None) => e(a)?
+            .as_string()
+            .map(|a: &str| timestamp_from_string(a, DEFAULT_ZONE)),
+        F::TimestampFromString(a, Some(b)) => e(a)?
+            .as_string()
+            .bi_map(&e(b)?.as_string(), |a: &str, b: &str| {
+                timestamp_from_string(a, Zone::parse(b)?)
+            }),
         F::TimestampFromUnixMicrosInt64(a) => e(a)?.as_i64().map(timestamp),
+        F::ToBase64(a) => e(a)?.as_bytes().map(|a: &[u8]| base64::encode(a)),
+        F::ToHex(a) => e(a)?.as_bytes().map(to_hex),
         F::TruncDouble(a) => e(a)?.as_f64().map(f64::trunc),
         F::UnaryMinusDouble(a) => e(a)?.as_f64().map(|a: f64| -a),
-        F::UnaryMinusInt64(a) => e(a)?.as_i64().map(|a: i64| -a),
+        F::UnaryMinusInt64(a) => e(a)?.as_i64().map(|a: i64| {
+            a.checked_neg()
+                .map(Some)
+                .ok_or_else(|| Error::DataException(format!("int64 overflow: -{}", a)))
+        }),
         F::UnixDate(a) => e(a)?.as_date().map(|a| epoch_date(a) as i64),
         F::UnixMicrosFromTimestamp(a) => e(a)?.as_timestamp().map(|a| epoch_micros(a)),
         F::UnixMillisFromTimestamp(a) => e(a)?.as_timestamp().map(|a| epoch_millis(a)),
@@ -159,20 +322,40 @@ fn eval_function(
         F::ExtractFromDate(a, date_part) => {
             e(a)?.as_date().map(|a| extract_from_date(a, *date_part))
         }
-        F::ExtractFromTimestamp(a, date_part) => e(a)?
+        F::ExtractFromTimestamp(a, date_part, None) => e(a)?
             .as_timestamp()
-            .map(|a| extract_from_timestamp(a, *date_part)),
-        F::TimestampTrunc(a, date_part) => {
-            e(a)?.as_timestamp().map(|a| timestamp_trunc(a, *date_part))
-        }
+            .map(|a| extract_from_timestamp(a, *date_part, DEFAULT_ZONE)),
+        F::ExtractFromTimestamp(a, date_part, Some(b)) => e(a)?
+            .as_timestamp()
+            .bi_map(&e(b)?.as_string(), |a, b: &str| {
+                extract_from_timestamp(a, *date_part, Zone::parse(b)?)
+            }),
+        F::TimestampTrunc(a, date_part, None) => e(a)?
+            .as_timestamp()
+            .map(|a| timestamp_trunc(a, *date_part, DEFAULT_ZONE)),
+        F::TimestampTrunc(a, date_part, Some(b)) => e(a)?
+            .as_timestamp()
+            .bi_map(&e(b)?.as_string(), |a, b: &str| {
+                timestamp_trunc(a, *date_part, Zone::parse(b)?)
+            }),
         F::In(a, varargs) => Ok(e(a)?.equal_any(es(varargs)?).as_any()),
-        F::AddDouble(a, b) => e(a)?
-            .as_f64()
-            .bi_map(&e(b)?.as_f64(), |a: f64, b: f64| a + b),
-        F::AddInt64(a, b) => e(a)?
-            .as_i64()
-            .bi_map(&e(b)?.as_i64(), |a: i64, b: i64| a + b),
+        F::AddDouble(a, b) => e(a)?.as_f64().bi_map(&e(b)?.as_f64(), |a: f64, b: f64| {
+            check_f64(a + b, a, "+", b)
+        }),
+        F::AddInt64(a, b) => e(a)?.as_i64().bi_map(&e(b)?.as_i64(), |a: i64, b: i64| {
+            check_i64(a.checked_add(b), a, "+", b)
+        }),
         F::And(a, b) => Ok(e(a)?.as_bool().and(&e(b)?.as_bool()).as_any()),
+        F::ArrayAtOffset(a, b) => array_at(&e(a)?.as_array(), &e(b)?.as_i64(), 0, false),
+        F::ArrayAtOrdinal(a, b) => array_at(&e(a)?.as_array(), &e(b)?.as_i64(), 1, false),
+        F::ArrayToString(a, b, None) => {
+            Ok(array_to_string(&e(a)?.as_array(), &e(b)?.as_string(), None))
+        }
+        F::ArrayToString(a, b, Some(c)) => Ok(array_to_string(
+            &e(a)?.as_array(),
+            &e(b)?.as_string(),
+            Some(&e(c)?.as_string()),
+        )),
         F::Atan2Double(a, b) => e(a)?
             .as_f64()
             .bi_map(&e(b)?.as_f64(), |a: f64, b: f64| a.atan2(b)),
@@ -189,13 +372,57 @@ fn eval_function(
         F::FormatDate(a, b) => e(a)?
             .as_string()
             .bi_map(&e(b)?.as_date(), |a, b| format_date(a, b)),
-        F::FormatTimestamp(a, b) => e(a)?
-            .as_string()
-            .bi_map(&e(b)?.as_timestamp(), |a, b| format_timestamp(a, b)),
+        F::FormatTimestamp(a, b, None) => {
+            e(a)?.as_string().bi_map(&e(b)?.as_timestamp(), |a, b| {
+                format_timestamp(a, b, DEFAULT_ZONE)
+            })
+        }
+        F::FormatTimestamp(a, b, Some(c)) => e(a)?.as_string().tri_map(
+            &e(b)?.as_timestamp(),
+            &e(c)?.as_string(),
+            |a, b, c: &str| format_timestamp(a, b, Zone::parse(c)?),
+        ),
         F::Greater(a, b) => Ok(e(a)?.greater(&e(b)?).as_any()),
         F::GreaterOrEqual(a, b) => Ok(e(a)?.greater_equal(&e(b)?).as_any()),
+        F::IeeeDivideDouble(a, b) => e(a)?
+            .as_f64()
+            .bi_map(&e(b)?.as_f64(), |a: f64, b: f64| a / b),
         F::Ifnull(a, b) => Ok(e(a)?.coalesce(&e(b)?)),
+        F::InitcapString(a, None) => e(a)?
+            .as_string()
+            .map(|a: &str| initcap(a, INITCAP_DELIMITERS)),
+        F::InitcapString(a, Some(b)) => e(a)?.as_string().bi_map(&e(b)?.as_string(), initcap),
         F::Is(a, b) => Ok(e(a)?.is(&e(b)?).as_any()),
+        F::JsonQuery(a, b) => e(a)?
+            .as_string()
+            .bi_map(&e(b)?.as_string(), json_query_string),
+        F::JsonQueryJson(a, b) => e(a)?
+            .as_json()
+            .bi_map(&e(b)?.as_string(), |a: serde_json::Value, b: &str| {
+                json_query(&a, b)
+            }),
+        F::JsonSubscriptInt64(a, b) => e(a)?
+            .as_json()
+            .bi_map(&e(b)?.as_i64(), |a: serde_json::Value, b: i64| {
+                json_subscript_int64(&a, b)
+            }),
+        F::JsonSubscriptString(a, b) => e(a)?
+            .as_json()
+            .bi_map(&e(b)?.as_string(), |a: serde_json::Value, b: &str| {
+                json_subscript_string(&a, b)
+            }),
+        F::JsonValue(a, None) => e(a)?.as_string().map(|a: &str| json_value_string(a, "$")),
+        F::JsonValue(a, Some(b)) => e(a)?
+            .as_string()
+            .bi_map(&e(b)?.as_string(), json_value_string),
+        F::JsonValueJson(a, None) => e(a)?
+            .as_json()
+            .map(|a: serde_json::Value| json_value(&a, "$")),
+        F::JsonValueJson(a, Some(b)) => e(a)?
+            .as_json()
+            .bi_map(&e(b)?.as_string(), |a: serde_json::Value, b: &str| {
+                json_value(&a, b)
+            }),
         F::LeftString(a, b) => e(a)?
             .as_string()
             .bi_map(&e(b)?.as_i64(), |a: &str, b: i64| {
@@ -210,20 +437,27 @@ fn eval_function(
         F::LtrimString(a, Some(b)) => e(a)?.as_string().bi_map(&e(b)?.as_string(), ltrim),
         F::ModInt64(a, b) => e(a)?
             .as_i64()
-            .bi_map(&e(b)?.as_i64(), |a: i64, b: i64| a % b),
-        F::MultiplyDouble(a, b) => e(a)?
-            .as_f64()
-            .bi_map(&e(b)?.as_f64(), |a: f64, b: f64| a * b),
-        F::MultiplyInt64(a, b) => e(a)?
-            .as_i64()
-            .bi_map(&e(b)?.as_i64(), |a: i64, b: i64| a * b),
+            .bi_map(&e(b)?.as_i64(), |a: i64, b: i64| mod_i64(a, b)),
+        F::MultiplyDouble(a, b) => e(a)?.as_f64().bi_map(&e(b)?.as_f64(), |a: f64, b: f64| {
+            check_f64(a * b, a, "*", b)
+        }),
+        F::MultiplyInt64(a, b) => e(a)?.as_i64().bi_map(&e(b)?.as_i64(), |a: i64, b: i64| {
+            check_i64(a.checked_mul(b), a, "*", b)
+        }),
         F::NotEqual(a, b) => Ok(e(a)?.not_equal(&e(b)?).as_any()),
         F::Nullif(a, b) => Ok(e(a)?.null_if(&e(b)?)),
         F::Or(a, b) => Ok(e(a)?.as_bool().or(&e(b)?.as_bool()).as_any()),
         F::ParseDate(a, b) => e(a)?.as_string().bi_map(&e(b)?.as_string(), parse_date),
-        F::ParseTimestamp(a, b) => e(a)?
-            .as_string()
-            .bi_map(&e(b)?.as_string(), parse_timestamp),
+        F::ParseTimestamp(a, b, None) => e(a)?.as_string().bi_map(&e(b)?.as_string(), |a, b| {
+            parse_timestamp(a, b, DEFAULT_ZONE)
+        }),
+        F::ParseTimestamp(a, b, Some(c)) => {
+            e(a)?
+                .as_string()
+                .tri_map(&e(b)?.as_string(), &e(c)?.as_string(), |a, b, c: &str| {
+                    parse_timestamp(a, b, Zone::parse(c)?)
+                })
+        }
         F::PowDouble(a, b) => e(a)?.as_f64().bi_map(&e(b)?.as_f64(), f64::powf),
         F::RegexpContainsString(a, b) => e(a)?
             .as_string()
@@ -232,10 +466,17 @@ fn eval_function(
             e(a)?
                 .as_string()
                 .bi_map(&e(b)?.as_string(), |a, b| match (a, b) {
-                    (Some(a), Some(b)) => Ok(regexp_extract(a, b)),
-                    _ => Ok(None),
+                    (Some(a), Some(b)) => regexp_extract(a, b),
+                    _ => None,
                 })
         }
+        F::RegexpExtractAllString(a, b) => {
+            let (a, b) = (e(a)?.as_string(), e(b)?.as_string());
+            collect_arrays::<StringArray>(a.len(), |i| match (a.get_str(i), b.get_str(i)) {
+                (Some(a), Some(b)) => Ok(Some(regexp_extract_all(a, b)?)),
+                _ => Ok(None),
+            })
+        }
         F::RepeatString(a, b) => e(a)?
             .as_string()
             .bi_map(&e(b)?.as_i64(), |a: &str, b: i64| a.repeat(b as usize)),
@@ -243,17 +484,36 @@ fn eval_function(
         F::RoundWithDigitsDouble(a, b) => e(a)?.as_f64().bi_map(&e(b)?.as_i64(), round),
         F::RtrimString(a, None) => e(a)?.as_string().map(|a: &str| a.trim_end().to_string()),
         F::RtrimString(a, Some(b)) => e(a)?.as_string().bi_map(&e(b)?.as_string(), rtrim),
+        F::SafeArrayAtOffset(a, b) => array_at(&e(a)?.as_array(), &e(b)?.as_i64(), 0, true),
+        F::SafeArrayAtOrdinal(a, b) => array_at(&e(a)?.as_array(), &e(b)?.as_i64(), 1, true),
+        F::SafeDivideDouble(a, b) => e(a)?.as_f64().bi_map(&e(b)?.as_f64(), safe_divide),
+        F::SplitBytes(a, b) => {
+            let (a, b) = (e(a)?.as_bytes(), e(b)?.as_bytes());
+            collect_arrays::<BytesArray>(a.len(), |i| match (a.get_bytes(i), b.get_bytes(i)) {
+                (Some(a), Some(b)) => Ok(Some(split_bytes(a, b))),
+                _ => Ok(None),
+            })
+        }
+        F::SplitString(a, b) => {
+            let (a, b) = (e(a)?.as_string(), e(b)?.as_string());
+            collect_arrays::<StringArray>(a.len(), |i| match (a.get_str(i), b.get_str(i)) {
+                (Some(a), Some(b)) => Ok(Some(split_string(a, b))),
+                _ => Ok(None),
+            })
+        }
         F::StartsWithString(a, b) => e(a)?
             .as_string()
             .bi_map(&e(b)?.as_string(), |a: &str, b: &str| a.starts_with(b)),
         F::StringLike(a, b) => e(a)?.as_string().bi_map(&e(b)?.as_string(), like),
         F::StrposString(a, b) => e(a)?.as_string().bi_map(&e(b)?.as_string(), strpos),
-        F::SubtractDouble(a, b) => e(a)?
-            .as_f64()
-            .bi_map(&e(b)?.as_f64(), |a: f64, b: f64| a - b),
-        F::SubtractInt64(a, b) => e(a)?
-            .as_i64()
-            .bi_map(&e(b)?.as_i64(), |a: i64, b: i64| a - b),
+        F::SubtractDouble(a, b) => e(a)?.as_f64().bi_map(&e(b)?.as_f64(), |a: f64, b: f64| {
+            check_f64(a - b, a, "-", b)
+        }),
+        F::SubtractInt64(a, b) => e(a)?.as_i64().bi_map(&e(b)?.as_i64(), |a: i64, b: i64| {
+            check_i64(a.checked_sub(b), a, "-", b)
+        }),
+        F::ToJsonString(a, None) => to_json_string(&e(a)?, &BoolArray::falses(input.len())),
+        F::ToJsonString(a, Some(b)) => to_json_string(&e(a)?, &e(b)?.as_bool()),
         F::TrimString(a, None) => e(a)?.as_string().map(|a: &str| a.trim().to_string()),
         F::TrimString(a, Some(b)) => e(a)?.as_string().bi_map(&e(b)?.as_string(), trim),
         F::TruncWithDigitsDouble(a, b) => e(a)?.as_f64().bi_map(&e(b)?.as_i64(), trunc),
@@ -288,9 +548,7 @@ fn eval_function(
         F::DateFromYearMonthDay(a, b, c) => {
             e(a)?
                 .as_i64()
-                .tri_map(&e(b)?.as_i64(), &e(c)?.as_i64(), |a, b, c| {
-                    date_from_ymd(a, b, c)
-                })
+                .tri_map(&e(b)?.as_i64(), &e(c)?.as_i64(), date_from_year_month_day)
         }
         F::If(a, b, c) => Ok(e(a)?.as_bool().blend(&e(b)?, &e(c)?)),
         F::LpadString(a, b, c) => {
@@ -321,6 +579,11 @@ fn eval_function(
                 .as_string()
                 .tri_map(&e(b)?.as_i64(), &e(c)?.as_i64(), |a, b, c| substr(a, b, c))
         }
+        F::TranslateString(a, b, c) => e(a)?.as_string().tri_map(
+            &e(b)?.as_string(),
+            &e(c)?.as_string(),
+            |a: &str, b: &str, c: &str| translate(a, b, c),
+        ),
         F::CaseNoValue(cases, default) => {
             let mut acc = e(default)?;
             for (test, value) in cases.iter().rev() {
@@ -351,24 +614,83 @@ fn trunc(value: f64, digits: i64) -> f64 {
     (value * mul).trunc() / mul
 }
 
-fn div_f64(a: f64, b: f64) -> Result<Option<f64>, String> {
-    let n = a / b;
-    if n.is_infinite() {
-        Err("division by zero".to_string())
+/// Integer arithmetic fails on overflow instead of wrapping around.
+fn check_i64(n: Option<i64>, a: i64, op: &str, b: i64) -> Result<Option<i64>, Error> {
+    match n {
+        Some(n) => Ok(Some(n)),
+        None => Err(Error::DataException(format!(
+            "int64 overflow: {} {} {}",
+            a, op, b
+        ))),
+    }
+}
+
+/// Arithmetic on finite doubles fails when the result overflows, but infinities and NaN in the
+/// inputs propagate to the result as usual.
+fn check_f64(n: f64, a: f64, op: &str, b: f64) -> Result<Option<f64>, Error> {
+    if n.is_infinite() && a.is_finite() && b.is_finite() {
+        Err(Error::DataException(format!(
+            "double overflow: {} {} {}",
+            a, op, b
+        )))
     } else {
         Ok(Some(n))
     }
 }
 
-fn div_i64(a: i64, b: i64) -> Result<Option<f64>, String> {
-    let n = a as f64 / b as f64;
-    if n.is_infinite() {
-        Err("division by zero".to_string())
+fn mod_i64(a: i64, b: i64) -> Result<Option<i64>, Error> {
+    if b == 0 {
+        Err(Error::DivisionByZero("division by zero".to_string()))
     } else {
-        Ok(Some(n))
+        // MOD(i64::MIN, -1) is 0, even though i64::MIN / -1 overflows.
+        Ok(Some(a.checked_rem(b).unwrap_or(0)))
     }
 }
 
+pub(crate) fn div_f64(a: f64, b: f64) -> Result<Option<f64>, Error> {
+    if b == 0.0 {
+        Err(Error::DivisionByZero("division by zero".to_string()))
+    } else {
+        check_f64(a / b, a, "/", b)
+    }
+}
+
+/// Integer division rounds towards zero, like DIV(a, b) in ZetaSQL.
+fn div_i64(a: i64, b: i64) -> Result<Option<i64>, Error> {
+    if b == 0 {
+        Err(Error::DivisionByZero("division by zero".to_string()))
+    } else {
+        match a.checked_div(b) {
+            Some(n) => Ok(Some(n)),
+            None => Err(Error::DataException(format!(
+                "int64 overflow: DIV({}, {})",
+                a, b
+            ))),
+        }
+    }
+}
+
+/// SAFE_DIVIDE is like a / b, but returns NULL where a / b would fail.
+pub(crate) fn safe_divide(a: f64, b: f64) -> Option<f64> {
+    div_f64(a, b).unwrap_or(None)
+}
+
+fn generate_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::random();
+    // Version 4 (random) in the high nibble of byte 6, and the RFC 4122 variant in byte 8.
+    bytes[6] = bytes[6] & 0x0f | 0x40;
+    bytes[8] = bytes[8] & 0x3f | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
 // String functions.
 
 fn chr(code_point: i64) -> String {
@@ -419,7 +741,7 @@ pub(crate) fn substr(value: &str, mut position: i64, mut length: i64) -> String
         .collect()
 }
 
-fn strpos(string: Option<&str>, substring: Option<&str>) -> Result<Option<i64>, String> {
+fn strpos(string: Option<&str>, substring: Option<&str>) -> Result<Option<i64>, Error> {
     match (string, substring) {
         (Some(string), Some(substring)) => Ok(string.find(substring).map(|i| i as i64 + 1)),
         _ => Ok(None),
@@ -477,6 +799,58 @@ pub(crate) fn regexp_extract(value: &str, regexp: &str) -> Option<String> {
     }
 }
 
+pub(crate) fn regexp_extract_all(value: &str, regexp: &str) -> Result<Vec<String>, Error> {
+    let re = Regex::new(regexp).map_err(|err| Error::DataException(err.to_string()))?;
+    match re.captures_len() {
+        1 => Ok(re
+            .find_iter(value)
+            .map(|m| m.as_str().to_string())
+            .collect()),
+        2 => Ok(re
+            .captures_iter(value)
+            .map(|c| c.get(1).map(|m| m.as_str()).unwrap_or("").to_string())
+            .collect()),
+        _ => Err(Error::DataException(format!(
+            "Regular expression r'{}' has more than 1 capturing group",
+            re
+        ))),
+    }
+}
+
+/// SPLIT with an empty delimiter splits value into characters, but an empty value is still one empty element.
+pub(crate) fn split_string(value: &str, delimiter: &str) -> Vec<String> {
+    if delimiter.is_empty() && !value.is_empty() {
+        value.chars().map(|c| c.to_string()).collect()
+    } else if delimiter.is_empty() {
+        vec!["".to_string()]
+    } else {
+        value
+            .split(delimiter)
+            .map(|part| part.to_string())
+            .collect()
+    }
+}
+
+pub(crate) fn split_bytes(value: &[u8], delimiter: &[u8]) -> Vec<Vec<u8>> {
+    if delimiter.is_empty() && !value.is_empty() {
+        return value.iter().map(|byte| vec![*byte]).collect();
+    }
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while !delimiter.is_empty() && i + delimiter.len() <= value.len() {
+        if &value[i..i + delimiter.len()] == delimiter {
+            parts.push(value[start..i].to_vec());
+            i += delimiter.len();
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    parts.push(value[start..].to_vec());
+    parts
+}
+
 fn regexp_contains(value: &str, regexp: &str) -> bool {
     Regex::new(regexp).expect(regexp).is_match(value)
 }
@@ -485,13 +859,13 @@ pub(crate) fn regexp_replace(
     value: &str,
     regexp: &str,
     replacement: &str,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, Error> {
     let regexp = match Regex::new(regexp) {
         Ok(regexp) => regexp,
-        Err(err) => return Err(err.to_string()),
+        Err(err) => return Err(Error::DataException(err.to_string())),
     };
     if let Some(err) = check_replacement(replacement) {
-        return Err(err);
+        return Err(Error::DataException(err));
     }
     let rewrite = |captures: &Captures| -> String {
         let mut buffer = String::new();
@@ -594,70 +968,687 @@ fn is_meta_character(c: char) -> bool {
     }
 }
 
+// Separators that start a new word in INITCAP(value).
+const INITCAP_DELIMITERS: &str = " \t\n\r\u{b}\u{c}[](){}/|<>!?@\"^#$&~_,.:;*%+-";
+
+fn initcap(value: &str, delimiters: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut start_of_word = true;
+    for c in value.chars() {
+        if start_of_word {
+            result.extend(c.to_uppercase());
+        } else {
+            result.extend(c.to_lowercase());
+        }
+        start_of_word = delimiters.contains(c);
+    }
+    result
+}
+
+pub(crate) fn translate(value: &str, source: &str, target: &str) -> Result<Option<String>, Error> {
+    let source: Vec<char> = source.chars().collect();
+    let target: Vec<char> = target.chars().collect();
+    for (i, c) in source.iter().enumerate() {
+        if source[..i].contains(c) {
+            return Err(Error::DataException(format!(
+                "Duplicate character \"{}\" in TRANSLATE source characters",
+                c
+            )));
+        }
+    }
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match source.iter().position(|s| *s == c) {
+            // Source characters without a matching target character are removed.
+            Some(i) => result.extend(target.get(i)),
+            None => result.push(c),
+        }
+    }
+    Ok(Some(result))
+}
+
+/// TO_HEX prints lowercase digits, two per byte.
+pub(crate) fn to_hex(value: &[u8]) -> String {
+    value.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// FROM_HEX accepts either case, and an odd number of digits is read as if it had a leading 0.
+pub(crate) fn from_hex(value: &str) -> Result<Option<Vec<u8>>, Error> {
+    if !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::DataException(format!(
+            "Failed to decode invalid hexadecimal string: {:?}",
+            value
+        )));
+    }
+    let digits = if value.len() % 2 == 1 {
+        format!("0{}", value)
+    } else {
+        value.to_string()
+    };
+    let bytes = (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect();
+    Ok(Some(bytes))
+}
+
+pub(crate) fn from_base64(value: &str) -> Result<Option<Vec<u8>>, Error> {
+    base64::decode(value).map(Some).map_err(|_| {
+        Error::DataException(format!(
+            "Failed to decode invalid base64 string: {:?}",
+            value
+        ))
+    })
+}
+
+/// American Soundex, which ignores every character that is not a Latin letter.
+pub(crate) fn soundex(value: &str) -> String {
+    let mut letters = value.chars().filter(char::is_ascii_alphabetic);
+    let first = match letters.next() {
+        Some(first) => first,
+        None => return "".to_string(),
+    };
+    let mut result = first.to_ascii_uppercase().to_string();
+    let mut last = soundex_code(first);
+    for c in letters {
+        match soundex_code(c) {
+            // H and W don't separate letters with the same code.
+            None => {}
+            Some(code) => {
+                if code != '0' && Some(code) != last {
+                    result.push(code);
+                    if result.len() == 4 {
+                        break;
+                    }
+                }
+                last = Some(code);
+            }
+        }
+    }
+    while result.len() < 4 {
+        result.push('0');
+    }
+    result
+}
+
+fn soundex_code(letter: char) -> Option<char> {
+    match letter.to_ascii_lowercase() {
+        'b' | 'f' | 'p' | 'v' => Some('1'),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+        'd' | 't' => Some('3'),
+        'l' => Some('4'),
+        'm' | 'n' => Some('5'),
+        'r' => Some('6'),
+        'h' | 'w' => None,
+        _ => Some('0'),
+    }
+}
+
+/// FORMAT(format, ...) formats one row of its arguments using printf-style specifiers
+/// %[flags][width][.precision]specifier. Like ZetaSQL, the result is NULL if any argument is NULL,
+/// except for arguments of %t and %T, which print NULL.
+pub(crate) fn format_string(
+    format: &str,
+    args: &Vec<AnyArray>,
+    row: usize,
+) -> Result<Option<String>, Error> {
+    let error = |message: String| {
+        Error::DataException(format!("Invalid format string \"{}\": {}", format, message))
+    };
+    let mut output = String::new();
+    let mut chars = format.chars().peekable();
+    let mut next = 0;
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+        let mut flags = String::new();
+        while let Some(c) = chars.peek().filter(|c| "-+ #0".contains(**c)) {
+            flags.push(*c);
+            chars.next();
+        }
+        let width = format_digits(&mut chars);
+        let precision = if chars.peek() == Some(&'.') {
+            chars.next();
+            Some(format_digits(&mut chars).unwrap_or(0))
+        } else {
+            None
+        };
+        let specifier = chars
+            .next()
+            .ok_or_else(|| error("ends with %".to_string()))?;
+        if specifier == '%' {
+            output.push('%');
+            continue;
+        }
+        let arg = args
+            .get(next)
+            .ok_or_else(|| error("has more specifiers than arguments".to_string()))?;
+        next += 1;
+        let formatted = match specifier {
+            'd' | 'i' => format_i64(arg, row)?
+                .map(|n| (format_sign(n < 0, &flags), (n as i128).abs().to_string())),
+            'o' | 'x' | 'X' => match format_i64(arg, row)? {
+                Some(n) if n < 0 => {
+                    return Err(error(format!("%{} of a negative number", specifier)))
+                }
+                Some(n) => {
+                    let (prefix, digits) = match specifier {
+                        'o' => ("0", format!("{:o}", n)),
+                        'x' => ("0x", format!("{:x}", n)),
+                        _ => ("0X", format!("{:X}", n)),
+                    };
+                    let prefix = if flags.contains('#') { prefix } else { "" };
+                    Some((prefix.to_string(), digits))
+                }
+                None => None,
+            },
+            'f' | 'F' | 'e' | 'E' | 'g' | 'G' => format_f64(arg, row)?.map(|n| {
+                (
+                    format_sign(n.is_sign_negative() && !n.is_nan(), &flags),
+                    format_double(n.abs(), specifier, precision, flags.contains('#')),
+                )
+            }),
+            's' => format_text(arg, row, false).map(|text| match precision {
+                Some(precision) => ("".to_string(), text.chars().take(precision).collect()),
+                None => ("".to_string(), text),
+            }),
+            't' | 'T' => Some((
+                "".to_string(),
+                format_text(arg, row, specifier == 'T').unwrap_or("NULL".to_string()),
+            )),
+            other => return Err(error(format!("%{} is not a valid specifier", other))),
+        };
+        let (prefix, body) = match formatted {
+            Some(formatted) => formatted,
+            None => return Ok(None),
+        };
+        let numeric = !"stT".contains(specifier);
+        output.push_str(&format_pad(
+            prefix,
+            body,
+            &flags,
+            width.unwrap_or(0),
+            numeric,
+        ));
+    }
+    if next < args.len() {
+        return Err(error("has fewer specifiers than arguments".to_string()));
+    }
+    Ok(Some(output))
+}
+
+fn format_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<usize> {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+        digits.push(*c);
+        chars.next();
+    }
+    digits.parse().ok()
+}
+
+fn format_i64(arg: &AnyArray, row: usize) -> Result<Option<i64>, Error> {
+    match arg {
+        AnyArray::I64(array) => Ok(array.get(row)),
+        other => Err(Error::DataException(format!(
+            "FORMAT expected INT64 but found {}",
+            other.data_type()
+        ))),
+    }
+}
+
+fn format_f64(arg: &AnyArray, row: usize) -> Result<Option<f64>, Error> {
+    match arg {
+        AnyArray::F64(array) => Ok(array.get(row)),
+        AnyArray::I64(array) => Ok(array.get(row).map(|n| n as f64)),
+        other => Err(Error::DataException(format!(
+            "FORMAT expected DOUBLE but found {}",
+            other.data_type()
+        ))),
+    }
+}
+
+/// The text of an argument of FORMAT, or its SQL literal if `literal` is set.
+fn format_text(arg: &AnyArray, row: usize, literal: bool) -> Option<String> {
+    // BYTES may not be valid UTF-8, so they are printed with escapes instead of cast to STRING.
+    if let AnyArray::Bytes(array) = arg {
+        let text = bytes_literal(array.get_bytes(row)?);
+        if literal {
+            return Some(text);
+        }
+        return Some(text[2..text.len() - 1].to_string());
+    }
+    let text = arg
+        .slice(row..row + 1)
+        .cast(DataType::String)
+        .unwrap()
+        .as_string()
+        .get(0)?;
+    if !literal {
+        return Some(text);
+    }
+    match arg.data_type() {
+        DataType::String => Some(format!("{:?}", text)),
+        DataType::Date => Some(format!("DATE {:?}", text)),
+        DataType::Timestamp => Some(format!("TIMESTAMP {:?}", text)),
+        DataType::Json => Some(format!("JSON {:?}", text)),
+        _ => Some(text),
+    }
+}
+
+/// TO_JSON_STRING prints NULL as null, so it reads its input as Options.
+fn to_json_string(value: &AnyArray, pretty: &BoolArray) -> Result<AnyArray, Error> {
+    match value {
+        AnyArray::Bool(a) => a.bi_map(pretty, |a: Option<bool>, pretty: bool| {
+            print_json(to_json(a), pretty)
+        }),
+        AnyArray::I64(a) => a.bi_map(pretty, |a: Option<i64>, pretty: bool| {
+            print_json(to_json(a), pretty)
+        }),
+        AnyArray::F64(a) => a.bi_map(pretty, |a: Option<f64>, pretty: bool| {
+            print_json(double_to_json(a), pretty)
+        }),
+        AnyArray::Date(a) => a.bi_map(pretty, |a: Option<Date<Utc>>, pretty: bool| {
+            print_json(date_to_json(a), pretty)
+        }),
+        AnyArray::Timestamp(a) => a.bi_map(pretty, |a: Option<DateTime<Utc>>, pretty: bool| {
+            print_json(timestamp_to_json(a), pretty)
+        }),
+        AnyArray::String(a) => a.bi_map(pretty, |a: Option<&str>, pretty: bool| {
+            print_json(to_json(a), pretty)
+        }),
+        AnyArray::Json(a) => a.bi_map(pretty, |a: Option<serde_json::Value>, pretty: bool| {
+            print_json(to_json(a), pretty)
+        }),
+        AnyArray::Bytes(a) => a.bi_map(pretty, |a: Option<&[u8]>, pretty: bool| {
+            print_json(to_json(a.map(base64::encode)), pretty)
+        }),
+        AnyArray::Array(a) => {
+            let mut result = StringArray::with_capacity(a.len());
+            for i in 0..a.len() {
+                let json = match a.get(i) {
+                    // The elements are printed by TO_JSON_STRING itself and parsed back into the array.
+                    Some(elements) => {
+                        let compact = BoolArray::from_values(vec![false].repeat(elements.len()));
+                        let elements = to_json_string(&elements, &compact)?.as_string();
+                        serde_json::Value::Array(
+                            (0..elements.len())
+                                .map(|j| {
+                                    serde_json::from_str(elements.get_str(j).unwrap()).unwrap()
+                                })
+                                .collect(),
+                        )
+                    }
+                    None => serde_json::Value::Null,
+                };
+                result.push(pretty.get(i).map(|pretty| print_json(json, pretty)));
+            }
+            Ok(AnyArray::String(result))
+        }
+    }
+}
+
+fn format_sign(negative: bool, flags: &str) -> String {
+    if negative {
+        "-".to_string()
+    } else if flags.contains('+') {
+        "+".to_string()
+    } else if flags.contains(' ') {
+        " ".to_string()
+    } else {
+        "".to_string()
+    }
+}
+
+fn format_double(n: f64, specifier: char, precision: Option<usize>, alternate: bool) -> String {
+    let precision = precision.unwrap_or(6);
+    let text = if n.is_nan() {
+        "nan".to_string()
+    } else if n.is_infinite() {
+        "inf".to_string()
+    } else {
+        match specifier.to_ascii_lowercase() {
+            'f' => format!("{:.*}", precision, n),
+            'e' => format_exponent(n, precision),
+            _ => format_general(n, precision, alternate),
+        }
+    };
+    if specifier.is_ascii_uppercase() {
+        text.to_uppercase()
+    } else {
+        text
+    }
+}
+
+/// Rust writes 1.5e2 where printf writes 1.5e+02.
+fn format_exponent(n: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, n);
+    let (mantissa, exponent) = text.split_at(text.find('e').unwrap());
+    let exponent: i32 = exponent[1..].parse().unwrap();
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+}
+
+/// %g uses %e for very large and very small numbers and %f otherwise, without trailing zeros.
+fn format_general(n: f64, precision: usize, alternate: bool) -> String {
+    let precision = precision.max(1);
+    let text = format!("{:.*e}", precision - 1, n);
+    let exponent: i32 = text[text.find('e').unwrap() + 1..].parse().unwrap();
+    let text = if exponent < -4 || exponent >= precision as i32 {
+        format_exponent(n, precision - 1)
+    } else {
+        format!("{:.*}", (precision as i32 - 1 - exponent) as usize, n)
+    };
+    if alternate {
+        return text;
+    }
+    let (number, exponent) = text.split_at(text.find('e').unwrap_or(text.len()));
+    let number = if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    };
+    format!("{}{}", number, exponent)
+}
+
+fn format_pad(prefix: String, body: String, flags: &str, width: usize, numeric: bool) -> String {
+    let len = prefix.chars().count() + body.chars().count();
+    if len >= width {
+        return prefix + &body;
+    }
+    let fill = width - len;
+    if flags.contains('-') {
+        format!("{}{}{}", prefix, body, " ".repeat(fill))
+    } else if flags.contains('0') && numeric {
+        format!("{}{}{}", prefix, "0".repeat(fill), body)
+    } else {
+        format!("{}{}{}", " ".repeat(fill), prefix, body)
+    }
+}
+
+// Array functions.
+
+/// ArrayArray doesn't implement Array, so functions that return arrays can't use map,
+/// and instead build the elements of each row with f.
+fn collect_arrays<A: Array>(
+    len: usize,
+    f: impl Fn(usize) -> Result<Option<Vec<A::Element>>, Error>,
+) -> Result<AnyArray, Error> {
+    let mut result = ArrayArray::with_capacity(A::default().data_type(), len);
+    for i in 0..len {
+        let elements = f(i)?.map(|elements| A::from_values(elements).as_any());
+        result.push(elements.as_ref());
+    }
+    Ok(AnyArray::Array(result))
+}
+
+fn array_length(array: &ArrayArray) -> AnyArray {
+    let mut result = I64Array::with_capacity(array.len());
+    for i in 0..array.len() {
+        result.push(array.get(i).map(|elements| elements.len() as i64));
+    }
+    result.as_any()
+}
+
+/// array[OFFSET(index)] counts from 0 and array[ORDINAL(index)] counts from 1.
+/// The SAFE_ variants return NULL instead of failing when the index is out of bounds.
+fn array_at(
+    array: &ArrayArray,
+    index: &I64Array,
+    base: i64,
+    safe: bool,
+) -> Result<AnyArray, Error> {
+    assert_eq!(array.len(), index.len());
+    let mut result = AnyArray::with_capacity(array.element_type(), array.len());
+    for i in 0..array.len() {
+        if let (Some(elements), Some(index)) = (array.get(i), index.get(i)) {
+            let j = index - base;
+            if 0 <= j && j < elements.len() as i64 {
+                result.extend(&elements.slice(j as usize..j as usize + 1));
+                continue;
+            } else if !safe {
+                return Err(Error::DataException(format!(
+                    "Array index {} is out of bounds",
+                    index
+                )));
+            }
+        }
+        result.extend(&AnyArray::nulls(array.element_type(), 1));
+    }
+    Ok(result)
+}
+
+/// ARRAY_TO_STRING skips NULL elements, unless null_text is given to replace them.
+fn array_to_string(
+    array: &ArrayArray,
+    delimiter: &StringArray,
+    null_text: Option<&StringArray>,
+) -> AnyArray {
+    let mut result = StringArray::with_capacity(array.len());
+    for i in 0..array.len() {
+        let null_text = match null_text {
+            Some(null_text) => match null_text.get_str(i) {
+                Some(null_text) => Some(null_text),
+                None => {
+                    result.push(None);
+                    continue;
+                }
+            },
+            None => None,
+        };
+        if let (Some(elements), Some(delimiter)) = (array.get(i), delimiter.get_str(i)) {
+            let elements = elements.as_string();
+            let parts: Vec<&str> = (0..elements.len())
+                .filter_map(|j| elements.get_str(j).or(null_text))
+                .collect();
+            result.push(Some(parts.join(delimiter)));
+        } else {
+            result.push(None);
+        }
+    }
+    result.as_any()
+}
+
 // Datetime functions.
 
-pub(crate) fn date_from_ymd(year: i64, month: i64, day: i64) -> Date<Utc> {
-    Utc.from_utc_date(&NaiveDate::from_ymd(year as i32, month as u32, day as u32))
+pub(crate) fn date_from_year_month_day(
+    year: i64,
+    month: i64,
+    day: i64,
+) -> Result<Option<Date<Utc>>, Error> {
+    let naive =
+        if (1..=9999).contains(&year) && (1..=12).contains(&month) && (1..=31).contains(&day) {
+            NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32)
+        } else {
+            None
+        };
+    let naive = naive.ok_or_else(|| {
+        Error::DataException(format!("Invalid date DATE({}, {}, {})", year, month, day))
+    })?;
+    Ok(Some(Utc.from_utc_date(&naive)))
 }
 
-fn date_from_timestamp(value: DateTime<Utc>) -> Date<Utc> {
-    value.date()
+fn date_from_timestamp(value: DateTime<Utc>, zone: Zone) -> Date<Utc> {
+    Utc.from_utc_date(&zone.local(value).date())
 }
 
-fn date_from_unix_date(value: i64) -> Date<Utc> {
-    let naive = NaiveDate::from_ymd(1970, 1, 1) + Duration::days(value as i64);
-    Utc.from_utc_date(&naive)
+fn date_from_unix_date(value: i64) -> Result<Option<Date<Utc>>, Error> {
+    let naive = days(value)
+        .and_then(|days| NaiveDate::from_ymd(1970, 1, 1).checked_add_signed(days))
+        .ok_or_else(|| {
+            Error::DataException(format!("date_from_unix_date({}) overflowed", value))
+        })?;
+    Ok(Some(Utc.from_utc_date(&naive)))
 }
 
-fn timestamp_from_date(value: Date<Utc>) -> DateTime<Utc> {
-    value.and_hms(0, 0, 0)
+fn timestamp_from_date(value: Date<Utc>, zone: Zone) -> DateTime<Utc> {
+    zone.instant(value.naive_utc().and_hms(0, 0, 0))
 }
 
-fn timestamp_from_string(value: &str) -> DateTime<Utc> {
-    parse_timestamp("%+", value)
+/// Parse the canonical format of a timestamp, which is a date, an optional time and an optional
+/// time zone, like 2020-01-01, 2020-01-01 12:34:56.789+05:30 or 2020-01-01T12:34 Europe/Paris.
+pub(crate) fn timestamp_from_string(
+    value: &str,
+    zone: Zone,
+) -> Result<Option<DateTime<Utc>>, Error> {
+    let error = || Error::DataException(format!("Invalid timestamp string \"{}\"", value));
+    let trimmed = value.trim();
+    let (date, rest) = match trimmed.find(|c| c == ' ' || c == 'T') {
+        Some(i) => (&trimmed[..i], trimmed[i + 1..].trim_start()),
+        None => (trimmed, ""),
+    };
+    let (time, name) = match rest
+        .find(|c: char| c == '+' || c == '-' || c.is_whitespace() || c.is_ascii_alphabetic())
+    {
+        Some(i) => (&rest[..i], rest[i..].trim()),
+        None => (rest, ""),
+    };
+    let zone = match name {
+        "" => zone,
+        "Z" => DEFAULT_ZONE,
+        name => Zone::parse(name)?,
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| error())?;
+    let time = if time.is_empty() {
+        NaiveTime::from_hms(0, 0, 0)
+    } else {
+        NaiveTime::parse_from_str(time, "%H:%M:%S%.f")
+            .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+            .map_err(|_| error())?
+    };
+    let ts = zone.instant(date.and_time(time));
+    if is_overflow_ts(ts) {
+        return Err(error());
+    }
+    Ok(Some(ts))
 }
 
-fn parse_date(format: &str, value: &str) -> Date<Utc> {
-    Utc.from_utc_date(&NaiveDate::parse_from_str(value, format).expect(value))
+fn parse_date(format: &str, value: &str) -> Result<Option<Date<Utc>>, Error> {
+    let naive = NaiveDate::parse_from_str(value, &strftime(format)?)
+        .map_err(|_| Error::DataException(format!("Failed to parse input string \"{}\"", value)))?;
+    Ok(Some(Utc.from_utc_date(&naive)))
 }
 
-fn parse_timestamp(format: &str, value: &str) -> DateTime<Utc> {
-    Utc.from_utc_datetime(&NaiveDateTime::parse_from_str(value, format).expect(value))
+/// Parse `value` according to `format`. If `value` doesn't include an offset from UTC, it is a time
+/// in `zone`, and fields that are missing from `format` default to 1970-01-01 00:00:00.
+fn parse_timestamp(format: &str, value: &str, zone: Zone) -> Result<Option<DateTime<Utc>>, Error> {
+    let error = || Error::DataException(format!("Failed to parse input string \"{}\"", value));
+    let format = strftime(format)?;
+    let mut parsed = Parsed::new();
+    format::parse(&mut parsed, value, StrftimeItems::new(&format)).map_err(|_| error())?;
+    if parsed.timestamp.is_some() && parsed.offset.is_none() {
+        parsed.offset = Some(0);
+    }
+    let ts = if parsed.offset.is_some() {
+        parsed
+            .to_datetime()
+            .map_err(|_| error())?
+            .with_timezone(&Utc)
+    } else {
+        if parsed.year.is_none() && parsed.year_mod_100.is_none() && parsed.isoyear.is_none() {
+            parsed.year = Some(1970);
+        }
+        if parsed.month.is_none() && parsed.ordinal.is_none() {
+            parsed.month = Some(1);
+        }
+        if parsed.day.is_none() && parsed.ordinal.is_none() {
+            parsed.day = Some(1);
+        }
+        if parsed.hour_div_12.is_none() && parsed.hour_mod_12.is_none() {
+            parsed.hour_div_12 = Some(0);
+            parsed.hour_mod_12 = Some(0);
+        }
+        if parsed.minute.is_none() {
+            parsed.minute = Some(0);
+        }
+        let local = parsed
+            .to_naive_datetime_with_offset(0)
+            .map_err(|_| error())?;
+        zone.instant(local)
+    };
+    if is_overflow_ts(ts) {
+        return Err(error());
+    }
+    Ok(Some(ts))
 }
 
 fn string_from_date(value: Date<Utc>) -> String {
     value.format("%F").to_string()
 }
 
-fn string_from_timestamp(value: DateTime<Utc>) -> String {
-    format_timestamp("%+", value)
+fn string_from_timestamp(value: DateTime<Utc>, zone: Zone) -> String {
+    zone.format("%+", value)
 }
 
-fn format_date(format: &str, value: Date<Utc>) -> String {
-    value.format(format).to_string()
+fn format_date(format: &str, value: Date<Utc>) -> Result<Option<String>, Error> {
+    let format = strftime(format)?;
+    Ok(Some(value.and_hms(0, 0, 0).format(&format).to_string()))
 }
 
-fn format_timestamp(format: &str, value: DateTime<Utc>) -> String {
-    value.format(format).to_string()
+pub(crate) fn format_timestamp(
+    format: &str,
+    value: DateTime<Utc>,
+    zone: Zone,
+) -> Result<Option<String>, Error> {
+    let format = strftime(format)?;
+    Ok(Some(zone.format(&format, value)))
 }
 
-fn date_trunc(d: Date<Utc>, date_part: DatePart) -> Date<Utc> {
-    match date_part {
-        DatePart::Day => d,
-        DatePart::Week(weekday) => prev_weekday_or_today(d, weekday),
-        DatePart::IsoWeek => prev_weekday_or_today(d, Weekday::Mon),
-        DatePart::Month => d.with_day(1).unwrap(),
-        DatePart::Quarter => d
-            .with_month0(d.month0() / 4 * 4)
-            .unwrap()
-            .with_day(1)
-            .unwrap(),
-        DatePart::Year => d.with_month(1).unwrap().with_day(1).unwrap(),
-        DatePart::IsoYear => {
-            let iso = d.iso_week();
-            let naive = NaiveDate::from_isoywd(iso.year(), 1, Weekday::Mon);
-            Utc.from_utc_date(&naive)
+/// ZetaSQL extends strftime with a few format elements, which we rewrite to the chrono equivalent.
+const FORMAT_ELEMENTS: [(&str, &str); 6] = [
+    ("%Ez", "%:z"),
+    ("%E*S", "%S%.6f"),
+    ("%E3S", "%S%.3f"),
+    ("%E6S", "%S%.6f"),
+    ("%E9S", "%S%.9f"),
+    ("%E4Y", "%Y"),
+];
+
+fn strftime(format: &str) -> Result<String, Error> {
+    let mut translated = String::with_capacity(format.len());
+    let mut rest = format;
+    while let Some(i) = rest.find('%') {
+        translated.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some((from, to)) = FORMAT_ELEMENTS
+            .iter()
+            .find(|(from, _)| rest.starts_with(from))
+        {
+            translated.push_str(to);
+            rest = &rest[from.len()..];
+        } else {
+            let len = 1 + rest[1..].chars().next().map(char::len_utf8).unwrap_or(0);
+            translated.push_str(&rest[..len]);
+            rest = &rest[len..];
         }
+    }
+    translated.push_str(rest);
+    if StrftimeItems::new(&translated).any(|item| item == Item::Error) {
+        return Err(Error::DataException(format!(
+            "Invalid format string \"{}\"",
+            format
+        )));
+    }
+    Ok(translated)
+}
+
+pub(crate) fn date_trunc(d: Date<Utc>, date_part: DatePart) -> Result<Option<Date<Utc>>, Error> {
+    let naive = trunc_date(d.naive_utc(), date_part).ok_or_else(|| {
+        Error::NotSupported(format!("date_trunc(_, {}) is not supported", date_part))
+    })?;
+    Ok(Some(Utc.from_utc_date(&naive)))
+}
+
+fn trunc_date(d: NaiveDate, date_part: DatePart) -> Option<NaiveDate> {
+    match date_part {
+        DatePart::Day => Some(d),
+        DatePart::Week(weekday) => Some(prev_weekday_or_today(d, weekday)),
+        DatePart::IsoWeek => Some(prev_weekday_or_today(d, Weekday::Mon)),
+        DatePart::Month => d.with_day(1),
+        DatePart::Quarter => NaiveDate::from_ymd_opt(d.year(), d.month0() / 3 * 3 + 1, 1),
+        DatePart::Year => NaiveDate::from_ymd_opt(d.year(), 1, 1),
+        DatePart::IsoYear => NaiveDate::from_isoywd_opt(d.iso_week().year(), 1, Weekday::Mon),
         DatePart::Nanosecond
         | DatePart::Microsecond
         | DatePart::Millisecond
@@ -665,48 +1656,60 @@ fn date_trunc(d: Date<Utc>, date_part: DatePart) -> Date<Utc> {
         | DatePart::Minute
         | DatePart::Hour
         | DatePart::DayOfWeek
-        | DatePart::DayOfYear => panic!("date_trunc(_, {:?}) is not supported", date_part),
+        | DatePart::DayOfYear => None,
     }
 }
 
-fn timestamp_trunc(
-    ts: Option<DateTime<Utc>>,
+pub(crate) fn timestamp_trunc(
+    ts: DateTime<Utc>,
     date_part: DatePart,
-) -> Result<Option<DateTime<Utc>>, String> {
-    if let Some(ts) = ts {
-        let part = match date_part {
-            DatePart::Nanosecond => {
-                return Err("timestamp_trunc(_, NANOSECOND) is not supported".to_string())
-            }
-            DatePart::Microsecond => ts,
-            DatePart::Millisecond => ts.duration_trunc(Duration::milliseconds(1)).unwrap(),
-            DatePart::Second => ts.duration_trunc(Duration::seconds(1)).unwrap(),
-            DatePart::Minute => ts.duration_trunc(Duration::minutes(1)).unwrap(),
-            DatePart::Hour => ts.duration_trunc(Duration::hours(1)).unwrap(),
-            DatePart::Day
-            | DatePart::Week(_)
-            | DatePart::IsoWeek
-            | DatePart::Month
-            | DatePart::Quarter
-            | DatePart::Year
-            | DatePart::IsoYear => date_trunc(ts.date(), date_part).and_hms(0, 0, 0),
-            DatePart::DayOfWeek | DatePart::DayOfYear => {
-                panic!("timestamp_trunc(_, {:?}) is not supported", date_part)
-            }
-        };
-        Ok(Some(part))
-    } else {
-        Ok(None)
-    }
+    zone: Zone,
+) -> Result<Option<DateTime<Utc>>, Error> {
+    let part = match date_part {
+        // Timestamps only have microsecond precision.
+        DatePart::Nanosecond | DatePart::Microsecond => ts,
+        DatePart::Millisecond => ts.duration_trunc(Duration::milliseconds(1)).unwrap(),
+        DatePart::Second => ts.duration_trunc(Duration::seconds(1)).unwrap(),
+        DatePart::Minute => ts.duration_trunc(Duration::minutes(1)).unwrap(),
+        DatePart::Hour => {
+            // Some time zones are not a whole number of hours from UTC.
+            let local = zone.local(ts);
+            zone.instant(local.date().and_hms(local.hour(), 0, 0))
+        }
+        DatePart::Day
+        | DatePart::Week(_)
+        | DatePart::IsoWeek
+        | DatePart::Month
+        | DatePart::Quarter
+        | DatePart::Year
+        | DatePart::IsoYear => {
+            let local = trunc_date(zone.local(ts).date(), date_part).unwrap();
+            zone.instant(local.and_hms(0, 0, 0))
+        }
+        DatePart::DayOfWeek | DatePart::DayOfYear => {
+            return Err(Error::NotSupported(format!(
+                "timestamp_trunc(_, {}) is not supported",
+                date_part
+            )))
+        }
+    };
+    Ok(Some(part))
 }
 
-pub(crate) fn extract_from_date(d: Date<Utc>, date_part: DatePart) -> i64 {
-    match date_part {
+pub(crate) fn extract_from_date(d: Date<Utc>, date_part: DatePart) -> Result<Option<i64>, Error> {
+    let part = extract_date(d.naive_utc(), date_part).ok_or_else(|| {
+        Error::NotSupported(format!("extract({} from DATE) is not supported", date_part))
+    })?;
+    Ok(Some(part))
+}
+
+fn extract_date(d: NaiveDate, date_part: DatePart) -> Option<i64> {
+    let part = match date_part {
         DatePart::DayOfWeek => d.weekday().num_days_from_sunday() as i64 + 1,
         DatePart::Day => d.day() as i64,
         DatePart::DayOfYear => d.ordinal() as i64,
         DatePart::Week(weekday) => {
-            let first_calendar_day_of_year = date_from_ymd(d.year() as i64, 1, 1);
+            let first_calendar_day_of_year = NaiveDate::from_ymd(d.year(), 1, 1);
             let effective_first_day_of_year =
                 next_weekday_or_today(first_calendar_day_of_year, weekday);
             if d < effective_first_day_of_year {
@@ -725,72 +1728,84 @@ pub(crate) fn extract_from_date(d: Date<Utc>, date_part: DatePart) -> i64 {
         | DatePart::Millisecond
         | DatePart::Second
         | DatePart::Minute
-        | DatePart::Hour => panic!("extract {:?} from DATE is not supported", date_part),
-    }
+        | DatePart::Hour => return None,
+    };
+    Some(part)
 }
 
 /// If `day` is a `weekday`, return `day`, otherwise return the next `weekday`.
-fn next_weekday_or_today(mut d: Date<Utc>, weekday: Weekday) -> Date<Utc> {
+fn next_weekday_or_today(mut d: NaiveDate, weekday: Weekday) -> NaiveDate {
     while d.weekday() != weekday {
         d = d.succ()
     }
     d
 }
 /// If `day` is a `weekday`, return `day`, otherwise return the previous `weekday`.
-fn prev_weekday_or_today(mut d: Date<Utc>, weekday: Weekday) -> Date<Utc> {
+fn prev_weekday_or_today(mut d: NaiveDate, weekday: Weekday) -> NaiveDate {
     while d.weekday() != weekday {
         d = d.pred()
     }
     d
 }
 
-fn extract_from_timestamp(
-    ts: Option<DateTime<Utc>>,
+pub(crate) fn extract_from_timestamp(
+    ts: DateTime<Utc>,
     date_part: DatePart,
-) -> Result<Option<i64>, String> {
-    if let Some(ts) = ts {
-        let part = match date_part {
-            DatePart::Nanosecond => {
-                return Err("extract(NANOSECOND from _) is not supported".to_string())
-            }
-            DatePart::Microsecond => ts.timestamp_subsec_micros() as i64,
-            DatePart::Millisecond => ts.timestamp_subsec_millis() as i64,
-            DatePart::Second => ts.second() as i64,
-            DatePart::Minute => ts.minute() as i64,
-            DatePart::Hour => ts.hour() as i64,
-            DatePart::Day
-            | DatePart::Week(_)
-            | DatePart::IsoWeek
-            | DatePart::Month
-            | DatePart::Quarter
-            | DatePart::Year
-            | DatePart::IsoYear
-            | DatePart::DayOfWeek
-            | DatePart::DayOfYear => extract_from_date(ts.date(), date_part),
-        };
-        Ok(Some(part))
-    } else {
-        Ok(None)
-    }
+    zone: Zone,
+) -> Result<Option<i64>, Error> {
+    let local = zone.local(ts);
+    let part = match date_part {
+        DatePart::Nanosecond => local.nanosecond() as i64,
+        DatePart::Microsecond => local.nanosecond() as i64 / 1_000,
+        DatePart::Millisecond => local.nanosecond() as i64 / 1_000_000,
+        DatePart::Second => local.second() as i64,
+        DatePart::Minute => local.minute() as i64,
+        DatePart::Hour => local.hour() as i64,
+        DatePart::Day
+        | DatePart::Week(_)
+        | DatePart::IsoWeek
+        | DatePart::Month
+        | DatePart::Quarter
+        | DatePart::Year
+        | DatePart::IsoYear
+        | DatePart::DayOfWeek
+        | DatePart::DayOfYear => extract_date(local.date(), date_part).unwrap(),
+    };
+    Ok(Some(part))
 }
 
 const OVERFLOW: &'static str = "date_add/subtract overflowed";
 
+/// The number of days between 0001-01-01 and 9999-12-31.
+const MAX_DAYS: i64 = 3_652_058;
+
+fn days(amount: i64) -> Option<Duration> {
+    if (-MAX_DAYS..=MAX_DAYS).contains(&amount) {
+        Some(Duration::days(amount))
+    } else {
+        None
+    }
+}
+
 pub(crate) fn date_add(
     date: Date<Utc>,
     amount: i64,
     date_part: DatePart,
-) -> Result<Option<Date<Utc>>, String> {
+) -> Result<Option<Date<Utc>>, Error> {
+    let date = date.naive_utc();
     let ok = match date_part {
-        DatePart::Day => date
-            .checked_add_signed(Duration::days(amount))
-            .ok_or(OVERFLOW.to_string())?,
-        DatePart::Week(_) => date
-            .checked_add_signed(Duration::days(amount * 7))
-            .ok_or(OVERFLOW.to_string())?,
-        DatePart::Month => add_months(date, amount).ok_or(OVERFLOW.to_string())?,
-        DatePart::Quarter => return date_add(date, amount * 3, DatePart::Month),
-        DatePart::Year => return date_add(date, amount * 12, DatePart::Month),
+        DatePart::Day => days(amount).and_then(|days| date.checked_add_signed(days)),
+        DatePart::Week(_) => amount
+            .checked_mul(7)
+            .and_then(days)
+            .and_then(|days| date.checked_add_signed(days)),
+        DatePart::Month => add_months(date, amount),
+        DatePart::Quarter => amount
+            .checked_mul(3)
+            .and_then(|amount| add_months(date, amount)),
+        DatePart::Year => amount
+            .checked_mul(12)
+            .and_then(|amount| add_months(date, amount)),
         DatePart::Nanosecond
         | DatePart::Microsecond
         | DatePart::Millisecond
@@ -800,27 +1815,33 @@ pub(crate) fn date_add(
         | DatePart::DayOfWeek
         | DatePart::DayOfYear
         | DatePart::IsoWeek
-        | DatePart::IsoYear => panic!("date_add/subtract(_, {:?}) is not supported", date_part),
+        | DatePart::IsoYear => {
+            return Err(Error::NotSupported(format!(
+                "date_add/subtract(_, {}) is not supported",
+                date_part
+            )))
+        }
     };
+    let ok = Utc.from_utc_date(&ok.ok_or_else(|| Error::DataException(OVERFLOW.to_string()))?);
     if is_overflow_date(ok) {
-        return Err(OVERFLOW.to_string());
+        return Err(Error::DataException(OVERFLOW.to_string()));
     }
     Ok(Some(ok))
 }
 
-fn add_months(date: Date<Utc>, amount: i64) -> Option<Date<Utc>> {
-    let y = (date.year() as i64 * 12 + date.month0() as i64 + amount).div_euclid(12) as i32;
-    let m = (date.month0() as i64 + amount).rem_euclid(12) as u32 + 1;
+fn add_months(date: NaiveDate, amount: i64) -> Option<NaiveDate> {
+    let months = (date.year() as i64 * 12 + date.month0() as i64).checked_add(amount)?;
+    let y = i32::try_from(months.div_euclid(12)).ok()?;
+    let m = months.rem_euclid(12) as u32 + 1;
     let d = date.day().min(days_in_month(y, m));
-    let naive = NaiveDate::from_ymd_opt(y, m, d)?;
-    Some(Utc.from_utc_date(&naive))
+    NaiveDate::from_ymd_opt(y, m, d)
 }
 
 fn days_in_month(year: i32, month: u32) -> u32 {
     match month {
         9 | 4 | 6 | 11 => 30,
         2 if is_leap_year(year) => 29,
-        2 => 29,
+        2 => 28,
         _ => 31,
     }
 }
@@ -845,27 +1866,37 @@ pub(crate) fn date_sub(
     date: Date<Utc>,
     amount: i64,
     date_part: DatePart,
-) -> Result<Option<Date<Utc>>, String> {
-    date_add(date, -amount, date_part)
+) -> Result<Option<Date<Utc>>, Error> {
+    date_add(
+        date,
+        amount
+            .checked_neg()
+            .ok_or_else(|| Error::DataException(OVERFLOW.to_string()))?,
+        date_part,
+    )
 }
 
-pub(crate) fn date_diff(later: Date<Utc>, earlier: Date<Utc>, date_part: DatePart) -> i64 {
-    match date_part {
+pub(crate) fn date_diff(
+    later: Date<Utc>,
+    earlier: Date<Utc>,
+    date_part: DatePart,
+) -> Result<Option<i64>, Error> {
+    let diff = match date_part {
         DatePart::Day => (later - earlier).num_days() as i64,
         DatePart::Week(_) | DatePart::IsoWeek => {
-            let later = date_trunc(later, date_part);
-            let earlier = date_trunc(earlier, date_part);
+            let later = trunc_date(later.naive_utc(), date_part).unwrap();
+            let earlier = trunc_date(earlier.naive_utc(), date_part).unwrap();
             (later - earlier).num_weeks() as i64
         }
         DatePart::Month => {
-            let years = later.year() - earlier.year();
-            let months = later.month() - earlier.month();
-            years as i64 * 12 + months as i64
+            let years = later.year() as i64 - earlier.year() as i64;
+            let months = later.month0() as i64 - earlier.month0() as i64;
+            years * 12 + months
         }
         DatePart::Quarter => {
-            let years = later.year() - earlier.year();
-            let months = later.month() - earlier.month();
-            years as i64 * 4 + months as i64 / 3
+            let years = later.year() as i64 - earlier.year() as i64;
+            let quarters = (later.month0() / 3) as i64 - (earlier.month0() / 3) as i64;
+            years * 4 + quarters
         }
         DatePart::Year => (later.year() - earlier.year()) as i64,
         DatePart::IsoYear => (later.iso_week().year() - earlier.iso_week().year()) as i64,
@@ -876,18 +1907,26 @@ pub(crate) fn date_diff(later: Date<Utc>, earlier: Date<Utc>, date_part: DatePar
         | DatePart::Minute
         | DatePart::Hour
         | DatePart::DayOfWeek
-        | DatePart::DayOfYear => panic!("date_diff(_, _, {:?}) is not supported", date_part),
-    }
+        | DatePart::DayOfYear => {
+            return Err(Error::NotSupported(format!(
+                "date_diff(_, _, {}) is not supported",
+                date_part
+            )))
+        }
+    };
+    Ok(Some(diff))
 }
 
 fn timestamp_add(
     ts: DateTime<Utc>,
     amount: i64,
     date_part: DatePart,
-) -> Result<Option<DateTime<Utc>>, String> {
-    let ok = ts + timestamp_duration(amount, date_part)?;
+) -> Result<Option<DateTime<Utc>>, Error> {
+    let ok = timestamp_duration(amount, date_part)?
+        .and_then(|duration| ts.checked_add_signed(duration))
+        .ok_or_else(|| Error::DataException(OVERFLOW.to_string()))?;
     if is_overflow_ts(ok) {
-        return Err(OVERFLOW.to_string());
+        return Err(Error::DataException(OVERFLOW.to_string()));
     }
     Ok(Some(ok))
 }
@@ -896,21 +1935,26 @@ fn timestamp_sub(
     ts: DateTime<Utc>,
     amount: i64,
     date_part: DatePart,
-) -> Result<Option<DateTime<Utc>>, String> {
-    timestamp_add(ts, -amount, date_part)
+) -> Result<Option<DateTime<Utc>>, Error> {
+    timestamp_add(
+        ts,
+        amount
+            .checked_neg()
+            .ok_or_else(|| Error::DataException(OVERFLOW.to_string()))?,
+        date_part,
+    )
 }
 
-fn timestamp_duration(amount: i64, date_part: DatePart) -> Result<Duration, String> {
-    Ok(match date_part {
-        DatePart::Nanosecond => {
-            return Err("timestamp_add/subtract(_, NANOSECOND) is not supported".to_string())
-        }
-        DatePart::Microsecond => Duration::microseconds(amount),
-        DatePart::Millisecond => Duration::milliseconds(amount),
-        DatePart::Second => Duration::seconds(amount),
-        DatePart::Minute => Duration::minutes(amount),
-        DatePart::Hour => Duration::hours(amount),
-        DatePart::Day => Duration::days(amount),
+/// The duration of `amount` units of `date_part`, or None if it overflows.
+fn timestamp_duration(amount: i64, date_part: DatePart) -> Result<Option<Duration>, Error> {
+    let micros = match date_part {
+        DatePart::Nanosecond => return Ok(Some(Duration::nanoseconds(amount))),
+        DatePart::Microsecond => 1,
+        DatePart::Millisecond => MICROSECONDS / MILLISECONDS,
+        DatePart::Second => MICROSECONDS,
+        DatePart::Minute => 60 * MICROSECONDS,
+        DatePart::Hour => 60 * 60 * MICROSECONDS,
+        DatePart::Day => 24 * 60 * 60 * MICROSECONDS,
         DatePart::DayOfWeek
         | DatePart::DayOfYear
         | DatePart::Week(_)
@@ -919,45 +1963,46 @@ fn timestamp_duration(amount: i64, date_part: DatePart) -> Result<Duration, Stri
         | DatePart::Quarter
         | DatePart::Year
         | DatePart::IsoYear => {
-            return Err(format!(
-                "timestamp_add/subtract(_, {:?}) is not supported",
+            return Err(Error::NotSupported(format!(
+                "timestamp_add/subtract(_, {}) is not supported",
                 date_part,
-            ))
+            )))
         }
-    })
+    };
+    Ok(amount.checked_mul(micros).map(Duration::microseconds))
 }
 
 fn timestamp_diff(
-    later: Option<DateTime<Utc>>,
-    earlier: Option<DateTime<Utc>>,
+    later: DateTime<Utc>,
+    earlier: DateTime<Utc>,
     date_part: DatePart,
-) -> Result<Option<i64>, String> {
-    match (later, earlier) {
-        (Some(later), Some(earlier)) => {
-            let diff = match date_part {
-                DatePart::Nanosecond => {
-                    return Err("timestamp_diff(_, NANOSECOND) is not supported".to_string())
-                }
-                DatePart::Microsecond => (later - earlier).num_microseconds().unwrap(),
-                DatePart::Millisecond => (later - earlier).num_milliseconds() as i64,
-                DatePart::Second => (later - earlier).num_seconds() as i64,
-                DatePart::Minute => (later - earlier).num_minutes() as i64,
-                DatePart::Hour => (later - earlier).num_hours() as i64,
-                DatePart::Day
-                | DatePart::Week(_)
-                | DatePart::IsoWeek
-                | DatePart::Month
-                | DatePart::Quarter
-                | DatePart::Year
-                | DatePart::IsoYear => date_diff(later.date(), earlier.date(), date_part),
-                DatePart::DayOfWeek | DatePart::DayOfYear => {
-                    panic!("timestamp_diff(_, _, {:?}) is not supported", date_part)
-                }
-            };
-            Ok(Some(diff))
+) -> Result<Option<i64>, Error> {
+    let duration = later - earlier;
+    let diff = match date_part {
+        DatePart::Nanosecond => duration.num_nanoseconds(),
+        DatePart::Microsecond => duration.num_microseconds(),
+        DatePart::Millisecond => Some(duration.num_milliseconds()),
+        DatePart::Second => Some(duration.num_seconds()),
+        DatePart::Minute => Some(duration.num_minutes()),
+        DatePart::Hour => Some(duration.num_hours()),
+        // A day is always 24 hours, even when the clocks change.
+        DatePart::Day => Some(duration.num_days()),
+        DatePart::DayOfWeek
+        | DatePart::DayOfYear
+        | DatePart::Week(_)
+        | DatePart::IsoWeek
+        | DatePart::Month
+        | DatePart::Quarter
+        | DatePart::Year
+        | DatePart::IsoYear => {
+            return Err(Error::NotSupported(format!(
+                "timestamp_diff(_, _, {}) is not supported",
+                date_part
+            )))
         }
-        _ => Ok(None),
-    }
+    };
+    let diff = diff.ok_or_else(|| Error::DataException("timestamp_diff overflowed".to_string()))?;
+    Ok(Some(diff))
 }
 
 fn epoch_date(d: Date<Utc>) -> i32 {
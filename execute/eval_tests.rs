@@ -1,7 +1,17 @@
-use ast::DatePart;
-use chrono::Weekday;
+use std::sync::Mutex;
+
+use ast::{Column, DatePart, Scalar, Value, F};
+use chrono::*;
+use kernel::{Array, DataType, Error, F64Array, I64Array, RecordBatch, StringArray};
+use storage::Storage;
 
 use crate::eval::*;
+use crate::json::*;
+use crate::time_zone::Zone;
+
+fn date_from_ymd(year: i64, month: i64, day: i64) -> Date<Utc> {
+    date_from_year_month_day(year, month, day).unwrap().unwrap()
+}
 
 #[test]
 fn test_extract_from_date() {
@@ -39,7 +49,7 @@ fn test_extract_from_date() {
     ];
     for (year, month, day, part, expect) in cases {
         let date = date_from_ymd(year, month, day);
-        let found = extract_from_date(date, part);
+        let found = extract_from_date(date, part).unwrap().unwrap();
         assert_eq!(
             expect, found,
             "extract({:?} from '{}-{}-{}')",
@@ -64,11 +74,15 @@ fn test_date_diff() {
         (2020, 1, 1, 2001, 1, 1, DatePart::Week(Weekday::Thu), 991),
         (2020, 1, 1, 2001, 1, 1, DatePart::Week(Weekday::Fri), 991),
         (2020, 1, 1, 2001, 1, 1, DatePart::Week(Weekday::Sat), 991),
+        (2020, 1, 1, 2019, 12, 31, DatePart::Month, 1),
+        (2020, 1, 1, 2019, 12, 31, DatePart::Quarter, 1),
+        (2020, 4, 1, 2020, 3, 31, DatePart::Quarter, 1),
+        (2020, 3, 31, 2020, 1, 1, DatePart::Quarter, 0),
     ];
     for (y1, m1, d1, y2, m2, d2, part, expect) in cases {
         let d1 = date_from_ymd(y1, m1, d1);
         let d2 = date_from_ymd(y2, m2, d2);
-        let found = date_diff(d1, d2, part);
+        let found = date_diff(d1, d2, part).unwrap().unwrap();
         assert_eq!(expect, found, "date_diff('{}', '{}', {:?})", d1, d2, part);
     }
 }
@@ -87,6 +101,8 @@ fn test_date_add_sub() {
         (1, 1, 1, 1, DatePart::Day, 1, 1, 2),
         (1, 1, 1, 1, DatePart::Month, 1, 2, 1),
         (1, 1, 1, 1, DatePart::Year, 2, 1, 1),
+        (2019, 1, 31, 1, DatePart::Month, 2019, 2, 28),
+        (2020, 1, 31, 1, DatePart::Month, 2020, 2, 29),
     ];
     for (y1, m1, d1, amount, part, y2, m2, d2) in cases {
         let date = date_from_ymd(y1, m1, d1);
@@ -108,6 +124,81 @@ fn test_date_add_sub() {
     }
 }
 
+#[test]
+fn test_date_trunc() {
+    let cases = vec![
+        (2020, 5, 14, DatePart::Week(Weekday::Sun), 2020, 5, 10),
+        (2020, 5, 14, DatePart::Week(Weekday::Fri), 2020, 5, 8),
+        (2020, 5, 14, DatePart::IsoWeek, 2020, 5, 11),
+        (2020, 5, 14, DatePart::Month, 2020, 5, 1),
+        (2020, 5, 14, DatePart::Quarter, 2020, 4, 1),
+        (2020, 12, 31, DatePart::Quarter, 2020, 10, 1),
+        (2020, 5, 14, DatePart::Year, 2020, 1, 1),
+        (2021, 1, 1, DatePart::IsoYear, 2019, 12, 30),
+    ];
+    for (y1, m1, d1, part, y2, m2, d2) in cases {
+        let date = date_from_ymd(y1, m1, d1);
+        let found = date_trunc(date, part).unwrap().unwrap();
+        assert_eq!(
+            date_from_ymd(y2, m2, d2),
+            found,
+            "date_trunc('{}', {})",
+            date,
+            part
+        );
+    }
+    assert!(date_trunc(date_from_ymd(2020, 1, 1), DatePart::Hour).is_err());
+}
+
+#[test]
+fn test_time_zones() {
+    let utc = Zone::parse("UTC").unwrap();
+    let la = Zone::parse("America/Los_Angeles").unwrap();
+    let india = Zone::parse("+05:30").unwrap();
+    assert_eq!(india, Zone::parse("UTC+5:30").unwrap());
+    assert!(Zone::parse("Mars/Olympus_Mons").is_err());
+    assert!(Zone::parse("+25").is_err());
+    let ts = |value: &str| timestamp_from_string(value, utc).unwrap().unwrap();
+    // 2020-03-08 is when the clocks go forward in Los Angeles.
+    let noon = ts("2020-03-08 12:00:00");
+    let cases = vec![
+        (la, DatePart::Day, "2020-03-08 08:00:00"),
+        (la, DatePart::Hour, "2020-03-08 12:00:00"),
+        (la, DatePart::Month, "2020-03-01 08:00:00"),
+        (india, DatePart::Day, "2020-03-07 18:30:00"),
+        (india, DatePart::Hour, "2020-03-08 11:30:00"),
+        (utc, DatePart::Week(Weekday::Mon), "2020-03-02 00:00:00"),
+    ];
+    for (zone, part, expect) in cases {
+        let found = timestamp_trunc(noon, part, zone).unwrap().unwrap();
+        assert_eq!(ts(expect), found, "timestamp_trunc({}, {:?})", part, zone);
+    }
+    assert_eq!(
+        Some(5),
+        extract_from_timestamp(noon, DatePart::Hour, la).unwrap()
+    );
+    assert_eq!(
+        Some(7),
+        extract_from_timestamp(ts("2020-03-08 01:00:00"), DatePart::Day, la).unwrap()
+    );
+    assert_eq!(noon, ts("2020-03-08 05:00:00 America/Los_Angeles"));
+    assert_eq!(noon, ts("2020-03-08T17:30:00+05:30"));
+    assert_eq!(noon, ts("2020-03-08 12:00:00Z"));
+    assert_eq!(
+        ts("2020-03-08 10:00:00"),
+        timestamp_from_string("2020-03-08 02:00:00", la)
+            .unwrap()
+            .unwrap(),
+        "2:00 doesn't exist in Los Angeles, so it is shifted forward",
+    );
+    assert!(timestamp_from_string("2020-13-01", utc).is_err());
+    assert_eq!(
+        Some("2020-03-08 05:00:00 PDT -07:00".to_string()),
+        format_timestamp("%F %T %Z %Ez", ts("2020-03-08 12:00:00"), la).unwrap()
+    );
+    assert!(format_timestamp("%Q", noon, utc).is_err());
+}
+
 #[test]
 fn test_substr() {
     let cases = vec![
@@ -209,6 +300,50 @@ fn test_regexp_extract() {
     }
 }
 
+#[test]
+fn test_regexp_extract_all() {
+    let cases = vec![
+        ("", "abc", vec![]),
+        ("abcabc", "b", vec!["b", "b"]),
+        ("a1b22c333", "[0-9]+", vec!["1", "22", "333"]),
+        ("a1b22c333", "[a-z]([0-9]+)", vec!["1", "22", "333"]),
+        ("abc", "x(y)?", vec![]),
+        ("ab", "a(x)?", vec![""]),
+    ];
+    for (value, pattern, expect) in cases {
+        let expect: Vec<String> = expect.iter().map(|s| s.to_string()).collect();
+        assert_eq!(expect, regexp_extract_all(value, pattern).unwrap());
+    }
+    assert!(regexp_extract_all("abc", "(a)(b)").is_err());
+    assert!(regexp_extract_all("abc", "(").is_err());
+}
+
+#[test]
+fn test_split() {
+    let cases = vec![
+        ("a,b,,c", ",", vec!["a", "b", "", "c"]),
+        ("", ",", vec![""]),
+        ("abc", "", vec!["a", "b", "c"]),
+        ("", "", vec![""]),
+        ("a::b", "::", vec!["a", "b"]),
+        ("щцф", "", vec!["щ", "ц", "ф"]),
+    ];
+    for (value, delimiter, expect) in cases {
+        let expect: Vec<String> = expect.iter().map(|s| s.to_string()).collect();
+        assert_eq!(expect, split_string(value, delimiter));
+    }
+    assert_eq!(
+        vec![b"a".to_vec(), b"".to_vec(), b"\x00".to_vec()],
+        split_bytes(b"a,,\x00", b",")
+    );
+    assert_eq!(
+        vec![vec![0x00], vec![0xff]],
+        split_bytes(&[0x00, 0xff], b"")
+    );
+    assert_eq!(vec![b"".to_vec()], split_bytes(b"", b""));
+    assert_eq!(vec![b"ab".to_vec()], split_bytes(b"ab", b"abc"));
+}
+
 #[test]
 fn test_left_pad() {
     let cases = vec![
@@ -367,3 +502,132 @@ fn test_regexp_replace() {
         );
     }
 }
+
+#[test]
+fn test_soundex_translate() {
+    let cases = vec![
+        ("Robert", "R163"),
+        ("Rupert", "R163"),
+        ("Ashcraft", "A261"),
+        ("Tymczak", "T522"),
+        ("Pfister", "P236"),
+        ("Honeyman", "H555"),
+        ("  - 42", ""),
+    ];
+    for (value, expect) in cases {
+        assert_eq!(expect, soundex(value), "soundex({})", value);
+    }
+    let found = translate("This is a cookie", "sco", "zk").unwrap().unwrap();
+    assert_eq!("Thiz iz a kkie", found);
+    assert!(translate("abc", "aba", "xyz").is_err());
+}
+
+#[test]
+fn test_eval_safe() {
+    let storage = Mutex::new(Storage::default());
+    let x = Column::fresh("x", DataType::I64);
+    let y = Column::fresh("y", DataType::I64);
+    let input = RecordBatch::new(vec![
+        (
+            x.canonical_name(),
+            I64Array::from_values(vec![6, 6, 6]).as_any(),
+        ),
+        (
+            y.canonical_name(),
+            I64Array::from_values(vec![2, 0, 3]).as_any(),
+        ),
+    ]);
+    // Only the row that fails is NULL.
+    let div = Scalar::SafeCall(Box::new(F::DivInt64(
+        Scalar::Column(x.clone()),
+        Scalar::Column(y.clone()),
+    )));
+    let output = eval(&div, &input, &storage, 0).unwrap().as_i64();
+    assert_eq!(
+        vec![Some(3), None, Some(2)],
+        (0..3).map(|i| output.get(i)).collect::<Vec<_>>()
+    );
+    // Errors in the arguments are not caught.
+    let div = Scalar::SafeCall(Box::new(F::DivInt64(
+        Scalar::Call(Box::new(F::DivInt64(Scalar::Column(x), Scalar::Column(y)))),
+        Scalar::Literal(Value::I64(Some(1))),
+    )));
+    assert!(eval(&div, &input, &storage, 0).is_err());
+}
+
+#[test]
+fn test_div_f64() {
+    assert_eq!(Some(2.5), div_f64(5.0, 2.0).unwrap());
+    assert!(matches!(div_f64(1.0, 0.0), Err(Error::DivisionByZero(_))));
+    assert!(matches!(
+        div_f64(f64::MAX, 0.5),
+        Err(Error::DataException(_))
+    ));
+    assert_eq!(Some(f64::INFINITY), div_f64(f64::INFINITY, 1.0).unwrap());
+    assert_eq!(Some(f64::INFINITY), safe_divide(f64::INFINITY, 1.0));
+    assert_eq!(None, safe_divide(1.0, 0.0));
+    assert_eq!(None, safe_divide(f64::MAX, 0.5));
+}
+
+#[test]
+fn test_hex_base64() {
+    assert_eq!("00ff10", to_hex(&[0x00, 0xff, 0x10]));
+    assert_eq!(Some(vec![0x00, 0xff, 0x10]), from_hex("00FF10").unwrap());
+    assert_eq!(Some(vec![0x0a, 0xbc]), from_hex("abc").unwrap());
+    assert_eq!(Some(vec![]), from_hex("").unwrap());
+    assert!(from_hex("0g").is_err());
+    assert_eq!(Some(b"hello".to_vec()), from_base64("aGVsbG8=").unwrap());
+    assert!(from_base64("not base64!").is_err());
+}
+
+#[test]
+fn test_format() {
+    let args = vec![
+        I64Array::from_options(vec![Some(-42), None]).as_any(),
+        F64Array::from_values(vec![3.14159, 3.14159]).as_any(),
+        StringArray::from_str_values(vec!["abc", "abc"]).as_any(),
+    ];
+    let cases = vec![
+        ("%d|%.2f|%s", Some("-42|3.14|abc")),
+        ("%+06d|%e|%T", Some("-00042|3.141590e+00|\"abc\"")),
+        ("%5i|%g|%-5s|", Some("  -42|3.14159|abc  |")),
+    ];
+    for (format, expect) in cases {
+        let found = format_string(format, &args, 0).unwrap();
+        assert_eq!(expect.map(String::from), found, "format({})", format);
+    }
+    assert_eq!(None, format_string("%d|%f|%s", &args, 1).unwrap());
+    assert_eq!(
+        Some("NULL|3.1|abc".to_string()),
+        format_string("%t|%.1f|%t", &args, 1).unwrap()
+    );
+    assert!(format_string("%d", &args, 0).is_err());
+    assert!(format_string("%d|%f|%s|%s", &args, 0).is_err());
+    assert!(format_string("%s|%f|%s", &args, 0).is_ok());
+}
+
+#[test]
+fn test_json_path() {
+    let document = r#"{"a": {"b c": [1, "two", null]}, "d": true}"#;
+    let cases = vec![
+        ("$", None),
+        ("$.d", Some("true")),
+        ("$.a.\"b c\"[1]", Some("two")),
+        ("$['a']['b c'][0]", Some("1")),
+        ("$.a[\"b c\"][2]", None),
+        ("$.a.b", None),
+        ("$.d[0]", None),
+    ];
+    for (path, expect) in cases {
+        let found = json_value_string(document, path).unwrap();
+        assert_eq!(expect.map(String::from), found, "json_value({})", path);
+    }
+    assert_eq!(
+        Some(r#"[1,"two",null]"#.to_string()),
+        json_query_string(document, "$.a['b c']").unwrap()
+    );
+    assert_eq!(None, json_query_string("{", "$").unwrap());
+    assert!(json_value_string(document, "a.b").is_err());
+    assert!(json_value_string(document, "$.a[").is_err());
+    assert!(json_value_string(document, "$..a").is_err());
+}
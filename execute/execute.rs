@@ -6,6 +6,7 @@ use std::{
 use ast::{Expr, Index, *};
 use globals::Global;
 use kernel::*;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
 use remote_execution::RecordStream;
 use storage::{Heap, Page, Storage, PAGE_SIZE};
 
@@ -20,6 +21,8 @@ pub enum Node {
         projects: Vec<Column>,
         predicates: Vec<Scalar>,
         table: Table,
+        sample: Option<Sample>,
+        rng: SmallRng,
         scan: Option<Vec<Arc<Page>>>,
     },
     IndexScan {
@@ -31,6 +34,18 @@ pub enum Node {
         table: Table,
         input: Box<Node>,
     },
+    IndexOrderScan {
+        projects: Vec<Column>,
+        predicates: Vec<Scalar>,
+        index: Index,
+        table: Table,
+        limit: usize,
+        // The number of rows returned so far.
+        found: usize,
+        // The index key of the last row fetched, which the next batch starts after.
+        after: Option<Vec<u8>>,
+        done: bool,
+    },
     Filter {
         predicates: Vec<Scalar>,
         input: Box<Node>,
@@ -85,6 +100,12 @@ pub enum Node {
         aggregate: Vec<AggregateExpr>,
         input: Box<Node>,
     },
+    PartialAggregate {
+        finished: bool,
+        group_by: Vec<Column>,
+        aggregate: Vec<AggregateExpr>,
+        input: Box<Node>,
+    },
     Limit {
         cursor: usize,
         limit: usize,
@@ -99,6 +120,17 @@ pub enum Node {
         left: Box<Node>,
         right: Box<Node>,
     },
+    Iterate {
+        finished: bool,
+        name: String,
+        columns: Vec<Column>,
+        recursion_limit: usize,
+        stage: i32,
+        iteration: usize,
+        working: Heap,
+        input: Box<Node>,
+        step: Expr,
+    },
     Broadcast {
         stage: i32,
         input: Option<Expr>,
@@ -116,6 +148,8 @@ pub enum Node {
     },
     Insert {
         finished: bool,
+        /// The number of rows inserted so far.
+        count: i64,
         table: Table,
         indexes: Vec<Index>,
         input: Box<Node>,
@@ -128,6 +162,8 @@ pub enum Node {
         input: Box<Node>,
     },
     Delete {
+        /// The number of rows deleted so far.
+        count: i64,
         table: Table,
         tid: Column,
         input: Box<Node>,
@@ -137,6 +173,8 @@ pub enum Node {
         stmts: Vec<Node>,
     },
     Call {
+        /// The number of rows deleted by TRUNCATE.
+        count: i64,
         procedure: Procedure,
         input: Box<Node>,
     },
@@ -158,12 +196,26 @@ impl Node {
                 projects,
                 predicates,
                 table,
-            } => Node::SeqScan {
-                projects,
-                predicates,
-                table,
-                scan: None,
-            },
+                sample,
+            } => {
+                // REPEATABLE (seed) samples the same pages and rows as long as the table doesn't change.
+                // Each worker mixes in its id, so workers don't all keep the same positions of their pages.
+                let rng = match sample.and_then(|sample| sample.repeatable) {
+                    Some(seed) => {
+                        let worker = globals::WORKER.get() as u64;
+                        SmallRng::seed_from_u64(seed as u64 ^ (worker << 32))
+                    }
+                    None => SmallRng::from_entropy(),
+                };
+                Node::SeqScan {
+                    projects,
+                    predicates,
+                    table,
+                    sample,
+                    rng,
+                    scan: None,
+                }
+            }
             IndexScan {
                 include_existing,
                 projects,
@@ -181,6 +233,22 @@ impl Node {
                 table,
                 input: Box::new(Node::compile(*input)),
             },
+            IndexOrderScan {
+                projects,
+                predicates,
+                index,
+                table,
+                limit,
+            } => Node::IndexOrderScan {
+                projects,
+                predicates,
+                index,
+                table,
+                limit,
+                found: 0,
+                after: None,
+                done: false,
+            },
             Filter { predicates, input } => Node::Filter {
                 predicates,
                 input: Box::new(Node::compile(*input)),
@@ -267,6 +335,16 @@ impl Node {
                 aggregate,
                 input: Box::new(Node::compile(*input)),
             },
+            PartialAggregate {
+                group_by,
+                aggregate,
+                input,
+            } => Node::PartialAggregate {
+                finished: false,
+                group_by,
+                aggregate,
+                input: Box::new(Node::compile(*input)),
+            },
             Limit {
                 limit,
                 offset,
@@ -285,6 +363,24 @@ impl Node {
                 left: Box::new(Node::compile(*left)),
                 right: Box::new(Node::compile(*right)),
             },
+            Iterate {
+                name,
+                columns,
+                recursion_limit,
+                stage,
+                base,
+                step,
+            } => Node::Iterate {
+                finished: false,
+                name,
+                columns,
+                recursion_limit,
+                stage: stage.unwrap(),
+                iteration: 0,
+                working: Heap::default(),
+                input: Box::new(Node::compile(*base)),
+                step: *step,
+            },
             Broadcast { input, stage } => Node::Broadcast {
                 input: Some(*input),
                 stream: None,
@@ -309,8 +405,10 @@ impl Node {
                 indexes,
                 input,
                 columns,
+                ..
             } => Node::Insert {
                 finished: false,
+                count: 0,
                 table,
                 indexes,
                 input: Box::new(Node::compile(*input)),
@@ -326,6 +424,7 @@ impl Node {
                 input: Box::new(Node::compile(*input)),
             },
             Delete { table, tid, input } => Node::Delete {
+                count: 0,
                 table,
                 tid,
                 input: Box::new(Node::compile(*input)),
@@ -341,6 +440,7 @@ impl Node {
                 }
             }
             Call { procedure, input } => Node::Call {
+                count: 0,
                 procedure,
                 input: Box::new(Node::compile(*input)),
             },
@@ -351,6 +451,7 @@ impl Node {
             Leaf { .. }
             | LogicalSingleGet
             | LogicalGet { .. }
+            | LogicalIndexGet { .. }
             | LogicalFilter { .. }
             | LogicalOut { .. }
             | LogicalMap { .. }
@@ -363,14 +464,22 @@ impl Node {
             | LogicalLimit { .. }
             | LogicalSort { .. }
             | LogicalUnion { .. }
+            | LogicalIterate { .. }
             | LogicalInsert { .. }
             | LogicalValues { .. }
             | LogicalUpdate { .. }
             | LogicalDelete { .. }
             | LogicalCreateDatabase { .. }
             | LogicalCreateTable { .. }
+            | LogicalCreateTableAs { .. }
             | LogicalCreateIndex { .. }
+            | LogicalCreateSequence { .. }
             | LogicalDrop { .. }
+            | LogicalAddColumn { .. }
+            | LogicalRename { .. }
+            | LogicalCreateView { .. }
+            | LogicalCreateFunction { .. }
+            | LogicalRefresh { .. }
             | LogicalScript { .. }
             | LogicalRewrite { .. }
             | LogicalCall { .. }
@@ -395,22 +504,42 @@ impl Node {
                 projects,
                 predicates,
                 table,
+                sample,
+                rng,
                 scan,
             } => {
                 if scan.is_none() {
-                    *scan = Some(storage.lock().unwrap().table(table.id).scan());
+                    let storage = storage.lock().unwrap();
+                    let heap = storage.table(table.id);
+                    *scan = Some(match sample {
+                        Some(sample) if sample.method == SampleMethod::System => {
+                            heap.sample(sample.fraction(), rng)
+                        }
+                        _ => heap.scan(),
+                    });
                 }
                 let page = match scan.as_mut().unwrap().pop() {
                     Some(page) => page,
                     None => return Next::End,
                 };
-                let select_names = projects.iter().map(|c| c.name.clone()).collect();
+                let select_schema = projects
+                    .iter()
+                    .map(|c| (c.name.clone(), c.data_type.clone()))
+                    .collect();
                 let query_names = projects
                     .iter()
                     .map(|c| (c.name.clone(), c.canonical_name()))
                     .collect();
-                let input = page.select(&select_names).rename(&query_names);
-                let boolean = crate::eval::all(predicates, &input, storage, txn)?;
+                let input = page.select_or_null(&select_schema).rename(&query_names);
+                let mut boolean = crate::eval::all(predicates, &input, storage, txn)?;
+                if let Some(sample) = sample {
+                    if sample.method == SampleMethod::Bernoulli {
+                        let keep = (0..input.len())
+                            .map(|_| rng.gen_bool(sample.fraction()))
+                            .collect();
+                        boolean = boolean.and(&BoolArray::from_values(keep));
+                    }
+                }
                 Next::Page(input.compress(&boolean))
             }
             Node::IndexScan {
@@ -424,7 +553,7 @@ impl Node {
             } => {
                 let input = input.next(storage, txn)?;
                 // Perform a bitmap scan on the left side of the join.
-                let keys = evaluate_index_keys(lookup, &input, storage, txn)?;
+                let keys = evaluate_index_keys(lookup, index, &input, storage, txn)?;
                 let sorted_tids = lookup_index_tids(keys, index, storage);
                 let matching_pages = storage
                     .lock()
@@ -455,6 +584,48 @@ impl Node {
                 }
                 Next::Page(output)
             }
+            Node::IndexOrderScan {
+                projects,
+                predicates,
+                index,
+                table,
+                limit,
+                found,
+                after,
+                done,
+            } => {
+                let query_names = projects
+                    .iter()
+                    .map(|c| (c.name.clone(), c.canonical_name()))
+                    .collect();
+                // Fetch rows in index order, one batch at a time, until limit rows have passed the predicates.
+                // Rows are fetched in batches, so we may return more than limit rows.
+                while !*done && *found < *limit {
+                    let (mut tids, last) = storage
+                        .lock()
+                        .unwrap()
+                        .index(index.index_id)
+                        .range_after(after.as_deref(), *limit - *found);
+                    match last {
+                        Some(key) => *after = Some(key),
+                        None => *done = true,
+                    }
+                    tids.sort();
+                    let matching_pages = storage.lock().unwrap().table(table.id).bitmap_scan(&tids);
+                    let mut output = vec![];
+                    for page in filter_pages_using_tids(projects, &tids, matching_pages) {
+                        let page = page.rename(&query_names);
+                        let boolean = crate::eval::all(predicates, &page, storage, txn)?;
+                        let page = page.compress(&boolean);
+                        *found += page.len();
+                        output.push(page);
+                    }
+                    if let Some(batch) = RecordBatch::cat(output) {
+                        return Next::Page(batch);
+                    }
+                }
+                Next::End
+            }
             Node::Filter { predicates, input } => {
                 let input = input.next(storage, txn)?;
                 let boolean = crate::eval::all(predicates, &input, storage, txn)?;
@@ -783,19 +954,22 @@ impl Node {
                 loop {
                     match input.next(storage, txn) {
                         Next::Page(batch) => {
-                            let aggregate_columns: Vec<AnyArray> = aggregate
-                                .iter()
-                                .map(|a| batch.find_always(&a.input.canonical_name()).clone())
-                                .collect();
-                            operator.insert(aggregate_columns);
+                            let aggregate_columns =
+                                crate::aggregate::find_columns(aggregate, &batch);
+                            if let Err(error) = operator.insert(aggregate_columns) {
+                                return Next::Error(error);
+                            }
                         }
                         Next::End => {
                             let mut names = vec![];
                             for e in aggregate {
                                 names.push(e.output.canonical_name());
                             }
-                            let columns = operator
-                                .finish()
+                            let mut arrays = match operator.finish() {
+                                Ok(arrays) => arrays,
+                                Err(error) => return Next::Error(error),
+                            };
+                            let columns = arrays
                                 .drain(..)
                                 .enumerate()
                                 .map(|(i, array)| (std::mem::take(&mut names[i]), array))
@@ -825,11 +999,12 @@ impl Node {
                                 .iter()
                                 .map(|c| batch.find_always(&c.canonical_name()).clone())
                                 .collect();
-                            let aggregate_columns: Vec<AnyArray> = aggregate
-                                .iter()
-                                .map(|a| batch.find_always(&a.input.canonical_name()).clone())
-                                .collect();
-                            operator.insert(group_by_columns, aggregate_columns);
+                            let aggregate_columns =
+                                crate::aggregate::find_columns(aggregate, &batch);
+                            if let Err(error) = operator.insert(group_by_columns, aggregate_columns)
+                            {
+                                return Next::Error(error);
+                            }
                         }
                         Next::Error(message) => return Next::Error(message),
                         Next::End => {
@@ -840,8 +1015,11 @@ impl Node {
                             for e in aggregate {
                                 names.push(e.output.canonical_name());
                             }
-                            let columns = operator
-                                .finish()
+                            let mut arrays = match operator.finish() {
+                                Ok(arrays) => arrays,
+                                Err(error) => return Next::Error(error),
+                            };
+                            let columns = arrays
                                 .drain(..)
                                 .enumerate()
                                 .map(|(i, array)| (std::mem::take(&mut names[i]), array))
@@ -851,6 +1029,76 @@ impl Node {
                     }
                 }
             }
+            Node::PartialAggregate {
+                finished,
+                group_by,
+                aggregate,
+                input,
+            } => {
+                if *finished {
+                    return Next::End;
+                } else {
+                    *finished = true;
+                }
+                let mut names = vec![];
+                for c in group_by.iter() {
+                    names.push(c.canonical_name());
+                }
+                for e in aggregate.iter() {
+                    names.push(e.output.canonical_name());
+                }
+                // Without group-by columns, every worker produces exactly one row, even if it has no input.
+                if group_by.is_empty() {
+                    let mut operator = crate::aggregate::SimpleAggregate::new(aggregate);
+                    loop {
+                        let batch = match input.next(storage, txn) {
+                            Next::Page(batch) => batch,
+                            Next::Error(message) => return Next::Error(message),
+                            Next::End => break,
+                        };
+                        let aggregate_columns = crate::aggregate::find_columns(aggregate, &batch);
+                        if let Err(error) = operator.insert(aggregate_columns) {
+                            return Next::Error(error);
+                        }
+                    }
+                    let mut arrays = match operator.finish() {
+                        Ok(arrays) => arrays,
+                        Err(error) => return Next::Error(error),
+                    };
+                    let columns = names.drain(..).zip(arrays.drain(..)).collect();
+                    return Next::Page(RecordBatch::new(columns));
+                }
+                let mut operator = crate::aggregate::GroupByAggregate::new(aggregate);
+                let mut empty = true;
+                loop {
+                    let batch = match input.next(storage, txn) {
+                        Next::Page(batch) => batch,
+                        Next::Error(message) => return Next::Error(message),
+                        Next::End => break,
+                    };
+                    if batch.len() == 0 {
+                        continue;
+                    }
+                    let group_by_columns: Vec<AnyArray> = group_by
+                        .iter()
+                        .map(|c| batch.find_always(&c.canonical_name()).clone())
+                        .collect();
+                    let aggregate_columns = crate::aggregate::find_columns(aggregate, &batch);
+                    if let Err(error) = operator.insert(group_by_columns, aggregate_columns) {
+                        return Next::Error(error);
+                    }
+                    empty = false;
+                }
+                if empty {
+                    return Next::End;
+                }
+                let mut arrays = match operator.finish() {
+                    Ok(arrays) => arrays,
+                    Err(error) => return Next::Error(error),
+                };
+                let columns = names.drain(..).zip(arrays.drain(..)).collect();
+                return Next::Page(RecordBatch::new(columns));
+            }
             Node::Limit {
                 cursor,
                 limit,
@@ -894,6 +1142,61 @@ impl Node {
                 Next::Error(message) => Next::Error(message),
                 Next::End => right.next(storage, txn),
             },
+            Node::Iterate {
+                finished,
+                name,
+                columns,
+                recursion_limit,
+                stage,
+                iteration,
+                working,
+                input,
+                step,
+            } => loop {
+                if *finished {
+                    return Next::End;
+                }
+                match input.next(storage, txn) {
+                    Next::Page(batch) => {
+                        // Put the columns in the same order as the working table.
+                        let batch = RecordBatch::new(
+                            columns
+                                .iter()
+                                .map(|c| {
+                                    let name = c.canonical_name();
+                                    let array = batch.find_always(&name).clone();
+                                    (name, array)
+                                })
+                                .collect(),
+                        );
+                        working.insert(&batch, txn);
+                        return Next::Page(batch);
+                    }
+                    Next::Error(message) => return Next::Error(message),
+                    Next::End => {
+                        // The rows produced by this iteration become the input to the next iteration.
+                        storage.lock().unwrap().create_temp_table(
+                            txn,
+                            name.clone(),
+                            std::mem::take(working),
+                        );
+                        // Each worker produces rows independently, so we can only stop when every worker is done.
+                        // This also ensures that every worker has finished the exchanges in this iteration before any worker starts the next one.
+                        if !any_rows(name, columns, txn, *stage)? {
+                            *finished = true;
+                            return Next::End;
+                        }
+                        *iteration += 1;
+                        if *iteration > *recursion_limit {
+                            return Next::Error(Error::DataException(format!(
+                                "Recursive query {} exceeded the limit of {} iterations",
+                                name, recursion_limit
+                            )));
+                        }
+                        *input = Box::new(Node::compile(step.clone()));
+                    }
+                }
+            },
             Node::Broadcast {
                 input,
                 stream,
@@ -939,6 +1242,7 @@ impl Node {
             }
             Node::Insert {
                 finished,
+                count,
                 table,
                 indexes,
                 input,
@@ -968,11 +1272,12 @@ impl Node {
                         .unwrap()
                         .table_mut(table.id)
                         .insert(&input, txn);
+                    *count += input.len() as i64;
                     // Update indexes.
                     for index in indexes.iter_mut() {
                         crate::index::insert(
                             storage.lock().unwrap().index_mut(index.index_id),
-                            &index.columns,
+                            index,
                             &input,
                             &tids,
                         );
@@ -1001,7 +1306,12 @@ impl Node {
                 }
                 Next::Page(RecordBatch::new(output))
             }
-            Node::Delete { table, tid, input } => {
+            Node::Delete {
+                count,
+                table,
+                tid,
+                input,
+            } => {
                 let input = input.next(storage, txn)?;
                 // If no input, try next page.
                 if input.len() == 0 {
@@ -1024,10 +1334,15 @@ impl Node {
                         && pid == tids.get(i).unwrap() as usize / storage::PAGE_SIZE
                     {
                         let rid = tids.get(i).unwrap() as usize % storage::PAGE_SIZE;
-                        page.delete(rid, txn);
+                        if !page.delete(rid, txn) {
+                            return Next::Error(Error::SerializationFailure(
+                                "Could not serialize access due to a concurrent update".to_string(),
+                            ));
+                        }
                         i += 1;
                     }
                 }
+                *count += tids.len() as i64;
                 Next::Page(input)
             }
             Node::Script { offset, stmts } => {
@@ -1046,25 +1361,59 @@ impl Node {
                 }
                 Next::End
             }
-            Node::Call { procedure, input } => {
+            Node::Call {
+                count,
+                procedure,
+                input,
+            } => {
                 let input = input.next(storage, txn)?;
                 match procedure {
                     Procedure::CreateCatalog => {
                         storage.lock().unwrap().create_catalog();
                     }
-                    Procedure::CreateTable => {
-                        storage.lock().unwrap().create_table();
+                    Procedure::CreateTable(page_size) => {
+                        let page_size = crate::eval::eval(page_size, &input, storage, txn)?
+                            .as_i64()
+                            .get(0)
+                            .unwrap();
+                        let table_id = storage.lock().unwrap().create_table(page_size as usize);
+                        return Next::Page(RecordBatch::new(vec![(
+                            "table_id".to_string(),
+                            AnyArray::I64(I64Array::from_values(vec![table_id])),
+                        )]));
                     }
                     Procedure::CreateIndex => {
                         storage.lock().unwrap().create_index();
                     }
-                    Procedure::Assert(test, description) => {
+                    Procedure::CreateSequence(start, increment) => {
+                        let start = crate::eval::eval(start, &input, storage, txn)?
+                            .as_i64()
+                            .get(0)
+                            .unwrap();
+                        let increment = crate::eval::eval(increment, &input, storage, txn)?
+                            .as_i64()
+                            .get(0)
+                            .unwrap();
+                        storage.lock().unwrap().create_sequence(start, increment);
+                    }
+                    Procedure::Truncate(table) => {
+                        match storage.lock().unwrap().truncate_table(table.id, txn) {
+                            Some(truncated) => *count += truncated as i64,
+                            None => {
+                                return Next::Error(Error::SerializationFailure(
+                                    "Could not serialize access due to a concurrent update"
+                                        .to_string(),
+                                ))
+                            }
+                        }
+                    }
+                    Procedure::Assert(test, error) => {
                         let test = crate::eval::eval(test, &input, storage, txn)?
                             .as_bool()
                             .get(0)
                             .unwrap_or(false);
                         if !test {
-                            return Next::Error(description.clone());
+                            return Next::Error(error.clone());
                         }
                     }
                 };
@@ -1084,11 +1433,25 @@ impl Node {
         }
     }
 
+    /// The number of rows inserted, updated or deleted by this node and the statements it contains.
+    pub fn rows_affected(&self) -> i64 {
+        match self {
+            // UPDATE is an Insert of the new row versions on top of a Delete of the old versions,
+            // so we don't count the rows of the inner Delete a second time.
+            Node::Insert { count, .. } | Node::Delete { count, .. } | Node::Call { count, .. } => {
+                *count
+            }
+            Node::Script { stmts, .. } => stmts.iter().map(Node::rows_affected).sum(),
+            _ => 0,
+        }
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             Node::TableFreeScan { .. } => "TableFreeScan",
             Node::SeqScan { .. } => "SeqScan",
             Node::IndexScan { .. } => "IndexScan",
+            Node::IndexOrderScan { .. } => "IndexOrderScan",
             Node::Filter { .. } => "Filter",
             Node::Out { .. } => "Out",
             Node::Map { .. } => "Map",
@@ -1098,9 +1461,11 @@ impl Node {
             Node::GetTempTable { .. } => "GetTempTable",
             Node::SimpleAggregate { .. } => "SimpleAggregate",
             Node::GroupByAggregate { .. } => "GroupByAggregate",
+            Node::PartialAggregate { .. } => "PartialAggregate",
             Node::Limit { .. } => "Limit",
             Node::Sort { .. } => "Sort",
             Node::Union { .. } => "Union",
+            Node::Iterate { .. } => "Iterate",
             Node::Broadcast { .. } => "Broadcast",
             Node::Exchange { .. } => "Exchange",
             Node::Gather { .. } => "Gather",
@@ -1138,7 +1503,7 @@ fn build_or_empty(
     storage: &Mutex<Storage>,
     txn: i64,
     left_schema: &Vec<(String, DataType)>,
-) -> Result<RecordBatch, String> {
+) -> Result<RecordBatch, Error> {
     match build(left, storage, txn) {
         Next::Page(batch) => Ok(batch),
         Next::Error(message) => Err(message),
@@ -1165,15 +1530,19 @@ fn build(input: &mut Node, storage: &Mutex<Storage>, txn: i64) -> Next {
 
 fn evaluate_index_keys(
     lookup: &Vec<Scalar>,
+    index: &Index,
     input: &RecordBatch,
     storage: &Mutex<Storage>,
     txn: i64,
-) -> Result<PackedBytes, String> {
+) -> Result<PackedBytes, Error> {
     let columns: Result<Vec<_>, _> = lookup
         .iter()
         .map(|scalar| crate::eval::eval(scalar, &input, storage, txn))
         .collect();
-    let keys = crate::index::byte_key_prefix(columns?.iter().map(|c| c).collect());
+    let keys = crate::index::byte_key_prefix(
+        columns?.iter().map(|c| c).collect(),
+        &index.descending[..lookup.len()],
+    );
     Ok(keys)
 }
 
@@ -1196,7 +1565,10 @@ fn filter_pages_using_tids(
     sorted_tids: &Vec<i64>,
     matching_pages: Vec<Arc<Page>>,
 ) -> Vec<RecordBatch> {
-    let select_names = projects.iter().map(|c| c.name.clone()).collect();
+    let select_schema = projects
+        .iter()
+        .map(|c| (c.name.clone(), c.data_type.clone()))
+        .collect();
     let mut i = 0;
     let mut j = 0;
     let mut filtered_pages = vec![];
@@ -1211,7 +1583,9 @@ fn filter_pages_using_tids(
         } else {
             // Filter the current page.
             let rids = rids(&sorted_tids[i..], pid);
-            let page = matching_pages[j].select(&select_names).gather(&rids);
+            let page = matching_pages[j]
+                .select_or_null(&select_schema)
+                .gather(&rids);
             filtered_pages.push(page);
             // Go to the next page.
             j += 1
@@ -1233,6 +1607,27 @@ fn rids(tids: &[i64], pid: usize) -> I32Array {
     rids
 }
 
+/// Check if the temp table produced by the last iteration of a recursive query contains any rows on any worker.
+fn any_rows(name: &String, columns: &Vec<Column>, txn: i64, stage: i32) -> Result<bool, Error> {
+    let expr = Limit {
+        limit: 1,
+        offset: 0,
+        input: Box::new(GetTempTable {
+            name: name.clone(),
+            columns: columns.clone(),
+        }),
+    };
+    let mut stream = remote_execution::broadcast(&expr, txn, stage);
+    let mut any = false;
+    loop {
+        match stream.next() {
+            Next::Page(batch) => any = any || batch.len() > 0,
+            Next::Error(message) => return Err(message),
+            Next::End => return Ok(any),
+        }
+    }
+}
+
 /// We select a single worker as the "leader" for each transaction in a round-robin manner.
 /// The leader is responsible for executing operations that take place on a single node.
 fn leader(txn: i64) -> i32 {
@@ -1,14 +1,18 @@
+use ast::Index;
 use kernel::*;
 use storage::*;
 
-pub(crate) fn insert(index: &mut Art, columns: &Vec<String>, input: &RecordBatch, tids: &I64Array) {
+pub(crate) fn insert(art: &mut Art, index: &Index, input: &RecordBatch, tids: &I64Array) {
     // Convert each row into bytes that match the lexicographic order of rows.
-    let index_columns: Vec<&AnyArray> =
-        columns.iter().map(|name| input.find_always(name)).collect();
-    let keys = byte_keys(index_columns, tids);
+    let index_columns: Vec<&AnyArray> = index
+        .columns
+        .iter()
+        .map(|name| input.find_always(name))
+        .collect();
+    let keys = byte_keys(index_columns, &index.descending, tids);
     // Insert the keys into the index.
     for i in 0..keys.len() {
-        index.insert(keys.get(i), tids.get(i).unwrap());
+        art.insert(keys.get(i), tids.get(i).unwrap());
     }
 }
 
@@ -67,23 +71,27 @@ impl PackedBytes {
     }
 }
 
-pub(crate) fn byte_keys(columns: Vec<&AnyArray>, tids: &I64Array) -> PackedBytes {
+pub(crate) fn byte_keys(
+    columns: Vec<&AnyArray>,
+    descending: &[bool],
+    tids: &I64Array,
+) -> PackedBytes {
     let byte_len = columns.iter().map(|c| byte_len(*c)).sum::<usize>()
         + tids.len() * std::mem::size_of::<i64>();
     let mut result = PackedBytes::with_capacity(columns[0].len(), byte_len);
     for i in 0..columns[0].len() {
-        push_byte_key_prefix(&mut result, &columns, i);
+        push_byte_key_prefix(&mut result, &columns, descending, i);
         result.push(&byte_key_i64(tids.get(i).unwrap()));
         result.next();
     }
     result
 }
 
-pub(crate) fn byte_key_prefix(columns: Vec<&AnyArray>) -> PackedBytes {
+pub(crate) fn byte_key_prefix(columns: Vec<&AnyArray>, descending: &[bool]) -> PackedBytes {
     let byte_len = columns.iter().map(|c| byte_len(*c)).sum::<usize>();
     let mut result = PackedBytes::with_capacity(columns[0].len(), byte_len);
     for i in 0..columns[0].len() {
-        push_byte_key_prefix(&mut result, &columns, i);
+        push_byte_key_prefix(&mut result, &columns, descending, i);
         result.next();
     }
     result
@@ -97,11 +105,20 @@ fn byte_len(column: &AnyArray) -> usize {
         AnyArray::Date(array) => array.len() * std::mem::size_of::<i32>(),
         AnyArray::Timestamp(array) => array.len() * std::mem::size_of::<i64>(),
         AnyArray::String(array) => array.byte_len(),
+        AnyArray::Json(array) => array.byte_len(),
+        AnyArray::Bytes(array) => array.byte_len(),
+        AnyArray::Array(_) => panic!("ARRAY values are not ordered, so they can't be an index key"),
     }
 }
 
-fn push_byte_key_prefix(result: &mut PackedBytes, columns: &Vec<&AnyArray>, i: usize) {
+fn push_byte_key_prefix(
+    result: &mut PackedBytes,
+    columns: &Vec<&AnyArray>,
+    descending: &[bool],
+    i: usize,
+) {
     for j in 0..columns.len() {
+        let start = result.bytes.len();
         match columns[j] {
             AnyArray::Bool(column) => {
                 let b = match column.get(i) {
@@ -164,6 +181,34 @@ fn push_byte_key_prefix(result: &mut PackedBytes, columns: &Vec<&AnyArray>, i: u
                     result.push(&[0]);
                 }
             }
+            AnyArray::Json(_) => {
+                panic!("JSON values are not ordered, so they can't be an index key")
+            }
+            AnyArray::Array(_) => {
+                panic!("ARRAY values are not ordered, so they can't be an index key")
+            }
+            AnyArray::Bytes(column) => {
+                if let Some(value) = column.get_bytes(i) {
+                    // Values start with 1 so they sort after NULL without colliding with it.
+                    // Unlike strings, bytes can contain 0, so 0 is escaped as [0, 1] and the
+                    // key is terminated with [0, 0], which sorts before any escaped byte.
+                    result.push(&[1]);
+                    for byte in value {
+                        if *byte == 0 {
+                            result.push(&[0, 1]);
+                        } else {
+                            result.push(&[*byte]);
+                        }
+                    }
+                    result.push(&[0, 0]);
+                } else {
+                    result.push(&[0]);
+                }
+            }
+        }
+        // Descending columns are complemented, which also moves NULLs from first to last.
+        if descending[j] {
+            byte_key_desc(&mut result.bytes[start..]);
         }
     }
 }
@@ -7,10 +7,10 @@ pub fn hash_join(
     left: &HashTable,
     right: &RecordBatch,
     partition_right: &I64Array,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
     keep_unmatched_left: Option<&mut BoolArray>,
     keep_unmatched_right: bool,
-) -> Result<RecordBatch, String> {
+) -> Result<RecordBatch, Error> {
     let (left_index, right_index) = left.probe(partition_right);
     let left_input = left.build().gather(&left_index);
     let right_input = right.gather(&right_index);
@@ -38,8 +38,8 @@ pub fn hash_join_semi(
     left: &HashTable,
     right: &RecordBatch,
     partition_right: &I64Array,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let (left_index, right_index) = left.probe(partition_right);
     let left_input = left.build().gather(&left_index);
     let right_input = right.gather(&right_index);
@@ -62,8 +62,8 @@ pub fn hash_join_anti(
     left: &HashTable,
     right: &RecordBatch,
     partition_right: &I64Array,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let (left_index, right_index) = left.probe(partition_right);
     let left_input = left.build().gather(&left_index);
     let right_input = right.gather(&right_index);
@@ -86,8 +86,8 @@ pub fn hash_join_single(
     left: &HashTable,
     right: &RecordBatch,
     partition_right: &I64Array,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let (left_index, right_index) = left.probe(partition_right);
     let left_input = left.build().gather(&left_index);
     let right_input = right.gather(&right_index);
@@ -119,8 +119,8 @@ pub fn hash_join_mark(
     left: &HashTable,
     right: &RecordBatch,
     partition_right: &I64Array,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let (left_index, right_index) = left.probe(partition_right);
     let left_input = left.build().gather(&left_index);
     let right_input = right.gather(&right_index);
@@ -146,7 +146,7 @@ pub fn unmatched_tuples(
     left: &RecordBatch,
     unmatched_left: &BoolArray,
     right: &Vec<(String, DataType)>,
-) -> Result<RecordBatch, String> {
+) -> Result<RecordBatch, Error> {
     let unmatched_left = left.compress(unmatched_left);
     let unmatched_right = RecordBatch::nulls(right.clone(), unmatched_left.len());
     Ok(RecordBatch::zip(unmatched_left, unmatched_right))
@@ -155,10 +155,10 @@ pub fn unmatched_tuples(
 pub fn nested_loop(
     left: &RecordBatch,
     right: &RecordBatch,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
     keep_unmatched_left: Option<&mut BoolArray>,
     keep_unmatched_right: bool,
-) -> Result<RecordBatch, String> {
+) -> Result<RecordBatch, Error> {
     let input = cross_product(left, right);
     let mask = filter(&input)?;
     let matched = input.compress(&mask);
@@ -179,8 +179,8 @@ pub fn nested_loop(
 pub fn nested_loop_semi(
     left: &RecordBatch,
     right: &RecordBatch,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let input = cross_product(left, right);
     let mask = filter(&input)?;
     let right_mask = mask.any(right.len());
@@ -190,8 +190,8 @@ pub fn nested_loop_semi(
 pub fn nested_loop_anti(
     left: &RecordBatch,
     right: &RecordBatch,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let input = cross_product(left, right);
     let mask = filter(&input)?;
     let right_mask = mask.none(right.len());
@@ -201,8 +201,8 @@ pub fn nested_loop_anti(
 pub fn nested_loop_single(
     left: &RecordBatch,
     right: &RecordBatch,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let head = cross_product(left, &right);
     let mask = filter(&head)?;
     let count = mask.count(right.len());
@@ -222,8 +222,8 @@ pub fn nested_loop_mark(
     mark: &Column,
     left: &RecordBatch,
     right: &RecordBatch,
-    filter: impl Fn(&RecordBatch) -> Result<BoolArray, String>,
-) -> Result<RecordBatch, String> {
+    filter: impl Fn(&RecordBatch) -> Result<BoolArray, Error>,
+) -> Result<RecordBatch, Error> {
     let input = cross_product(left, right);
     let mask = filter(&input)?;
     let right_mask = mask.any(right.len());
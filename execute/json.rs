@@ -0,0 +1,164 @@
+use chrono::*;
+use kernel::Error;
+use serde_json::Value;
+
+/// A JSONPath like $.a.b[0]."c d" or $['a']['b'][0], in the subset that ZetaSQL supports: member
+/// names and array indexes, without wildcards, filters or recursive descent.
+#[derive(Debug, PartialEq)]
+pub(crate) struct JsonPath {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Member(String),
+    Index(usize),
+}
+
+impl JsonPath {
+    pub(crate) fn parse(path: &str) -> Result<JsonPath, Error> {
+        let invalid = || Error::DataException(format!("Invalid JSONPath: {}", path));
+        let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+        let mut steps = vec![];
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let (name, after) = if after.starts_with('"') {
+                    quoted(after).ok_or_else(invalid)?
+                } else {
+                    let end = after.find(&['.', '['][..]).unwrap_or(after.len());
+                    (after[..end].to_string(), &after[end..])
+                };
+                if name.is_empty() {
+                    return Err(invalid());
+                }
+                steps.push(Step::Member(name));
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let (step, after) = if after.starts_with('"') || after.starts_with('\'') {
+                    let (name, after) = quoted(after).ok_or_else(invalid)?;
+                    (Step::Member(name), after)
+                } else {
+                    let end = after.find(']').ok_or_else(invalid)?;
+                    let index = after[..end].trim().parse().map_err(|_| invalid())?;
+                    (Step::Index(index), &after[end..])
+                };
+                steps.push(step);
+                rest = after.strip_prefix(']').ok_or_else(invalid)?;
+            } else {
+                return Err(invalid());
+            }
+        }
+        Ok(JsonPath { steps })
+    }
+
+    /// The part of `document` that the path points to, if there is one.
+    pub(crate) fn find<'a>(&self, document: &'a Value) -> Option<&'a Value> {
+        let mut value = document;
+        for step in &self.steps {
+            value = match step {
+                Step::Member(name) => value.as_object()?.get(name)?,
+                Step::Index(index) => value.as_array()?.get(*index)?,
+            };
+        }
+        Some(value)
+    }
+}
+
+/// Split a name in single or double quotes, with backslash escapes, off the start of `text`.
+fn quoted(text: &str) -> Option<(String, &str)> {
+    let quote = text.chars().next()?;
+    let mut name = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Some((name, &text[i + 1..]));
+        } else if c == '\\' {
+            name.push(chars.next()?.1);
+        } else {
+            name.push(c);
+        }
+    }
+    None
+}
+
+/// JSON_QUERY and JSON_EXTRACT of a JSON value.
+pub(crate) fn json_query(document: &Value, path: &str) -> Result<Option<Value>, Error> {
+    Ok(JsonPath::parse(path)?.find(document).cloned())
+}
+
+/// JSON_QUERY and JSON_EXTRACT of a string, which is NULL if the string is not valid JSON.
+pub(crate) fn json_query_string(document: &str, path: &str) -> Result<Option<String>, Error> {
+    let path = JsonPath::parse(path)?;
+    match serde_json::from_str(document) {
+        Ok(document) => Ok(path.find(&document).map(Value::to_string)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// JSON_VALUE and JSON_EXTRACT_SCALAR of a JSON value. Objects, arrays and JSON null are not
+/// scalars, so they return NULL.
+pub(crate) fn json_value(document: &Value, path: &str) -> Result<Option<String>, Error> {
+    Ok(JsonPath::parse(path)?.find(document).and_then(scalar))
+}
+
+/// JSON_VALUE and JSON_EXTRACT_SCALAR of a string, which is NULL if the string is not valid JSON.
+pub(crate) fn json_value_string(document: &str, path: &str) -> Result<Option<String>, Error> {
+    let path = JsonPath::parse(path)?;
+    match serde_json::from_str(document) {
+        Ok(document) => Ok(path.find(&document).and_then(scalar)),
+        Err(_) => Ok(None),
+    }
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(bool) => Some(bool.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// json[index], which is NULL if json is not an array or the index is out of bounds.
+pub(crate) fn json_subscript_int64(document: &Value, index: i64) -> Option<Value> {
+    if index < 0 {
+        return None;
+    }
+    document.as_array()?.get(index as usize).cloned()
+}
+
+/// json['name'], which is NULL if json is not an object or doesn't have the member.
+pub(crate) fn json_subscript_string(document: &Value, name: &str) -> Option<Value> {
+    document.as_object()?.get(name).cloned()
+}
+
+/// The JSON representation of a SQL value, which TO_JSON_STRING prints. Dates and timestamps
+/// are strings, and so are the doubles that JSON numbers can't represent.
+pub(crate) fn to_json<T: Into<Value>>(value: Option<T>) -> Value {
+    value.map(Into::into).unwrap_or(Value::Null)
+}
+
+pub(crate) fn double_to_json(value: Option<f64>) -> Value {
+    match value {
+        Some(value) if value.is_nan() => Value::String("NaN".to_string()),
+        Some(value) if value.is_infinite() && value > 0.0 => Value::String("Infinity".to_string()),
+        Some(value) if value.is_infinite() => Value::String("-Infinity".to_string()),
+        value => to_json(value),
+    }
+}
+
+pub(crate) fn date_to_json(value: Option<Date<Utc>>) -> Value {
+    to_json(value.map(|value| value.format("%Y-%m-%d").to_string()))
+}
+
+pub(crate) fn timestamp_to_json(value: Option<DateTime<Utc>>) -> Value {
+    to_json(value.map(|value| value.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()))
+}
+
+pub(crate) fn print_json(value: Value, pretty: bool) -> String {
+    if pretty {
+        serde_json::to_string_pretty(&value).unwrap()
+    } else {
+        value.to_string()
+    }
+}
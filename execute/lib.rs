@@ -8,6 +8,8 @@ mod hash_table;
 mod hash_table_tests;
 mod index;
 mod join;
+mod json;
 mod map;
+mod time_zone;
 
 pub use crate::execute::Node;
@@ -10,24 +10,24 @@ pub trait FromArray<'a>: Sized {
 pub trait IntoArray: Sized {
     type Container: Array;
 
-    fn into_element(self) -> Result<Option<<Self::Container as Array>::Element>, String>;
+    fn into_element(self) -> Result<Option<<Self::Container as Array>::Element>, Error>;
 }
 
 pub trait ArrayExt<'a, A: FromArray<'a>> {
-    fn map<B: IntoArray>(&'a self, f: impl Fn(A) -> B) -> Result<AnyArray, String>;
+    fn map<B: IntoArray>(&'a self, f: impl Fn(A) -> B) -> Result<AnyArray, Error>;
 
     fn bi_map<'b, B: FromArray<'b>, C: IntoArray>(
         &'a self,
         right: &'b B::Container,
         f: impl Fn(A, B) -> C,
-    ) -> Result<AnyArray, String>;
+    ) -> Result<AnyArray, Error>;
 
     fn tri_map<'b, 'c, B: FromArray<'b>, C: FromArray<'c>, D: IntoArray>(
         &'a self,
         middle: &'b B::Container,
         right: &'c C::Container,
         f: impl Fn(A, B, C) -> D,
-    ) -> Result<AnyArray, String>;
+    ) -> Result<AnyArray, Error>;
 }
 
 impl<'a> FromArray<'a> for bool {
@@ -49,7 +49,7 @@ impl<'a> FromArray<'a> for Option<bool> {
 impl IntoArray for bool {
     type Container = BoolArray;
 
-    fn into_element(self) -> Result<Option<bool>, String> {
+    fn into_element(self) -> Result<Option<bool>, Error> {
         Ok(Some(self))
     }
 }
@@ -57,16 +57,16 @@ impl IntoArray for bool {
 impl IntoArray for Option<bool> {
     type Container = BoolArray;
 
-    fn into_element(self) -> Result<Option<bool>, String> {
+    fn into_element(self) -> Result<Option<bool>, Error> {
         Ok(self)
     }
 }
 
-impl IntoArray for Result<Option<bool>, String> {
+impl<E: Into<Error>> IntoArray for Result<Option<bool>, E> {
     type Container = BoolArray;
 
-    fn into_element(self) -> Result<Option<bool>, String> {
-        self
+    fn into_element(self) -> Result<Option<bool>, Error> {
+        self.map_err(Into::into)
     }
 }
 
@@ -89,7 +89,7 @@ impl<'a> FromArray<'a> for Option<i64> {
 impl IntoArray for i64 {
     type Container = I64Array;
 
-    fn into_element(self) -> Result<Option<i64>, String> {
+    fn into_element(self) -> Result<Option<i64>, Error> {
         Ok(Some(self))
     }
 }
@@ -97,16 +97,16 @@ impl IntoArray for i64 {
 impl IntoArray for Option<i64> {
     type Container = I64Array;
 
-    fn into_element(self) -> Result<Option<i64>, String> {
+    fn into_element(self) -> Result<Option<i64>, Error> {
         Ok(self)
     }
 }
 
-impl IntoArray for Result<Option<i64>, String> {
+impl<E: Into<Error>> IntoArray for Result<Option<i64>, E> {
     type Container = I64Array;
 
-    fn into_element(self) -> Result<Option<i64>, String> {
-        self
+    fn into_element(self) -> Result<Option<i64>, Error> {
+        self.map_err(Into::into)
     }
 }
 
@@ -129,7 +129,7 @@ impl<'a> FromArray<'a> for Option<f64> {
 impl IntoArray for f64 {
     type Container = F64Array;
 
-    fn into_element(self) -> Result<Option<f64>, String> {
+    fn into_element(self) -> Result<Option<f64>, Error> {
         Ok(Some(self))
     }
 }
@@ -137,16 +137,16 @@ impl IntoArray for f64 {
 impl IntoArray for Option<f64> {
     type Container = F64Array;
 
-    fn into_element(self) -> Result<Option<f64>, String> {
+    fn into_element(self) -> Result<Option<f64>, Error> {
         Ok(self)
     }
 }
 
-impl IntoArray for Result<Option<f64>, String> {
+impl<E: Into<Error>> IntoArray for Result<Option<f64>, E> {
     type Container = F64Array;
 
-    fn into_element(self) -> Result<Option<f64>, String> {
-        self
+    fn into_element(self) -> Result<Option<f64>, Error> {
+        self.map_err(Into::into)
     }
 }
 
@@ -169,7 +169,7 @@ impl<'a> FromArray<'a> for Option<Date<Utc>> {
 impl IntoArray for Date<Utc> {
     type Container = DateArray;
 
-    fn into_element(self) -> Result<Option<i32>, String> {
+    fn into_element(self) -> Result<Option<i32>, Error> {
         Ok(Some(epoch_date(self)))
     }
 }
@@ -177,16 +177,17 @@ impl IntoArray for Date<Utc> {
 impl IntoArray for Option<Date<Utc>> {
     type Container = DateArray;
 
-    fn into_element(self) -> Result<Option<i32>, String> {
+    fn into_element(self) -> Result<Option<i32>, Error> {
         Ok(self.map(epoch_date))
     }
 }
 
-impl IntoArray for Result<Option<Date<Utc>>, String> {
+impl<E: Into<Error>> IntoArray for Result<Option<Date<Utc>>, E> {
     type Container = DateArray;
 
-    fn into_element(self) -> Result<Option<i32>, String> {
+    fn into_element(self) -> Result<Option<i32>, Error> {
         self.map(|option| option.map(epoch_date))
+            .map_err(Into::into)
     }
 }
 
@@ -209,7 +210,7 @@ impl<'a> FromArray<'a> for Option<DateTime<Utc>> {
 impl IntoArray for DateTime<Utc> {
     type Container = TimestampArray;
 
-    fn into_element(self) -> Result<Option<i64>, String> {
+    fn into_element(self) -> Result<Option<i64>, Error> {
         Ok(Some(epoch_micros(self)))
     }
 }
@@ -217,16 +218,17 @@ impl IntoArray for DateTime<Utc> {
 impl IntoArray for Option<DateTime<Utc>> {
     type Container = TimestampArray;
 
-    fn into_element(self) -> Result<Option<i64>, String> {
+    fn into_element(self) -> Result<Option<i64>, Error> {
         Ok(self.map(epoch_micros))
     }
 }
 
-impl IntoArray for Result<Option<DateTime<Utc>>, String> {
+impl<E: Into<Error>> IntoArray for Result<Option<DateTime<Utc>>, E> {
     type Container = TimestampArray;
 
-    fn into_element(self) -> Result<Option<i64>, String> {
+    fn into_element(self) -> Result<Option<i64>, Error> {
         self.map(|option| option.map(epoch_micros))
+            .map_err(Into::into)
     }
 }
 
@@ -249,7 +251,7 @@ impl<'a> FromArray<'a> for Option<&'a str> {
 impl IntoArray for String {
     type Container = StringArray;
 
-    fn into_element(self) -> Result<Option<String>, String> {
+    fn into_element(self) -> Result<Option<String>, Error> {
         Ok(Some(self))
     }
 }
@@ -257,21 +259,102 @@ impl IntoArray for String {
 impl IntoArray for Option<String> {
     type Container = StringArray;
 
-    fn into_element(self) -> Result<Option<String>, String> {
+    fn into_element(self) -> Result<Option<String>, Error> {
         Ok(self)
     }
 }
 
-impl IntoArray for Result<Option<String>, String> {
+impl<E: Into<Error>> IntoArray for Result<Option<String>, E> {
     type Container = StringArray;
 
-    fn into_element(self) -> Result<Option<String>, String> {
-        self
+    fn into_element(self) -> Result<Option<String>, Error> {
+        self.map_err(Into::into)
+    }
+}
+
+impl<'a> FromArray<'a> for serde_json::Value {
+    type Container = JsonArray;
+
+    fn from_array(array: &'a JsonArray, i: usize) -> Option<Self> {
+        array.get_json(i)
+    }
+}
+
+impl<'a> FromArray<'a> for Option<serde_json::Value> {
+    type Container = JsonArray;
+
+    fn from_array(array: &'a JsonArray, i: usize) -> Option<Self> {
+        Some(array.get_json(i))
+    }
+}
+
+impl IntoArray for serde_json::Value {
+    type Container = JsonArray;
+
+    fn into_element(self) -> Result<Option<String>, Error> {
+        Ok(Some(self.to_string()))
+    }
+}
+
+impl IntoArray for Option<serde_json::Value> {
+    type Container = JsonArray;
+
+    fn into_element(self) -> Result<Option<String>, Error> {
+        Ok(self.map(|value| value.to_string()))
+    }
+}
+
+impl<E: Into<Error>> IntoArray for Result<Option<serde_json::Value>, E> {
+    type Container = JsonArray;
+
+    fn into_element(self) -> Result<Option<String>, Error> {
+        self.map(|option| option.map(|value| value.to_string()))
+            .map_err(Into::into)
+    }
+}
+
+impl<'a> FromArray<'a> for &'a [u8] {
+    type Container = BytesArray;
+
+    fn from_array(array: &'a BytesArray, i: usize) -> Option<Self> {
+        array.get_bytes(i)
+    }
+}
+
+impl<'a> FromArray<'a> for Option<&'a [u8]> {
+    type Container = BytesArray;
+
+    fn from_array(array: &'a BytesArray, i: usize) -> Option<Self> {
+        Some(array.get_bytes(i))
+    }
+}
+
+impl IntoArray for Vec<u8> {
+    type Container = BytesArray;
+
+    fn into_element(self) -> Result<Option<Vec<u8>>, Error> {
+        Ok(Some(self))
+    }
+}
+
+impl IntoArray for Option<Vec<u8>> {
+    type Container = BytesArray;
+
+    fn into_element(self) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self)
+    }
+}
+
+impl<E: Into<Error>> IntoArray for Result<Option<Vec<u8>>, E> {
+    type Container = BytesArray;
+
+    fn into_element(self) -> Result<Option<Vec<u8>>, Error> {
+        self.map_err(Into::into)
     }
 }
 
 impl<'a, A: FromArray<'a>> ArrayExt<'a, A> for A::Container {
-    fn map<B: IntoArray>(&'a self, f: impl Fn(A) -> B) -> Result<AnyArray, String> {
+    fn map<B: IntoArray>(&'a self, f: impl Fn(A) -> B) -> Result<AnyArray, Error> {
         let mut output = B::Container::with_capacity(self.len());
         for i in 0..self.len() {
             if let Some(next) = <A as FromArray<'a>>::from_array(self, i) {
@@ -287,7 +370,7 @@ impl<'a, A: FromArray<'a>> ArrayExt<'a, A> for A::Container {
         &'a self,
         right: &'b B::Container,
         f: impl Fn(A, B) -> C,
-    ) -> Result<AnyArray, String> {
+    ) -> Result<AnyArray, Error> {
         assert_eq!(self.len(), right.len());
         let mut output = C::Container::with_capacity(self.len());
         for i in 0..self.len() {
@@ -307,7 +390,7 @@ impl<'a, A: FromArray<'a>> ArrayExt<'a, A> for A::Container {
         middle: &'b B::Container,
         right: &'c C::Container,
         f: impl Fn(A, B, C) -> D,
-    ) -> Result<AnyArray, String> {
+    ) -> Result<AnyArray, Error> {
         assert_eq!(self.len(), middle.len());
         assert_eq!(self.len(), right.len());
         let mut output = D::Container::with_capacity(self.len());
@@ -0,0 +1,83 @@
+use chrono::*;
+use chrono_tz::Tz;
+use kernel::Error;
+
+/// The time zone argument of a timestamp function, which is either a name from the tz database
+/// like America/Los_Angeles or a fixed offset from UTC like +05:30 or UTC-8.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Zone {
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+/// Timestamp functions that are called without a time zone use UTC.
+pub(crate) const DEFAULT_ZONE: Zone = Zone::Named(Tz::UTC);
+
+impl Zone {
+    pub(crate) fn parse(name: &str) -> Result<Zone, Error> {
+        let offset = match name.strip_prefix("UTC") {
+            Some(offset) if !offset.is_empty() => offset,
+            _ => name,
+        };
+        if offset.starts_with('+') || offset.starts_with('-') {
+            parse_offset(offset)
+                .ok_or_else(|| Error::DataException(format!("Invalid time zone: {}", name)))
+        } else {
+            name.parse::<Tz>()
+                .map(Zone::Named)
+                .map_err(|_| Error::DataException(format!("Invalid time zone: {}", name)))
+        }
+    }
+
+    /// The wall clock time in this time zone at the instant `ts`.
+    pub(crate) fn local(&self, ts: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Zone::Fixed(offset) => ts.with_timezone(offset).naive_local(),
+            Zone::Named(tz) => ts.with_timezone(tz).naive_local(),
+        }
+    }
+
+    /// The instant when the wall clock in this time zone shows `local`. Like ZetaSQL, a time that
+    /// is repeated when the clocks go back resolves to the earlier instant, and a time that is
+    /// skipped when the clocks go forward is shifted by the offset before the transition.
+    pub(crate) fn instant(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self {
+            Zone::Fixed(offset) => Utc.from_utc_datetime(&(local - *offset)),
+            Zone::Named(tz) => match tz.from_local_datetime(&local) {
+                LocalResult::Single(ts) | LocalResult::Ambiguous(ts, _) => ts.with_timezone(&Utc),
+                LocalResult::None => {
+                    let before = tz
+                        .offset_from_utc_datetime(&(local - Duration::days(1)))
+                        .fix();
+                    Utc.from_utc_datetime(&(local - before))
+                }
+            },
+        }
+    }
+
+    /// Format `ts` as it appears on the wall clock in this time zone. The format must already have
+    /// been checked, because chrono panics when it formats an invalid one.
+    pub(crate) fn format(&self, format: &str, ts: DateTime<Utc>) -> String {
+        match self {
+            Zone::Fixed(offset) => ts.with_timezone(offset).format(format).to_string(),
+            Zone::Named(tz) => ts.with_timezone(tz).format(format).to_string(),
+        }
+    }
+}
+
+/// Parse an offset like +5, -08 or +05:30.
+fn parse_offset(offset: &str) -> Option<Zone> {
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let mut parts = offset[1..].splitn(2, ':');
+    let hours = parts.next()?;
+    let minutes = parts.next().unwrap_or("0");
+    if hours.is_empty() || hours.len() > 2 || minutes.is_empty() || minutes.len() > 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 14 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).map(Zone::Fixed)
+}
@@ -3,8 +3,8 @@ use std::{cmp::Ordering, ops::Range};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Array, BoolArray, DataType, DateArray, F64Array, I32Array, I64Array, StringArray,
-    TimestampArray,
+    Array, ArrayArray, BoolArray, BytesArray, DataType, DateArray, Error, F64Array, I32Array,
+    I64Array, JsonArray, StringArray, TimestampArray,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +15,9 @@ pub enum AnyArray {
     Date(DateArray),
     Timestamp(TimestampArray),
     String(StringArray),
+    Bytes(BytesArray),
+    Json(JsonArray),
+    Array(ArrayArray),
 }
 
 // trait ArrayVisitor {
@@ -44,6 +47,9 @@ macro_rules! unary_method {
             AnyArray::Date($matched) => $result,
             AnyArray::Timestamp($matched) => $result,
             AnyArray::String($matched) => $result,
+            AnyArray::Bytes($matched) => $result,
+            AnyArray::Json($matched) => $result,
+            AnyArray::Array($matched) => $result,
         }
     };
 }
@@ -57,6 +63,9 @@ macro_rules! binary_method {
             (AnyArray::Date($left), AnyArray::Date($right)) => $result,
             (AnyArray::Timestamp($left), AnyArray::Timestamp($right)) => $result,
             (AnyArray::String($left), AnyArray::String($right)) => $result,
+            (AnyArray::Bytes($left), AnyArray::Bytes($right)) => $result,
+            (AnyArray::Json($left), AnyArray::Json($right)) => $result,
+            (AnyArray::Array($left), AnyArray::Array($right)) => $result,
             (left, right) => panic!("{} does not match {}", left.data_type(), right.data_type()),
         }
     };
@@ -71,6 +80,9 @@ macro_rules! unary_operator {
             AnyArray::Date($matched) => AnyArray::Date($result),
             AnyArray::Timestamp($matched) => AnyArray::Timestamp($result),
             AnyArray::String($matched) => AnyArray::String($result),
+            AnyArray::Bytes($matched) => AnyArray::Bytes($result),
+            AnyArray::Json($matched) => AnyArray::Json($result),
+            AnyArray::Array($matched) => AnyArray::Array($result),
         }
     };
 }
@@ -86,6 +98,9 @@ macro_rules! binary_operator {
                 AnyArray::Timestamp($result)
             }
             (AnyArray::String($left), AnyArray::String($right)) => AnyArray::String($result),
+            (AnyArray::Bytes($left), AnyArray::Bytes($right)) => AnyArray::Bytes($result),
+            (AnyArray::Json($left), AnyArray::Json($right)) => AnyArray::Json($result),
+            (AnyArray::Array($left), AnyArray::Array($right)) => AnyArray::Array($result),
             (left, right) => panic!("{} does not match {}", left.data_type(), right.data_type()),
         }
     };
@@ -101,6 +116,9 @@ impl AnyArray {
             DataType::Date => AnyArray::Date(DateArray::default()),
             DataType::Timestamp => AnyArray::Timestamp(TimestampArray::default()),
             DataType::String => AnyArray::String(StringArray::default()),
+            DataType::Bytes => AnyArray::Bytes(BytesArray::default()),
+            DataType::Json => AnyArray::Json(JsonArray::default()),
+            DataType::Array(element) => AnyArray::Array(ArrayArray::with_capacity(*element, 0)),
         }
     }
 
@@ -112,6 +130,11 @@ impl AnyArray {
             DataType::Date => AnyArray::Date(DateArray::with_capacity(capacity)),
             DataType::Timestamp => AnyArray::Timestamp(TimestampArray::with_capacity(capacity)),
             DataType::String => AnyArray::String(StringArray::with_capacity(capacity)),
+            DataType::Bytes => AnyArray::Bytes(BytesArray::with_capacity(capacity)),
+            DataType::Json => AnyArray::Json(JsonArray::with_capacity(capacity)),
+            DataType::Array(element) => {
+                AnyArray::Array(ArrayArray::with_capacity(*element, capacity))
+            }
         }
     }
 
@@ -123,6 +146,9 @@ impl AnyArray {
             DataType::Date => AnyArray::Date(DateArray::nulls(len)),
             DataType::Timestamp => AnyArray::Timestamp(TimestampArray::nulls(len)),
             DataType::String => AnyArray::String(StringArray::nulls(len)),
+            DataType::Bytes => AnyArray::Bytes(BytesArray::nulls(len)),
+            DataType::Json => AnyArray::Json(JsonArray::nulls(len)),
+            DataType::Array(element) => AnyArray::Array(ArrayArray::nulls(*element, len)),
         }
     }
 
@@ -146,6 +172,15 @@ impl AnyArray {
             DataType::String => AnyArray::String(StringArray::cat(
                 &arrays.drain(..).map(|array| array.as_string()).collect(),
             )),
+            DataType::Bytes => AnyArray::Bytes(BytesArray::cat(
+                &arrays.drain(..).map(|array| array.as_bytes()).collect(),
+            )),
+            DataType::Json => AnyArray::Json(JsonArray::cat(
+                &arrays.drain(..).map(|array| array.as_json()).collect(),
+            )),
+            DataType::Array(_) => AnyArray::Array(ArrayArray::cat(
+                &arrays.drain(..).map(|array| array.as_array()).collect(),
+            )),
         }
     }
 
@@ -163,6 +198,9 @@ impl AnyArray {
             AnyArray::Date(_) => DataType::Date,
             AnyArray::Timestamp(_) => DataType::Timestamp,
             AnyArray::String(_) => DataType::String,
+            AnyArray::Bytes(_) => DataType::Bytes,
+            AnyArray::Json(_) => DataType::Json,
+            AnyArray::Array(array) => array.data_type(),
         }
     }
 
@@ -320,8 +358,30 @@ impl AnyArray {
         }
     }
 
-    pub fn cast(&self, data_type: DataType) -> Self {
-        match (self, data_type) {
+    pub fn as_bytes(self) -> BytesArray {
+        match self {
+            AnyArray::Bytes(array) => array,
+            other => panic!("expected BYTES but found {}", other.data_type()),
+        }
+    }
+
+    pub fn as_json(self) -> JsonArray {
+        match self {
+            AnyArray::Json(array) => array,
+            other => panic!("expected JSON but found {}", other.data_type()),
+        }
+    }
+
+    pub fn as_array(self) -> ArrayArray {
+        match self {
+            AnyArray::Array(array) => array,
+            other => panic!("expected ARRAY but found {}", other.data_type()),
+        }
+    }
+
+    /// Cast every element to `data_type`, or fail on the first element that can't be represented.
+    pub fn cast(&self, data_type: DataType) -> Result<Self, Error> {
+        let array = match (self, &data_type) {
             (AnyArray::Bool(_), DataType::Bool) => self.clone(),
             (AnyArray::Bool(array), DataType::I64) => AnyArray::I64(array.cast_i64()),
             (AnyArray::Bool(array), DataType::F64) => AnyArray::F64(array.cast_f64()),
@@ -330,7 +390,7 @@ impl AnyArray {
             (AnyArray::I64(_), DataType::I64) => self.clone(),
             (AnyArray::I64(array), DataType::F64) => AnyArray::F64(array.cast_f64()),
             (AnyArray::I64(array), DataType::String) => AnyArray::String(array.cast_string()),
-            (AnyArray::F64(array), DataType::I64) => AnyArray::I64(array.cast_i64()),
+            (AnyArray::F64(array), DataType::I64) => AnyArray::I64(array.cast_i64()?),
             (AnyArray::F64(_), DataType::F64) => self.clone(),
             (AnyArray::F64(array), DataType::String) => AnyArray::String(array.cast_string()),
             (AnyArray::Date(_), DataType::Date) => self.clone(),
@@ -341,15 +401,41 @@ impl AnyArray {
             (AnyArray::Timestamp(array), DataType::Date) => AnyArray::Date(array.cast_date()),
             (AnyArray::Timestamp(_), DataType::Timestamp) => self.clone(),
             (AnyArray::Timestamp(array), DataType::String) => AnyArray::String(array.cast_string()),
-            (AnyArray::String(array), DataType::Bool) => AnyArray::Bool(array.cast_bool()),
-            (AnyArray::String(array), DataType::I64) => AnyArray::I64(array.cast_i64()),
-            (AnyArray::String(array), DataType::F64) => AnyArray::F64(array.cast_f64()),
-            (AnyArray::String(array), DataType::Date) => AnyArray::Date(array.cast_date()),
+            (AnyArray::String(array), DataType::Bool) => AnyArray::Bool(array.cast_bool()?),
+            (AnyArray::String(array), DataType::I64) => AnyArray::I64(array.cast_i64()?),
+            (AnyArray::String(array), DataType::F64) => AnyArray::F64(array.cast_f64()?),
+            (AnyArray::String(array), DataType::Date) => AnyArray::Date(array.cast_date()?),
             (AnyArray::String(array), DataType::Timestamp) => {
-                AnyArray::Timestamp(array.cast_timestamp())
+                AnyArray::Timestamp(array.cast_timestamp()?)
             }
+            (AnyArray::String(array), DataType::Json) => AnyArray::Json(array.cast_json()?),
+            (AnyArray::String(array), DataType::Bytes) => AnyArray::Bytes(array.cast_bytes()),
             (AnyArray::String(_), DataType::String) => self.clone(),
+            (AnyArray::Bytes(array), DataType::String) => AnyArray::String(array.cast_string()?),
+            (AnyArray::Bytes(_), DataType::Bytes) => self.clone(),
+            (AnyArray::Json(array), DataType::String) => AnyArray::String(array.cast_string()),
+            (AnyArray::Json(_), DataType::Json) => self.clone(),
+            (AnyArray::Array(array), DataType::Array(_)) if array.data_type() == data_type => {
+                self.clone()
+            }
             (_, _) => panic!("cannot cast {} to {}", self.data_type(), data_type),
+        };
+        Ok(array)
+    }
+
+    /// SAFE_CAST, which is NULL in the rows that cannot be cast. Errors are rare, so we only cast
+    /// one row at a time after casting the whole array has failed.
+    pub fn safe_cast(&self, data_type: DataType) -> Self {
+        if let Ok(array) = self.cast(data_type.clone()) {
+            return array;
+        }
+        let mut output = AnyArray::with_capacity(data_type.clone(), self.len());
+        for i in 0..self.len() {
+            match self.slice(i..i + 1).cast(data_type.clone()) {
+                Ok(row) => output.extend(&row),
+                Err(_) => output.extend(&AnyArray::nulls(data_type.clone(), 1)),
+            }
         }
+        output
     }
 }
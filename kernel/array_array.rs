@@ -0,0 +1,295 @@
+use std::{cmp::Ordering, ops::Range};
+
+use serde::{Deserialize, Serialize};
+use twox_hash::xxh3;
+
+use crate::{AnyArray, Array, Bitmask, BoolArray, DataType, I32Array, I64Array};
+
+/// ArrayArray stores a column of ARRAY values the same way StringArray stores strings: the elements
+/// of every value are concatenated into one array, and offsets mark where each value begins.
+/// The element type can't be recovered from an empty array, so unlike the other arrays, ArrayArray
+/// doesn't implement Array and its constructors take the element type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArrayArray {
+    values: Box<AnyArray>,
+    offsets: Vec<i32>,
+    is_valid: Bitmask,
+}
+
+impl ArrayArray {
+    // Constructors.
+
+    pub fn with_capacity(element_type: DataType, capacity: usize) -> Self {
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+        Self {
+            values: Box::new(AnyArray::with_capacity(element_type, capacity)),
+            offsets,
+            is_valid: Bitmask::with_capacity(capacity),
+        }
+    }
+
+    pub fn nulls(element_type: DataType, len: usize) -> Self {
+        Self {
+            values: Box::new(AnyArray::new(element_type)),
+            offsets: vec![0].repeat(len + 1),
+            is_valid: Bitmask::falses(len),
+        }
+    }
+
+    pub fn cat(arrays: &Vec<Self>) -> Self {
+        let mut builder = Self::with_capacity(
+            arrays[0].element_type(),
+            arrays.iter().map(|a| a.len()).sum(),
+        );
+        for array in arrays {
+            builder.extend(array);
+        }
+        builder
+    }
+
+    // Basic container operations.
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn element_type(&self) -> DataType {
+        self.values.data_type()
+    }
+
+    pub fn data_type(&self) -> DataType {
+        DataType::Array(Box::new(self.element_type()))
+    }
+
+    /// The elements of the value at `index`, or None if the value is NULL.
+    pub fn get(&self, index: usize) -> Option<AnyArray> {
+        if index < self.is_valid.len() && self.is_valid.get(index) {
+            let begin = self.offsets[index] as usize;
+            let end = self.offsets[index + 1] as usize;
+            Some(self.values.slice(begin..end))
+        } else {
+            None
+        }
+    }
+
+    pub fn push(&mut self, value: Option<&AnyArray>) {
+        if let Some(value) = value {
+            self.values.extend(value);
+            self.is_valid.push(true);
+        } else {
+            self.is_valid.push(false);
+        }
+        self.offsets.push(self.values.len() as i32);
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        let start = self.offsets[range.start];
+        let end = self.offsets[range.end];
+        let values = Box::new(self.values.slice(start as usize..end as usize));
+        let offsets = self.offsets[range.start..range.end + 1]
+            .iter()
+            .map(|offset| *offset - start)
+            .collect();
+        let is_valid = Bitmask::from_slice(self.is_valid.slice(range.start..range.end));
+        Self {
+            values,
+            offsets,
+            is_valid,
+        }
+    }
+
+    pub fn extend(&mut self, other: &Self) {
+        for i in 0..other.len() {
+            self.push(other.get(i).as_ref())
+        }
+    }
+
+    pub fn repeat(&self, n: usize) -> Self {
+        let mut builder = Self::with_capacity(self.element_type(), self.len() * n);
+        for _ in 0..n {
+            builder.extend(self);
+        }
+        builder
+    }
+
+    // Vector operations.
+
+    pub fn gather(&self, indexes: &I32Array) -> Self {
+        let mut into = Self::with_capacity(self.element_type(), indexes.len());
+        for i in 0..indexes.len() {
+            if let Some(j) = indexes.get(i) {
+                into.push(self.get(j as usize).as_ref());
+            } else {
+                into.push(None);
+            }
+        }
+        into
+    }
+
+    pub fn compress(&self, mask: &BoolArray) -> Self {
+        assert_eq!(self.len(), mask.len());
+
+        let mut into = Self::with_capacity(self.element_type(), self.len());
+        for i in 0..self.len() {
+            if mask.get(i) == Some(true) {
+                into.push(self.get(i).as_ref());
+            }
+        }
+        into
+    }
+
+    pub fn scatter(&self, indexes: &I32Array, into: &mut Self) {
+        assert_eq!(self.len(), indexes.len());
+
+        let mut invert = vec![None].repeat(into.len());
+        for i in 0..self.len() {
+            if let Some(j) = indexes.get(i) {
+                invert[j as usize] = Some(i);
+            }
+        }
+
+        let mut builder = Self::with_capacity(self.element_type(), into.len());
+        for i in 0..invert.len() {
+            match invert[i] {
+                Some(j) => builder.push(self.get(j).as_ref()),
+                None => builder.push(into.get(i).as_ref()),
+            }
+        }
+
+        *into = builder
+    }
+
+    pub fn transpose(&self, stride: usize) -> Self {
+        if self.len() == 0 {
+            return self.clone();
+        }
+        assert_eq!(self.len() % stride, 0);
+        let mut builder = Self::with_capacity(self.element_type(), self.len());
+        for i in 0..stride {
+            for j in 0..self.len() / stride {
+                builder.push(self.get(j * stride + i).as_ref());
+            }
+        }
+        builder
+    }
+
+    pub fn sort(&self) -> I32Array {
+        not_ordered()
+    }
+
+    // Array comparison operators.
+    // ARRAY values are equal when they have the same elements in the same order, with NULL
+    // elements equal to each other, but ZetaSQL gives them no order.
+
+    pub fn is(&self, other: &Self) -> BoolArray {
+        self.binary_null_operator(other, |a, b| match (a, b) {
+            (Some(a), Some(b)) => Some(same_elements(&a, &b)),
+            (None, None) => Some(true),
+            _ => Some(false),
+        })
+    }
+
+    pub fn equal(&self, other: &Self) -> BoolArray {
+        self.binary_null_operator(other, |a, b| Some(same_elements(&a?, &b?)))
+    }
+
+    pub fn not_equal(&self, other: &Self) -> BoolArray {
+        self.binary_null_operator(other, |a, b| Some(!same_elements(&a?, &b?)))
+    }
+
+    pub fn less(&self, _other: &Self) -> BoolArray {
+        not_ordered()
+    }
+
+    pub fn less_equal(&self, _other: &Self) -> BoolArray {
+        not_ordered()
+    }
+
+    pub fn greater(&self, _other: &Self) -> BoolArray {
+        not_ordered()
+    }
+
+    pub fn greater_equal(&self, _other: &Self) -> BoolArray {
+        not_ordered()
+    }
+
+    pub fn is_null(&self) -> BoolArray {
+        let mut result = BoolArray::with_capacity(self.len());
+        for i in 0..self.len() {
+            result.push(Some(!self.is_valid.get(i)));
+        }
+        result
+    }
+
+    pub fn coalesce(&self, other: &Self) -> Self {
+        assert_eq!(self.len(), other.len());
+
+        let mut builder = Self::with_capacity(self.element_type(), self.len());
+        for i in 0..self.len() {
+            builder.push(self.get(i).or_else(|| other.get(i)).as_ref());
+        }
+        builder
+    }
+
+    pub fn null_if(&self, other: &Self) -> Self {
+        assert_eq!(self.len(), other.len());
+
+        let mut builder = Self::with_capacity(self.element_type(), self.len());
+        for i in 0..self.len() {
+            match (self.get(i), other.get(i)) {
+                (Some(left), Some(right)) if same_elements(&left, &right) => builder.push(None),
+                (left, _) => builder.push(left.as_ref()),
+            }
+        }
+        builder
+    }
+
+    // Support operations for data structures.
+
+    pub fn cmp(&self, _i: usize, _j: usize) -> Ordering {
+        not_ordered()
+    }
+
+    /// Hash each value by folding the hashes of its elements, so equal values hash the same.
+    pub fn hash(&self, state: &mut I64Array) {
+        let mut elements = I64Array::from_values(vec![0].repeat(self.values.len()));
+        self.values.hash(&mut elements);
+        for i in 0..self.len() {
+            if self.is_valid.get(i) {
+                let mut seed = u64::from_ne_bytes(state.get(i).unwrap().to_ne_bytes());
+                for j in self.offsets[i]..self.offsets[i + 1] {
+                    let element = elements.get(j as usize).unwrap().to_ne_bytes();
+                    seed = xxh3::hash64_with_seed(&element, seed);
+                }
+                state.set(i, Some(i64::from_ne_bytes(seed.to_ne_bytes())))
+            }
+        }
+    }
+
+    fn binary_null_operator(
+        &self,
+        other: &Self,
+        f: impl Fn(Option<AnyArray>, Option<AnyArray>) -> Option<bool>,
+    ) -> BoolArray {
+        assert_eq!(self.len(), other.len());
+
+        let mut result = BoolArray::with_capacity(self.len());
+        for i in 0..self.len() {
+            result.push(f(self.get(i), other.get(i)));
+        }
+        result
+    }
+}
+
+fn same_elements(left: &AnyArray, right: &AnyArray) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    let is = left.is(right);
+    (0..is.len()).all(|i| is.get(i) == Some(true))
+}
+
+fn not_ordered() -> ! {
+    panic!("ARRAY values are not ordered")
+}
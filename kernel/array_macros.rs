@@ -71,16 +71,29 @@ macro_rules! cast_operator {
     }};
 }
 
+macro_rules! try_cast_operator {
+    ($self:ident, $value:ident, $cast:expr, $T:ident) => {{
+        let mut result = $T::with_capacity($self.len());
+        for i in 0..$self.len() {
+            match $self.get(i) {
+                Some($value) => result.push(Some($cast?)),
+                None => result.push(None),
+            }
+        }
+        Ok(result)
+    }};
+}
+
 macro_rules! cast_from_string {
     ($self:ident, $value:ident, $cast:expr, $T:ident) => {{
         let mut result = $T::with_capacity($self.len());
         for i in 0..$self.len() {
             match $self.get_str(i) {
-                Some($value) => result.push(Some($cast)),
+                Some($value) => result.push(Some($cast?)),
                 None => result.push(None),
             }
         }
-        result
+        Ok(result)
     }};
 }
 
@@ -1,4 +1,7 @@
-use crate::{Array, BoolArray, F64Array, I32Array, I64Array, StringArray};
+use crate::{
+    bytes_literal, Array, ArrayArray, BoolArray, BytesArray, DataType, Error, F64Array, I32Array,
+    I64Array, StringArray,
+};
 
 #[test]
 fn test_gather_scatter_bool() {
@@ -48,3 +51,109 @@ fn test_conflict() {
     let mask = BoolArray::from_values(vec![true, true, true, true]);
     assert!(indexes.conflict(&mask, 13));
 }
+
+#[test]
+fn test_cast_errors() {
+    let strings = StringArray::from_str_options(vec![Some(" 42 "), Some("-7"), None]);
+    assert_eq!(
+        I64Array::from_options(vec![Some(42), Some(-7), None]),
+        strings.cast_i64().unwrap()
+    );
+    let strings = StringArray::from_str_values(vec!["1", "x"]);
+    assert_eq!(
+        Err(Error::DataException("Bad int64 value: x".to_string())),
+        strings.cast_i64()
+    );
+    let strings = StringArray::from_str_values(vec!["TRUE", "false"]);
+    assert_eq!(
+        BoolArray::from_values(vec![true, false]),
+        strings.cast_bool().unwrap()
+    );
+    let doubles = F64Array::from_values(vec![1.5, -2.5, 3.4]);
+    assert_eq!(
+        I64Array::from_values(vec![2, -3, 3]),
+        doubles.cast_i64().unwrap()
+    );
+    let doubles = F64Array::from_values(vec![1e19]);
+    assert!(doubles.cast_i64().is_err());
+    let doubles = F64Array::from_values(vec![f64::NAN]);
+    assert!(doubles.cast_i64().is_err());
+}
+
+#[test]
+fn test_bytes() {
+    let strings = StringArray::from_str_options(vec![Some("abc"), None]);
+    let bytes = strings.cast_bytes();
+    assert_eq!(Some(&b"abc"[..]), bytes.get_bytes(0));
+    assert_eq!(None, bytes.get_bytes(1));
+    assert_eq!(strings, bytes.cast_string().unwrap());
+    let bytes = BytesArray::from_values(vec![vec![0x61, 0xff]]);
+    assert!(bytes.cast_string().is_err());
+    assert_eq!("b\"a\\xff\"", bytes_literal(&[0x61, 0xff]));
+    assert_eq!("b\"\\\"\\\\\"", bytes_literal(b"\"\\"));
+}
+
+#[test]
+fn test_array_array() {
+    let mut arrays = ArrayArray::with_capacity(DataType::String, 4);
+    let ab = StringArray::from_str_options(vec![Some("a"), Some("b")]).as_any();
+    let a_null = StringArray::from_str_options(vec![Some("a"), None]).as_any();
+    arrays.push(Some(&ab));
+    arrays.push(None);
+    arrays.push(Some(&StringArray::default().as_any()));
+    arrays.push(Some(&a_null));
+    assert_eq!(4, arrays.len());
+    assert_eq!(
+        DataType::Array(Box::new(DataType::String)),
+        arrays.data_type()
+    );
+    assert!(arrays.get(1).is_none());
+    assert_eq!(0, arrays.get(2).unwrap().len());
+    assert_eq!(
+        StringArray::from_str_options(vec![Some("a"), None]),
+        arrays.get(3).unwrap().as_string()
+    );
+    let sliced = arrays.slice(2..4);
+    assert_eq!(2, sliced.len());
+    assert_eq!(
+        StringArray::from_str_options(vec![Some("a"), None]),
+        sliced.get(1).unwrap().as_string()
+    );
+    let gathered = arrays.gather(&I32Array::from_values(vec![3, 0, 0, 1]));
+    assert_eq!(
+        BoolArray::from_options(vec![Some(false), None, Some(false), None]),
+        gathered.equal(&arrays)
+    );
+    assert_eq!(
+        BoolArray::from_values(vec![false, false, false, true]),
+        gathered.is(&arrays.gather(&I32Array::from_values(vec![0, 1, 2, 1])))
+    );
+    let mut hashes = I64Array::from_values(vec![0, 0, 0, 0]);
+    gathered.hash(&mut hashes);
+    assert_eq!(hashes.get(1), hashes.get(2));
+    assert_ne!(hashes.get(0), hashes.get(1));
+}
+
+#[test]
+fn test_safe_cast() {
+    let strings = StringArray::from_str_options(vec![Some("1"), Some("x"), None]).as_any();
+    assert_eq!(
+        I64Array::from_options(vec![Some(1), None, None]),
+        strings.safe_cast(DataType::I64).as_i64()
+    );
+}
+
+#[test]
+fn test_slice() {
+    let ints = I64Array::from_options(vec![Some(1), None, Some(3), None, Some(5)]);
+    assert_eq!(
+        I64Array::from_options(vec![None, Some(3), None, Some(5)]),
+        ints.slice(1..5)
+    );
+    let strings = StringArray::from_str_options(vec![Some("a"), None, Some("c")]);
+    assert_eq!(
+        StringArray::from_str_options(vec![None, Some("c")]),
+        strings.slice(1..3)
+    );
+    assert_eq!(0, strings.slice(3..3).len());
+}
@@ -52,8 +52,8 @@ impl Bitmask {
     }
 
     pub fn slice(&self, range: Range<usize>) -> BitSlice<'_> {
-        assert!(range.start < self.len);
-        assert!(range.end < self.len);
+        assert!(range.start <= range.end);
+        assert!(range.end <= self.len);
 
         BitSlice::from_slice(&self.values, range)
     }
@@ -100,11 +100,13 @@ impl<'a> BitSlice<'a> {
     }
 
     fn to_vec(&self) -> Vec<u8> {
-        let byte_offset = self.offset / 8;
-        let byte_len = (self.len + 7) / 8;
-        let bit_offset = self.offset % 8;
-        let mut bytes = self.values[byte_offset..byte_offset + byte_len].to_vec();
-        bytes.rotate_left(bit_offset);
+        // The slice may start in the middle of a byte, so the bits are copied one at a time.
+        let mut bytes = vec![0; (self.len + 7) / 8];
+        for i in 0..self.len {
+            if self.get(i) {
+                set_bit(&mut bytes, i)
+            }
+        }
         bytes
     }
 }
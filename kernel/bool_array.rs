@@ -3,8 +3,8 @@ use std::ops::Range;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    AnyArray, Array, BitSlice, Bitmask, DataType, DateArray, F64Array, I64Array, StringArray,
-    TimestampArray,
+    AnyArray, Array, ArrayArray, BitSlice, Bitmask, BytesArray, DataType, DateArray, F64Array,
+    I64Array, JsonArray, StringArray, TimestampArray,
 };
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Default)]
@@ -99,6 +99,36 @@ impl BoolArray {
                 }
                 AnyArray::String(builder)
             }
+            (AnyArray::Bytes(if_true), AnyArray::Bytes(if_false)) => {
+                let mut builder = BytesArray::with_capacity(self.len());
+                for i in 0..self.len() {
+                    match self.get(i) {
+                        Some(true) => builder.push_bytes(if_true.get_bytes(i)),
+                        _ => builder.push_bytes(if_false.get_bytes(i)),
+                    }
+                }
+                AnyArray::Bytes(builder)
+            }
+            (AnyArray::Json(if_true), AnyArray::Json(if_false)) => {
+                let mut builder = JsonArray::with_capacity(self.len());
+                for i in 0..self.len() {
+                    match self.get(i) {
+                        Some(true) => builder.push(if_true.get(i)),
+                        _ => builder.push(if_false.get(i)),
+                    }
+                }
+                AnyArray::Json(builder)
+            }
+            (AnyArray::Array(if_true), AnyArray::Array(if_false)) => {
+                let mut builder = ArrayArray::with_capacity(if_true.element_type(), self.len());
+                for i in 0..self.len() {
+                    match self.get(i) {
+                        Some(true) => builder.push(if_true.get(i).as_ref()),
+                        _ => builder.push(if_false.get(i).as_ref()),
+                    }
+                }
+                AnyArray::Array(builder)
+            }
             (if_true, if_false) => panic!(
                 "{} does not match {}",
                 if_true.data_type(),
@@ -175,6 +205,39 @@ impl BoolArray {
                 }
                 AnyArray::String(builder)
             }
+            (AnyArray::Bytes(if_true), AnyArray::Bytes(if_false)) => {
+                let mut builder = BytesArray::with_capacity(self.len());
+                for i in 0..self.len() {
+                    match self.get(i) {
+                        Some(true) => builder.push_bytes(if_true.get_bytes(i)),
+                        Some(false) => builder.push_bytes(if_false.get_bytes(i)),
+                        None => builder.push_bytes(None),
+                    }
+                }
+                AnyArray::Bytes(builder)
+            }
+            (AnyArray::Json(if_true), AnyArray::Json(if_false)) => {
+                let mut builder = JsonArray::with_capacity(self.len());
+                for i in 0..self.len() {
+                    match self.get(i) {
+                        Some(true) => builder.push(if_true.get(i)),
+                        Some(false) => builder.push(if_false.get(i)),
+                        None => builder.push(None),
+                    }
+                }
+                AnyArray::Json(builder)
+            }
+            (AnyArray::Array(if_true), AnyArray::Array(if_false)) => {
+                let mut builder = ArrayArray::with_capacity(if_true.element_type(), self.len());
+                for i in 0..self.len() {
+                    match self.get(i) {
+                        Some(true) => builder.push(if_true.get(i).as_ref()),
+                        Some(false) => builder.push(if_false.get(i).as_ref()),
+                        None => builder.push(None),
+                    }
+                }
+                AnyArray::Array(builder)
+            }
             (if_true, if_false) => panic!(
                 "{} does not match {}",
                 if_true.data_type(),
@@ -0,0 +1,196 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AnyArray, Array, BitSlice, Bitmask, DataType, Error, I32Array, StringArray};
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BytesArray {
+    buffer: Vec<u8>,
+    offsets: Vec<i32>,
+    is_valid: Bitmask,
+}
+
+impl BytesArray {
+    // Constructors.
+
+    pub fn from_slice(values: &[u8], offsets: &[i32], is_valid: BitSlice) -> Self {
+        assert_eq!(offsets.len() - 1, is_valid.len());
+
+        Self {
+            buffer: values.to_vec(),
+            offsets: offsets.to_vec(),
+            is_valid: Bitmask::from_slice(is_valid),
+        }
+    }
+
+    pub fn get_bytes(&self, index: usize) -> Option<&[u8]> {
+        self.bytes(index)
+    }
+
+    pub fn push_bytes(&mut self, value: Option<&[u8]>) {
+        if let Some(value) = value {
+            self.buffer.extend_from_slice(value);
+            self.is_valid.push(true);
+            self.offsets.push(self.buffer.len() as i32);
+        } else {
+            self.is_valid.push(false);
+            self.offsets.push(self.buffer.len() as i32);
+        }
+    }
+
+    pub fn byte_len(&self) -> usize {
+        *self.offsets.last().unwrap_or(&0) as usize
+    }
+
+    pub fn scatter(&self, indexes: &I32Array, into: &mut Self) {
+        assert_eq!(self.len(), indexes.len());
+
+        let mut invert = vec![None].repeat(into.len());
+        for i in 0..self.len() {
+            if let Some(j) = indexes.get(i) {
+                invert[j as usize] = Some(i);
+            }
+        }
+
+        let mut builder = Self::with_capacity(into.len());
+        for i in 0..invert.len() {
+            match invert[i] {
+                Some(j) => builder.push_bytes(self.get_bytes(j)),
+                None => builder.push_bytes(into.get_bytes(i)),
+            }
+        }
+
+        *into = builder
+    }
+
+    // Casts.
+
+    /// BYTES are cast to STRING as UTF-8, and fail if they are not valid UTF-8.
+    pub fn cast_string(&self) -> Result<StringArray, Error> {
+        let mut builder = StringArray::with_capacity(self.len());
+        for i in 0..self.len() {
+            match self.get_bytes(i) {
+                Some(bytes) => match std::str::from_utf8(bytes) {
+                    Ok(value) => builder.push_str(Some(value)),
+                    Err(_) => {
+                        return Err(Error::DataException(format!(
+                            "Bad UTF-8 value: {:?}",
+                            bytes
+                        )))
+                    }
+                },
+                None => builder.push_str(None),
+            }
+        }
+        Ok(builder)
+    }
+}
+
+impl StringArray {
+    pub fn cast_bytes(&self) -> BytesArray {
+        let mut builder = BytesArray::with_capacity(self.len());
+        for i in 0..self.len() {
+            builder.push_bytes(self.get_str(i).map(|value| value.as_bytes()))
+        }
+        builder
+    }
+}
+
+/// Print bytes as a literal like b"abc\x00", which is how ZetaSQL prints BYTES values.
+pub fn bytes_literal(bytes: &[u8]) -> String {
+    let mut literal = "b\"".to_string();
+    for byte in bytes {
+        match byte {
+            b'"' => literal.push_str("\\\""),
+            b'\\' => literal.push_str("\\\\"),
+            b' '..=b'~' => literal.push(*byte as char),
+            _ => literal.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+impl Default for BytesArray {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::default(),
+            offsets: vec![0],
+            is_valid: Bitmask::default(),
+        }
+    }
+}
+
+impl Array for BytesArray {
+    type Element = Vec<u8>;
+
+    fn with_capacity(capacity: usize) -> Self {
+        const BYTES_LEN_ESTIMATE: usize = 10;
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+        Self {
+            offsets,
+            buffer: Vec::with_capacity(capacity * BYTES_LEN_ESTIMATE),
+            is_valid: Bitmask::with_capacity(capacity),
+        }
+    }
+
+    fn nulls(len: usize) -> Self {
+        Self {
+            offsets: vec![0].repeat(len + 1),
+            buffer: vec![],
+            is_valid: Bitmask::falses(len),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn get(&self, index: usize) -> Option<Self::Element> {
+        self.bytes(index).map(|bytes| bytes.to_vec())
+    }
+
+    fn bytes(&self, index: usize) -> Option<&[u8]> {
+        if index < self.is_valid.len() && self.is_valid.get(index) {
+            let begin = self.offsets[index] as usize;
+            let end = self.offsets[index + 1] as usize;
+            Some(&self.buffer[begin..end])
+        } else {
+            None
+        }
+    }
+
+    fn slice(&self, range: Range<usize>) -> Self {
+        let start = self.offsets[range.start];
+        let end = self.offsets[range.end];
+        let buffer = self.buffer[start as usize..end as usize].to_vec();
+        let offsets = self.offsets[range.start..range.end + 1]
+            .iter()
+            .map(|offset| *offset - start)
+            .collect();
+        let is_valid = Bitmask::from_slice(self.is_valid.slice(range.start..range.end));
+        Self {
+            buffer,
+            offsets,
+            is_valid,
+        }
+    }
+
+    fn push(&mut self, value: Option<Self::Element>) {
+        self.push_bytes(value.as_deref())
+    }
+
+    fn set(&mut self, _index: usize, _value: Option<Self::Element>) {
+        panic!("set is not available for bytes type")
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Bytes
+    }
+
+    fn as_any(self) -> AnyArray {
+        AnyArray::Bytes(self)
+    }
+}
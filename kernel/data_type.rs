@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DataType {
     Bool,
     I64,
@@ -10,6 +10,10 @@ pub enum DataType {
     Date,
     Timestamp,
     String,
+    Bytes,
+    Json,
+    /// ZetaSQL doesn't allow an ARRAY of ARRAYs, so the element type is always one of the others.
+    Array(Box<DataType>),
 }
 
 impl DataType {
@@ -31,6 +35,10 @@ impl DataType {
                 type_kind: Some(8),
                 ..Default::default()
             },
+            DataType::Bytes => zetasql::TypeProto {
+                type_kind: Some(9),
+                ..Default::default()
+            },
             DataType::Date => zetasql::TypeProto {
                 type_kind: Some(10),
                 ..Default::default()
@@ -39,6 +47,17 @@ impl DataType {
                 type_kind: Some(19),
                 ..Default::default()
             },
+            DataType::Json => zetasql::TypeProto {
+                type_kind: Some(26),
+                ..Default::default()
+            },
+            DataType::Array(element) => zetasql::TypeProto {
+                type_kind: Some(16),
+                array_type: Some(Box::new(zetasql::ArrayTypeProto {
+                    element_type: Some(Box::new(element.to_proto())),
+                })),
+                ..Default::default()
+            },
         }
     }
 }
@@ -50,8 +69,20 @@ impl From<&zetasql::TypeProto> for DataType {
             5 => DataType::Bool,
             7 => DataType::F64,
             8 => DataType::String,
+            9 => DataType::Bytes,
             10 => DataType::Date,
             19 => DataType::Timestamp,
+            26 => DataType::Json,
+            16 => {
+                let element = column_type
+                    .array_type
+                    .as_ref()
+                    .unwrap()
+                    .element_type
+                    .as_ref()
+                    .unwrap();
+                DataType::Array(Box::new(DataType::from(element.as_ref())))
+            }
             other => panic!("type {:?} not supported", other),
         }
     }
@@ -66,6 +97,9 @@ impl Display for DataType {
             DataType::Date => write!(f, "DATE"),
             DataType::Timestamp => write!(f, "TIMESTAMP"),
             DataType::String => write!(f, "STRING"),
+            DataType::Bytes => write!(f, "BYTES"),
+            DataType::Json => write!(f, "JSON"),
+            DataType::Array(element) => write!(f, "ARRAY<{}>", element),
         }
     }
 }
@@ -79,6 +113,11 @@ impl From<&str> for DataType {
             "DATE" => DataType::Date,
             "TIMESTAMP" => DataType::Timestamp,
             "STRING" => DataType::String,
+            "BYTES" => DataType::Bytes,
+            "JSON" => DataType::Json,
+            other if other.starts_with("ARRAY<") && other.ends_with('>') => {
+                DataType::Array(Box::new(DataType::from(&other[6..other.len() - 1])))
+            }
             other => panic!("{:?}", other),
         }
     }
@@ -6,8 +6,13 @@ fn test_to_string() {
     assert_eq!("INT64".to_string(), DataType::I64.to_string());
     assert_eq!("DOUBLE".to_string(), DataType::F64.to_string());
     assert_eq!("STRING".to_string(), DataType::String.to_string());
+    assert_eq!("BYTES".to_string(), DataType::Bytes.to_string());
     assert_eq!("DATE".to_string(), DataType::Date.to_string());
     assert_eq!("TIMESTAMP".to_string(), DataType::Timestamp.to_string());
+    assert_eq!(
+        "ARRAY<STRING>".to_string(),
+        DataType::Array(Box::new(DataType::String)).to_string()
+    );
 }
 
 #[test]
@@ -17,8 +22,10 @@ fn test_to_from_string() {
         DataType::I64,
         DataType::F64,
         DataType::String,
+        DataType::Bytes,
         DataType::Date,
         DataType::Timestamp,
+        DataType::Array(Box::new(DataType::I64)),
     ];
     for data_type in examples {
         assert_eq!(data_type, DataType::from(data_type.to_string().as_str()));
@@ -32,8 +39,10 @@ fn test_to_from_proto() {
         DataType::I64,
         DataType::F64,
         DataType::String,
+        DataType::Bytes,
         DataType::Date,
         DataType::Timestamp,
+        DataType::Array(Box::new(DataType::I64)),
     ];
     for data_type in examples {
         let proto: zetasql::TypeProto = data_type.to_proto();
@@ -1,5 +1,7 @@
 use chrono::*;
 
+use crate::Error;
+
 pub(crate) fn date(value: i32) -> Date<Utc> {
     let naive = NaiveDate::from_ymd(1970, 1, 1) + Duration::days(value as i64);
     Utc.from_utc_date(&naive)
@@ -22,16 +24,17 @@ pub(crate) fn cast_timestamp_as_date(value: i64) -> i32 {
     duration.num_days() as i32
 }
 
-pub fn parse_date(value: &str) -> i32 {
-    let naive = NaiveDate::parse_from_str(value, "%Y-%m-%d").expect(value);
-    epoch_date(Utc.from_utc_date(&naive))
+pub fn parse_date(value: &str) -> Result<i32, Error> {
+    let naive = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| Error::DataException(format!("Invalid date: {}", value)))?;
+    Ok(epoch_date(Utc.from_utc_date(&naive)))
 }
 
-pub fn parse_timestamp(value: &str) -> i64 {
-    let ts = DateTime::parse_from_rfc3339(value)
-        .expect(value)
+pub fn parse_timestamp(value: &str) -> Result<i64, Error> {
+    let ts = DateTime::parse_from_rfc3339(value.trim())
+        .map_err(|_| Error::DataException(format!("Invalid timestamp: {}", value)))?
         .with_timezone(&Utc);
-    epoch_micros(ts)
+    Ok(epoch_micros(ts))
 }
 
 fn epoch_micros(ts: DateTime<Utc>) -> i64 {
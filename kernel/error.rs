@@ -0,0 +1,97 @@
+use std::{
+    any::Any,
+    fmt::{Display, Formatter},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// An error that fails a statement, classified by a SQLSTATE code so clients can tell what went wrong
+/// without parsing the message.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Error {
+    /// The statement could not be parsed or analyzed, or it refers to an object that does not exist.
+    Syntax(String),
+    /// The statement uses a feature we don't support.
+    NotSupported(String),
+    /// A row violates a NOT NULL, CHECK or FOREIGN KEY constraint.
+    Constraint(String),
+    DivisionByZero(String),
+    /// A function or cast failed on a value, for example because of overflow or a malformed string.
+    DataException(String),
+    /// The statement tried to change a row that was changed by a concurrent transaction.
+    SerializationFailure(String),
+    /// RAISE, ASSERT or ERROR(...) in user code.
+    Raise(String),
+    /// The request refers to a session that was never started or has already ended.
+    InvalidSession(String),
+    /// A bug, which is reported to the client instead of crashing the server.
+    Internal(String),
+}
+
+impl Error {
+    pub fn sqlstate(&self) -> &'static str {
+        match self {
+            Error::Syntax(_) => "42000",
+            Error::NotSupported(_) => "0A000",
+            Error::Constraint(_) => "23000",
+            Error::DivisionByZero(_) => "22012",
+            Error::DataException(_) => "22000",
+            Error::SerializationFailure(_) => "40001",
+            Error::Raise(_) => "P0001",
+            Error::InvalidSession(_) => "08003",
+            Error::Internal(_) => "XX000",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Error::Syntax(message)
+            | Error::NotSupported(message)
+            | Error::Constraint(message)
+            | Error::DivisionByZero(message)
+            | Error::DataException(message)
+            | Error::SerializationFailure(message)
+            | Error::Raise(message)
+            | Error::InvalidSession(message)
+            | Error::Internal(message) => message,
+        }
+    }
+
+    /// The inverse of sqlstate(), for errors that are sent between nodes.
+    pub fn from_sqlstate(sqlstate: &str, message: String) -> Self {
+        match sqlstate {
+            "42000" => Error::Syntax(message),
+            "0A000" => Error::NotSupported(message),
+            "23000" => Error::Constraint(message),
+            "22012" => Error::DivisionByZero(message),
+            "22000" => Error::DataException(message),
+            "40001" => Error::SerializationFailure(message),
+            "P0001" => Error::Raise(message),
+            "08003" => Error::InvalidSession(message),
+            _ => Error::Internal(message),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<Error> for String {
+    fn from(error: Error) -> Self {
+        error.message().to_string()
+    }
+}
+
+/// The message of a panic that was caught by catch_unwind.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "panic".to_string(),
+        },
+    }
+}
@@ -0,0 +1,22 @@
+use crate::error::*;
+
+#[test]
+fn test_sqlstate_round_trip() {
+    let examples = vec![
+        Error::Syntax("syntax".to_string()),
+        Error::NotSupported("not supported".to_string()),
+        Error::Constraint("constraint".to_string()),
+        Error::DivisionByZero("division by zero".to_string()),
+        Error::DataException("data exception".to_string()),
+        Error::SerializationFailure("serialization failure".to_string()),
+        Error::Raise("raise".to_string()),
+        Error::InvalidSession("invalid session".to_string()),
+        Error::Internal("internal".to_string()),
+    ];
+    for error in examples {
+        assert_eq!(
+            error,
+            Error::from_sqlstate(error.sqlstate(), error.message().to_string())
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    bytes_literal,
     dates::{date, timestamp},
     AnyArray, Array, RecordBatch,
 };
@@ -76,6 +77,24 @@ fn fixed_width_column(array: &AnyArray) -> Vec<String> {
                 Some(value) => value.to_string(),
             })
             .collect(),
+        AnyArray::Bytes(array) => (0..array.len())
+            .map(|i| match array.get_bytes(i) {
+                None => "NULL".to_string(),
+                Some(value) => bytes_literal(value),
+            })
+            .collect(),
+        AnyArray::Json(array) => (0..array.len())
+            .map(|i| match array.get(i) {
+                None => "NULL".to_string(),
+                Some(value) => value.to_string(),
+            })
+            .collect(),
+        AnyArray::Array(array) => (0..array.len())
+            .map(|i| match array.get(i) {
+                None => "NULL".to_string(),
+                Some(value) => format!("[{}]", fixed_width_column(&value).join(", ")),
+            })
+            .collect(),
     }
 }
 
@@ -0,0 +1,135 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AnyArray, Array, BitSlice, DataType, Error, I32Array, StringArray};
+
+/// JSON documents are parsed once, when they are created, and stored as compact text.
+/// Functions that read a document parse it again, but never have to check that it is valid.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Default)]
+pub struct JsonArray {
+    text: StringArray,
+}
+
+impl JsonArray {
+    // Constructors.
+
+    pub fn from_slice(values: &str, offsets: &[i32], is_valid: BitSlice) -> Self {
+        Self {
+            text: StringArray::from_slice(values, offsets, is_valid),
+        }
+    }
+
+    pub fn get_str(&self, index: usize) -> Option<&str> {
+        self.text.get_str(index)
+    }
+
+    pub fn get_json(&self, index: usize) -> Option<serde_json::Value> {
+        self.text
+            .get_str(index)
+            .map(|text| serde_json::from_str(text).unwrap())
+    }
+
+    pub fn push_json(&mut self, value: Option<&serde_json::Value>) {
+        self.text
+            .push_str(value.map(|value| value.to_string()).as_deref())
+    }
+
+    pub fn byte_len(&self) -> usize {
+        self.text.byte_len()
+    }
+
+    pub fn scatter(&self, indexes: &I32Array, into: &mut Self) {
+        self.text.scatter(indexes, &mut into.text)
+    }
+
+    pub fn transpose(&self, stride: usize) -> Self {
+        Self {
+            text: self.text.transpose(stride),
+        }
+    }
+
+    pub fn sort(&self) -> I32Array {
+        self.text.sort()
+    }
+
+    // Casts.
+
+    pub fn cast_string(&self) -> StringArray {
+        self.text.clone()
+    }
+}
+
+impl StringArray {
+    pub fn cast_json(&self) -> Result<JsonArray, Error> {
+        let mut builder = JsonArray::with_capacity(self.len());
+        for i in 0..self.len() {
+            match self.get_str(i) {
+                Some(text) => builder.push(Some(normalize_json(text)?)),
+                None => builder.push(None),
+            }
+        }
+        Ok(builder)
+    }
+}
+
+/// Parse a JSON document and print it in the compact form that JsonArray stores.
+pub fn normalize_json(text: &str) -> Result<String, Error> {
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(value) => Ok(value.to_string()),
+        Err(error) => Err(Error::DataException(format!(
+            "Bad JSON value: {}: {}",
+            text, error
+        ))),
+    }
+}
+
+impl Array for JsonArray {
+    type Element = String;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            text: StringArray::with_capacity(capacity),
+        }
+    }
+
+    fn nulls(len: usize) -> Self {
+        Self {
+            text: StringArray::nulls(len),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    fn get(&self, index: usize) -> Option<Self::Element> {
+        self.text.get(index)
+    }
+
+    fn bytes(&self, index: usize) -> Option<&[u8]> {
+        self.text.bytes(index)
+    }
+
+    fn slice(&self, range: Range<usize>) -> Self {
+        Self {
+            text: self.text.slice(range),
+        }
+    }
+
+    fn push(&mut self, value: Option<Self::Element>) {
+        self.text.push(value)
+    }
+
+    fn set(&mut self, _index: usize, _value: Option<Self::Element>) {
+        panic!("set is not available for json type")
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Json
+    }
+
+    fn as_any(self) -> AnyArray {
+        AnyArray::Json(self)
+    }
+}
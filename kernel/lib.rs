@@ -5,15 +5,21 @@ mod array_like;
 #[macro_use]
 mod array_macros;
 mod array;
+mod array_array;
 #[cfg(test)]
 mod array_tests;
 mod bitmask;
 mod bool_array;
+mod bytes_array;
 mod data_type;
 #[cfg(test)]
 mod data_type_tests;
 mod dates;
+mod error;
+#[cfg(test)]
+mod error_tests;
 mod fixed_width;
+mod json_array;
 mod next;
 mod primitive_array;
 mod record_batch;
@@ -22,6 +28,7 @@ mod record_batch_tests;
 mod string_array;
 
 pub use crate::{
-    any_array::*, array::*, array_like::*, bitmask::*, bool_array::*, data_type::*, fixed_width::*,
-    next::*, primitive_array::*, record_batch::*, string_array::*,
+    any_array::*, array::*, array_array::*, array_like::*, bitmask::*, bool_array::*,
+    bytes_array::*, data_type::*, error::*, fixed_width::*, json_array::*, next::*,
+    primitive_array::*, record_batch::*, string_array::*,
 };
@@ -3,18 +3,18 @@ use std::{
     ops::{ControlFlow, FromResidual, Try},
 };
 
-use crate::RecordBatch;
+use crate::{Error, RecordBatch};
 
 pub enum Next {
     Page(RecordBatch),
-    Error(String),
+    Error(Error),
     End,
 }
 
 impl Try for Next {
     type Output = RecordBatch;
 
-    type Residual = Option<String>;
+    type Residual = Option<Error>;
 
     fn from_output(output: Self::Output) -> Self {
         Next::Page(output)
@@ -23,26 +23,26 @@ impl Try for Next {
     fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
         match self {
             Next::Page(record_batch) => ControlFlow::Continue(record_batch),
-            Next::Error(message) => ControlFlow::Break(Some(message)),
+            Next::Error(error) => ControlFlow::Break(Some(error)),
             Next::End => ControlFlow::Break(None),
         }
     }
 }
 
-impl FromResidual<Option<String>> for Next {
-    fn from_residual(residual: Option<String>) -> Self {
+impl FromResidual<Option<Error>> for Next {
+    fn from_residual(residual: Option<Error>) -> Self {
         match residual {
-            Some(message) => Next::Error(message),
+            Some(error) => Next::Error(error),
             None => Next::End,
         }
     }
 }
 
-impl FromResidual<Result<Infallible, String>> for Next {
-    fn from_residual(residual: Result<Infallible, String>) -> Self {
+impl<E: Into<Error>> FromResidual<Result<Infallible, E>> for Next {
+    fn from_residual(residual: Result<Infallible, E>) -> Self {
         match residual {
             Ok(_) => panic!(),
-            Err(message) => Next::Error(message),
+            Err(error) => Next::Error(error.into()),
         }
     }
 }
@@ -2,7 +2,9 @@ use std::ops::Range;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{AnyArray, Array, BitSlice, Bitmask, BoolArray, DataType, I32Array, StringArray};
+use crate::{
+    AnyArray, Array, BitSlice, Bitmask, BoolArray, DataType, Error, I32Array, StringArray,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Default)]
 pub struct I64Array {
@@ -174,8 +176,9 @@ impl F64Array {
         cast_operator!(self, value, value != 0.0, BoolArray)
     }
 
-    pub fn cast_i64(&self) -> I64Array {
-        cast_operator!(self, value, value as i64, I64Array)
+    /// Round to the nearest integer, with halfway cases away from zero.
+    pub fn cast_i64(&self) -> Result<I64Array, Error> {
+        try_cast_operator!(self, value, f64_to_i64(value), I64Array)
     }
 
     pub fn cast_string(&self) -> StringArray {
@@ -187,6 +190,18 @@ impl F64Array {
     }
 }
 
+fn f64_to_i64(value: f64) -> Result<i64, Error> {
+    let rounded = value.round();
+    // i64::MAX is not exactly representable, and rounds up to 2^63 as an f64.
+    if rounded.is_nan() || rounded < i64::MIN as f64 || rounded >= i64::MAX as f64 {
+        return Err(Error::DataException(format!(
+            "int64 out of range: {}",
+            value
+        )));
+    }
+    Ok(rounded as i64)
+}
+
 impl DateArray {
     pub fn cast_timestamp(&self) -> TimestampArray {
         cast_operator!(
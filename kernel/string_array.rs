@@ -3,7 +3,7 @@ use std::ops::Range;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    AnyArray, Array, BitSlice, Bitmask, BoolArray, DataType, DateArray, F64Array, I32Array,
+    AnyArray, Array, BitSlice, Bitmask, BoolArray, DataType, DateArray, Error, F64Array, I32Array,
     I64Array, TimestampArray,
 };
 
@@ -135,23 +135,48 @@ impl StringArray {
 
     // Casts.
 
-    pub fn cast_bool(&self) -> BoolArray {
-        cast_from_string!(self, value, value.parse::<bool>().unwrap(), BoolArray)
+    pub fn cast_bool(&self) -> Result<BoolArray, Error> {
+        cast_from_string!(
+            self,
+            value,
+            match value.to_lowercase().as_str() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(Error::DataException(format!("Bad bool value: {}", value))),
+            },
+            BoolArray
+        )
     }
 
-    pub fn cast_i64(&self) -> I64Array {
-        cast_from_string!(self, value, value.parse::<i64>().unwrap(), I64Array)
+    pub fn cast_i64(&self) -> Result<I64Array, Error> {
+        cast_from_string!(
+            self,
+            value,
+            value
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| Error::DataException(format!("Bad int64 value: {}", value))),
+            I64Array
+        )
     }
 
-    pub fn cast_f64(&self) -> F64Array {
-        cast_from_string!(self, value, value.parse::<f64>().unwrap(), F64Array)
+    pub fn cast_f64(&self) -> Result<F64Array, Error> {
+        cast_from_string!(
+            self,
+            value,
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| Error::DataException(format!("Bad double value: {}", value))),
+            F64Array
+        )
     }
 
-    pub fn cast_date(&self) -> DateArray {
+    pub fn cast_date(&self) -> Result<DateArray, Error> {
         cast_from_string!(self, value, crate::dates::parse_date(value), DateArray)
     }
 
-    pub fn cast_timestamp(&self) -> TimestampArray {
+    pub fn cast_timestamp(&self) -> Result<TimestampArray, Error> {
         cast_from_string!(
             self,
             value,
@@ -219,7 +244,7 @@ impl Array for StringArray {
 
     fn slice(&self, range: Range<usize>) -> Self {
         let start = self.offsets[range.start];
-        let end = self.offsets[range.end + 1];
+        let end = self.offsets[range.end];
         let buffer = self.buffer[start as usize..end as usize].to_string();
         let offsets = self.offsets[range.start..range.end + 1]
             .iter()
@@ -50,6 +50,9 @@ pub fn bootstrap_metadata_catalog() -> SimpleCatalogProto {
                     column("column_id", TypeKind::TypeInt64),
                     column("column_name", TypeKind::TypeString),
                     column("column_type", TypeKind::TypeString),
+                    column("not_null", TypeKind::TypeBool),
+                    column("default_expr", TypeKind::TypeString),
+                    column("primary_key", TypeKind::TypeBool),
                 ],
             ),
             table(
@@ -67,6 +70,84 @@ pub fn bootstrap_metadata_catalog() -> SimpleCatalogProto {
                     column("index_id", TypeKind::TypeInt64),
                     column("column_id", TypeKind::TypeInt64),
                     column("index_order", TypeKind::TypeInt64),
+                    column("descending", TypeKind::TypeBool),
+                ],
+            ),
+            table(
+                "view",
+                vec![
+                    column("table_id", TypeKind::TypeInt64),
+                    column("view_sql", TypeKind::TypeString),
+                    column("materialized", TypeKind::TypeBool),
+                    column("refreshed_txn", TypeKind::TypeInt64),
+                ],
+            ),
+            table(
+                "check_constraint",
+                vec![
+                    column("table_id", TypeKind::TypeInt64),
+                    column("constraint_name", TypeKind::TypeString),
+                    column("check_expr", TypeKind::TypeString),
+                ],
+            ),
+            table(
+                "foreign_key",
+                vec![
+                    column("table_id", TypeKind::TypeInt64),
+                    column("constraint_name", TypeKind::TypeString),
+                    column("column_name", TypeKind::TypeString),
+                    column("referenced_table_id", TypeKind::TypeInt64),
+                    column("referenced_column_name", TypeKind::TypeString),
+                    column("key_order", TypeKind::TypeInt64),
+                ],
+            ),
+            table(
+                "function",
+                vec![
+                    column("catalog_id", TypeKind::TypeInt64),
+                    column("function_name", TypeKind::TypeString),
+                    column("function_sql", TypeKind::TypeString),
+                    column("arguments", TypeKind::TypeString),
+                    column("return_type", TypeKind::TypeString),
+                    column("output_columns", TypeKind::TypeString),
+                ],
+            ),
+            table(
+                "sequence",
+                vec![
+                    column("catalog_id", TypeKind::TypeInt64),
+                    column("sequence_id", TypeKind::TypeInt64),
+                    column("sequence_name", TypeKind::TypeString),
+                    column("start_value", TypeKind::TypeInt64),
+                    column("increment", TypeKind::TypeInt64),
+                    // The identity column that owns the sequence, if any.
+                    column("table_id", TypeKind::TypeInt64),
+                    column("column_name", TypeKind::TypeString),
+                ],
+            ),
+            table(
+                "table_option",
+                vec![
+                    column("table_id", TypeKind::TypeInt64),
+                    column("option_name", TypeKind::TypeString),
+                    column("option_value", TypeKind::TypeString),
+                ],
+            ),
+            table(
+                "index_option",
+                vec![
+                    column("index_id", TypeKind::TypeInt64),
+                    column("option_name", TypeKind::TypeString),
+                    column("option_value", TypeKind::TypeString),
+                ],
+            ),
+            // The number of values of each sequence that each worker has reserved, which only workers write.
+            table(
+                "sequence_worker",
+                vec![
+                    column("sequence_id", TypeKind::TypeInt64),
+                    column("worker", TypeKind::TypeInt64),
+                    column("high_water", TypeKind::TypeInt64),
                 ],
             ),
         ],
@@ -76,16 +157,50 @@ pub fn bootstrap_metadata_catalog() -> SimpleCatalogProto {
             simple_function("next_catalog_id", vec![], TypeKind::TypeInt64),
             simple_function("next_table_id", vec![], TypeKind::TypeInt64),
             simple_function("next_index_id", vec![], TypeKind::TypeInt64),
+            simple_function("next_sequence_id", vec![], TypeKind::TypeInt64),
+            simple_function("xid", vec![], TypeKind::TypeInt64),
         ],
         procedure: vec![
             simple_procedure("create_catalog", vec![], TypeKind::TypeBool),
-            simple_procedure("create_table", vec![], TypeKind::TypeBool),
+            simple_procedure(
+                "create_table",
+                vec![TypeKind::TypeInt64],
+                TypeKind::TypeBool,
+            ),
             simple_procedure("create_index", vec![], TypeKind::TypeBool),
+            simple_procedure(
+                "create_sequence",
+                vec![TypeKind::TypeInt64, TypeKind::TypeInt64],
+                TypeKind::TypeBool,
+            ),
         ],
         ..Default::default()
     }
 }
 
+/// Functions that are available in every user catalog, but aren't built in to ZetaSQL.
+pub(crate) fn user_functions() -> Vec<FunctionProto> {
+    // GROUPING(x) is an aggregate function that accepts any GROUP BY expression.
+    let mut any = simple_argument(TypeKind::TypeInt64);
+    any.r#type = None;
+    any.kind = Some(SignatureArgumentKind::ArgTypeAny1 as i32);
+    vec![
+        FunctionProto {
+            name_path: vec!["grouping".to_string()],
+            group: Some("System".to_string()),
+            signature: vec![FunctionSignatureProto {
+                argument: vec![any],
+                return_type: Some(simple_argument(TypeKind::TypeInt64)),
+                ..Default::default()
+            }],
+            mode: Some(Mode::Aggregate as i32),
+            ..Default::default()
+        },
+        // NEXTVAL('name') generates the next value of a sequence.
+        simple_function("nextval", vec![TypeKind::TypeString], TypeKind::TypeInt64),
+    ]
+}
+
 fn simple_function(name: &str, arguments: Vec<TypeKind>, returns: TypeKind) -> FunctionProto {
     FunctionProto {
         name_path: vec![name.to_string()],
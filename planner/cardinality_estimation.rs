@@ -27,7 +27,21 @@ pub(crate) fn compute_logical_props(mid: MultiExprID, ss: &SearchSpace) -> Logic
             predicates,
             projects,
             table,
-        } => filter(predicates, &scan(projects, table)),
+            sample,
+        } => {
+            let mut props = scan(projects, table);
+            if let Some(sample) = sample {
+                props.cardinality *= sample.fraction();
+            }
+            filter(predicates, &props)
+        }
+        LogicalIndexGet {
+            predicates,
+            projects,
+            table,
+            limit,
+            ..
+        } => limit_offset(*limit, 0, &filter(predicates, &scan(projects, table))),
         LogicalFilter { predicates, input } => filter(predicates, &ss[leaf(input)].props),
         LogicalOut { projects, input } => project(projects, &ss[leaf(input)].props),
         LogicalMap {
@@ -59,6 +73,8 @@ pub(crate) fn compute_logical_props(mid: MultiExprID, ss: &SearchSpace) -> Logic
         } => limit_offset(*limit, *offset, &ss[leaf(input)].props),
         LogicalSort { input, .. } => ss[leaf(input)].props.clone(),
         LogicalUnion { left, right } => union(&ss[leaf(left)].props, &ss[leaf(right)].props),
+        // We don't know how many iterations there will be, so we assume there is only one.
+        LogicalIterate { base, step, .. } => union(&ss[leaf(base)].props, &ss[leaf(step)].props),
         LogicalScript { stmts } => {
             let last = stmts.last().unwrap();
             ss[leaf(last)].props.clone()
@@ -70,9 +86,16 @@ pub(crate) fn compute_logical_props(mid: MultiExprID, ss: &SearchSpace) -> Logic
         | LogicalDelete { .. }
         | LogicalCreateDatabase { .. }
         | LogicalCreateTable { .. }
+        | LogicalCreateTableAs { .. }
         | LogicalCreateTempTable { .. }
         | LogicalCreateIndex { .. }
+        | LogicalCreateSequence { .. }
         | LogicalDrop { .. }
+        | LogicalAddColumn { .. }
+        | LogicalRename { .. }
+        | LogicalCreateView { .. }
+        | LogicalCreateFunction { .. }
+        | LogicalRefresh { .. }
         | LogicalCall { .. }
         | LogicalRewrite { .. } => LogicalProps {
             cardinality: 0.0,
@@ -81,6 +104,7 @@ pub(crate) fn compute_logical_props(mid: MultiExprID, ss: &SearchSpace) -> Logic
         Leaf { .. }
         | TableFreeScan { .. }
         | SeqScan { .. }
+        | IndexOrderScan { .. }
         | IndexScan { .. }
         | Filter { .. }
         | Out { .. }
@@ -91,9 +115,11 @@ pub(crate) fn compute_logical_props(mid: MultiExprID, ss: &SearchSpace) -> Logic
         | GetTempTable { .. }
         | SimpleAggregate { .. }
         | GroupByAggregate { .. }
+        | PartialAggregate { .. }
         | Limit { .. }
         | Sort { .. }
         | Union { .. }
+        | Iterate { .. }
         | Broadcast { .. }
         | Exchange { .. }
         | Gather { .. }
@@ -254,6 +280,9 @@ fn selectivity(scalar: &Scalar, input: &LogicalProps) -> Option<f64> {
             | Value::F64(_)
             | Value::Date(_)
             | Value::Timestamp(_)
+            | Value::Json(_)
+            | Value::Bytes(_)
+            | Value::Array(_, _)
             | Value::EnumValue(_) => panic!("Bad bool value {}", value),
         },
         Scalar::Parameter(_, _) => None,
@@ -270,7 +299,10 @@ fn selectivity(scalar: &Scalar, input: &LogicalProps) -> Option<f64> {
             }
             ColumnStatistics::F64(_)
             | ColumnStatistics::Date(_)
-            | ColumnStatistics::Timestamp(_) => panic!("Bad bool value {}", column),
+            | ColumnStatistics::Timestamp(_)
+            | ColumnStatistics::Json(_)
+            | ColumnStatistics::Bytes(_)
+            | ColumnStatistics::Array(_, _) => panic!("Bad bool value {}", column),
         },
         Scalar::Call(function) => match function.as_ref() {
             // WHERE column1 = column2
@@ -342,7 +374,8 @@ fn selectivity(scalar: &Scalar, input: &LogicalProps) -> Option<f64> {
                 None
             }
         },
-        Scalar::Cast(scalar, _) => selectivity(scalar, input),
+        Scalar::Cast(scalar, _) | Scalar::SafeCast(scalar, _) => selectivity(scalar, input),
+        Scalar::SafeCall(_) => None,
     }
 }
 
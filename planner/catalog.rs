@@ -1,10 +1,16 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Mutex};
 
 use ast::{Index, *};
 use defaults::{builtin_function_options, builtin_named_types, METADATA_CATALOG_ID, RESERVED_IDS};
-use kernel::{Array, DataType, Next, RecordBatch};
-use once_cell::sync::OnceCell;
-use zetasql::{SimpleCatalogProto, SimpleColumnProto, SimpleTableProto};
+use kernel::{Array, DataType, Error, Next, RecordBatch};
+use once_cell::sync::{Lazy, OnceCell};
+use zetasql::{
+    function_enums::{ArgumentCardinality, Mode, TableValuedFunctionType},
+    FunctionArgumentTypeOptionsProto, FunctionArgumentTypeProto, FunctionProto,
+    FunctionSignatureProto, SignatureArgumentKind, SimpleCatalogProto, SimpleColumnProto,
+    SimpleConstantProto, SimpleTableProto, TableValuedFunctionProto, TvfRelationColumnProto,
+    TvfRelationProto, ValueProto,
+};
 
 #[derive(Hash, PartialEq, Eq)]
 pub enum SimpleCatalogProvider {
@@ -21,19 +27,69 @@ pub struct UserCatalog {
     name: Option<String>,
     tables: Vec<UserTable>,
     catalogs: Vec<UserCatalog>,
+    functions: Vec<UserFunction>,
+    // The names and ids of the sequences that can be passed to NEXTVAL.
+    sequences: Vec<(String, i64)>,
+    // Whether functions and sequences were looked up, which only happens if the statement uses one.
+    functions_registered: bool,
+    // The variables of the enclosing script, which are registered as constants and bound like query parameters.
+    variables: Vec<(String, DataType)>,
 }
 
 #[derive(Hash, PartialEq, Eq)]
-struct UserTable {
-    id: i64,
-    name: String,
-    columns: Vec<UserColumn>,
+pub struct UserTable {
+    pub id: i64,
+    pub name: String,
+    pub columns: Vec<UserColumn>,
+    pub view: Option<UserView>,
+    // The names and SQL of the CHECK constraints.
+    pub checks: Vec<(String, String)>,
+    // Foreign keys that reference this table or are declared by this table.
+    pub foreign_keys: Vec<ForeignKey>,
+    // The column that decides which worker each row is stored on, set by OPTIONS(distribution_key = ..).
+    pub distribution_key: Option<String>,
 }
 
 #[derive(Hash, PartialEq, Eq)]
-struct UserColumn {
-    name: String,
-    data_type: DataType,
+pub struct UserView {
+    pub sql: String,
+    pub materialized: bool,
+    // The view is defined over the metadata catalog, like the views of INFORMATION_SCHEMA.
+    pub metadata: bool,
+}
+
+/// A function defined by CREATE FUNCTION or CREATE TABLE FUNCTION, which is inlined into queries that call it.
+#[derive(Hash, PartialEq, Eq)]
+pub struct UserFunction {
+    pub name: String,
+    pub sql: String,
+    pub arguments: Vec<(String, DataType)>,
+    // The return type of a scalar function, or None for a table-valued function.
+    pub return_type: Option<DataType>,
+    // The output columns of a table-valued function.
+    pub columns: Vec<(String, DataType)>,
+}
+
+#[derive(Hash, PartialEq, Eq)]
+pub struct ForeignKey {
+    pub constraint_name: String,
+    pub table: Table,
+    pub columns: Vec<String>,
+    pub referenced_table: Table,
+    pub referenced_columns: Vec<String>,
+}
+
+#[derive(Hash, PartialEq, Eq, Clone)]
+pub struct UserColumn {
+    pub name: String,
+    pub data_type: DataType,
+    pub not_null: bool,
+    // The SQL of the DEFAULT expression, which is analyzed again by each statement that fills in the column.
+    pub default: Option<String>,
+    // Whether the column is part of the PRIMARY KEY.
+    pub primary_key: bool,
+    // The sequence that fills in an identity column.
+    pub sequence_id: Option<i64>,
 }
 
 impl SimpleCatalogProvider {
@@ -46,6 +102,20 @@ impl SimpleCatalogProvider {
         }
     }
 
+    /// Register the variables of a script, so the statements of the script can refer to them by name.
+    pub fn declare(mut self, variables: &HashMap<String, DataType>) -> Self {
+        if let SimpleCatalogProvider::UserCatalog { root_catalog, .. } = &mut self {
+            root_catalog.variables = variables
+                .iter()
+                .map(|(name, data_type)| (name.clone(), data_type.clone()))
+                .collect();
+            root_catalog
+                .variables
+                .sort_by(|(left, _), (right, _)| left.cmp(right));
+        }
+        self
+    }
+
     pub fn id(&self) -> i64 {
         match self {
             SimpleCatalogProvider::MetadataCatalog => METADATA_CATALOG_ID,
@@ -59,46 +129,227 @@ impl SimpleCatalogProvider {
             SimpleCatalogProvider::UserCatalog { all_indexes, .. } => all_indexes.clone(),
         }
     }
+
+    pub fn table(&self, table_id: i64) -> Option<&UserTable> {
+        match self {
+            SimpleCatalogProvider::MetadataCatalog => None,
+            SimpleCatalogProvider::UserCatalog { root_catalog, .. } => root_catalog.table(table_id),
+        }
+    }
+
+    /// The table at path, which must have been named when the catalog was built.
+    pub fn table_by_path(&self, path: &[String]) -> Option<&UserTable> {
+        match self {
+            SimpleCatalogProvider::MetadataCatalog => None,
+            SimpleCatalogProvider::UserCatalog { root_catalog, .. } => {
+                root_catalog.table_by_path(path)
+            }
+        }
+    }
+
+    /// The definition of a plain view, which is inlined into queries that reference it.
+    pub fn view(&self, table_id: i64) -> Option<&UserView> {
+        match self.table(table_id)?.view.as_ref()? {
+            view @ UserView {
+                materialized: false,
+                ..
+            } => Some(view),
+            _ => None,
+        }
+    }
+
+    pub fn function(&self, name: &str) -> Option<&UserFunction> {
+        match self {
+            SimpleCatalogProvider::MetadataCatalog => None,
+            SimpleCatalogProvider::UserCatalog { root_catalog, .. } => root_catalog
+                .functions
+                .iter()
+                .find(|function| function.name.eq_ignore_ascii_case(name)),
+        }
+    }
+
+    /// If functions and sequences were not registered, a statement that uses one has to be planned again with them.
+    /// Until then, the converter reports a missing function or sequence with not_registered.
+    pub fn functions_registered(&self) -> bool {
+        match self {
+            SimpleCatalogProvider::MetadataCatalog => true,
+            SimpleCatalogProvider::UserCatalog { root_catalog, .. } => {
+                root_catalog.functions_registered
+            }
+        }
+    }
+
+    pub fn sequence(&self, name: &str) -> Option<i64> {
+        match self {
+            SimpleCatalogProvider::MetadataCatalog => None,
+            SimpleCatalogProvider::UserCatalog { root_catalog, .. } => root_catalog
+                .sequences
+                .iter()
+                .find(|(sequence_name, _)| sequence_name == name)
+                .map(|(_, sequence_id)| *sequence_id),
+        }
+    }
 }
 
 impl UserCatalog {
     fn to_proto(&self) -> SimpleCatalogProto {
+        let mut custom_function = crate::bootstrap::user_functions();
+        let mut custom_tvf = vec![];
+        for function in &self.functions {
+            match function.return_type {
+                Some(_) => custom_function.push(function.to_proto()),
+                None => custom_tvf.push(function.to_tvf_proto()),
+            }
+        }
         SimpleCatalogProto {
             name: self.name.clone(),
             catalog: self.catalogs.iter().map(UserCatalog::to_proto).collect(),
             table: self.tables.iter().map(UserTable::to_proto).collect(),
             builtin_function_options: Some(builtin_function_options()),
             named_type: builtin_named_types(),
+            custom_function,
+            custom_tvf,
+            constant: self
+                .variables
+                .iter()
+                .map(|(name, data_type)| SimpleConstantProto {
+                    name_path: vec![name.clone()],
+                    r#type: Some(data_type.to_proto()),
+                    // The value is bound when the statement is executed, so the plan only needs the type.
+                    value: Some(ValueProto::default()),
+                })
+                .collect(),
             ..Default::default()
         }
     }
+
+    fn table(&self, table_id: i64) -> Option<&UserTable> {
+        match self.tables.iter().find(|table| table.id == table_id) {
+            Some(table) => Some(table),
+            None => self
+                .catalogs
+                .iter()
+                .find_map(|catalog| catalog.table(table_id)),
+        }
+    }
+
+    fn table_by_path(&self, path: &[String]) -> Option<&UserTable> {
+        match path {
+            [table_name] => self.tables.iter().find(|table| &table.name == table_name),
+            [catalog_name, path @ ..] => self
+                .catalogs
+                .iter()
+                .find(|catalog| catalog.name.as_ref() == Some(catalog_name))?
+                .table_by_path(path),
+            [] => None,
+        }
+    }
 }
 
 impl UserTable {
     fn to_proto(&self) -> SimpleTableProto {
+        // The heap of a plain view is always empty, so DML against it would be silently lost.
+        let writable = !matches!(
+            self.view,
+            Some(UserView {
+                materialized: false,
+                ..
+            })
+        );
         SimpleTableProto {
             name: Some(self.name.clone()),
             serialization_id: Some(self.id),
-            column: self.columns.iter().map(UserColumn::to_proto).collect(),
+            column: self.columns.iter().map(|c| c.to_proto(writable)).collect(),
             ..Default::default()
         }
     }
 }
 
 impl UserColumn {
-    fn to_proto(&self) -> SimpleColumnProto {
+    fn to_proto(&self, writable: bool) -> SimpleColumnProto {
         SimpleColumnProto {
             name: Some(self.name.clone()),
             r#type: Some(self.data_type.to_proto()),
+            is_writable_column: Some(writable),
             ..Default::default()
         }
     }
 }
 
+impl UserFunction {
+    fn to_proto(&self) -> FunctionProto {
+        FunctionProto {
+            name_path: vec![self.name.clone()],
+            group: Some("User".to_string()),
+            signature: vec![FunctionSignatureProto {
+                argument: self.arguments.iter().map(fixed_argument).collect(),
+                return_type: Some(fixed_type(self.return_type.clone().unwrap())),
+                ..Default::default()
+            }],
+            mode: Some(Mode::Scalar as i32),
+            ..Default::default()
+        }
+    }
+
+    fn to_tvf_proto(&self) -> TableValuedFunctionProto {
+        let output_schema = TvfRelationProto {
+            column: self
+                .columns
+                .iter()
+                .map(|(name, data_type)| TvfRelationColumnProto {
+                    name: Some(name.clone()),
+                    r#type: Some(data_type.to_proto()),
+                    ..Default::default()
+                })
+                .collect(),
+            is_value_table: Some(false),
+        };
+        TableValuedFunctionProto {
+            name_path: vec![self.name.clone()],
+            signature: Some(FunctionSignatureProto {
+                argument: self.arguments.iter().map(fixed_argument).collect(),
+                return_type: Some(FunctionArgumentTypeProto {
+                    kind: Some(SignatureArgumentKind::ArgTypeRelation as i32),
+                    num_occurrences: Some(1),
+                    options: Some(FunctionArgumentTypeOptionsProto {
+                        relation_input_schema: Some(output_schema),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            r#type: Some(TableValuedFunctionType::FixedOutputSchemaTvf as i32),
+            ..Default::default()
+        }
+    }
+}
+
+fn fixed_argument((name, data_type): &(String, DataType)) -> FunctionArgumentTypeProto {
+    let mut argument = fixed_type(data_type.clone());
+    argument.options.as_mut().unwrap().argument_name = Some(name.clone());
+    argument
+}
+
+fn fixed_type(data_type: DataType) -> FunctionArgumentTypeProto {
+    FunctionArgumentTypeProto {
+        kind: Some(SignatureArgumentKind::ArgTypeFixed as i32),
+        r#type: Some(data_type.to_proto()),
+        num_occurrences: Some(1),
+        options: Some(FunctionArgumentTypeOptionsProto {
+            cardinality: Some(ArgumentCardinality::Required as i32),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
 #[log::trace]
 pub fn simple_catalog(
     table_names: Vec<Vec<String>>,
     catalog_id: i64,
+    session: Option<i64>,
+    functions: bool,
     txn: i64,
 ) -> SimpleCatalogProvider {
     if catalog_id == METADATA_CATALOG_ID {
@@ -107,21 +358,73 @@ pub fn simple_catalog(
     let mut catalog_id_cache = HashMap::new();
     let mut root_catalog = UserCatalog::default();
     let mut all_indexes = vec![];
-    for name in &table_names {
+    let mut table_names = table_names;
+    // Functions are not named in the statement before it is analyzed, and sequences are named by string literals,
+    // so every function and sequence in the catalog is registered, but only once a statement turns out to need them.
+    if functions {
+        root_catalog.functions = catalog_functions(catalog_id, txn);
+        root_catalog.sequences = catalog_sequences(catalog_id, txn);
+        root_catalog.functions_registered = true;
+    }
+    for function in &root_catalog.functions {
+        // Table-valued functions are inlined during conversion, so the tables they reference must be in the catalog too.
+        if function.return_type.is_none() {
+            table_names.extend(crate::cached_table_names(&function.sql));
+        }
+    }
+    let mut i = 0;
+    while i < table_names.len() {
+        let name = table_names[i].clone();
+        i += 1;
+        // Views can reference the same table more than once.
+        if table_names[..i - 1].contains(&name) {
+            continue;
+        }
+        if name.len() == 2 && name[0].eq_ignore_ascii_case("information_schema") {
+            let catalog =
+                find_or_push_catalog(&mut root_catalog, &"information_schema".to_string());
+            if let Some(table) = information_schema_table(&name[1], catalog_id) {
+                if !catalog.tables.iter().any(|t| t.id == table.id) {
+                    catalog.tables.push(table);
+                }
+            }
+            continue;
+        }
+        // Temp tables shadow the tables of the catalog with the same name.
+        if name.len() == 1 {
+            if let Some(table) = temp_table(session, &name[0]) {
+                root_catalog.tables.push(table);
+                continue;
+            }
+        }
         let mut catalog_id = catalog_id;
         let mut catalog = &mut root_catalog;
         for catalog_name in &name[..name.len() - 1] {
             catalog_id = *catalog_id_cache
-                .entry((catalog_id, catalog_name))
+                .entry((catalog_id, catalog_name.clone()))
                 .or_insert_with(|| catalog_name_to_id(catalog_id, catalog_name, txn));
             catalog = find_or_push_catalog(catalog, catalog_name);
         }
         let table_name = &name[name.len() - 1];
         if let Some(table_id) = table_name_to_id(catalog_id, table_name, txn) {
+            let view = table_view(table_id, txn);
+            // Plain views are inlined during conversion, so the tables they reference must be in the catalog too.
+            if let Some(UserView {
+                sql,
+                materialized: false,
+                ..
+            }) = &view
+            {
+                table_names.extend(crate::cached_table_names(sql));
+            }
             catalog.tables.push(UserTable {
                 id: table_id,
                 name: table_name.clone(),
                 columns: table_columns(table_id, txn),
+                view,
+                checks: table_checks(table_id, txn),
+                foreign_keys: table_foreign_keys(table_id, txn),
+                distribution_key: table_distribution_key(table_id, txn),
             });
             all_indexes.append(&mut indexes(table_id, txn));
         }
@@ -157,7 +460,7 @@ pub fn indexes(table_id: i64, txn: i64) -> Vec<Index> {
     }
     let mut params = HashMap::new();
     params.insert("table_id".to_string(), Value::I64(Some(table_id)));
-    let sql = "select index_id, column_name from index join index_column using (index_id) join column using (table_id, column_id) where table_id = @table_id order by index_id, index_order";
+    let sql = "select index_id, column_name, descending from index join index_column using (index_id) join column using (table_id, column_id) where table_id = @table_id order by index_id, index_order";
     let expr = analyze_once!(sql, &params);
     let mut batch = execute_on_coordinator(sql, expr, &params, txn);
     let mut indexes: Vec<Index> = vec![];
@@ -165,17 +468,22 @@ pub fn indexes(table_id: i64, txn: i64) -> Vec<Index> {
     let index_id = index_id.as_i64();
     let (_, column_name) = batch.columns.remove(0);
     let column_name = column_name.as_string();
+    let (_, descending) = batch.columns.remove(0);
+    let descending = descending.as_bool();
     for i in 0..index_id.len() {
         let index_id = index_id.get(i).unwrap();
         let column_name = column_name.get(i).unwrap();
+        let descending = descending.get(i).unwrap();
         match indexes.last_mut() {
             Some(index) if index.index_id == index_id => {
-                index.columns.push(column_name.to_string())
+                index.columns.push(column_name.to_string());
+                index.descending.push(descending);
             }
             _ => indexes.push(Index {
                 table_id,
                 index_id,
                 columns: vec![column_name.to_string()],
+                descending: vec![descending],
             }),
         }
     }
@@ -220,7 +528,7 @@ fn table_name_to_id(catalog_id: i64, table_name: &String, txn: i64) -> Option<i6
 fn table_columns(table_id: i64, txn: i64) -> Vec<UserColumn> {
     let mut params = HashMap::new();
     params.insert("table_id".to_string(), Value::I64(Some(table_id)));
-    let sql = "select column_name, column_type from column where table_id = @table_id";
+    let sql = "select column_name, column_type, not_null, default_expr, primary_key, sequence_id from column left join sequence using (table_id, column_name) where table_id = @table_id order by column_id";
     let expr = analyze_once!(sql, &params);
     let mut batch = execute_on_coordinator(sql, expr, &params, txn);
     let mut columns = vec![];
@@ -228,15 +536,625 @@ fn table_columns(table_id: i64, txn: i64) -> Vec<UserColumn> {
     let column_name = column_name.as_string();
     let (_, column_type) = batch.columns.remove(0);
     let column_type = column_type.as_string();
+    let (_, not_null) = batch.columns.remove(0);
+    let not_null = not_null.as_bool();
+    let (_, default_expr) = batch.columns.remove(0);
+    let default_expr = default_expr.as_string();
+    let (_, primary_key) = batch.columns.remove(0);
+    let primary_key = primary_key.as_bool();
+    let (_, sequence_id) = batch.columns.remove(0);
+    let sequence_id = sequence_id.as_i64();
     for i in 0..column_name.len() {
         columns.push(UserColumn {
             name: column_name.get(i).unwrap(),
             data_type: DataType::from(column_type.get_str(i).unwrap()),
+            not_null: not_null.get(i).unwrap_or(false),
+            default: default_expr.get(i),
+            primary_key: primary_key.get(i).unwrap_or(false),
+            sequence_id: sequence_id.get(i),
         })
     }
     columns
 }
 
+#[log::trace]
+fn table_checks(table_id: i64, txn: i64) -> Vec<(String, String)> {
+    if table_id < RESERVED_IDS {
+        return vec![];
+    }
+    let mut params = HashMap::new();
+    params.insert("table_id".to_string(), Value::I64(Some(table_id)));
+    let sql = "select constraint_name, check_expr from check_constraint where table_id = @table_id";
+    let expr = analyze_once!(sql, &params);
+    let mut batch = execute_on_coordinator(sql, expr, &params, txn);
+    let mut checks = vec![];
+    let (_, constraint_name) = batch.columns.remove(0);
+    let constraint_name = constraint_name.as_string();
+    let (_, check_expr) = batch.columns.remove(0);
+    let check_expr = check_expr.as_string();
+    for i in 0..constraint_name.len() {
+        checks.push((constraint_name.get(i).unwrap(), check_expr.get(i).unwrap()))
+    }
+    checks
+}
+
+#[log::trace]
+fn table_distribution_key(table_id: i64, txn: i64) -> Option<String> {
+    if table_id < RESERVED_IDS {
+        return None;
+    }
+    let mut params = HashMap::new();
+    params.insert("table_id".to_string(), Value::I64(Some(table_id)));
+    let sql = "select option_value from table_option where table_id = @table_id and option_name = 'distribution_key'";
+    let expr = analyze_once!(sql, &params);
+    let mut batch = execute_on_coordinator(sql, expr, &params, txn);
+    let (_, column) = batch.columns.remove(0);
+    column.as_string().get(0)
+}
+
+#[log::trace]
+fn table_view(table_id: i64, txn: i64) -> Option<UserView> {
+    if table_id < RESERVED_IDS {
+        return None;
+    }
+    let mut params = HashMap::new();
+    params.insert("table_id".to_string(), Value::I64(Some(table_id)));
+    let sql = "select view_sql, materialized from view where table_id = @table_id";
+    let expr = analyze_once!(sql, &params);
+    let mut batch = execute_on_coordinator(sql, expr, &params, txn);
+    let (_, view_sql) = batch.columns.remove(0);
+    let (_, materialized) = batch.columns.remove(0);
+    Some(UserView {
+        sql: view_sql.as_string().get(0)?,
+        materialized: materialized.as_bool().get(0).unwrap(),
+        metadata: false,
+    })
+}
+
+/// The views of INFORMATION_SCHEMA. They are never stored, so they are given negative ids, which are never allocated to a table.
+const INFORMATION_SCHEMA: [&str; 5] = ["schemata", "tables", "columns", "indexes", "table_options"];
+
+/// A view of INFORMATION_SCHEMA, which describes the catalog in terms of names rather than ids.
+/// Tables in the catalog itself have an empty schema name, and tables in nested catalogs have the path to the nested catalog, like a.b.
+fn information_schema_table(table_name: &String, catalog_id: i64) -> Option<UserTable> {
+    let table_name = table_name.to_lowercase();
+    let id = -1
+        - INFORMATION_SCHEMA
+            .iter()
+            .position(|name| *name == table_name)? as i64;
+    // The schemata are found when the view is executed, so planning a query does not need to read the catalog first.
+    let schemata = format!("with recursive schemata as (select {} as catalog_id, '' as schema_name union all select c.catalog_id, if(s.schema_name = '', c.catalog_name, concat(s.schema_name, '.', c.catalog_name)) from schemata as s join catalog as c on c.parent_catalog_id = s.catalog_id) ", catalog_id);
+    let (sql, columns) = match table_name.as_str() {
+        "schemata" => (
+            format!("{}select schema_name from schemata where schema_name <> ''", schemata),
+            vec![("schema_name", DataType::String)],
+        ),
+        "tables" => (
+            format!("{}select s.schema_name as table_schema, t.table_name, case when v.table_id is null then 'BASE TABLE' when v.materialized then 'MATERIALIZED VIEW' else 'VIEW' end as table_type from schemata as s join table as t on t.catalog_id = s.catalog_id left join view as v on v.table_id = t.table_id", schemata),
+            vec![
+                ("table_schema", DataType::String),
+                ("table_name", DataType::String),
+                ("table_type", DataType::String),
+            ],
+        ),
+        "columns" => (
+            format!("{}select s.schema_name as table_schema, t.table_name, c.column_name, c.column_id + 1 as ordinal_position, c.column_type as data_type, if(coalesce(c.not_null, false), 'NO', 'YES') as is_nullable from schemata as s join table as t on t.catalog_id = s.catalog_id join column as c on c.table_id = t.table_id", schemata),
+            vec![
+                ("table_schema", DataType::String),
+                ("table_name", DataType::String),
+                ("column_name", DataType::String),
+                ("ordinal_position", DataType::I64),
+                ("data_type", DataType::String),
+                ("is_nullable", DataType::String),
+            ],
+        ),
+        "indexes" => (
+            format!("{}select s.schema_name as table_schema, t.table_name, i.index_name, c.column_name, ic.index_order + 1 as ordinal_position from schemata as s join table as t on t.catalog_id = s.catalog_id join index as i on i.table_id = t.table_id join index_column as ic on ic.index_id = i.index_id join column as c on c.table_id = t.table_id and c.column_id = ic.column_id", schemata),
+            vec![
+                ("table_schema", DataType::String),
+                ("table_name", DataType::String),
+                ("index_name", DataType::String),
+                ("column_name", DataType::String),
+                ("ordinal_position", DataType::I64),
+            ],
+        ),
+        "table_options" => (
+            format!("{}select s.schema_name as table_schema, t.table_name, o.option_name, o.option_value from schemata as s join table as t on t.catalog_id = s.catalog_id join table_option as o on o.table_id = t.table_id", schemata),
+            vec![
+                ("table_schema", DataType::String),
+                ("table_name", DataType::String),
+                ("option_name", DataType::String),
+                ("option_value", DataType::String),
+            ],
+        ),
+        _ => return None,
+    };
+    Some(UserTable {
+        id,
+        name: table_name,
+        columns: columns
+            .into_iter()
+            .map(|(name, data_type)| UserColumn {
+                name: name.to_string(),
+                data_type,
+                not_null: false,
+                default: None,
+                primary_key: false,
+                sequence_id: None,
+            })
+            .collect(),
+        view: Some(UserView {
+            sql,
+            materialized: false,
+            metadata: true,
+        }),
+        checks: vec![],
+        foreign_keys: vec![],
+        distribution_key: None,
+    })
+}
+
+/// The error the converter returns for a function or sequence that may exist but was not registered.
+pub(crate) fn not_registered(kind: &str, name: &str) -> Error {
+    Error::Syntax(format!("{} not found: {}", kind, name))
+}
+
+/// Did analysis fail because the statement uses a function or sequence that was not registered?
+pub(crate) fn needs_functions(error: &Error) -> bool {
+    match error {
+        // ZetaSQL reports "Function not found" and "Table-valued function not found",
+        // even when the function is called from a view or a DEFAULT.
+        Error::Syntax(message) => {
+            message.contains("unction not found: ") || message.contains("Sequence not found: ")
+        }
+        _ => false,
+    }
+}
+
+#[log::trace]
+fn catalog_functions(catalog_id: i64, txn: i64) -> Vec<UserFunction> {
+    let mut params = HashMap::new();
+    params.insert("catalog_id".to_string(), Value::I64(Some(catalog_id)));
+    let sql = "select function_name, function_sql, arguments, return_type, output_columns from function where catalog_id = @catalog_id";
+    let expr = analyze_once!(sql, &params);
+    let mut batch = execute_on_coordinator(sql, expr, &params, txn);
+    let mut functions = vec![];
+    let (_, function_name) = batch.columns.remove(0);
+    let function_name = function_name.as_string();
+    let (_, function_sql) = batch.columns.remove(0);
+    let function_sql = function_sql.as_string();
+    let (_, arguments) = batch.columns.remove(0);
+    let arguments = arguments.as_string();
+    let (_, return_type) = batch.columns.remove(0);
+    let return_type = return_type.as_string();
+    let (_, output_columns) = batch.columns.remove(0);
+    let output_columns = output_columns.as_string();
+    for i in 0..function_name.len() {
+        functions.push(UserFunction {
+            name: function_name.get(i).unwrap(),
+            sql: function_sql.get(i).unwrap(),
+            arguments: serde_json::from_str(arguments.get_str(i).unwrap()).unwrap(),
+            return_type: return_type.get_str(i).map(DataType::from),
+            columns: serde_json::from_str(output_columns.get_str(i).unwrap()).unwrap(),
+        })
+    }
+    functions
+}
+
+#[log::trace]
+fn catalog_sequences(catalog_id: i64, txn: i64) -> Vec<(String, i64)> {
+    let mut params = HashMap::new();
+    params.insert("catalog_id".to_string(), Value::I64(Some(catalog_id)));
+    let sql = "select sequence_name, sequence_id from sequence where catalog_id = @catalog_id and sequence_name is not null";
+    let expr = analyze_once!(sql, &params);
+    let mut batch = execute_on_coordinator(sql, expr, &params, txn);
+    let mut sequences = vec![];
+    let (_, sequence_name) = batch.columns.remove(0);
+    let sequence_name = sequence_name.as_string();
+    let (_, sequence_id) = batch.columns.remove(0);
+    let sequence_id = sequence_id.as_i64();
+    for i in 0..sequence_name.len() {
+        sequences.push((sequence_name.get(i).unwrap(), sequence_id.get(i).unwrap()))
+    }
+    sequences
+}
+
+/// Plan the refresh of a materialized view.
+/// If the view only filters and projects a single table, and no rows of that table have been deleted since the last refresh,
+/// we insert the rows that were added since the last refresh. Otherwise, we replace the contents of the view.
+pub fn refresh_materialized_view(
+    name: &Name,
+    session: Option<i64>,
+    txn: i64,
+) -> Result<Expr, Error> {
+    let view_catalog = simple_catalog(
+        vec![name.path.clone()],
+        name.catalog_id,
+        session,
+        false,
+        txn,
+    );
+    let mut catalog = match &view_catalog {
+        SimpleCatalogProvider::UserCatalog { root_catalog, .. } => root_catalog,
+        SimpleCatalogProvider::MetadataCatalog => {
+            return Err(Error::Syntax("Cannot refresh a metadata table".to_string()))
+        }
+    };
+    for catalog_name in &name.path[..name.path.len() - 1] {
+        match catalog
+            .catalogs
+            .iter()
+            .find(|c| c.name.as_ref() == Some(catalog_name))
+        {
+            Some(c) => catalog = c,
+            None => {
+                return Err(Error::Syntax(format!(
+                    "Catalog {} does not exist",
+                    catalog_name
+                )))
+            }
+        }
+    }
+    let table_name = name.path.last().unwrap();
+    let table = match catalog.tables.iter().find(|t| &t.name == table_name) {
+        Some(table) => table,
+        None => {
+            return Err(Error::Syntax(format!(
+                "Materialized view {} does not exist",
+                table_name
+            )))
+        }
+    };
+    let sql = match &table.view {
+        Some(UserView {
+            sql,
+            materialized: true,
+            ..
+        }) => sql,
+        _ => {
+            return Err(Error::Syntax(format!(
+                "{} is not a materialized view",
+                table_name
+            )))
+        }
+    };
+    let path: Vec<String> = name.path.iter().map(|n| format!("`{}`", n)).collect();
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|c| format!("`{}`", c.name))
+        .collect();
+    let delete = format!("delete from {} where true", path.join("."));
+    let insert = format!(
+        "insert into {} ({}) {}",
+        path.join("."),
+        columns.join(", "),
+        sql
+    );
+    let params = HashMap::new();
+    let catalog = simple_catalog(
+        crate::parser::extract_table_names_from_stmt(&insert),
+        name.catalog_id,
+        session,
+        true,
+        txn,
+    );
+    let mut insert = crate::parser::analyze(&insert, &params, &catalog)?;
+    let mut stmts = vec![];
+    match (view_refreshed_txn(table.id, txn), single_scan(&mut insert)) {
+        (
+            Some(refreshed_txn),
+            Some(Expr::LogicalGet {
+                predicates,
+                projects,
+                table: base_table,
+                ..
+            }),
+        ) if !deleted_since(base_table, refreshed_txn, txn) => {
+            // Rows that were visible to the last refresh are already in the view.
+            let xmin = projects.iter().find(|c| c.name == "$xmin").unwrap();
+            predicates.push(Scalar::Call(Box::new(F::Less(
+                Scalar::Literal(Value::I64(Some(refreshed_txn))),
+                Scalar::Column(xmin.clone()),
+            ))));
+        }
+        _ => stmts.push(crate::parser::analyze(&delete, &params, &catalog)?),
+    }
+    stmts.push(insert);
+    stmts.push(Expr::LogicalRewrite {
+        sql: format!(
+            "update view set refreshed_txn = xid() where table_id = {};",
+            table.id
+        ),
+    });
+    Ok(crate::optimize::optimize(
+        Expr::LogicalScript { stmts },
+        catalog.indexes(),
+    ))
+}
+
+/// The transaction that last refreshed a materialized view, or None if it has never been refreshed.
+fn view_refreshed_txn(table_id: i64, txn: i64) -> Option<i64> {
+    let mut params = HashMap::new();
+    params.insert("table_id".to_string(), Value::I64(Some(table_id)));
+    let sql = "select refreshed_txn from view where table_id = @table_id";
+    let expr = analyze_once!(sql, &params);
+    let mut batch = execute_on_coordinator(sql, expr, &params, txn);
+    let (_, refreshed_txn) = batch.columns.remove(0);
+    refreshed_txn.as_i64().get(0)
+}
+
+/// If the rows produced by expr are a filter and projection of a single table, return the scan of that table.
+/// New rows of the table then produce new rows of expr, without changing the rows it already produced.
+fn single_scan(expr: &mut Expr) -> Option<&mut Expr> {
+    let volatile = match &*expr {
+        Expr::LogicalInsert { .. } | Expr::LogicalOut { .. } => false,
+        Expr::LogicalMap { projects, .. } => projects.iter().any(|(x, _)| is_volatile(x)),
+        Expr::LogicalFilter { predicates, .. } => predicates.iter().any(is_volatile),
+        Expr::LogicalGet {
+            predicates, sample, ..
+        } => sample.is_some() || predicates.iter().any(is_volatile),
+        _ => return None,
+    };
+    if volatile {
+        return None;
+    }
+    if let Expr::LogicalGet { .. } = expr {
+        return Some(expr);
+    }
+    single_scan(&mut expr[0])
+}
+
+/// Functions like CURRENT_TIMESTAMP return a different value each time the view is refreshed.
+fn is_volatile(x: &Scalar) -> bool {
+    match x {
+        Scalar::Literal(_) | Scalar::Parameter(_, _) | Scalar::Column(_) => false,
+        Scalar::Call(f) | Scalar::SafeCall(f) => match f.as_ref() {
+            F::CurrentDate | F::CurrentTimestamp | F::GenerateUuid | F::Rand => true,
+            f => f.arguments().iter().any(|x| is_volatile(x)),
+        },
+        Scalar::Cast(x, _) | Scalar::SafeCast(x, _) => is_volatile(x),
+    }
+}
+
+/// Check if any rows of table were deleted or truncated after refreshed_txn.
+fn deleted_since(table: &Table, refreshed_txn: i64, txn: i64) -> bool {
+    let xmax = Column::fresh("$xmax", DataType::I64);
+    let expr = Expr::LogicalLimit {
+        limit: 1,
+        offset: 0,
+        input: Box::new(Expr::LogicalGet {
+            predicates: vec![
+                Scalar::Call(Box::new(F::Less(
+                    Scalar::Literal(Value::I64(Some(refreshed_txn))),
+                    Scalar::Column(xmax.clone()),
+                ))),
+                Scalar::Call(Box::new(F::LessOrEqual(
+                    Scalar::Column(xmax.clone()),
+                    Scalar::Call(Box::new(F::Xid)),
+                ))),
+            ],
+            projects: vec![xmax],
+            table: table.clone(),
+            sample: None,
+        }),
+    };
+    let expr = crate::optimize::optimize(expr, vec![]);
+    execute(&expr, txn).iter().any(|batch| batch.len() > 0)
+}
+
+/// A table created by CREATE TEMP TABLE.
+/// Its rows are stored in an ordinary heap, but it is only registered here, for the session that created it.
+pub struct TempTable {
+    id: i64,
+    name: String,
+    columns: Vec<UserColumn>,
+    checks: Vec<(String, String)>,
+    distribution_key: Option<String>,
+}
+
+static TEMP_TABLES: Lazy<Mutex<HashMap<i64, Vec<TempTable>>>> = Lazy::new(Default::default);
+
+fn temp_table(session: Option<i64>, table_name: &String) -> Option<UserTable> {
+    let temp_tables = TEMP_TABLES.lock().unwrap();
+    let table = temp_tables
+        .get(&session?)?
+        .iter()
+        .find(|t| &t.name == table_name)?;
+    Some(UserTable {
+        id: table.id,
+        name: table.name.clone(),
+        columns: table.columns.clone(),
+        view: None,
+        checks: table.checks.clone(),
+        foreign_keys: vec![],
+        distribution_key: table.distribution_key.clone(),
+    })
+}
+
+/// Check a temp table for the session, and return a plan that creates its heap on every worker and returns its table id.
+/// The table is registered by register_temp_table once the plan has run.
+pub fn create_temp_table(
+    session: Option<i64>,
+    name: &Name,
+    columns: Vec<(String, DataType)>,
+    constraints: Vec<Constraint>,
+    options: Vec<(String, Scalar)>,
+) -> Result<(Expr, TempTable), String> {
+    let session = match session {
+        Some(session) => session,
+        None => return Err("CREATE TEMP TABLE requires a session".to_string()),
+    };
+    let table_name = name.path.last().unwrap().clone();
+    let mut columns: Vec<UserColumn> = columns
+        .into_iter()
+        .map(|(name, data_type)| UserColumn {
+            name,
+            data_type,
+            not_null: false,
+            default: None,
+            primary_key: false,
+            sequence_id: None,
+        })
+        .collect();
+    let mut checks = vec![];
+    for constraint in constraints {
+        match constraint {
+            Constraint::NotNull(column_name) => {
+                for column in &mut columns {
+                    if column.name == column_name {
+                        column.not_null = true;
+                    }
+                }
+            }
+            Constraint::Default(column_name, sql) => {
+                for column in &mut columns {
+                    if column.name == column_name {
+                        column.default = Some(sql.clone());
+                    }
+                }
+            }
+            Constraint::Check(constraint_name, check) => checks.push((constraint_name, check)),
+            Constraint::PrimaryKey(column_names) => {
+                for column in &mut columns {
+                    if column_names.contains(&column.name) {
+                        column.primary_key = true;
+                    }
+                }
+            }
+            Constraint::ForeignKey(..) => {
+                return Err(format!(
+                    "Temp table {} cannot have a FOREIGN KEY",
+                    table_name
+                ))
+            }
+            Constraint::Identity(column_name) => {
+                return Err(format!(
+                    "Temp table {} cannot have identity column {}",
+                    table_name, column_name
+                ))
+            }
+        }
+    }
+    if temp_table(Some(session), &table_name).is_some() {
+        return Err(format!("Temp table {} already exists", table_name));
+    }
+    let page_size = crate::options::page_size(&options);
+    // create_table returns the id of the heap it adds, which stays right if other tables are created concurrently.
+    let expr = Expr::LogicalCall {
+        procedure: Procedure::CreateTable(Scalar::Literal(Value::I64(Some(page_size)))),
+        input: Box::new(Expr::LogicalSingleGet),
+    };
+    let table = TempTable {
+        id: 0,
+        name: table_name,
+        columns,
+        checks,
+        distribution_key: crate::options::distribution_key(&options),
+    };
+    Ok((crate::optimize::optimize(expr, vec![]), table))
+}
+
+/// Register a temp table for the session, once the plan from create_temp_table has created its heap with table id.
+pub fn register_temp_table(session: i64, mut table: TempTable, id: i64) -> Result<(), String> {
+    let mut temp_tables = TEMP_TABLES.lock().unwrap();
+    let tables = temp_tables.entry(session).or_default();
+    // Another statement of the session may have created a table with the same name in the meantime.
+    if tables.iter().any(|t| t.name == table.name) {
+        return Err(format!("Temp table {} already exists", table.name));
+    }
+    table.id = id;
+    tables.push(table);
+    Ok(())
+}
+
+/// Remove a temp table of the session, returning a plan that truncates its heap, or None if there is no temp table with that name.
+pub fn drop_temp_table(session: Option<i64>, name: &Name) -> Option<Expr> {
+    if name.path.len() != 1 {
+        return None;
+    }
+    let mut temp_tables = TEMP_TABLES.lock().unwrap();
+    let tables = temp_tables.get_mut(&session?)?;
+    let i = tables.iter().position(|t| t.name == name.path[0])?;
+    let table = tables.remove(i);
+    Some(truncate_temp_tables(vec![table]))
+}
+
+/// Remove all the temp tables of a session, returning a plan that truncates their heaps.
+pub fn end_session(session: i64) -> Option<Expr> {
+    let tables = TEMP_TABLES.lock().unwrap().remove(&session)?;
+    if tables.is_empty() {
+        return None;
+    }
+    Some(truncate_temp_tables(tables))
+}
+
+fn truncate_temp_tables(tables: Vec<TempTable>) -> Expr {
+    let stmts = tables
+        .into_iter()
+        .map(|table| Expr::LogicalCall {
+            procedure: Procedure::Truncate(Table {
+                id: table.id,
+                name: table.name,
+            }),
+            input: Box::new(Expr::LogicalSingleGet),
+        })
+        .collect();
+    crate::optimize::optimize(Expr::LogicalScript { stmts }, vec![])
+}
+
+#[log::trace]
+fn table_foreign_keys(table_id: i64, txn: i64) -> Vec<ForeignKey> {
+    if table_id < RESERVED_IDS {
+        return vec![];
+    }
+    let mut params = HashMap::new();
+    params.insert("table_id".to_string(), Value::I64(Some(table_id)));
+    let sql = "select constraint_name, table_id, t.table_name, column_name, referenced_table_id, r.table_name as referenced_table_name, referenced_column_name from foreign_key join table t using (table_id) join table r on referenced_table_id = r.table_id where table_id = @table_id or referenced_table_id = @table_id order by table_id, constraint_name, key_order";
+    let expr = analyze_once!(sql, &params);
+    let mut batch = execute_on_coordinator(sql, expr, &params, txn);
+    let mut foreign_keys: Vec<ForeignKey> = vec![];
+    let (_, constraint_name) = batch.columns.remove(0);
+    let constraint_name = constraint_name.as_string();
+    let (_, table_id) = batch.columns.remove(0);
+    let table_id = table_id.as_i64();
+    let (_, table_name) = batch.columns.remove(0);
+    let table_name = table_name.as_string();
+    let (_, column_name) = batch.columns.remove(0);
+    let column_name = column_name.as_string();
+    let (_, referenced_table_id) = batch.columns.remove(0);
+    let referenced_table_id = referenced_table_id.as_i64();
+    let (_, referenced_table_name) = batch.columns.remove(0);
+    let referenced_table_name = referenced_table_name.as_string();
+    let (_, referenced_column_name) = batch.columns.remove(0);
+    let referenced_column_name = referenced_column_name.as_string();
+    for i in 0..constraint_name.len() {
+        let table = Table {
+            id: table_id.get(i).unwrap(),
+            name: table_name.get(i).unwrap(),
+        };
+        let name = constraint_name.get(i).unwrap();
+        match foreign_keys.last_mut() {
+            Some(fk) if fk.table == table && fk.constraint_name == name => {
+                fk.columns.push(column_name.get(i).unwrap());
+                fk.referenced_columns
+                    .push(referenced_column_name.get(i).unwrap());
+            }
+            _ => foreign_keys.push(ForeignKey {
+                constraint_name: name,
+                table,
+                columns: vec![column_name.get(i).unwrap()],
+                referenced_table: Table {
+                    id: referenced_table_id.get(i).unwrap(),
+                    name: referenced_table_name.get(i).unwrap(),
+                },
+                referenced_columns: vec![referenced_column_name.get(i).unwrap()],
+            }),
+        }
+    }
+    foreign_keys
+}
+
 fn find_or_push_catalog<'a>(
     parent_catalog: &'a mut UserCatalog,
     catalog_name: &String,
@@ -1,85 +1,163 @@
-use std::{borrow::Borrow, collections::HashMap};
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+};
 
 use ast::*;
 use kernel::*;
 use zetasql::{
-    any_resolved_aggregate_scan_base_proto::Node::*, any_resolved_create_statement_proto::Node::*,
-    any_resolved_create_table_stmt_base_proto::Node::*, any_resolved_expr_proto::Node::*,
+    any_resolved_aggregate_scan_base_proto::Node::*, any_resolved_alter_action_proto::Node::*,
+    any_resolved_alter_object_stmt_proto::Node::*, any_resolved_create_statement_proto::Node::*,
+    any_resolved_create_table_stmt_base_proto::Node::*,
+    any_resolved_create_view_base_proto::Node::*, any_resolved_expr_proto::Node::*,
     any_resolved_function_call_base_proto::Node::*,
     any_resolved_non_scalar_function_call_base_proto::Node::*, any_resolved_scan_proto::Node::*,
     any_resolved_statement_proto::Node::*, resolved_create_statement_enums::CreateScope,
-    value_proto::Value::*, *,
+    resolved_function_call_base_enums::ErrorMode, resolved_merge_when_enums::ActionType,
+    resolved_merge_when_enums::MatchType,
+    resolved_non_scalar_function_call_base_enums::NullHandlingModifier,
+    resolved_sample_scan_enums::SampleUnit, value_proto::Value::*, *,
 };
 
+use crate::catalog::{SimpleCatalogProvider, UserColumn, UserTable, UserView};
+
 #[log::trace]
-pub fn convert(stmts: &Vec<AnyResolvedStatementProto>, catalog_id: i64) -> Expr {
-    let mut converter = Converter { catalog_id };
-    let mut stmts: Vec<Expr> = stmts.iter().map(|s| converter.any_stmt(s)).collect();
+pub fn convert(
+    stmts: &Vec<AnyResolvedStatementProto>,
+    sql: &str,
+    catalog: &SimpleCatalogProvider,
+) -> Result<Expr, Error> {
+    let mut converter = Converter {
+        catalog_id: catalog.id(),
+        catalog,
+        sql,
+        recursive: None,
+        recursion_limit: DEFAULT_RECURSION_LIMIT,
+        arguments: HashMap::new(),
+    };
+    let mut stmts = stmts
+        .iter()
+        .map(|s| converter.any_stmt(s))
+        .collect::<Result<Vec<Expr>, Error>>()?;
     if stmts.len() == 1 {
-        stmts.pop().unwrap()
+        Ok(stmts.pop().unwrap())
     } else {
-        LogicalScript { stmts }
+        Ok(LogicalScript { stmts })
     }
 }
 
-struct Converter {
+struct Converter<'a> {
     catalog_id: i64,
+    catalog: &'a SimpleCatalogProvider,
+    // The SQL that the statements were analyzed from.
+    sql: &'a str,
+    // The working table of the recursive query we are currently converting, if any.
+    recursive: Option<String>,
+    // The maximum number of iterations of a recursive query, set by the statement hint @{recursion_limit = ..}.
+    recursion_limit: usize,
+    // The arguments of the user-defined function whose body we are currently converting, if any.
+    arguments: HashMap<String, Scalar>,
 }
 
-impl Converter {
-    fn any_stmt(&mut self, q: &AnyResolvedStatementProto) -> Expr {
+impl<'a> Converter<'a> {
+    fn any_stmt(&mut self, q: &AnyResolvedStatementProto) -> Result<Expr, Error> {
         match q.node.get() {
             ResolvedQueryStmtNode(q) => self.query(q),
             ResolvedCreateStatementNode(q) => self.create(q),
-            ResolvedDropStmtNode(q) => self.drop(q),
+            ResolvedDropStmtNode(q) => Ok(self.drop(q)),
+            ResolvedDropMaterializedViewStmtNode(q) => Ok(self.drop_materialized_view(q)),
+            ResolvedDropFunctionStmtNode(q) => {
+                self.drop_function(&q.name_path, q.is_if_exists == Some(true))
+            }
+            ResolvedDropTableFunctionStmtNode(q) => {
+                self.drop_function(&q.name_path, q.is_if_exists == Some(true))
+            }
+            ResolvedAlterObjectStmtNode(q) => self.alter(q),
+            ResolvedRenameStmtNode(q) => self.rename(q),
             ResolvedInsertStmtNode(q) => self.insert(q),
             ResolvedDeleteStmtNode(q) => self.delete(q),
             ResolvedUpdateStmtNode(q) => self.update(q),
+            ResolvedMergeStmtNode(q) => self.merge(q),
+            ResolvedTruncateStmtNode(q) => self.truncate(q),
             ResolvedCreateDatabaseStmtNode(q) => self.create_database(q),
             ResolvedCallStmtNode(q) => self.call(q),
             ResolvedExplainStmtNode(q) => self.explain(q),
             ResolvedAssertStmtNode(q) => self.assert(q),
-            other => panic!("{:?}", other),
+            other => not_supported(other),
         }
     }
 
-    fn query(&mut self, q: &ResolvedQueryStmtProto) -> Expr {
-        let input = self.any_resolved_scan(q.query.get());
-        LogicalOut {
+    fn query(&mut self, q: &ResolvedQueryStmtProto) -> Result<Expr, Error> {
+        self.statement_hints(&q.parent.get().hint_list)?;
+        let input = self.any_resolved_scan(q.query.get())?;
+        Ok(LogicalOut {
             projects: q
                 .output_column_list
                 .iter()
                 .map(|c| Column::from(c.column.get()))
                 .collect(),
             input: Box::new(input),
+        })
+    }
+
+    /// Apply the hints of a statement, like @{recursion_limit = 1000} SELECT ...
+    fn statement_hints(&mut self, hint_list: &Vec<ResolvedOptionProto>) -> Result<(), Error> {
+        for hint in hint_list {
+            match (
+                hint.qualifier.as_deref(),
+                hint.name().to_lowercase().as_str(),
+            ) {
+                (None | Some(""), "recursion_limit") => {
+                    let limit = self.int_literal(hint.value.get())?;
+                    if limit < 1 {
+                        return Err(Error::Syntax(
+                            "Hint recursion_limit must be a positive integer".to_string(),
+                        ));
+                    }
+                    self.recursion_limit = limit as usize;
+                }
+                _ => {
+                    return Err(Error::NotSupported(format!(
+                        "Hint {} is not supported",
+                        hint.name()
+                    )))
+                }
+            }
         }
+        Ok(())
     }
 
-    fn any_resolved_scan(&mut self, q: &AnyResolvedScanProto) -> Expr {
+    fn any_resolved_scan(&mut self, q: &AnyResolvedScanProto) -> Result<Expr, Error> {
         match q.node.get() {
             ResolvedSingleRowScanNode(q) => self.single_row(q),
             ResolvedTableScanNode(q) => self.table_scan(q),
+            ResolvedSampleScanNode(q) => self.sample(q),
+            ResolvedTvfscanNode(q) => self.tvf_scan(q),
             ResolvedJoinScanNode(q) => self.join(q),
             ResolvedFilterScanNode(q) => self.filter(q),
             ResolvedSetOperationScanNode(q) => self.set_operation(q),
             ResolvedOrderByScanNode(q) => self.order_by(q),
             ResolvedLimitOffsetScanNode(q) => self.limit_offset(q),
             ResolvedWithRefScanNode(q) => self.with_ref(q),
+            ResolvedRecursiveRefScanNode(q) => self.recursive_ref(q),
             ResolvedProjectScanNode(q) => self.project(q),
             ResolvedWithScanNode(q) => self.with(q),
             ResolvedAggregateScanBaseNode(q) => match q.node.get() {
                 ResolvedAggregateScanNode(q) => self.aggregate(q),
-                other => panic!("{:?}", other),
+                other => not_supported(other),
             },
-            other => panic!("{:?}", other),
+            other => not_supported(other),
         }
     }
 
-    fn single_row(&mut self, _: &ResolvedSingleRowScanProto) -> Expr {
-        LogicalSingleGet
+    fn single_row(&mut self, _: &ResolvedSingleRowScanProto) -> Result<Expr, Error> {
+        Ok(LogicalSingleGet)
     }
 
-    fn table_scan(&mut self, q: &ResolvedTableScanProto) -> Expr {
+    fn table_scan(&mut self, q: &ResolvedTableScanProto) -> Result<Expr, Error> {
+        if let Some(view) = self.catalog.view(q.table.get().serialization_id.unwrap()) {
+            return self.view_scan(q, view);
+        }
         let mut projects: Vec<Column> = q
             .parent
             .get()
@@ -102,11 +180,178 @@ impl Converter {
         ];
         projects.push(xmin);
         projects.push(xmax);
-        LogicalGet {
+        Ok(LogicalGet {
             projects,
             predicates,
             table,
+            sample: None,
+        })
+    }
+
+    fn sample(&mut self, q: &ResolvedSampleScanProto) -> Result<Expr, Error> {
+        let method = match q.method.get().to_lowercase().as_str() {
+            "system" => SampleMethod::System,
+            "bernoulli" => SampleMethod::Bernoulli,
+            other => {
+                return Err(Error::NotSupported(format!(
+                    "TABLESAMPLE {} is not supported",
+                    other
+                )))
+            }
+        };
+        if q.unit != Some(SampleUnit::Percent as i32) {
+            return Err(Error::NotSupported(
+                "TABLESAMPLE only supports PERCENT".to_string(),
+            ));
+        }
+        let percent = self.double_literal(q.size.get())?;
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(Error::Syntax(
+                "TABLESAMPLE percent must be between 0 and 100".to_string(),
+            ));
+        }
+        let repeatable = match &q.repeatable_argument {
+            Some(x) => Some(self.int_literal(x)?),
+            None => None,
+        };
+        match self.any_resolved_scan(q.input_scan.get())? {
+            LogicalGet {
+                projects,
+                predicates,
+                table,
+                sample: None,
+            } => Ok(LogicalGet {
+                projects,
+                predicates,
+                table,
+                sample: Some(Sample {
+                    method,
+                    percent,
+                    repeatable,
+                }),
+            }),
+            _ => Err(Error::NotSupported(
+                "TABLESAMPLE is only supported on tables".to_string(),
+            )),
+        }
+    }
+
+    fn view_scan(&mut self, q: &ResolvedTableScanProto, view: &UserView) -> Result<Expr, Error> {
+        let table = q.table.get().name.get();
+        let metadata = SimpleCatalogProvider::MetadataCatalog;
+        let catalog = if view.metadata {
+            &metadata
+        } else {
+            self.catalog
+        };
+        // The tables that a view reads may have been dropped or altered since it was created.
+        let view =
+            crate::parser::analyze(&view.sql, &HashMap::new(), catalog).map_err(|e| match e {
+                Error::Syntax(message) => {
+                    Error::Syntax(format!("View {} is invalid: {}", table, message))
+                }
+                other => other,
+            })?;
+        let (outputs, mut input) = match view {
+            LogicalOut { projects, input } => (projects, *input),
+            other => {
+                return Err(Error::Syntax(format!(
+                    "View {} is not a query: {}",
+                    table,
+                    other.name()
+                )))
+            }
+        };
+        // ZetaSQL numbered the columns of the view independently of the enclosing query, so give them fresh IDs.
+        let fresh = fresh_ids(&mut input);
+        let projects = q
+            .column_index_list
+            .iter()
+            .zip(&q.parent.get().column_list)
+            .map(|(i, c)| {
+                let output = &outputs[*i as usize];
+                let output = fresh.get(output).unwrap_or(output).clone();
+                (Scalar::Column(output), Column::table(c, q.table.get()))
+            })
+            .collect();
+        Ok(LogicalMap {
+            include_existing: false,
+            projects,
+            input: Box::new(input),
+        })
+    }
+
+    fn tvf_scan(&mut self, q: &ResolvedTvfScanProto) -> Result<Expr, Error> {
+        let name = q.tvf.get().name.get();
+        let catalog = self.catalog;
+        let function = match catalog.function(name) {
+            Some(function) => function,
+            None => {
+                return Err(Error::Syntax(format!(
+                    "Table function {} does not exist",
+                    name
+                )))
+            }
+        };
+        let mut arguments = vec![];
+        let mut input = LogicalSingleGet;
+        for argument in &q.argument_list {
+            arguments.push(self.expr(argument.expr.get(), &mut input)?);
+        }
+        let mut bindings = match function_bindings(input) {
+            Some(bindings) => bindings,
+            None => {
+                return Err(Error::NotSupported(
+                    "Subqueries are not allowed in table function arguments".to_string(),
+                ))
+            }
+        };
+        // The arguments of a table function are only in scope in a standalone expression, so we wrap the query in one.
+        let body = crate::parser::analyze_expression(
+            &format!("EXISTS({})", function.sql),
+            &function.arguments,
+            self.catalog,
+        )
+        .map_err(|message| {
+            Error::Syntax(format!("Table function {} is invalid: {}", name, message))
+        })?;
+        let query = match body.node.get() {
+            ResolvedSubqueryExprNode(x) => x.subquery.get(),
+            other => panic!("{:?}", other),
+        };
+        // Each argument is computed once, in a single row that the body is joined to, no matter how many rows use it.
+        let (nested, arguments) = bind_arguments(&function.arguments, arguments);
+        bindings.extend(nested);
+        let mut converter = self.function_body(&function.arguments, arguments);
+        let mut input = converter.any_resolved_scan(query)?;
+        let outputs: Vec<Column> = parent(query).column_list.iter().map(Column::from).collect();
+        // ZetaSQL numbered the columns of the function independently of the enclosing query, so give them fresh IDs.
+        let fresh = fresh_ids(&mut input);
+        if !bindings.is_empty() {
+            // The body reads the arguments, so it depends on the row that computes them.
+            let parameters = bindings.iter().map(|(_, c)| c.clone()).collect();
+            let domain = LogicalMap {
+                include_existing: false,
+                projects: bindings,
+                input: Box::new(LogicalSingleGet),
+            };
+            input = self.create_dependent_join(parameters, Join::Inner(vec![]), input, domain);
         }
+        let projects = q
+            .column_index_list
+            .iter()
+            .zip(&q.parent.get().column_list)
+            .map(|(i, c)| {
+                let output = &outputs[*i as usize];
+                let output = fresh.get(output).unwrap_or(output).clone();
+                (Scalar::Column(output), Column::from(c))
+            })
+            .collect();
+        Ok(LogicalMap {
+            include_existing: false,
+            projects,
+            input: Box::new(input),
+        })
     }
 
     fn table_scan_for_update(&mut self, q: &ResolvedTableScanProto) -> (Expr, Column) {
@@ -138,13 +383,14 @@ impl Converter {
             projects,
             predicates,
             table,
+            sample: None,
         };
         (expr, tid)
     }
 
-    fn join(&mut self, q: &ResolvedJoinScanProto) -> Expr {
-        let left = self.any_resolved_scan(q.left_scan.get());
-        let right = self.any_resolved_scan(q.right_scan.get());
+    fn join(&mut self, q: &ResolvedJoinScanProto) -> Result<Expr, Error> {
+        let left = self.any_resolved_scan(q.left_scan.get())?;
+        let right = self.any_resolved_scan(q.right_scan.get())?;
         // Convert inner join to join-then-filter.
         if *q.join_type.get().borrow() == 0 {
             let mut input = LogicalJoin {
@@ -153,120 +399,219 @@ impl Converter {
                 right: Box::new(right),
             };
             let predicates = match &q.join_expr {
-                Some(expr) => self.predicate(expr.borrow(), &mut input),
+                Some(expr) => self.predicate(expr.borrow(), &mut input)?,
                 None => vec![],
             };
             if predicates.is_empty() {
-                return input;
+                return Ok(input);
             }
-            return LogicalFilter {
+            return Ok(LogicalFilter {
                 predicates,
                 input: Box::new(input),
-            };
+            });
         }
         // Convert outer join using join condition.
-        let dummy = LogicalSingleGet;
-        let mut input = dummy.clone();
-        let predicates = match &q.join_expr {
-            Some(expr) => self.predicate(expr.borrow(), &mut input),
-            None => vec![],
-        };
-        if input != dummy {
-            panic!("Nested expressions are not allowed on the ON expressions of outer joins")
-        }
-        match q.join_type.get().borrow() {
+        // The side of the join that may be null-extended is on the left of Join::Right.
+        let join_type = *q.join_type.get().borrow();
+        let (mut nullable, preserved) = match join_type {
             // Left
-            1 => LogicalJoin {
-                join: Join::Right(predicates),
-                left: Box::new(right),
-                right: Box::new(left),
-            },
+            1 => (right, left),
             // Right
-            2 => LogicalJoin {
-                join: Join::Right(predicates),
-                left: Box::new(left),
-                right: Box::new(right),
-            },
-            // Full
-            3 => LogicalJoin {
-                join: Join::Outer(predicates),
-                left: Box::new(left),
-                right: Box::new(right),
-            },
+            2 | 3 => (left, right),
             // Invalid
             other => panic!("{:?}", other),
+        };
+        let original = nullable.clone();
+        // Subqueries in the join condition are joined onto the nullable side.
+        let predicates = match &q.join_expr {
+            Some(expr) => self.predicate(expr.borrow(), &mut nullable)?,
+            None => vec![],
+        };
+        let mut preserved_columns: Vec<Column> = preserved.attributes().into_iter().collect();
+        preserved_columns.sort();
+        // The subqueries reference the preserved side if the nullable side now does.
+        let references: HashSet<Column> = nullable
+            .pre_order()
+            .flat_map(|expr| expr.references())
+            .collect();
+        if !preserved_columns.iter().any(|c| references.contains(c)) {
+            let join = if join_type == 3 {
+                Join::Outer(predicates)
+            } else {
+                Join::Right(predicates)
+            };
+            return Ok(LogicalJoin {
+                join,
+                left: Box::new(nullable),
+                right: Box::new(preserved),
+            });
+        }
+        // The subqueries also depend on the preserved side, so evaluate the join condition
+        // once for every distinct combination of the preserved columns it references,
+        // and null-extend the rows with no match.
+        let subquery = LogicalFilter {
+            predicates,
+            input: Box::new(nullable),
+        };
+        let mut correlated: Vec<Column> = subquery
+            .pre_order()
+            .flat_map(|expr| expr.references())
+            .filter(|c| preserved_columns.contains(c))
+            .collect::<HashSet<Column>>()
+            .into_iter()
+            .collect();
+        correlated.sort();
+        if join_type != 3 {
+            return Ok(self.create_dependent_join(
+                correlated,
+                Join::Right(vec![]),
+                subquery,
+                preserved,
+            ));
+        }
+        // A full join adds the rows of the nullable side that match no row of the preserved side.
+        // The join condition depends only on the values of a row, so rows with the same values match the same rows,
+        // and the unmatched rows are the rows whose values differ from every matched row.
+        let mut nullable_columns: Vec<Column> = original.attributes().into_iter().collect();
+        nullable_columns.sort();
+        let matched = self.create_dependent_join(
+            correlated.clone(),
+            Join::Inner(vec![]),
+            subquery.clone(),
+            preserved.clone(),
+        );
+        let mut matched = LogicalMap {
+            include_existing: false,
+            projects: nullable_columns
+                .iter()
+                .map(|c| (Scalar::Column(c.clone()), c.clone()))
+                .collect(),
+            input: Box::new(matched),
+        };
+        let rename = fresh_ids(&mut matched);
+        let unmatched = LogicalJoin {
+            join: Join::Anti(
+                nullable_columns
+                    .iter()
+                    .map(|c| {
+                        let (left, right) =
+                            (Scalar::Column(rename[c].clone()), Scalar::Column(c.clone()));
+                        Scalar::Call(Box::new(F::Or(
+                            Scalar::Call(Box::new(F::Equal(left.clone(), right.clone()))),
+                            Scalar::Call(Box::new(F::And(
+                                Scalar::Call(Box::new(F::IsNull(left))),
+                                Scalar::Call(Box::new(F::IsNull(right))),
+                            ))),
+                        )))
+                    })
+                    .collect(),
+            ),
+            left: Box::new(matched),
+            right: Box::new(original),
+        };
+        let mut projects: Vec<(Scalar, Column)> = nullable_columns
+            .iter()
+            .map(|c| (Scalar::Column(c.clone()), c.clone()))
+            .collect();
+        let mut null_extended = projects.clone();
+        for c in preserved_columns {
+            null_extended.push((Scalar::Literal(Value::null(c.data_type.clone())), c.clone()));
+            projects.push((Scalar::Column(c.clone()), c));
         }
+        let joined =
+            self.create_dependent_join(correlated, Join::Right(vec![]), subquery, preserved);
+        Ok(LogicalUnion {
+            left: Box::new(LogicalMap {
+                include_existing: false,
+                projects,
+                input: Box::new(joined),
+            }),
+            right: Box::new(LogicalMap {
+                include_existing: false,
+                projects: null_extended,
+                input: Box::new(unmatched),
+            }),
+        })
     }
 
-    fn filter(&mut self, q: &ResolvedFilterScanProto) -> Expr {
-        let mut input = self.any_resolved_scan(q.input_scan.get());
-        let predicates = self.predicate(q.filter_expr.get(), &mut input);
-        LogicalFilter {
+    fn filter(&mut self, q: &ResolvedFilterScanProto) -> Result<Expr, Error> {
+        let mut input = self.any_resolved_scan(q.input_scan.get())?;
+        let predicates = self.predicate(q.filter_expr.get(), &mut input)?;
+        Ok(LogicalFilter {
             predicates,
             input: Box::new(input),
-        }
+        })
     }
 
-    fn predicate(&mut self, x: &AnyResolvedExprProto, outer: &mut Expr) -> Vec<Scalar> {
-        match self.predicate_and(x, outer) {
-            Some(ps) => ps,
-            None => vec![self.expr(x, outer)],
+    fn predicate(
+        &mut self,
+        x: &AnyResolvedExprProto,
+        outer: &mut Expr,
+    ) -> Result<Vec<Scalar>, Error> {
+        match self.predicate_and(x, outer)? {
+            Some(ps) => Ok(ps),
+            None => Ok(vec![self.expr(x, outer)?]),
         }
     }
 
-    fn predicate_and(&mut self, x: &AnyResolvedExprProto, outer: &mut Expr) -> Option<Vec<Scalar>> {
+    fn predicate_and(
+        &mut self,
+        x: &AnyResolvedExprProto,
+        outer: &mut Expr,
+    ) -> Result<Option<Vec<Scalar>>, Error> {
         let x = match x.node.get() {
             ResolvedFunctionCallBaseNode(x) => x,
-            _ => return None,
+            _ => return Ok(None),
         };
         let x = match x.node.get() {
             ResolvedFunctionCallNode(x) => x,
-            _ => return None,
+            _ => return Ok(None),
         };
         let x = x.parent.get();
         if x.function.get().name.get() != "ZetaSQL:$and" {
-            return None;
+            return Ok(None);
         }
-        Some(self.exprs(&x.argument_list, outer))
+        Ok(Some(self.exprs(&x.argument_list, outer)?))
     }
 
-    fn set_operation(&mut self, q: &ResolvedSetOperationScanProto) -> Expr {
-        match q.op_type.unwrap() {
+    fn set_operation(&mut self, q: &ResolvedSetOperationScanProto) -> Result<Expr, Error> {
+        let op = match q.op_type.unwrap() {
             // UnionAll
-            0 => self.union(&q.parent.get().column_list, &q.input_item_list),
+            0 => return self.union(&q.parent.get().column_list, &q.input_item_list),
             // UnionDistinct
-            1 => panic!("UNION DISTINCT is not supported"),
+            1 => "UNION DISTINCT",
             // IntersectAll
-            2 => panic!("UNION DISTINCT is not supported"),
+            2 => "INTERSECT ALL",
             // IntersectDistinct
-            3 => panic!("INTERSECT DISTINCT is not supported"),
+            3 => "INTERSECT DISTINCT",
             // ExceptAll
-            4 => panic!("EXCEPT ALL is not supported"),
+            4 => "EXCEPT ALL",
             // ExceptDistinct
-            5 => panic!("EXCEPT DISTINCT is not supported"),
+            5 => "EXCEPT DISTINCT",
             // Other
             other => panic!("{:?}", other),
-        }
+        };
+        Err(Error::NotSupported(format!("{} is not supported", op)))
     }
 
     fn union(
         &mut self,
         outputs: &Vec<ResolvedColumnProto>,
         items: &[ResolvedSetOperationItemProto],
-    ) -> Expr {
+    ) -> Result<Expr, Error> {
         match items.len() {
             0 => panic!(),
             1 => {
-                let input = self.any_resolved_scan(items[0].scan.get());
-                self.rename_columns(outputs, &items[0].output_column_list, input)
+                let input = self.any_resolved_scan(items[0].scan.get())?;
+                Ok(self.rename_columns(outputs, &items[0].output_column_list, input))
             }
             _ => {
-                let left = self.union(outputs, &items[0..1]);
-                let right = self.union(outputs, &items[1..]);
-                LogicalUnion {
+                let left = self.union(outputs, &items[0..1])?;
+                let right = self.union(outputs, &items[1..])?;
+                Ok(LogicalUnion {
                     left: Box::new(left),
                     right: Box::new(right),
-                }
+                })
             }
         }
     }
@@ -295,8 +640,8 @@ impl Converter {
         }
     }
 
-    fn order_by(&mut self, q: &ResolvedOrderByScanProto) -> Expr {
-        let input = self.any_resolved_scan(q.input_scan.get().borrow());
+    fn order_by(&mut self, q: &ResolvedOrderByScanProto) -> Result<Expr, Error> {
+        let input = self.any_resolved_scan(q.input_scan.get().borrow())?;
         let mut order_by = vec![];
         for x in &q.order_by_item_list {
             let descending = x.is_descending.unwrap_or(false);
@@ -311,27 +656,27 @@ impl Converter {
                 descending,
             });
         }
-        LogicalSort {
+        Ok(LogicalSort {
             order_by,
             input: Box::new(input),
-        }
+        })
     }
 
-    fn limit_offset(&mut self, q: &ResolvedLimitOffsetScanProto) -> Expr {
-        let input = self.any_resolved_scan(q.input_scan.get().borrow());
-        let limit = self.int_literal(q.limit.get().borrow()) as usize;
+    fn limit_offset(&mut self, q: &ResolvedLimitOffsetScanProto) -> Result<Expr, Error> {
+        let input = self.any_resolved_scan(q.input_scan.get().borrow())?;
+        let limit = self.int_literal(q.limit.get().borrow())? as usize;
         let offset = match &q.offset {
-            Some(offset) => self.int_literal(offset) as usize,
+            Some(offset) => self.int_literal(offset)? as usize,
             None => 0,
         };
-        LogicalLimit {
+        Ok(LogicalLimit {
             limit,
             offset,
             input: Box::new(input),
-        }
+        })
     }
 
-    fn int_literal(&mut self, x: &AnyResolvedExprProto) -> i64 {
+    fn int_literal(&mut self, x: &AnyResolvedExprProto) -> Result<i64, Error> {
         match x {
             AnyResolvedExprProto {
                 node:
@@ -348,16 +693,34 @@ impl Converter {
                         ..
                     })),
                 ..
-            } => *x,
-            other => panic!("{:?}", other),
+            } => Ok(*x),
+            _ => Err(Error::NotSupported(
+                "Only integer literals are supported here".to_string(),
+            )),
+        }
+    }
+
+    fn double_literal(&mut self, x: &AnyResolvedExprProto) -> Result<f64, Error> {
+        let value = match x.node.get() {
+            ResolvedLiteralNode(x) => {
+                literal(x.value.get().value.get(), x.value.get().r#type.get())?
+            }
+            _ => Value::F64(None),
+        };
+        match value {
+            Value::I64(Some(x)) => Ok(x as f64),
+            Value::F64(Some(x)) => Ok(x),
+            _ => Err(Error::NotSupported(
+                "Only numeric literals are supported here".to_string(),
+            )),
         }
     }
 
-    fn project(&mut self, q: &ResolvedProjectScanProto) -> Expr {
-        let mut input = self.any_resolved_scan(q.input_scan.get());
+    fn project(&mut self, q: &ResolvedProjectScanProto) -> Result<Expr, Error> {
+        let mut input = self.any_resolved_scan(q.input_scan.get())?;
         let mut projects = vec![];
         for x in &q.expr_list {
-            projects.push(self.computed_column(x, &mut input));
+            projects.push(self.computed_column(x, &mut input)?);
         }
         for c in &q.parent.get().column_list {
             if q.expr_list
@@ -370,25 +733,25 @@ impl Converter {
             projects.push((Scalar::Column(column.clone()), column))
         }
         assert!(!projects.is_empty());
-        LogicalMap {
+        Ok(LogicalMap {
             include_existing: false,
             projects,
             input: Box::new(input),
-        }
+        })
     }
 
     fn computed_column(
         &mut self,
         x: &ResolvedComputedColumnProto,
         input: &mut Expr,
-    ) -> (Scalar, Column) {
-        let value = self.expr(x.expr.get(), input);
+    ) -> Result<(Scalar, Column), Error> {
+        let value = self.expr(x.expr.get(), input)?;
         let column = Column::computed(x);
-        (value, column)
+        Ok((value, column))
     }
 
-    fn with(&mut self, q: &ResolvedWithScanProto) -> Expr {
-        let mut right = self.any_resolved_scan(q.query.get().borrow());
+    fn with(&mut self, q: &ResolvedWithScanProto) -> Result<Expr, Error> {
+        let mut right = self.any_resolved_scan(q.query.get().borrow())?;
         for i in (0..q.with_entry_list.len()).rev() {
             match &q.with_entry_list[i] {
                 ResolvedWithEntryProto {
@@ -396,7 +759,10 @@ impl Converter {
                     with_subquery: Some(query),
                     ..
                 } => {
-                    let left = self.any_resolved_scan(&query);
+                    let left = match query.node.get() {
+                        ResolvedRecursiveScanNode(query) => self.recursive(name, query)?,
+                        _ => self.any_resolved_scan(&query)?,
+                    };
                     // Strangely, if you rename a column in a CTE, for example
                     //   with cte as (select i as j from t) select * from cte
                     // these are the original names (i), not the renamed names (j).
@@ -419,10 +785,45 @@ impl Converter {
                 other => panic!("{:?}", other),
             }
         }
-        right
+        Ok(right)
+    }
+
+    fn recursive(&mut self, name: &String, q: &ResolvedRecursiveScanProto) -> Result<Expr, Error> {
+        // UnionDistinct
+        if q.op_type == Some(1) {
+            return Err(Error::NotSupported(
+                "WITH RECURSIVE does not support UNION DISTINCT".to_string(),
+            ));
+        }
+        let outputs = &q.parent.get().column_list;
+        let base = self.union(outputs, std::slice::from_ref(q.non_recursive_term.get()))?;
+        // The recursive term reads the rows produced by the previous iteration from a working table.
+        let working = format!("{}$working", name);
+        let outer = self.recursive.replace(working.clone());
+        let step = self.union(outputs, std::slice::from_ref(q.recursive_term.get()));
+        self.recursive = outer;
+        Ok(LogicalIterate {
+            name: working,
+            columns: outputs.iter().map(Column::reference).collect(),
+            recursion_limit: self.recursion_limit,
+            base: Box::new(base),
+            step: Box::new(step?),
+        })
+    }
+
+    fn recursive_ref(&mut self, q: &ResolvedRecursiveRefScanProto) -> Result<Expr, Error> {
+        let name = self.recursive.clone().unwrap();
+        let columns = q
+            .parent
+            .get()
+            .column_list
+            .iter()
+            .map(Column::reference)
+            .collect();
+        Ok(LogicalGetWith { name, columns })
     }
 
-    fn with_ref(&mut self, q: &ResolvedWithRefScanProto) -> Expr {
+    fn with_ref(&mut self, q: &ResolvedWithRefScanProto) -> Result<Expr, Error> {
         let name = q.with_query_name.get().clone();
         let columns = q
             .parent
@@ -431,18 +832,32 @@ impl Converter {
             .iter()
             .map(Column::reference)
             .collect();
-        LogicalGetWith { name, columns }
+        Ok(LogicalGetWith { name, columns })
     }
 
-    fn aggregate(&mut self, q: &ResolvedAggregateScanProto) -> Expr {
+    fn aggregate(&mut self, q: &ResolvedAggregateScanProto) -> Result<Expr, Error> {
+        // ROLLUP is resolved into a list of grouping sets, each of which is a subset of the group-by columns.
+        let grouping_sets: Vec<Vec<i64>> = q
+            .grouping_set_list
+            .iter()
+            .map(|set| {
+                set.group_by_column_list
+                    .iter()
+                    .map(|c| Column::reference(c.column.get()).id)
+                    .collect()
+            })
+            .collect();
+        let grouping_id = Column::fresh("$grouping_id", DataType::I64);
         let q = q.parent.get();
-        let mut input = self.any_resolved_scan(q.input_scan.get());
+        let mut input = self.any_resolved_scan(q.input_scan.get())?;
         // Project each of the group-by columns under its own name.
         let mut input_projects: Vec<(Scalar, Column)> = vec![];
+        let mut group_by_scalars: Vec<Scalar> = vec![];
         let mut group_by_columns: Vec<Column> = vec![];
         for compute in &q.group_by_list {
-            let scalar = self.expr(compute.expr.get(), &mut input);
+            let scalar = self.expr(compute.expr.get(), &mut input)?;
             let column = Column::computed(compute);
+            group_by_scalars.push(scalar.clone());
             input_projects.push((scalar, column.clone()));
             group_by_columns.push(column);
         }
@@ -462,41 +877,219 @@ impl Converter {
                 ResolvedAggregateFunctionCallNode(function) => function,
                 other => panic!("{:?}", other),
             };
+            // HAVING MAX/MIN, ORDER BY and LIMIT read additional columns, which are projected alongside the input.
+            let having = match &function.having_modifier {
+                Some(modifier) => {
+                    let scalar = self.expr(modifier.having_expr.get(), &mut input)?;
+                    let column = Column::fresh("$having", scalar.data_type());
+                    input_projects.push((scalar, column.clone()));
+                    match modifier.kind.unwrap_or(0) {
+                        1 => Some(HavingModifier::Max(column)), // Max
+                        2 => Some(HavingModifier::Min(column)), // Min
+                        other => panic!("unexpected HAVING modifier {}", other),
+                    }
+                }
+                None => None,
+            };
+            let mut order_by = vec![];
+            for x in &function.order_by_item_list {
+                let reference = Column::reference(x.column_ref.get().column.get());
+                let column = Column::fresh("$order_by", reference.data_type.clone());
+                input_projects.push((Scalar::Column(reference), column.clone()));
+                order_by.push(OrderBy {
+                    column,
+                    descending: x.is_descending.unwrap_or(false),
+                });
+            }
+            let limit = match &function.limit {
+                Some(limit) => Some(self.int_literal(limit)? as usize),
+                None => None,
+            };
             let function = function.parent.get();
             let distinct = function.distinct.unwrap_or(false);
-            let ignore_nulls = function.null_handling_modifier.unwrap_or(0) == 1;
-            if ignore_nulls {
-                panic!("IGNORE NULLS is not supported");
-            }
+            let null_handling =
+                NullHandlingModifier::from_i32(function.null_handling_modifier.unwrap_or(0));
+            let ignore_nulls = null_handling == Some(NullHandlingModifier::IgnoreNulls);
+            // Every aggregate this call is planned into inherits its modifiers.
+            let modified = |function, input, output| AggregateExpr {
+                distinct,
+                ignore_nulls,
+                having: having.clone(),
+                order_by: order_by.clone(),
+                limit,
+                ..AggregateExpr::new(function, input, output)
+            };
             let function = function.parent.get();
             let arguments = &function.argument_list;
             let function = function.function.get().name.get().clone();
             if &function == "ZetaSQL:avg" {
                 assert!(arguments.len() == 1);
 
-                let input_expr = self.expr(&arguments[0], &mut input);
+                let input_expr = self.expr(&arguments[0], &mut input)?;
                 let input_column = Column::fresh("$avg", input_expr.data_type());
                 input_projects.push((input_expr.clone(), input_column.clone()));
                 let sum_column = Column::fresh("$avg$sum", input_expr.data_type());
                 let count_column = Column::fresh("$avg$count", input_expr.data_type());
-                aggregate_operators.push(AggregateExpr {
-                    function: AggregateFunction::Sum,
-                    distinct,
-                    input: input_column.clone(),
-                    output: sum_column.clone(),
-                });
-                aggregate_operators.push(AggregateExpr {
-                    function: AggregateFunction::Count,
-                    distinct,
-                    input: input_column.clone(),
-                    output: count_column.clone(),
-                });
+                aggregate_operators.push(modified(
+                    AggregateFunction::Sum,
+                    input_column.clone(),
+                    sum_column.clone(),
+                ));
+                aggregate_operators.push(modified(
+                    AggregateFunction::Count,
+                    input_column.clone(),
+                    count_column.clone(),
+                ));
                 let avg_expr = Scalar::Call(Box::new(F::DivideDouble(
                     Scalar::Cast(Box::new(Scalar::Column(sum_column)), DataType::F64),
                     Scalar::Cast(Box::new(Scalar::Column(count_column)), DataType::F64),
                 )));
                 let avg_column = Column::computed(aggregate);
                 output_projects.push((avg_expr, avg_column));
+            } else if matches!(
+                function.as_str(),
+                "ZetaSQL:stddev"
+                    | "ZetaSQL:stddev_samp"
+                    | "ZetaSQL:stddev_pop"
+                    | "ZetaSQL:variance"
+                    | "ZetaSQL:var_samp"
+                    | "ZetaSQL:var_pop"
+            ) {
+                assert!(arguments.len() == 1);
+                if distinct {
+                    return Err(Error::NotSupported(format!(
+                        "DISTINCT is not supported for {}",
+                        function
+                    )));
+                }
+
+                let input_expr = Scalar::Cast(
+                    Box::new(self.expr(&arguments[0], &mut input)?),
+                    DataType::F64,
+                );
+                let input_column = Column::fresh("$variance", DataType::F64);
+                input_projects.push((input_expr, input_column.clone()));
+                aggregate_operators.push(modified(
+                    AggregateFunction::from(&function),
+                    input_column,
+                    Column::computed(aggregate),
+                ));
+            } else if &function == "ZetaSQL:countif" {
+                assert!(arguments.len() == 1);
+
+                // COUNTIF(x) counts the rows where x is TRUE, which is COUNT(IF(x, TRUE, NULL)).
+                let input_expr = Scalar::Call(Box::new(F::If(
+                    self.expr(&arguments[0], &mut input)?,
+                    Scalar::Literal(Value::Bool(Some(true))),
+                    Scalar::Literal(Value::Bool(None)),
+                )));
+                let input_column = Column::fresh("$countif", DataType::Bool);
+                input_projects.push((input_expr, input_column.clone()));
+                aggregate_operators.push(modified(
+                    AggregateFunction::Count,
+                    input_column,
+                    Column::computed(aggregate),
+                ));
+            } else if &function == "ZetaSQL:string_agg" {
+                assert!(arguments.len() == 1 || arguments.len() == 2);
+
+                // Prefix each value with the delimiter, concatenate, and strip the leading delimiter.
+                // Concatenation doesn't need to know where the boundaries are, so it can be merged across workers.
+                let delimiter = if arguments.len() == 2 {
+                    self.expr(&arguments[1], &mut input)?
+                } else {
+                    Scalar::Literal(Value::String(Some(",".to_string())))
+                };
+                let input_expr = Scalar::Call(Box::new(F::ConcatString(vec![
+                    delimiter.clone(),
+                    self.expr(&arguments[0], &mut input)?,
+                ])));
+                let input_column = Column::fresh("$string_agg", DataType::String);
+                input_projects.push((input_expr, input_column.clone()));
+                let concat_column = Column::fresh("$string_agg$concat", DataType::String);
+                aggregate_operators.push(modified(
+                    AggregateFunction::StringAgg,
+                    input_column,
+                    concat_column.clone(),
+                ));
+                let string_agg_expr = Scalar::Call(Box::new(F::SubstrString(
+                    Scalar::Column(concat_column),
+                    Scalar::Call(Box::new(F::AddInt64(
+                        Scalar::Call(Box::new(F::LengthString(delimiter))),
+                        Scalar::Literal(Value::I64(Some(1))),
+                    ))),
+                    None,
+                )));
+                output_projects.push((string_agg_expr, Column::computed(aggregate)));
+            } else if &function == "ZetaSQL:approx_quantiles" {
+                assert!(arguments.len() == 2);
+                if distinct {
+                    return Err(Error::NotSupported(format!(
+                        "DISTINCT is not supported for {}",
+                        function
+                    )));
+                }
+                // The quantiles are computed from the values that aren't NULL.
+                if null_handling == Some(NullHandlingModifier::RespectNulls) {
+                    return Err(Error::NotSupported(format!(
+                        "RESPECT NULLS is not supported for {}",
+                        function
+                    )));
+                }
+
+                let number = self.int_literal(&arguments[1])?;
+                if number < 1 {
+                    return Err(Error::DataException(format!(
+                        "APPROX_QUANTILES requires a number of quantiles greater than 0 but found {}",
+                        number
+                    )));
+                }
+                let input_expr = self.expr(&arguments[0], &mut input)?;
+                let input_column = Column::fresh("$approx_quantiles", input_expr.data_type());
+                input_projects.push((input_expr, input_column.clone()));
+                aggregate_operators.push(modified(
+                    AggregateFunction::ApproxQuantiles(number as usize),
+                    input_column,
+                    Column::computed(aggregate),
+                ));
+            } else if &function == "System:grouping" {
+                assert!(arguments.len() == 1);
+
+                // GROUPING(x) is 1 in the rows of grouping sets where x has been rolled up, and 0 otherwise.
+                let argument = self.expr(&arguments[0], &mut input)?;
+                let i = match group_by_scalars
+                    .iter()
+                    .position(|scalar| scalar == &argument)
+                {
+                    Some(i) => i,
+                    None => {
+                        return Err(Error::Syntax(
+                            "The argument of GROUPING must be a GROUP BY expression".to_string(),
+                        ))
+                    }
+                };
+                let id = group_by_columns[i].id;
+                let mut cases = vec![];
+                for (set, columns) in grouping_sets.iter().enumerate() {
+                    if !columns.contains(&id) {
+                        cases.push((
+                            Scalar::Call(Box::new(F::Equal(
+                                Scalar::Column(grouping_id.clone()),
+                                Scalar::Literal(Value::I64(Some(set as i64))),
+                            ))),
+                            Scalar::Literal(Value::I64(Some(1))),
+                        ))
+                    }
+                }
+                let grouping_expr = if cases.is_empty() {
+                    Scalar::Literal(Value::I64(Some(0)))
+                } else {
+                    Scalar::Call(Box::new(F::CaseNoValue(
+                        cases,
+                        Scalar::Literal(Value::I64(Some(0))),
+                    )))
+                };
+                output_projects.push((grouping_expr, Column::computed(aggregate)));
             } else if &function == "ZetaSQL:$count_star" {
                 assert!(arguments.len() == 0);
 
@@ -504,29 +1097,64 @@ impl Converter {
                 let input_column = Column::fresh("$star", DataType::I64);
                 input_projects.push((input_expr, input_column.clone()));
                 let count_column = Column::computed(aggregate);
-                aggregate_operators.push(AggregateExpr {
-                    function: AggregateFunction::Count,
-                    distinct,
-                    input: input_column,
-                    output: count_column,
-                });
+                aggregate_operators.push(modified(
+                    AggregateFunction::Count,
+                    input_column,
+                    count_column,
+                ));
             } else {
                 assert!(arguments.len() == 1);
+                // ARRAY_AGG collects its values in the order they arrive, so duplicates can't be removed on the way.
+                if distinct && &function == "ZetaSQL:array_agg" {
+                    return Err(Error::NotSupported(format!(
+                        "DISTINCT is not supported for {}",
+                        function
+                    )));
+                }
 
-                let input_expr = self.expr(&arguments[0], &mut input);
+                let input_expr = self.expr(&arguments[0], &mut input)?;
                 let input_column = Column::fresh(&function_name(&function), input_expr.data_type());
                 input_projects.push((input_expr, input_column.clone()));
                 let aggregate_column = Column::computed(aggregate);
-                aggregate_operators.push(AggregateExpr {
-                    function: AggregateFunction::from(&function),
-                    distinct,
-                    input: input_column,
-                    output: aggregate_column,
-                });
+                aggregate_operators.push(modified(
+                    AggregateFunction::from(&function),
+                    input_column,
+                    aggregate_column,
+                ));
             }
         }
         // Form the result, using as many stages as are necessary.
         let mut result = input;
+        let mut expand_projects: Vec<(Scalar, Column)> = vec![];
+        if !grouping_sets.is_empty() {
+            // The group-by columns are produced by the expansion below, so project their inputs under temporary names.
+            for i in 0..group_by_columns.len() {
+                let column = &group_by_columns[i];
+                let pre_expand = Column::fresh(&column.name, column.data_type.clone());
+                let mut cases = vec![];
+                for (set, columns) in grouping_sets.iter().enumerate() {
+                    if !columns.contains(&column.id) {
+                        cases.push((
+                            Scalar::Call(Box::new(F::Equal(
+                                Scalar::Column(grouping_id.clone()),
+                                Scalar::Literal(Value::I64(Some(set as i64))),
+                            ))),
+                            Scalar::Literal(Value::null(column.data_type.clone())),
+                        ))
+                    }
+                }
+                let expand_expr = if cases.is_empty() {
+                    Scalar::Column(pre_expand.clone())
+                } else {
+                    Scalar::Call(Box::new(F::CaseNoValue(
+                        cases,
+                        Scalar::Column(pre_expand.clone()),
+                    )))
+                };
+                expand_projects.push((expand_expr, column.clone()));
+                input_projects[i].1 = pre_expand;
+            }
+        }
         if input_projects.len() > 0 {
             assert!(!input_projects.is_empty());
             result = LogicalMap {
@@ -535,6 +1163,29 @@ impl Converter {
                 input: Box::new(result),
             };
         }
+        if !grouping_sets.is_empty() {
+            // Scan the input once, and replicate each row once per grouping set, tagged with the index of the set.
+            // The grouping ids are on the left so the nested loop join broadcasts them rather than the input.
+            let grouping_ids = (0..grouping_sets.len())
+                .map(|set| Scalar::Literal(Value::I64(Some(set as i64))))
+                .collect();
+            result = LogicalJoin {
+                join: Join::Inner(vec![]),
+                left: Box::new(LogicalValues {
+                    columns: vec![grouping_id.clone()],
+                    values: vec![grouping_ids],
+                    input: Box::new(LogicalSingleGet),
+                }),
+                right: Box::new(result),
+            };
+            result = LogicalMap {
+                include_existing: true,
+                projects: expand_projects,
+                input: Box::new(result),
+            };
+            // Rows from different grouping sets must not be combined, even when their group-by columns are equal.
+            group_by_columns.push(grouping_id);
+        }
         result = LogicalAggregate {
             group_by: group_by_columns,
             aggregate: aggregate_operators,
@@ -548,79 +1199,539 @@ impl Converter {
                 input: Box::new(result),
             };
         }
-        result
+        Ok(result)
     }
 
-    fn create(&mut self, q: &AnyResolvedCreateStatementProto) -> Expr {
+    fn create(&mut self, q: &AnyResolvedCreateStatementProto) -> Result<Expr, Error> {
         match q.node.get() {
             ResolvedCreateIndexStmtNode(q) => self.create_index(q),
             ResolvedCreateTableStmtBaseNode(AnyResolvedCreateTableStmtBaseProto {
                 node: Some(ResolvedCreateTableStmtNode(q)),
             }) => self.create_table(q),
-            other => panic!("{:?}", other),
+            ResolvedCreateTableStmtBaseNode(AnyResolvedCreateTableStmtBaseProto {
+                node: Some(ResolvedCreateTableAsSelectStmtNode(q)),
+            }) => self.create_table_as(q),
+            ResolvedCreateViewBaseNode(AnyResolvedCreateViewBaseProto {
+                node: Some(ResolvedCreateViewStmtNode(q)),
+            }) => self.create_view(q.parent.get(), false),
+            ResolvedCreateViewBaseNode(AnyResolvedCreateViewBaseProto {
+                node: Some(ResolvedCreateMaterializedViewStmtNode(q)),
+            }) => self.create_view(q.parent.get(), true),
+            ResolvedCreateFunctionStmtNode(q) => self.create_function(q),
+            ResolvedCreateTableFunctionStmtNode(q) => self.create_table_function(q),
+            other => not_supported(other),
         }
     }
 
-    fn create_index(&mut self, q: &ResolvedCreateIndexStmtProto) -> Expr {
-        for option in &q.option_list {
-            panic!("CREATE INDEX does not support option {}", option.name());
+    fn create_function(&mut self, q: &ResolvedCreateFunctionStmtProto) -> Result<Expr, Error> {
+        if q.parent.get().create_scope == Some(CreateScope::CreateTemp as i32) {
+            return Err(Error::NotSupported(
+                "CREATE TEMP FUNCTION is not supported".to_string(),
+            ));
+        }
+        if q.is_aggregate == Some(true) {
+            return Err(Error::NotSupported(
+                "CREATE AGGREGATE FUNCTION is not supported".to_string(),
+            ));
         }
+        if q.function_expression.is_none() {
+            return Err(Error::NotSupported(
+                "CREATE FUNCTION only supports SQL functions".to_string(),
+            ));
+        }
+        if let Some(option) = q.option_list.first() {
+            return Err(Error::NotSupported(format!(
+                "CREATE FUNCTION does not support option {}",
+                option.name()
+            )));
+        }
+        Ok(LogicalCreateFunction {
+            name: Name {
+                catalog_id: self.catalog_id,
+                path: q.parent.get().name_path.clone(),
+            },
+            arguments: self.function_arguments(&q.argument_name_list, q.signature.get())?,
+            return_type: Some(DataType::from(q.return_type.get())),
+            columns: vec![],
+            sql: q.code.get().clone(),
+        })
+    }
+
+    fn create_table_function(
+        &mut self,
+        q: &ResolvedCreateTableFunctionStmtProto,
+    ) -> Result<Expr, Error> {
+        if q.parent.get().create_scope == Some(CreateScope::CreateTemp as i32) {
+            return Err(Error::NotSupported(
+                "CREATE TEMP TABLE FUNCTION is not supported".to_string(),
+            ));
+        }
+        if q.query.is_none() {
+            return Err(Error::NotSupported(
+                "CREATE TABLE FUNCTION only supports SQL functions".to_string(),
+            ));
+        }
+        if q.is_value_table == Some(true) {
+            return Err(Error::NotSupported(
+                "CREATE TABLE FUNCTION AS VALUE is not supported".to_string(),
+            ));
+        }
+        if let Some(option) = q.option_list.first() {
+            return Err(Error::NotSupported(format!(
+                "CREATE TABLE FUNCTION does not support option {}",
+                option.name()
+            )));
+        }
+        let columns = q
+            .output_column_list
+            .iter()
+            .map(|c| {
+                (
+                    c.name.get().clone(),
+                    DataType::from(c.column.get().r#type.get()),
+                )
+            })
+            .collect();
+        Ok(LogicalCreateFunction {
+            name: Name {
+                catalog_id: self.catalog_id,
+                path: q.parent.get().name_path.clone(),
+            },
+            arguments: self.function_arguments(&q.argument_name_list, q.signature.get())?,
+            return_type: None,
+            columns,
+            sql: q.code.get().clone(),
+        })
+    }
+
+    fn function_arguments(
+        &mut self,
+        names: &Vec<String>,
+        signature: &FunctionSignatureProto,
+    ) -> Result<Vec<(String, DataType)>, Error> {
+        names
+            .iter()
+            .zip(&signature.argument)
+            .map(|(name, argument)| match &argument.r#type {
+                Some(data_type) => Ok((name.clone(), DataType::from(data_type))),
+                None => Err(Error::NotSupported(format!(
+                    "Templated argument {} is not supported",
+                    name
+                ))),
+            })
+            .collect()
+    }
+
+    fn create_index(&mut self, q: &ResolvedCreateIndexStmtProto) -> Result<Expr, Error> {
+        let options = self.options(&q.option_list)?;
         let name = Name {
             catalog_id: self.catalog_id,
             path: q.parent.get().name_path.clone(),
         };
         let table = Table::from(q.table_scan.get().table.get());
         if q.is_unique == Some(true) {
-            panic!("unique index is not supported")
+            return Err(Error::NotSupported(
+                "UNIQUE indexes are not supported".to_string(),
+            ));
         }
         let mut columns = vec![];
+        let mut descending = vec![];
         for item in &q.index_item_list {
-            if item.descending == Some(true) {
-                panic!("descending index is not supported")
+            let column = item.column_ref.get().column.get();
+            // Index keys are stored in order, so they must have a type that can be ordered.
+            match DataType::from(column.r#type.get()) {
+                data_type @ DataType::Json | data_type @ DataType::Array(_) => {
+                    return Err(Error::NotSupported(format!(
+                        "Index key {} has type {}, which is not ordered",
+                        column.name.get(),
+                        data_type
+                    )))
+                }
+                _ => {}
             }
-            columns.push(item.column_ref.get().column.get().name.get().clone())
+            columns.push(column.name.get().clone());
+            descending.push(item.descending == Some(true));
         }
-        LogicalCreateIndex {
+        Ok(LogicalCreateIndex {
             name,
             table,
             columns,
+            descending,
+            options,
+        })
+    }
+
+    /// Convert OPTIONS(name = value, ..). The planner checks that each option is supported before it is stored.
+    fn options(
+        &mut self,
+        option_list: &Vec<ResolvedOptionProto>,
+    ) -> Result<Vec<(String, Scalar)>, Error> {
+        let mut options = vec![];
+        for option in option_list {
+            let mut input = LogicalSingleGet;
+            let value = self.expr(option.value.get(), &mut input)?;
+            options.push((option.name().to_lowercase(), value));
         }
+        Ok(options)
     }
 
-    fn create_table(&mut self, q: &ResolvedCreateTableStmtProto) -> Expr {
+    fn create_table(&mut self, q: &ResolvedCreateTableStmtProto) -> Result<Expr, Error> {
         self.create_table_base(q.parent.get())
     }
 
-    fn create_table_base(&mut self, q: &ResolvedCreateTableStmtBaseProto) -> Expr {
-        if q.parent.get().create_scope == Some(CreateScope::CreateTemp as i32) {
-            panic!("CREATE TEMP TABLE is not supported");
-        }
-        for option in &q.option_list {
-            panic!("CREATE TABLE does not support option {}", option.name());
+    fn create_table_base(&mut self, q: &ResolvedCreateTableStmtBaseProto) -> Result<Expr, Error> {
+        let temp = q.parent.get().create_scope == Some(CreateScope::CreateTemp as i32);
+        if temp && q.parent.get().name_path.len() != 1 {
+            return Err(Error::Syntax(format!(
+                "Temp table {} cannot be created in a catalog",
+                q.parent.get().name_path.join(".")
+            )));
         }
+        let options = self.options(&q.option_list)?;
         let name = Name {
             catalog_id: self.catalog_id,
             path: q.parent.get().name_path.clone(),
         };
         let columns = self.column_definitions(&q.column_definition_list);
-        LogicalCreateTable { name, columns }
-    }
-
-    fn column_definitions(
-        &mut self,
-        cs: &Vec<ResolvedColumnDefinitionProto>,
-    ) -> Vec<(String, DataType)> {
-        let mut columns = Vec::with_capacity(cs.len());
-        for c in cs {
-            columns.push(self.column_definition(c))
-        }
-        columns
+        let mut constraints = vec![];
+        for c in &q.column_definition_list {
+            if c.annotations.as_ref().and_then(|a| a.not_null) == Some(true) {
+                constraints.push(Constraint::NotNull(c.name.get().clone()));
+            }
+            if let Some((sql, _)) = self.default_expression(c)? {
+                constraints.push(Constraint::Default(c.name.get().clone(), sql));
+            }
+            if self.is_identity(c)? {
+                if DataType::from(c.r#type.get()) != DataType::I64 {
+                    return Err(Error::Syntax(format!(
+                        "Identity column {} must have type INT64",
+                        c.name.get()
+                    )));
+                }
+                if c.default_expression.is_some() {
+                    return Err(Error::Syntax(format!(
+                        "Identity column {} cannot have a DEFAULT",
+                        c.name.get()
+                    )));
+                }
+                constraints.push(Constraint::Identity(c.name.get().clone()));
+            }
+        }
+        if let Some(primary_key) = &q.primary_key {
+            if primary_key.unenforced != Some(true) {
+                let column_names: Vec<String> = primary_key
+                    .column_offset_list
+                    .iter()
+                    .map(|i| q.column_definition_list[*i as usize].name.get().clone())
+                    .collect();
+                // Primary key columns are implicitly NOT NULL.
+                for column_name in &column_names {
+                    if !constraints
+                        .iter()
+                        .any(|c| matches!(c, Constraint::NotNull(c) if c == column_name))
+                    {
+                        constraints.push(Constraint::NotNull(column_name.clone()));
+                    }
+                }
+                constraints.push(Constraint::PrimaryKey(column_names));
+            }
+        }
+        for (i, check) in q.check_constraint_list.iter().enumerate() {
+            if check.enforced == Some(false) {
+                continue;
+            }
+            let constraint_name = match check.constraint_name.as_deref() {
+                Some(constraint_name) if !constraint_name.is_empty() => constraint_name.to_string(),
+                _ => format!("check_{}", i),
+            };
+            let mut input = LogicalSingleGet;
+            self.expr(check.expression.get(), &mut input)?;
+            if function_bindings(input).is_none() {
+                return Err(Error::NotSupported(
+                    "Subqueries are not allowed in CHECK constraints".to_string(),
+                ));
+            }
+            let sql = self.sql_of(check.expression.get())?;
+            constraints.push(Constraint::Check(constraint_name, sql));
+        }
+        for (i, fk) in q.foreign_key_list.iter().enumerate() {
+            if fk.enforced == Some(false) {
+                continue;
+            }
+            if fk.match_mode.unwrap_or(0) != 0 {
+                return Err(Error::NotSupported(
+                    "FOREIGN KEY only supports MATCH SIMPLE".to_string(),
+                ));
+            }
+            for action in vec![fk.update_action, fk.delete_action] {
+                // NO_ACTION | RESTRICT
+                if !matches!(action.unwrap_or(0), 0 | 1) {
+                    return Err(Error::NotSupported(
+                        "FOREIGN KEY does not support ON UPDATE or ON DELETE actions".to_string(),
+                    ));
+                }
+            }
+            let constraint_name = match fk.constraint_name.as_deref() {
+                Some(constraint_name) if !constraint_name.is_empty() => constraint_name.to_string(),
+                _ => format!("fk_{}", i),
+            };
+            let referenced_table = Table::from(fk.referenced_table.get());
+            let referenced_columns: Vec<String> = match self.catalog.table(referenced_table.id) {
+                Some(t) => {
+                    let referenced_columns: Vec<String> = fk
+                        .referenced_column_offset_list
+                        .iter()
+                        .map(|i| t.columns[*i as usize].name.clone())
+                        .collect();
+                    // Each row must reference at most one row, so the referenced columns must be unique.
+                    let primary_key: HashSet<&String> = t
+                        .columns
+                        .iter()
+                        .filter(|c| c.primary_key)
+                        .map(|c| &c.name)
+                        .collect();
+                    if primary_key.is_empty()
+                        || primary_key != referenced_columns.iter().collect::<HashSet<_>>()
+                    {
+                        return Err(Error::Syntax(format!(
+                            "Foreign key {} must reference the primary key of table {}",
+                            constraint_name, referenced_table.name
+                        )));
+                    }
+                    referenced_columns
+                }
+                None => {
+                    return Err(Error::Syntax(format!(
+                        "Table {} not found",
+                        referenced_table.name
+                    )))
+                }
+            };
+            constraints.push(Constraint::ForeignKey(
+                constraint_name,
+                fk.referencing_column_list.clone(),
+                referenced_table,
+                referenced_columns,
+            ));
+        }
+        Ok(LogicalCreateTable {
+            name,
+            columns,
+            constraints,
+            temp,
+            options,
+        })
+    }
+
+    fn create_table_as(&mut self, q: &ResolvedCreateTableAsSelectStmtProto) -> Result<Expr, Error> {
+        // Check that the table definition is supported.
+        self.create_table_base(q.parent.get())?;
+        // Split the statement into CREATE TABLE followed by INSERT ... SELECT.
+        let create = AnyResolvedStatementProto {
+            node: Some(ResolvedCreateStatementNode(
+                AnyResolvedCreateStatementProto {
+                    node: Some(ResolvedCreateTableStmtBaseNode(
+                        AnyResolvedCreateTableStmtBaseProto {
+                            node: Some(ResolvedCreateTableStmtNode(ResolvedCreateTableStmtProto {
+                                parent: q.parent.clone(),
+                                ..Default::default()
+                            })),
+                        },
+                    )),
+                },
+            )),
+        };
+        let query = AnyResolvedStatementProto {
+            node: Some(ResolvedQueryStmtNode(ResolvedQueryStmtProto {
+                output_column_list: q.output_column_list.clone(),
+                is_value_table: Some(false),
+                query: q.query.clone(),
+                ..Default::default()
+            })),
+        };
+        let create = crate::parser::build_sql(create, self.catalog).map_err(Error::NotSupported)?;
+        let query = crate::parser::build_sql(query, self.catalog).map_err(Error::NotSupported)?;
+        let table: Vec<String> = q
+            .parent
+            .get()
+            .parent
+            .get()
+            .name_path
+            .iter()
+            .map(|name| format!("`{}`", name))
+            .collect();
+        let columns: Vec<String> = q
+            .parent
+            .get()
+            .column_definition_list
+            .iter()
+            .map(|c| format!("`{}`", c.name.get()))
+            .collect();
+        Ok(LogicalCreateTableAs {
+            create,
+            insert: format!(
+                "insert into {} ({}) {}",
+                table.join("."),
+                columns.join(", "),
+                query
+            ),
+            drop: format!("drop table {}", table.join(".")),
+        })
+    }
+
+    fn create_view(
+        &mut self,
+        q: &ResolvedCreateViewBaseProto,
+        materialized: bool,
+    ) -> Result<Expr, Error> {
+        if q.parent.get().create_scope == Some(CreateScope::CreateTemp as i32) {
+            return Err(Error::NotSupported(
+                "CREATE TEMP VIEW is not supported".to_string(),
+            ));
+        }
+        if q.is_value_table == Some(true) {
+            return Err(Error::NotSupported(
+                "CREATE VIEW AS VALUE is not supported".to_string(),
+            ));
+        }
+        if q.recursive == Some(true) {
+            return Err(Error::NotSupported(
+                "CREATE RECURSIVE VIEW is not supported".to_string(),
+            ));
+        }
+        // A materialized view is populated when it is created, unless OPTIONS (refresh = false).
+        let mut refresh = materialized;
+        for option in &q.option_list {
+            match option.name().to_lowercase().as_str() {
+                "refresh" if materialized => {
+                    let mut input = LogicalSingleGet;
+                    refresh = self.expr(option.value.get(), &mut input)?
+                        == Scalar::Literal(Value::Bool(Some(true)))
+                }
+                _ => {
+                    return Err(Error::NotSupported(format!(
+                        "CREATE VIEW does not support option {}",
+                        option.name()
+                    )))
+                }
+            }
+        }
+        let name = Name {
+            catalog_id: self.catalog_id,
+            path: q.parent.get().name_path.clone(),
+        };
+        if refresh {
+            // Split the statement into CREATE MATERIALIZED VIEW followed by a refresh, like CREATE TABLE ... AS SELECT.
+            let path: Vec<String> = name.path.iter().map(|n| format!("`{}`", n)).collect();
+            return Ok(LogicalCreateTableAs {
+                create: format!(
+                    "create materialized view {} options (refresh = false) as {}",
+                    path.join("."),
+                    q.sql.get()
+                ),
+                insert: format!(
+                    "alter materialized view {} set options (refresh = true)",
+                    path.join(".")
+                ),
+                drop: format!("drop materialized view {}", path.join(".")),
+            });
+        }
+        let columns = q
+            .output_column_list
+            .iter()
+            .map(|c| {
+                (
+                    c.name.get().clone(),
+                    DataType::from(c.column.get().r#type.get()),
+                )
+            })
+            .collect();
+        Ok(LogicalCreateView {
+            name,
+            columns,
+            sql: q.sql.get().clone(),
+            materialized,
+        })
+    }
+
+    fn column_definitions(
+        &mut self,
+        cs: &Vec<ResolvedColumnDefinitionProto>,
+    ) -> Vec<(String, DataType)> {
+        let mut columns = Vec::with_capacity(cs.len());
+        for c in cs {
+            columns.push(self.column_definition(c))
+        }
+        columns
+    }
+
+    /// Convert the DEFAULT of a column, returning the SQL that is stored in the catalog and its current value.
+    fn default_expression(
+        &mut self,
+        c: &ResolvedColumnDefinitionProto,
+    ) -> Result<Option<(String, Scalar)>, Error> {
+        let default = match &c.default_expression {
+            Some(default) => default,
+            None => return Ok(None),
+        };
+        let mut input = LogicalSingleGet;
+        let value = self.expr(default, &mut input)?;
+        let value = match function_bindings(input) {
+            Some(bindings) => inline_bindings(value, bindings),
+            None => {
+                return Err(Error::NotSupported(
+                    "Subqueries are not allowed in DEFAULT".to_string(),
+                ))
+            }
+        };
+        Ok(Some((self.sql_of(default)?, value)))
+    }
+
+    /// The SQL text that x was parsed from, which is stored in the catalog like the SQL of a view.
+    fn sql_of(&self, x: &AnyResolvedExprProto) -> Result<String, Error> {
+        match parse_location(x) {
+            Some(location) => {
+                let start = location.start.unwrap_or(0) as usize;
+                let end = location.end.unwrap_or(0) as usize;
+                Ok(self.sql[start..end].to_string())
+            }
+            None => Err(Error::NotSupported(
+                "DEFAULT and CHECK must be a literal, a column, a function call or a cast"
+                    .to_string(),
+            )),
+        }
     }
 
     fn column_definition(&mut self, c: &ResolvedColumnDefinitionProto) -> (String, DataType) {
         (c.name.get().clone(), DataType::from(c.r#type.get()))
     }
 
+    /// Whether a column is declared with OPTIONS (identity = true), which fills it in from a sequence.
+    fn is_identity(&mut self, c: &ResolvedColumnDefinitionProto) -> Result<bool, Error> {
+        let mut identity = false;
+        for option in c.annotations.iter().flat_map(|a| &a.option_list) {
+            let mut input = LogicalSingleGet;
+            let value = self.expr(option.value.get(), &mut input)?;
+            match (option.name().to_lowercase().as_str(), value) {
+                ("identity", Scalar::Literal(Value::Bool(Some(value)))) => identity = value,
+                ("identity", _) => {
+                    return Err(Error::Syntax(format!(
+                        "Option identity of column {} must be TRUE or FALSE",
+                        c.name.get()
+                    )))
+                }
+                (other, _) => {
+                    return Err(Error::NotSupported(format!(
+                        "Column {} does not support option {}",
+                        c.name.get(),
+                        other
+                    )))
+                }
+            }
+        }
+        Ok(identity)
+    }
+
     fn drop(&mut self, q: &ResolvedDropStmtProto) -> Expr {
         let object = ObjectType::from(q.object_type.get());
         let name = Name {
@@ -630,212 +1741,1401 @@ impl Converter {
         LogicalDrop { object, name }
     }
 
-    fn insert(&mut self, q: &ResolvedInsertStmtProto) -> Expr {
+    fn drop_function(&mut self, name_path: &Vec<String>, if_exists: bool) -> Result<Expr, Error> {
+        let function_name = name_path.last().unwrap();
+        if self.catalog.function(function_name).is_none() {
+            if !self.catalog.functions_registered() {
+                return Err(crate::catalog::not_registered("Function", function_name));
+            }
+            if if_exists {
+                return Ok(LogicalScript { stmts: vec![] });
+            }
+            return Err(Error::Syntax(format!(
+                "Function {} does not exist",
+                function_name
+            )));
+        }
+        let name = Name {
+            catalog_id: self.catalog_id,
+            path: name_path.clone(),
+        };
+        Ok(LogicalDrop {
+            object: ObjectType::Function,
+            name,
+        })
+    }
+
+    fn drop_materialized_view(&mut self, q: &ResolvedDropMaterializedViewStmtProto) -> Expr {
+        let name = Name {
+            catalog_id: self.catalog_id,
+            path: q.name_path.clone(),
+        };
+        LogicalDrop {
+            object: ObjectType::View,
+            name,
+        }
+    }
+
+    fn alter(&mut self, q: &AnyResolvedAlterObjectStmtProto) -> Result<Expr, Error> {
+        match q.node.get() {
+            ResolvedAlterTableStmtNode(q) => self.alter_table(q.parent.get()),
+            ResolvedAlterMaterializedViewStmtNode(q) => {
+                self.alter_materialized_view(q.parent.get())
+            }
+            other => not_supported(other),
+        }
+    }
+
+    fn alter_table(&mut self, q: &ResolvedAlterObjectStmtProto) -> Result<Expr, Error> {
+        let name = Name {
+            catalog_id: self.catalog_id,
+            path: q.name_path.clone(),
+        };
+        let user_table = match self.catalog.table_by_path(&q.name_path) {
+            Some(user_table) => user_table,
+            None if q.is_if_exists == Some(true) => return Ok(LogicalScript { stmts: vec![] }),
+            None => {
+                return Err(Error::Syntax(format!(
+                    "Table {} does not exist",
+                    q.name_path.join(".")
+                )))
+            }
+        };
+        let table = Table {
+            id: user_table.id,
+            name: user_table.name.clone(),
+        };
+        // The columns of the table before and after the statement.
+        let existing: Vec<(String, DataType)> = user_table
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+        let mut kept = existing.clone();
+        let mut added = vec![];
+        let mut stmts = vec![];
+        let mut renames = vec![];
+        for action in &q.alter_action_list {
+            match action.node.get() {
+                ResolvedAddColumnActionNode(action) => {
+                    let column = action.column_definition.get();
+                    if action.is_if_not_exists == Some(true)
+                        && existing.iter().any(|(name, _)| name == column.name.get())
+                    {
+                        continue;
+                    }
+                    if self.is_identity(column)? {
+                        return Err(Error::NotSupported(
+                            "ADD COLUMN does not support identity columns".to_string(),
+                        ));
+                    }
+                    let not_null =
+                        column.annotations.as_ref().and_then(|a| a.not_null) == Some(true);
+                    let (column_name, data_type) = self.column_definition(column);
+                    let (default, value) = match self.default_expression(column)? {
+                        Some((sql, value)) => (Some(sql), value),
+                        None => (None, Scalar::Literal(Value::null(data_type.clone()))),
+                    };
+                    if not_null {
+                        // Existing rows get the DEFAULT, so the column can only be NOT NULL if the DEFAULT is not NULL or the table is empty.
+                        let (input, _, _) = scan(&table, &existing);
+                        stmts.push(assert_empty(
+                            LogicalFilter {
+                                predicates: vec![Scalar::Call(Box::new(F::IsNull(value.clone())))],
+                                input: Box::new(input),
+                            },
+                            format!(
+                                "Column {} of table {} cannot be NULL",
+                                column_name, table.name
+                            ),
+                        ));
+                    }
+                    added.push((column_name.clone(), value));
+                    stmts.push(LogicalAddColumn {
+                        name: name.clone(),
+                        column: (column_name, data_type),
+                        default,
+                        not_null,
+                        if_not_exists: action.is_if_not_exists == Some(true),
+                    })
+                }
+                ResolvedDropColumnActionNode(action) => {
+                    kept.retain(|(name, _)| name != action.name.get());
+                    added.retain(|(name, _)| name != action.name.get());
+                    let mut path = q.name_path.clone();
+                    path.push(action.name.get().clone());
+                    stmts.push(LogicalDrop {
+                        object: ObjectType::Column,
+                        name: Name {
+                            catalog_id: self.catalog_id,
+                            path,
+                        },
+                    })
+                }
+                // Other actions refer to the table by its old name, so rename it last.
+                ResolvedRenameToActionNode(action) => renames.push(LogicalRename {
+                    object: ObjectType::Table,
+                    name: name.clone(),
+                    new_name: Name {
+                        catalog_id: self.catalog_id,
+                        path: action.new_path.clone(),
+                    },
+                }),
+                other => return not_supported(other),
+            }
+        }
+        // Existing rows get a new version with the values of the new columns,
+        // which also replaces the values of any column of the same name that was dropped earlier.
+        if !added.is_empty() {
+            let (input, columns, tid) = scan(&table, &kept);
+            let mut values: Vec<(Scalar, String)> = columns
+                .into_iter()
+                .map(|c| {
+                    let name = c.name.clone();
+                    (Scalar::Column(c), name)
+                })
+                .collect();
+            for (name, default) in added {
+                values.push((default, name));
+            }
+            stmts.push(rewrite_rows(table, input, tid, values));
+        }
+        stmts.extend(renames);
+        if stmts.len() == 1 {
+            Ok(stmts.pop().unwrap())
+        } else {
+            Ok(LogicalScript { stmts })
+        }
+    }
+
+    fn alter_materialized_view(&mut self, q: &ResolvedAlterObjectStmtProto) -> Result<Expr, Error> {
+        let name = Name {
+            catalog_id: self.catalog_id,
+            path: q.name_path.clone(),
+        };
+        let mut refresh = false;
+        for action in &q.alter_action_list {
+            match action.node.get() {
+                ResolvedSetOptionsActionNode(action) => {
+                    for option in &action.option_list {
+                        match option.name().to_lowercase().as_str() {
+                            "refresh" => {
+                                let mut input = LogicalSingleGet;
+                                refresh = self.expr(option.value.get(), &mut input)?
+                                    == Scalar::Literal(Value::Bool(Some(true)))
+                            }
+                            other => {
+                                return Err(Error::NotSupported(format!(
+                                    "ALTER MATERIALIZED VIEW does not support option {}",
+                                    other
+                                )))
+                            }
+                        }
+                    }
+                }
+                other => return not_supported(other),
+            }
+        }
+        if !refresh {
+            return Err(Error::NotSupported(
+                "ALTER MATERIALIZED VIEW requires SET OPTIONS (refresh = true)".to_string(),
+            ));
+        }
+        Ok(LogicalRefresh { name })
+    }
+
+    fn rename(&mut self, q: &ResolvedRenameStmtProto) -> Result<Expr, Error> {
+        let object = ObjectType::from(q.object_type.get());
+        let mut new_name_path = q.new_name_path.clone();
+        if object == ObjectType::Column {
+            // RENAME COLUMN t.a TO b is the same as RENAME COLUMN t.a TO t.b.
+            let table_path = &q.old_name_path[..q.old_name_path.len() - 1];
+            if new_name_path.len() == 1 {
+                new_name_path = [table_path, &new_name_path[..]].concat();
+            }
+            if table_path.is_empty() || new_name_path[..new_name_path.len() - 1] != *table_path {
+                return Err(Error::Syntax(
+                    "RENAME COLUMN cannot move a column to another table".to_string(),
+                ));
+            }
+        }
+        Ok(LogicalRename {
+            object,
+            name: Name {
+                catalog_id: self.catalog_id,
+                path: q.old_name_path.clone(),
+            },
+            new_name: Name {
+                catalog_id: self.catalog_id,
+                path: new_name_path,
+            },
+        })
+    }
+
+    fn insert(&mut self, q: &ResolvedInsertStmtProto) -> Result<Expr, Error> {
         let table = self.table_scan_for_insert(q.table_scan.get());
+        let (input, columns) = if let Some(scan) = &q.query {
+            let input = self.any_resolved_scan(scan)?;
+            let columns = (0..q.query_output_column_list.len())
+                .map(|i| {
+                    (
+                        Column::reference(&q.query_output_column_list[i]),
+                        q.insert_column_list[i].name.get().clone(),
+                    )
+                })
+                .collect();
+            (input, columns)
+        } else {
+            let input = self.rows(q)?;
+            let columns = q
+                .insert_column_list
+                .iter()
+                .map(|c| (Column::reference(c), c.name.get().clone()))
+                .collect();
+            (input, columns)
+        };
+        let (input, columns) = self.insert_defaults(&table, input, columns)?;
+        let (mut stmts, mut input, rename) =
+            self.check_constraints("$insert", &table, input, &columns, None)?;
+        let mut columns: Vec<(Column, String)> = columns
+            .iter()
+            .map(|(c, name)| (rename.get(c).unwrap_or(c).clone(), name.clone()))
+            .collect();
+        let mut output = None;
+        if let Some(returning) = &q.returning {
+            let (create, copy, rename, out) =
+                self.returning(returning, "INSERT", q.table_scan.get(), input, &columns)?;
+            stmts.push(create);
+            input = copy;
+            for (c, _) in &mut columns {
+                *c = rename[c].clone();
+            }
+            output = Some(out);
+        }
+        let (input, partition_by) = self.distribute(&table, input, &columns);
+        stmts.push(LogicalInsert {
+            table,
+            input: Box::new(input),
+            columns,
+            partition_by,
+        });
+        stmts.extend(output);
+        if stmts.len() == 1 {
+            Ok(stmts.pop().unwrap())
+        } else {
+            Ok(LogicalScript { stmts })
+        }
+    }
 
-        if let Some(scan) = &q.query {
-            LogicalInsert {
-                table,
-                input: Box::new(self.any_resolved_scan(scan)),
-                columns: (0..q.query_output_column_list.len())
-                    .map(|i| {
-                        (
-                            Column::reference(&q.query_output_column_list[i]),
-                            q.insert_column_list[i].name.get().clone(),
-                        )
+    /// Hash the distribution key of a table, so each inserted row is sent to the worker that owns its key.
+    fn distribute(
+        &mut self,
+        table: &Table,
+        input: Expr,
+        columns: &Vec<(Column, String)>,
+    ) -> (Expr, Option<Column>) {
+        let user_table = match self.catalog.table(table.id) {
+            Some(user_table) => user_table,
+            None => return (input, None),
+        };
+        let key = match &user_table.distribution_key {
+            Some(key) => key,
+            None => return (input, None),
+        };
+        let value = match columns.iter().find(|(_, name)| name == key) {
+            Some((column, _)) => Scalar::Column(column.clone()),
+            None => {
+                let column = user_table.columns.iter().find(|c| &c.name == key).unwrap();
+                Scalar::Literal(Value::null(column.data_type.clone()))
+            }
+        };
+        let hash = Column::fresh("$hash", DataType::I64);
+        let input = LogicalMap {
+            projects: vec![(Scalar::Call(Box::new(F::Hash(vec![value]))), hash.clone())],
+            include_existing: true,
+            input: Box::new(input),
+        };
+        (input, Some(hash))
+    }
+
+    /// Add the columns that are omitted from an INSERT and have a DEFAULT.
+    fn insert_defaults(
+        &mut self,
+        table: &Table,
+        input: Expr,
+        mut columns: Vec<(Column, String)>,
+    ) -> Result<(Expr, Vec<(Column, String)>), Error> {
+        let mut projects = vec![];
+        self.omitted_defaults(table, &mut projects, &mut columns)?;
+        if projects.is_empty() {
+            return Ok((input, columns));
+        }
+        let input = LogicalMap {
+            include_existing: true,
+            projects,
+            input: Box::new(input),
+        };
+        Ok((input, columns))
+    }
+
+    /// Compute the DEFAULT of each column of table that has one and is missing from columns.
+    fn omitted_defaults(
+        &mut self,
+        table: &Table,
+        projects: &mut Vec<(Scalar, Column)>,
+        columns: &mut Vec<(Column, String)>,
+    ) -> Result<(), Error> {
+        let catalog = self.catalog;
+        if let Some(user_table) = catalog.table(table.id) {
+            for c in &user_table.columns {
+                if !columns.iter().any(|(_, name)| name == &c.name) {
+                    if let Some(default) = self.column_default(user_table, c)? {
+                        let column = Column::fresh(&c.name, c.data_type.clone());
+                        projects.push((default, column.clone()));
+                        columns.push((column, c.name.clone()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Analyze the DEFAULT of a column again, or the NEXTVAL of the sequence of an identity column.
+    fn column_default(
+        &mut self,
+        user_table: &UserTable,
+        c: &UserColumn,
+    ) -> Result<Option<Scalar>, Error> {
+        if let Some(sequence_id) = c.sequence_id {
+            return Ok(Some(Scalar::Call(Box::new(F::NextVal(Scalar::Literal(
+                Value::I64(Some(sequence_id)),
+            ))))));
+        }
+        let sql = match &c.default {
+            Some(sql) => sql,
+            None => return Ok(None),
+        };
+        let default =
+            crate::parser::analyze_expression(sql, &vec![], self.catalog).map_err(|message| {
+                Error::Syntax(format!(
+                    "DEFAULT of column {} of table {} is invalid: {}",
+                    c.name, user_table.name, message
+                ))
+            })?;
+        let mut input = LogicalSingleGet;
+        let value = self.expr(&default, &mut input)?;
+        let value = inline_bindings(value, function_bindings(input).unwrap_or_default());
+        if value.data_type() != c.data_type {
+            return Ok(Some(Scalar::Cast(Box::new(value), c.data_type.clone())));
+        }
+        Ok(Some(value))
+    }
+
+    /// Save the rows that are about to be modified by a DML statement in a temp table, so THEN RETURN can read them after the statement.
+    /// Returns the statement that creates the temp table, a fresh copy of the temp table to use as the input of the DML statement,
+    /// the mapping from input columns to the columns of the copy, and the query that computes the THEN RETURN output.
+    fn returning(
+        &mut self,
+        returning: &ResolvedReturningClauseProto,
+        action: &str,
+        table_scan: &ResolvedTableScanProto,
+        input: Expr,
+        columns: &Vec<(Column, String)>,
+    ) -> Result<(Expr, Expr, HashMap<Column, Column>, Expr), Error> {
+        // THEN RETURN refers to the columns of the target table, so compute them from the columns of the input.
+        let mut projects = vec![];
+        for c in &table_scan.parent.get().column_list {
+            let column = Column::reference(c);
+            let value = match columns.iter().find(|(_, name)| name == c.name.get()) {
+                Some((input_column, _)) if input_column == &column => continue,
+                Some((input_column, _)) => Scalar::Column(input_column.clone()),
+                None => Scalar::Literal(Value::null(column.data_type.clone())),
+            };
+            projects.push((value, column));
+        }
+        let input = if projects.is_empty() {
+            input
+        } else {
+            LogicalMap {
+                include_existing: true,
+                projects,
+                input: Box::new(input),
+            }
+        };
+        let mut temp_columns: Vec<Column> = input.attributes().into_iter().collect();
+        temp_columns.sort_by_key(|c| c.id);
+        let create = LogicalCreateTempTable {
+            name: "$returning".to_string(),
+            columns: temp_columns.clone(),
+            input: Box::new(input),
+        };
+        let get_with = LogicalGetWith {
+            name: "$returning".to_string(),
+            columns: temp_columns,
+        };
+        let (copy, rename) = fresh_copy(&get_with);
+        // Compute the THEN RETURN expressions and the WITH ACTION column from the saved rows.
+        let mut input = get_with;
+        let mut projects = vec![];
+        for x in &returning.expr_list {
+            projects.push(self.computed_column(x, &mut input)?);
+        }
+        if let Some(holder) = &returning.action_column {
+            projects.push((
+                Scalar::Literal(Value::String(Some(action.to_string()))),
+                Column::reference(holder.column.get()),
+            ));
+        }
+        if !projects.is_empty() {
+            input = LogicalMap {
+                include_existing: true,
+                projects,
+                input: Box::new(input),
+            };
+        }
+        let output = LogicalOut {
+            projects: returning
+                .output_column_list
+                .iter()
+                .map(|c| Column::from(c.column.get()))
+                .collect(),
+            input: Box::new(input),
+        };
+        Ok((create, copy, rename, output))
+    }
+
+    /// The value of DEFAULT for a column, which is NULL unless the column has a DEFAULT constraint.
+    fn default_value(
+        &mut self,
+        table: &Table,
+        column: &str,
+        data_type: DataType,
+    ) -> Result<Scalar, Error> {
+        let catalog = self.catalog;
+        if let Some(user_table) = catalog.table(table.id) {
+            if let Some(c) = user_table.columns.iter().find(|c| c.name == column) {
+                if let Some(default) = self.column_default(user_table, c)? {
+                    return Ok(default);
+                }
+            }
+        }
+        Ok(Scalar::Literal(Value::null(data_type)))
+    }
+
+    /// Materialize the rows that are about to be written to table, and assert that they satisfy its constraints.
+    /// If tid is present, the rows replace existing rows, which may be referenced by foreign keys.
+    /// Returns the assertions, the materialized rows, and the new names of columns and tid.
+    fn check_constraints(
+        &mut self,
+        name: &str,
+        table: &Table,
+        input: Expr,
+        columns: &Vec<(Column, String)>,
+        tid: Option<Column>,
+    ) -> Result<(Vec<Expr>, Expr, HashMap<Column, Column>), Error> {
+        let no_constraints = match self.catalog.table(table.id) {
+            Some(t) => {
+                t.checks.is_empty()
+                    && !t.columns.iter().any(|c| c.not_null || c.primary_key)
+                    && !t.foreign_keys.iter().any(|fk| {
+                        fk.table.id == table.id
+                            || (tid.is_some() && fk.referenced_table.id == table.id)
                     })
-                    .collect(),
             }
+            None => true,
+        };
+        if no_constraints {
+            return Ok((vec![], input, HashMap::new()));
+        }
+        let mut temp_columns: Vec<Column> = tid.iter().cloned().collect();
+        for (c, _) in columns {
+            if !temp_columns.contains(c) {
+                temp_columns.push(c.clone());
+            }
+        }
+        let mut stmts = vec![LogicalCreateTempTable {
+            name: name.to_string(),
+            columns: temp_columns.clone(),
+            input: Box::new(input),
+        }];
+        let get_with = LogicalGetWith {
+            name: name.to_string(),
+            columns: temp_columns,
+        };
+        stmts.extend(self.constraint_assertions(table, &get_with, columns)?);
+        let added = vec![(get_with.clone(), columns.clone())];
+        if let Some(tid) = &tid {
+            stmts.extend(self.referenced_assertions(table, &get_with, tid, &added));
+        }
+        let removed = tid.as_ref().map(|tid| (&get_with, tid));
+        stmts.extend(self.primary_key_assertions(table, removed, &added));
+        let (input, rename) = fresh_copy(&get_with);
+        Ok((stmts, input, rename))
+    }
+
+    /// Assert that the rows produced by input satisfy the NOT NULL, CHECK and FOREIGN KEY constraints of table.
+    /// Each assertion counts the rows that violate a single constraint.
+    fn constraint_assertions(
+        &mut self,
+        table: &Table,
+        input: &Expr,
+        columns: &Vec<(Column, String)>,
+    ) -> Result<Vec<Expr>, Error> {
+        let catalog = self.catalog;
+        let user_table = match catalog.table(table.id) {
+            Some(user_table) => user_table,
+            None => return Ok(vec![]),
+        };
+        let find = |name: &String| {
+            columns
+                .iter()
+                .find(|(_, column_name)| column_name == name)
+                .map(|(c, _)| c.clone())
+        };
+        let mut stmts = vec![];
+        for c in &user_table.columns {
+            if c.not_null {
+                let violation = match find(&c.name) {
+                    Some(column) => Scalar::Call(Box::new(F::IsNull(Scalar::Column(column)))),
+                    None => Scalar::Literal(Value::Bool(Some(true))),
+                };
+                let (input, rename) = fresh_copy(input);
+                stmts.push(assert_empty(
+                    LogicalFilter {
+                        predicates: vec![violation.subst(&rename)],
+                        input: Box::new(input),
+                    },
+                    format!("Column {} of table {} cannot be NULL", c.name, table.name),
+                ));
+            }
+        }
+        for (constraint_name, sql) in &user_table.checks {
+            // Columns in the CHECK expression are matched by name to the columns that are being written.
+            let mut check = check_expression(user_table, constraint_name, sql, catalog)?;
+            for c in check.references() {
+                let value = match find(&c.name) {
+                    Some(column) => Scalar::Column(column),
+                    None => self.default_value(table, &c.name, c.data_type.clone())?,
+                };
+                check = check.inline(&value, &c);
+            }
+            let violation = Scalar::Call(Box::new(F::IsFalse(check)));
+            let (input, rename) = fresh_copy(input);
+            stmts.push(assert_empty(
+                LogicalFilter {
+                    predicates: vec![violation.subst(&rename)],
+                    input: Box::new(input),
+                },
+                format!(
+                    "Row violates check constraint {} of table {}",
+                    constraint_name, table.name
+                ),
+            ));
+        }
+        for fk in &user_table.foreign_keys {
+            if fk.table.id != table.id {
+                continue;
+            }
+            // If any column is omitted, it is NULL, so the foreign key is satisfied.
+            let keys: Option<Vec<Column>> = fk.columns.iter().map(|name| find(name)).collect();
+            let keys = match keys {
+                Some(keys) => keys,
+                None => continue,
+            };
+            // Rows with a NULL in any column are not checked.
+            let (input, rename) = fresh_copy(input);
+            let keys: Vec<Column> = keys.iter().map(|c| rename[c].clone()).collect();
+            let input = filter_not_null(input, &keys);
+            let referenced_columns = typed_columns(&fk.referenced_columns, &keys);
+            let (parent, parent_keys, _) = scan(&fk.referenced_table, &referenced_columns);
+            stmts.push(assert_empty(
+                LogicalJoin {
+                    join: Join::Anti(equal_columns(&keys, &parent_keys)),
+                    left: Box::new(parent),
+                    right: Box::new(input),
+                },
+                format!(
+                    "Row violates foreign key {} of table {}",
+                    fk.constraint_name, table.name
+                ),
+            ));
+        }
+        Ok(stmts)
+    }
+
+    /// Assert that removing the rows identified by tid in removed, and adding the rows in added,
+    /// leaves every row that references table through a foreign key with a matching row.
+    fn referenced_assertions(
+        &mut self,
+        table: &Table,
+        removed: &Expr,
+        tid: &Column,
+        added: &Vec<(Expr, Vec<(Column, String)>)>,
+    ) -> Vec<Expr> {
+        let user_table = match self.catalog.table(table.id) {
+            Some(user_table) => user_table,
+            None => return vec![],
+        };
+        let mut stmts = vec![];
+        for fk in &user_table.foreign_keys {
+            if fk.referenced_table.id != table.id {
+                continue;
+            }
+            let referenced_columns: Vec<(String, DataType)> = fk
+                .referenced_columns
+                .iter()
+                .map(|name| {
+                    let c = user_table.columns.iter().find(|c| &c.name == name).unwrap();
+                    (c.name.clone(), c.data_type.clone())
+                })
+                .collect();
+            // Find the removed rows.
+            let (removed_rows, rename) = fresh_copy(removed);
+            let (old, old_keys, old_tid) = scan(table, &referenced_columns);
+            let old = LogicalJoin {
+                join: Join::Semi(equal_columns(&vec![rename[tid].clone()], &vec![old_tid])),
+                left: Box::new(removed_rows),
+                right: Box::new(old),
+            };
+            // Find the rows that reference the removed rows.
+            let columns = fk
+                .columns
+                .iter()
+                .zip(&referenced_columns)
+                .map(|(name, (_, data_type))| (name.clone(), data_type.clone()))
+                .collect();
+            let (children, child_keys, _) = scan(&fk.table, &columns);
+            let mut input = LogicalJoin {
+                join: Join::Semi(equal_columns(&old_keys, &child_keys)),
+                left: Box::new(old),
+                right: Box::new(children),
+            };
+            // Keep only the ones that don't reference a row that remains.
+            let (removed_rows, rename) = fresh_copy(removed);
+            let (remaining, remaining_keys, remaining_tid) = scan(table, &referenced_columns);
+            let remaining = LogicalJoin {
+                join: Join::Anti(equal_columns(
+                    &vec![rename[tid].clone()],
+                    &vec![remaining_tid],
+                )),
+                left: Box::new(removed_rows),
+                right: Box::new(remaining),
+            };
+            input = LogicalJoin {
+                join: Join::Anti(equal_columns(&remaining_keys, &child_keys)),
+                left: Box::new(remaining),
+                right: Box::new(input),
+            };
+            // Or a row that is added.
+            for (added_rows, added_columns) in added {
+                let keys: Option<Vec<Column>> = fk
+                    .referenced_columns
+                    .iter()
+                    .map(|name| {
+                        added_columns
+                            .iter()
+                            .find(|(_, column_name)| column_name == name)
+                            .map(|(c, _)| c.clone())
+                    })
+                    .collect();
+                if let Some(keys) = keys {
+                    let (added_rows, rename) = fresh_copy(added_rows);
+                    let keys: Vec<Column> = keys.iter().map(|c| rename[c].clone()).collect();
+                    input = LogicalJoin {
+                        join: Join::Anti(equal_columns(&keys, &child_keys)),
+                        left: Box::new(added_rows),
+                        right: Box::new(input),
+                    };
+                }
+            }
+            stmts.push(assert_empty(
+                input,
+                format!(
+                    "Table {} references rows of table {} through foreign key {}",
+                    fk.table.name, table.name, fk.constraint_name
+                ),
+            ));
+        }
+        stmts
+    }
+
+    /// Assert that removing the rows identified by tid in removed, and adding the rows in added,
+    /// leaves no two rows of table with the same primary key.
+    /// The added rows are joined to the table on the primary key, so they are looked up in its index instead of scanning the table.
+    fn primary_key_assertions(
+        &mut self,
+        table: &Table,
+        removed: Option<(&Expr, &Column)>,
+        added: &Vec<(Expr, Vec<(Column, String)>)>,
+    ) -> Vec<Expr> {
+        let primary_key = match self.catalog.table(table.id) {
+            Some(user_table) => primary_key(user_table),
+            None => return vec![],
+        };
+        if primary_key.is_empty() || added.is_empty() {
+            return vec![];
+        }
+        let violation =
+            || Error::Constraint(format!("Row violates primary key of table {}", table.name));
+        let names: Vec<String> = primary_key.iter().map(|(name, _)| name.clone()).collect();
+        let keys: Vec<Column> = primary_key
+            .iter()
+            .map(|(name, data_type)| Column::fresh(name, data_type.clone()))
+            .collect();
+        let mut stmts = vec![];
+        let mut all_added: Option<Expr> = None;
+        for (added_rows, added_columns) in added {
+            if let Some(added_keys) = named_columns(&names, added_columns) {
+                // The added rows can't match a row that remains.
+                let (rows, rename) = fresh_copy(added_rows);
+                let row_keys: Vec<Column> = added_keys.iter().map(|c| rename[c].clone()).collect();
+                let (existing, existing_keys, existing_tid) = scan(table, &primary_key);
+                let mut matches = LogicalJoin {
+                    join: Join::Inner(equal_columns(&existing_keys, &row_keys)),
+                    left: Box::new(existing),
+                    right: Box::new(rows),
+                };
+                if let Some((removed, tid)) = removed {
+                    let (removed_rows, rename) = fresh_copy(removed);
+                    matches = LogicalJoin {
+                        join: Join::Anti(equal_columns(
+                            &vec![rename[tid].clone()],
+                            &vec![existing_tid],
+                        )),
+                        left: Box::new(removed_rows),
+                        right: Box::new(matches),
+                    };
+                }
+                stmts.push(assert_no_rows(matches, violation()));
+                // Or each other.
+                let (rows, rename) = fresh_copy(added_rows);
+                let rows = LogicalMap {
+                    include_existing: false,
+                    projects: added_keys
+                        .iter()
+                        .zip(&keys)
+                        .map(|(c, key)| (Scalar::Column(rename[c].clone()), key.clone()))
+                        .collect(),
+                    input: Box::new(rows),
+                };
+                all_added = Some(match all_added {
+                    Some(left) => LogicalUnion {
+                        left: Box::new(left),
+                        right: Box::new(rows),
+                    },
+                    None => rows,
+                });
+            }
+        }
+        if let Some(all_added) = all_added {
+            stmts.push(assert_at_most_once(all_added, keys, violation()));
+        }
+        stmts
+    }
+
+    fn table_scan_for_insert(&mut self, q: &ResolvedTableScanProto) -> Table {
+        Table::from(q.table.get())
+    }
+
+    fn rows(&mut self, q: &ResolvedInsertStmtProto) -> Result<Expr, Error> {
+        let table = Table::from(q.table_scan.get().table.get());
+        let num_columns = q.insert_column_list.len();
+        let mut input = LogicalSingleGet;
+        let mut values: Vec<Vec<Scalar>> = Vec::with_capacity(num_columns);
+        values.resize_with(num_columns, Vec::new);
+        for i in 0..q.row_list.len() {
+            for j in 0..num_columns {
+                values[j].push(self.dml_value(
+                    &table,
+                    &q.insert_column_list[j],
+                    q.row_list[i].value_list[j].value.get(),
+                    &mut input,
+                )?);
+            }
+        }
+        let columns = q.insert_column_list.iter().map(Column::reference).collect();
+        Ok(LogicalValues {
+            columns,
+            values,
+            input: Box::new(input),
+        })
+    }
+
+    fn delete(&mut self, q: &ResolvedDeleteStmtProto) -> Result<Expr, Error> {
+        let table = Table::from(q.table_scan.get().table.get());
+        let (mut input, mut tid) = self.table_scan_for_update(q.table_scan.get());
+        let predicates = self.predicate(q.where_expr.get(), &mut input)?;
+        let mut input = LogicalFilter {
+            predicates,
+            input: Box::new(input),
+        };
+        let mut stmts = vec![];
+        let mut output = None;
+        if let Some(returning) = &q.returning {
+            let columns = q
+                .table_scan
+                .get()
+                .parent
+                .get()
+                .column_list
+                .iter()
+                .map(|c| (Column::reference(c), c.name.get().clone()))
+                .collect();
+            let (create, copy, rename, out) =
+                self.returning(returning, "DELETE", q.table_scan.get(), input, &columns)?;
+            stmts.push(create);
+            input = copy;
+            tid = rename[&tid].clone();
+            output = Some(out);
+        }
+        let referenced = match self.catalog.table(table.id) {
+            Some(t) => t
+                .foreign_keys
+                .iter()
+                .any(|fk| fk.referenced_table.id == table.id),
+            None => false,
+        };
+        if !referenced {
+            stmts.push(LogicalDelete {
+                table,
+                tid,
+                input: Box::new(input),
+            });
+        } else {
+            // Check that no rows reference the deleted rows before deleting them.
+            stmts.push(LogicalCreateTempTable {
+                name: "$delete".to_string(),
+                columns: vec![tid.clone()],
+                input: Box::new(input),
+            });
+            let get_with = LogicalGetWith {
+                name: "$delete".to_string(),
+                columns: vec![tid.clone()],
+            };
+            stmts.extend(self.referenced_assertions(&table, &get_with, &tid, &vec![]));
+            let (input, rename) = fresh_copy(&get_with);
+            stmts.push(LogicalDelete {
+                table: table.clone(),
+                tid: rename[&tid].clone(),
+                input: Box::new(input),
+            });
+        }
+        stmts.extend(output);
+        if stmts.len() == 1 {
+            Ok(stmts.pop().unwrap())
+        } else {
+            Ok(LogicalScript { stmts })
+        }
+    }
+
+    fn update(&mut self, q: &ResolvedUpdateStmtProto) -> Result<Expr, Error> {
+        let table = Table::from(q.table_scan.get().table.get());
+        let (mut input, tid) = self.table_scan_for_update(q.table_scan.get());
+        if let Some(from) = &q.from_scan {
+            let from = self.any_resolved_scan(from)?;
+            let predicates = vec![];
+            input = LogicalJoin {
+                join: Join::Inner(predicates),
+                left: Box::new(input),
+                right: Box::new(from),
+            };
+        }
+        if let Some(pred) = &q.where_expr {
+            let predicates = self.predicate(pred, &mut input)?;
+            input = LogicalFilter {
+                predicates,
+                input: Box::new(input),
+            };
+        }
+        let column_list = &q.table_scan.get().parent.get().column_list;
+        let mut projects = vec![];
+        for column in column_list {
+            let as_column = Column::reference(column);
+            let value = self
+                .updated_column(&table, &q.update_item_list, column, &mut input)?
+                .unwrap_or(Scalar::Column(as_column.clone()));
+            projects.push((value, as_column))
+        }
+        projects.push((Scalar::Column(tid.clone()), tid.clone()));
+        let input = LogicalMap {
+            include_existing: false,
+            projects,
+            input: Box::new(input),
+        };
+        let columns = column_list
+            .iter()
+            .map(|c| (Column::reference(c), c.name.get().clone()))
+            .collect();
+        let (mut stmts, mut input, rename) =
+            self.check_constraints("$update", &table, input, &columns, Some(tid.clone()))?;
+        let mut tid = rename.get(&tid).unwrap_or(&tid).clone();
+        let mut columns: Vec<(Column, String)> = columns
+            .iter()
+            .map(|(c, name)| (rename.get(c).unwrap_or(c).clone(), name.clone()))
+            .collect();
+        let mut output = None;
+        if let Some(returning) = &q.returning {
+            let (create, copy, rename, out) =
+                self.returning(returning, "UPDATE", q.table_scan.get(), input, &columns)?;
+            stmts.push(create);
+            input = copy;
+            tid = rename[&tid].clone();
+            for (c, _) in &mut columns {
+                *c = rename[c].clone();
+            }
+            output = Some(out);
+        }
+        if self.updates_distribution_key(&table, &q.update_item_list) {
+            // Like MERGE, insert the new versions of the rows on top of the delete of the old versions,
+            // so each row moves to the worker that owns its new key.
+            let input = LogicalDelete {
+                table: table.clone(),
+                tid,
+                input: Box::new(input),
+            };
+            let (input, partition_by) = self.distribute(&table, input, &columns);
+            stmts.push(LogicalInsert {
+                table,
+                input: Box::new(input),
+                columns,
+                partition_by,
+            });
         } else {
-            LogicalInsert {
+            stmts.push(LogicalUpdate {
                 table,
-                input: Box::new(self.rows(q)),
-                columns: q
-                    .insert_column_list
-                    .iter()
-                    .map(|c| (Column::reference(c), c.name.get().clone()))
-                    .collect(),
+                tid,
+                input: Box::new(input),
+                columns,
+            });
+        }
+        stmts.extend(output);
+        if stmts.len() == 1 {
+            Ok(stmts.pop().unwrap())
+        } else {
+            Ok(LogicalScript { stmts })
+        }
+    }
+
+    /// The value of a column in INSERT or UPDATE, which may be DEFAULT.
+    fn dml_value(
+        &mut self,
+        table: &Table,
+        column: &ResolvedColumnProto,
+        value: &AnyResolvedExprProto,
+        outer: &mut Expr,
+    ) -> Result<Scalar, Error> {
+        match value.node.get() {
+            ResolvedDmldefaultNode(_) => self.default_value(
+                table,
+                column.name.get(),
+                DataType::from(column.r#type.get()),
+            ),
+            other => self.expr_node(other, outer),
+        }
+    }
+
+    fn updated_column(
+        &mut self,
+        table: &Table,
+        update_item_list: &Vec<ResolvedUpdateItemProto>,
+        column: &ResolvedColumnProto,
+        outer: &mut Expr,
+    ) -> Result<Option<Scalar>, Error> {
+        for item in update_item_list {
+            if let ResolvedColumnRefNode(target) = item.target.get().node.get() {
+                if target.column.get().name == column.name {
+                    let value = item.set_value.get().value.get();
+                    return Ok(Some(self.dml_value(table, column, value, outer)?));
+                }
             }
         }
+        Ok(None)
     }
 
-    fn table_scan_for_insert(&mut self, q: &ResolvedTableScanProto) -> Table {
-        Table::from(q.table.get())
-    }
-
-    fn rows(&mut self, q: &ResolvedInsertStmtProto) -> Expr {
-        let num_columns = q.insert_column_list.len();
-        let mut input = LogicalSingleGet;
-        let mut values: Vec<Vec<Scalar>> = Vec::with_capacity(num_columns);
-        values.resize_with(num_columns, Vec::new);
-        for i in 0..q.row_list.len() {
-            for j in 0..num_columns {
-                values[j].push(self.expr(
-                    q.row_list[i].value_list[j].value.as_ref().unwrap(),
-                    &mut input,
-                ));
+    fn updates_distribution_key(
+        &self,
+        table: &Table,
+        update_item_list: &Vec<ResolvedUpdateItemProto>,
+    ) -> bool {
+        let key = match self.catalog.table(table.id) {
+            Some(user_table) => match &user_table.distribution_key {
+                Some(key) => key.clone(),
+                None => return false,
+            },
+            None => return false,
+        };
+        update_item_list.iter().any(|item| {
+            if let ResolvedColumnRefNode(target) = item.target.get().node.get() {
+                target.column.get().name.get() == &key
+            } else {
+                false
             }
-        }
-        let columns = q.insert_column_list.iter().map(Column::reference).collect();
-        LogicalValues {
-            columns,
-            values,
-            input: Box::new(input),
-        }
+        })
     }
 
-    fn delete(&mut self, q: &ResolvedDeleteStmtProto) -> Expr {
-        let (mut input, tid) = self.table_scan_for_update(q.table_scan.get());
-        let predicates = self.predicate(q.where_expr.get(), &mut input);
-        LogicalDelete {
-            table: Table::from(q.table_scan.get().table.get()),
-            tid,
-            input: Box::new(LogicalFilter {
-                predicates,
-                input: Box::new(input),
-            }),
+    fn truncate(&mut self, q: &ResolvedTruncateStmtProto) -> Result<Expr, Error> {
+        if q.where_expr.is_some() {
+            return Err(Error::NotSupported(
+                "TRUNCATE TABLE ... WHERE is not supported".to_string(),
+            ));
+        }
+        let table = Table::from(q.table_scan.get().table.get());
+        // Every row is removed, so rows of other tables must not reference any of them.
+        let (removed, _, tid) = scan(&table, &vec![]);
+        let mut stmts = self.referenced_assertions(&table, &removed, &tid, &vec![]);
+        stmts.push(LogicalCall {
+            procedure: Procedure::Truncate(table),
+            input: Box::new(LogicalSingleGet),
+        });
+        if stmts.len() == 1 {
+            Ok(stmts.pop().unwrap())
+        } else {
+            Ok(LogicalScript { stmts })
         }
     }
 
-    fn update(&mut self, q: &ResolvedUpdateStmtProto) -> Expr {
+    fn merge(&mut self, q: &ResolvedMergeStmtProto) -> Result<Expr, Error> {
         let table = Table::from(q.table_scan.get().table.get());
-        let (mut input, tid) = self.table_scan_for_update(q.table_scan.get());
-        if let Some(from) = &q.from_scan {
-            let from = self.any_resolved_scan(from);
-            let predicates = vec![];
-            input = LogicalJoin {
-                join: Join::Inner(predicates),
-                left: Box::new(input),
-                right: Box::new(from),
-            };
+        let (target, tid) = self.table_scan_for_update(q.table_scan.get());
+        // Mark every source row, so we can recognize target rows that were not matched by the source.
+        let source = self.any_resolved_scan(q.from_scan.get())?;
+        let matched = Column::fresh("$matched", DataType::Bool);
+        let source = LogicalMap {
+            include_existing: true,
+            projects: vec![(Scalar::Literal(Value::Bool(Some(true))), matched.clone())],
+            input: Box::new(source),
+        };
+        // Join source and target, preserving unmatched rows only if a WHEN NOT MATCHED clause needs them.
+        let dummy = LogicalSingleGet;
+        let mut input = dummy.clone();
+        let predicates = self.predicate(q.merge_expr.get(), &mut input)?;
+        if input != dummy {
+            return Err(Error::NotSupported(
+                "Subqueries are not allowed in the ON expression of MERGE".to_string(),
+            ));
         }
-        if let Some(pred) = &q.where_expr {
-            let predicates = self.predicate(pred, &mut input);
-            input = LogicalFilter {
+        let by_source = q
+            .when_clause_list
+            .iter()
+            .any(|w| w.match_type == Some(MatchType::NotMatchedBySource as i32));
+        let by_target = q
+            .when_clause_list
+            .iter()
+            .any(|w| w.match_type == Some(MatchType::NotMatchedByTarget as i32));
+        let mut input = match (by_source, by_target) {
+            (false, false) => LogicalFilter {
                 predicates,
-                input: Box::new(input),
+                input: Box::new(LogicalJoin {
+                    join: Join::Inner(vec![]),
+                    left: Box::new(source),
+                    right: Box::new(target),
+                }),
+            },
+            (false, true) => LogicalJoin {
+                join: Join::Right(predicates),
+                left: Box::new(target),
+                right: Box::new(source),
+            },
+            (true, false) => LogicalJoin {
+                join: Join::Right(predicates),
+                left: Box::new(source),
+                right: Box::new(target),
+            },
+            (true, true) => LogicalJoin {
+                join: Join::Outer(predicates),
+                left: Box::new(source),
+                right: Box::new(target),
+            },
+        };
+        // Each row is acted on by the first WHEN clause that applies to it, if any.
+        let mut cases = vec![];
+        for (i, when) in q.when_clause_list.iter().enumerate() {
+            let is_null = |c: &Column| Scalar::Call(Box::new(F::IsNull(Scalar::Column(c.clone()))));
+            let not_null = |c: &Column| Scalar::Call(Box::new(F::Not(is_null(c))));
+            let mut condition = match MatchType::from_i32(*when.match_type.get()) {
+                Some(MatchType::Matched) => {
+                    Scalar::Call(Box::new(F::And(not_null(&matched), not_null(&tid))))
+                }
+                Some(MatchType::NotMatchedBySource) => is_null(&matched),
+                Some(MatchType::NotMatchedByTarget) => is_null(&tid),
+                None => {
+                    return Err(Error::NotSupported(format!(
+                        "MERGE does not support match type {}",
+                        when.match_type.get()
+                    )))
+                }
             };
+            if let Some(match_expr) = &when.match_expr {
+                let match_expr = self.expr(match_expr, &mut input)?;
+                condition = Scalar::Call(Box::new(F::And(condition, match_expr)));
+            }
+            cases.push((condition, Scalar::Literal(Value::I64(Some(i as i64)))));
         }
+        let when_column = Column::fresh("$when", DataType::I64);
+        let when = Scalar::Call(Box::new(F::CaseNoValue(
+            cases,
+            Scalar::Literal(Value::I64(None)),
+        )));
+        // Compute the new values of every updated or inserted row before we modify the target.
         let column_list = &q.table_scan.get().parent.get().column_list;
-        let mut projects = vec![];
-        for column in column_list {
-            let as_column = Column::reference(column);
-            let value = self
-                .updated_column(q, column, &mut input)
-                .unwrap_or(Scalar::Column(as_column.clone()));
-            projects.push((value, as_column))
+        let mut projects = vec![
+            (Scalar::Column(tid.clone()), tid.clone()),
+            (when, when_column.clone()),
+        ];
+        let mut deletes = vec![];
+        let mut updates = vec![];
+        let mut inserts = vec![];
+        for (i, when) in q.when_clause_list.iter().enumerate() {
+            match ActionType::from_i32(*when.action_type.get()) {
+                Some(ActionType::Insert) => {
+                    let mut columns = vec![];
+                    for (j, c) in when.insert_column_list.iter().enumerate() {
+                        let value = when.insert_row.get().value_list[j].value.get();
+                        let value = self.dml_value(&table, c, value, &mut input)?;
+                        let column = Column::fresh(c.name.get(), DataType::from(c.r#type.get()));
+                        projects.push((value, column.clone()));
+                        columns.push((column, c.name.get().clone()));
+                    }
+                    self.omitted_defaults(&table, &mut projects, &mut columns)?;
+                    inserts.push((i, columns));
+                }
+                Some(ActionType::Update) => {
+                    let mut columns = vec![];
+                    for c in column_list {
+                        let value = self
+                            .updated_column(&table, &when.update_item_list, c, &mut input)?
+                            .unwrap_or(Scalar::Column(Column::reference(c)));
+                        let column = Column::fresh(c.name.get(), DataType::from(c.r#type.get()));
+                        projects.push((value, column.clone()));
+                        columns.push((column, c.name.get().clone()));
+                    }
+                    deletes.push(i);
+                    updates.push(i);
+                    inserts.push((i, columns));
+                }
+                Some(ActionType::Delete) => deletes.push(i),
+                None => {
+                    return Err(Error::NotSupported(format!(
+                        "MERGE does not support action type {}",
+                        when.action_type.get()
+                    )))
+                }
+            }
         }
-        projects.push((Scalar::Column(tid.clone()), tid.clone()));
-        LogicalUpdate {
-            table,
-            tid,
+        // Materialize the joined rows once, then delete old row versions and insert new ones.
+        // A failed statement doesn't roll back the statements before it, so every assertion is checked before the first write.
+        let name = "$merge".to_string();
+        let columns: Vec<Column> = projects.iter().map(|(_, c)| c.clone()).collect();
+        let mut stmts = vec![LogicalCreateTempTable {
+            name: name.clone(),
+            columns: columns.clone(),
             input: Box::new(LogicalMap {
                 include_existing: false,
                 projects,
                 input: Box::new(input),
             }),
-            columns: column_list
+        }];
+        // A target row that is matched by two source rows would be deleted once and inserted twice.
+        if !deletes.is_empty() {
+            let (input, rename) = self.merge_clauses(&name, &columns, &when_column, &deletes);
+            stmts.push(assert_at_most_once(
+                input,
+                vec![rename[&tid].clone()],
+                Error::Constraint(
+                    "MERGE must match at most one source row for each target row".to_string(),
+                ),
+            ));
+        }
+        // Check constraints before any rows are modified.
+        let mut added = vec![];
+        for (i, columns_i) in &inserts {
+            let (input, rename) = self.merge_clauses(&name, &columns, &when_column, &vec![*i]);
+            let columns_i = columns_i
                 .iter()
-                .map(|c| (Column::reference(c), c.name.get().clone()))
-                .collect(),
+                .map(|(c, name)| (rename[c].clone(), name.clone()))
+                .collect();
+            stmts.extend(self.constraint_assertions(&table, &input, &columns_i)?);
+            added.push((input, columns_i));
+        }
+        let removed = if deletes.is_empty() {
+            None
+        } else {
+            let (removed, rename) = self.merge_clauses(&name, &columns, &when_column, &deletes);
+            stmts.extend(self.referenced_assertions(&table, &removed, &rename[&tid], &added));
+            Some((removed, rename[&tid].clone()))
+        };
+        let removed = removed.as_ref().map(|(removed, tid)| (removed, tid));
+        stmts.extend(self.primary_key_assertions(&table, removed, &added));
+        // Like UPDATE, the new versions of updated rows are inserted on top of the delete of the old versions,
+        // so each updated row is counted once in the number of rows affected.
+        let deletes: Vec<usize> = deletes
+            .into_iter()
+            .filter(|i| !updates.contains(i))
+            .collect();
+        if !deletes.is_empty() {
+            let (input, rename) = self.merge_clauses(&name, &columns, &when_column, &deletes);
+            stmts.push(LogicalDelete {
+                table: table.clone(),
+                tid: rename[&tid].clone(),
+                input: Box::new(input),
+            });
+        }
+        for (i, columns_i) in inserts {
+            let (mut input, rename) = self.merge_clauses(&name, &columns, &when_column, &vec![i]);
+            if updates.contains(&i) {
+                input = LogicalDelete {
+                    table: table.clone(),
+                    tid: rename[&tid].clone(),
+                    input: Box::new(input),
+                };
+            }
+            let columns_i: Vec<(Column, String)> = columns_i
+                .iter()
+                .map(|(c, name)| (rename[c].clone(), name.clone()))
+                .collect();
+            let (input, partition_by) = self.distribute(&table, input, &columns_i);
+            stmts.push(LogicalInsert {
+                table: table.clone(),
+                input: Box::new(input),
+                columns: columns_i,
+                partition_by,
+            });
         }
+        Ok(LogicalScript { stmts })
     }
 
-    fn updated_column(
+    fn merge_clauses(
         &mut self,
-        q: &ResolvedUpdateStmtProto,
-        column: &ResolvedColumnProto,
-        outer: &mut Expr,
-    ) -> Option<Scalar> {
-        for item in &q.update_item_list {
-            if let ResolvedColumnRefNode(target) = item.target.get().node.get() {
-                if target.column.get().name == column.name {
-                    let value = match item.set_value.get().value.get().node.get() {
-                        ResolvedDmldefaultNode(_) => {
-                            panic!("DEFAULT is not supported");
-                        }
-                        other => self.expr_node(other, outer),
-                    };
-                    return Some(value);
-                }
-            }
-        }
-        None
+        name: &String,
+        columns: &Vec<Column>,
+        when_column: &Column,
+        clauses: &Vec<usize>,
+    ) -> (Expr, HashMap<Column, Column>) {
+        let rename: HashMap<Column, Column> = columns
+            .iter()
+            .map(|c| (c.clone(), Column::fresh(&c.name, c.data_type.clone())))
+            .collect();
+        let predicate = Scalar::Call(Box::new(F::In(
+            Scalar::Column(rename[when_column].clone()),
+            clauses
+                .iter()
+                .map(|i| Scalar::Literal(Value::I64(Some(*i as i64))))
+                .collect(),
+        )));
+        let input = LogicalFilter {
+            predicates: vec![predicate],
+            input: Box::new(LogicalGetWith {
+                name: name.clone(),
+                columns: columns.iter().map(|c| rename[c].clone()).collect(),
+            }),
+        };
+        (input, rename)
     }
 
-    fn create_database(&mut self, q: &ResolvedCreateDatabaseStmtProto) -> Expr {
-        for option in &q.option_list {
-            panic!("CREATE DATABASE does not support option {}", option.name());
+    fn create_database(&mut self, q: &ResolvedCreateDatabaseStmtProto) -> Result<Expr, Error> {
+        if let Some(option) = q.option_list.first() {
+            return Err(Error::NotSupported(format!(
+                "CREATE DATABASE does not support option {}",
+                option.name()
+            )));
         }
-        LogicalCreateDatabase {
+        // Names that start with information_schema refer to the views of INFORMATION_SCHEMA, so a database with that name could never be used.
+        if q.name_path.len() == 1 && q.name_path[0].eq_ignore_ascii_case("information_schema") {
+            return Err(Error::Syntax(format!(
+                "Database name {} is reserved",
+                q.name_path[0]
+            )));
+        }
+        Ok(LogicalCreateDatabase {
             name: Name {
                 catalog_id: self.catalog_id,
                 path: q.name_path.clone(),
             },
-        }
+        })
     }
 
-    fn call(&mut self, q: &ResolvedCallStmtProto) -> Expr {
+    fn call(&mut self, q: &ResolvedCallStmtProto) -> Result<Expr, Error> {
+        let mut input = LogicalSingleGet;
         let procedure = match q.procedure.get().name.get().as_str() {
             "create_catalog" => Procedure::CreateCatalog,
-            "create_table" => Procedure::CreateTable,
+            "create_table" => Procedure::CreateTable(self.expr(&q.argument_list[0], &mut input)?),
             "create_index" => Procedure::CreateIndex,
-            other => panic!("{}", other),
+            "create_sequence" => Procedure::CreateSequence(
+                self.expr(&q.argument_list[0], &mut input)?,
+                self.expr(&q.argument_list[1], &mut input)?,
+            ),
+            other => return Err(Error::Syntax(format!("Procedure {} does not exist", other))),
         };
-        LogicalCall {
+        Ok(LogicalCall {
             procedure,
-            input: Box::new(LogicalSingleGet),
-        }
+            input: Box::new(input),
+        })
     }
 
-    fn explain(&mut self, q: &ResolvedExplainStmtProto) -> Expr {
-        LogicalExplain {
-            input: Box::new(self.any_stmt(q.statement.get())),
-        }
+    fn explain(&mut self, q: &ResolvedExplainStmtProto) -> Result<Expr, Error> {
+        Ok(LogicalExplain {
+            input: Box::new(self.any_stmt(q.statement.get())?),
+        })
     }
 
-    fn assert(&mut self, q: &ResolvedAssertStmtProto) -> Expr {
+    fn assert(&mut self, q: &ResolvedAssertStmtProto) -> Result<Expr, Error> {
         let mut input = Expr::LogicalSingleGet;
-        LogicalCall {
+        Ok(LogicalCall {
             procedure: Procedure::Assert(
-                self.expr(q.expression.get(), &mut input),
-                q.description
-                    .clone()
-                    .unwrap_or("Assert failed.".to_string()),
+                self.expr(q.expression.get(), &mut input)?,
+                Error::Raise(
+                    q.description
+                        .clone()
+                        .unwrap_or("Assert failed.".to_string()),
+                ),
             ),
             input: Box::new(input),
-        }
+        })
     }
 
-    fn exprs(&mut self, xs: &Vec<AnyResolvedExprProto>, outer: &mut Expr) -> Vec<Scalar> {
+    fn exprs(
+        &mut self,
+        xs: &Vec<AnyResolvedExprProto>,
+        outer: &mut Expr,
+    ) -> Result<Vec<Scalar>, Error> {
         let mut list = vec![];
         for x in xs {
-            list.push(self.expr(x, outer));
+            list.push(self.expr(x, outer)?);
         }
-        list
+        Ok(list)
     }
 
-    fn expr(&mut self, x: &AnyResolvedExprProto, outer: &mut Expr) -> Scalar {
+    fn expr(&mut self, x: &AnyResolvedExprProto, outer: &mut Expr) -> Result<Scalar, Error> {
         self.expr_node(x.node.get(), outer)
     }
 
-    fn expr_node(&mut self, x: &any_resolved_expr_proto::Node, outer: &mut Expr) -> Scalar {
+    fn expr_node(
+        &mut self,
+        x: &any_resolved_expr_proto::Node,
+        outer: &mut Expr,
+    ) -> Result<Scalar, Error> {
         match x {
             ResolvedLiteralNode(x) => {
                 let value = x.value.get().value.get();
                 let data_type = x.value.get().r#type.get();
-                Scalar::Literal(literal(value, data_type))
+                Ok(Scalar::Literal(literal(value, data_type)?))
             }
-            ResolvedColumnRefNode(x) => Scalar::Column(Column::reference(x.column.get())),
+            ResolvedColumnRefNode(x) => Ok(Scalar::Column(Column::reference(x.column.get()))),
             ResolvedFunctionCallBaseNode(x) => self.function_call(x, outer),
             ResolvedCastNode(x) => self.cast(x, outer),
-            ResolvedParameterNode(x) => self.parameter(x),
+            ResolvedParameterNode(x) => Ok(self.parameter(x)),
+            ResolvedConstantNode(x) => Ok(self.constant(x)),
             ResolvedSubqueryExprNode(x) => self.subquery_expr(x, outer),
-            other => panic!("{:?}", other),
+            ResolvedExpressionColumnNode(x) => self.argument(x.name.get()),
+            other => not_supported(other),
         }
     }
 
-    fn function_call(&mut self, x: &AnyResolvedFunctionCallBaseProto, outer: &mut Expr) -> Scalar {
+    fn function_call(
+        &mut self,
+        x: &AnyResolvedFunctionCallBaseProto,
+        outer: &mut Expr,
+    ) -> Result<Scalar, Error> {
         match x {
             AnyResolvedFunctionCallBaseProto {
                 node:
@@ -845,19 +3145,125 @@ impl Converter {
                                 function: Some(function),
                                 argument_list,
                                 signature: Some(signature),
+                                error_mode,
                                 ..
                             }),
                         ..
                     })),
             } => {
-                let arguments = self.exprs(argument_list, outer);
-                Scalar::Call(Box::new(F::from(function, signature, arguments)))
+                let arguments = self.exprs(argument_list, outer)?;
+                if let Some(name) = function.name.get().strip_prefix("User:") {
+                    return self.user_function(name, arguments, outer);
+                }
+                if function.name.get() == "System:nextval" {
+                    return self.nextval(arguments);
+                }
+                let function = Box::new(F::from(function, signature, arguments));
+                if *error_mode == Some(ErrorMode::SafeErrorMode as i32) {
+                    Ok(Scalar::SafeCall(function))
+                } else {
+                    Ok(Scalar::Call(function))
+                }
             }
-            other => panic!("{:?}", other),
+            other => not_supported(other),
+        }
+    }
+
+    fn user_function(
+        &mut self,
+        name: &str,
+        arguments: Vec<Scalar>,
+        outer: &mut Expr,
+    ) -> Result<Scalar, Error> {
+        let catalog = self.catalog;
+        let function = match catalog.function(name) {
+            Some(function) => function,
+            None => return Err(Error::Syntax(format!("Function {} does not exist", name))),
+        };
+        let body =
+            crate::parser::analyze_expression(&function.sql, &function.arguments, self.catalog)
+                .map_err(|message| {
+                    Error::Syntax(format!("Function {} is invalid: {}", name, message))
+                })?;
+        // Each argument is computed once, below the call, no matter how many times the body uses it.
+        let (mut bindings, arguments) = bind_arguments(&function.arguments, arguments);
+        let mut converter = self.function_body(&function.arguments, arguments);
+        let mut input = LogicalSingleGet;
+        let value = converter.expr(&body, &mut input)?;
+        // The arguments of functions called by the body are computed below this call too.
+        match function_bindings(input) {
+            Some(nested) => bindings.extend(nested),
+            None => {
+                return Err(Error::NotSupported(format!(
+                    "Subqueries are not supported in function {}",
+                    name
+                )))
+            }
+        }
+        if !bindings.is_empty() {
+            *outer = LogicalMap {
+                include_existing: true,
+                projects: bindings,
+                input: Box::new(std::mem::take(outer)),
+            };
+        }
+        let return_type = function.return_type.clone().unwrap();
+        if value.data_type() != return_type {
+            return Ok(Scalar::Cast(Box::new(value), return_type));
+        }
+        Ok(value)
+    }
+
+    /// NEXTVAL('name') is resolved to the id of the sequence when the statement is planned, like a table name.
+    fn nextval(&mut self, arguments: Vec<Scalar>) -> Result<Scalar, Error> {
+        let name = match &arguments[0] {
+            Scalar::Literal(Value::String(Some(name))) => name,
+            _ => {
+                return Err(Error::Syntax(
+                    "The argument of NEXTVAL must be the name of a sequence".to_string(),
+                ))
+            }
+        };
+        let sequence_id = match self.catalog.sequence(name) {
+            Some(sequence_id) => sequence_id,
+            None if !self.catalog.functions_registered() => {
+                return Err(crate::catalog::not_registered("Sequence", name))
+            }
+            None => return Err(Error::Syntax(format!("Sequence {} does not exist", name))),
+        };
+        Ok(Scalar::Call(Box::new(F::NextVal(Scalar::Literal(
+            Value::I64(Some(sequence_id)),
+        )))))
+    }
+
+    /// A converter for the body of a user-defined function, which substitutes the arguments of the call for the parameters.
+    fn function_body(
+        &self,
+        parameters: &Vec<(String, DataType)>,
+        arguments: Vec<Scalar>,
+    ) -> Converter<'a> {
+        Converter {
+            catalog_id: self.catalog_id,
+            catalog: self.catalog,
+            sql: self.sql,
+            recursive: None,
+            recursion_limit: self.recursion_limit,
+            arguments: parameters
+                .iter()
+                .map(|(name, _)| name.to_lowercase())
+                .zip(arguments)
+                .collect(),
+        }
+    }
+
+    fn argument(&mut self, name: &String) -> Result<Scalar, Error> {
+        match self.arguments.get(&name.to_lowercase()) {
+            Some(argument) => Ok(argument.clone()),
+            None => Err(Error::Syntax(format!("Argument {} is not defined", name))),
         }
     }
 
-    fn cast(&mut self, x: &ResolvedCastProto, outer: &mut Expr) -> Scalar {
+    fn cast(&mut self, x: &ResolvedCastProto, outer: &mut Expr) -> Result<Scalar, Error> {
         let (expr, ty) = match x {
             ResolvedCastProto {
                 parent:
@@ -869,20 +3275,38 @@ impl Converter {
             } => (expr, ty),
             other => panic!("{:?}", other),
         };
-        Scalar::Cast(Box::new(self.expr(expr, outer)), DataType::from(ty))
+        let expr = Box::new(self.expr(expr, outer)?);
+        // SAFE_CAST returns NULL instead of an error.
+        if x.return_null_on_error == Some(true) {
+            Ok(Scalar::SafeCast(expr, DataType::from(ty)))
+        } else {
+            Ok(Scalar::Cast(expr, DataType::from(ty)))
+        }
     }
 
     fn parameter(&mut self, x: &ResolvedParameterProto) -> Scalar {
         Scalar::Parameter(x.name.get().clone(), x.parent.get().r#type.get().into())
     }
 
-    fn subquery_expr(&mut self, x: &ResolvedSubqueryExprProto, outer: &mut Expr) -> Scalar {
+    /// The variables of a script are registered as constants, and bound like query parameters when the statement is executed.
+    fn constant(&mut self, x: &ResolvedConstantProto) -> Scalar {
+        Scalar::Parameter(
+            x.constant.get().name.get().clone(),
+            x.parent.get().r#type.get().into(),
+        )
+    }
+
+    fn subquery_expr(
+        &mut self,
+        x: &ResolvedSubqueryExprProto,
+        outer: &mut Expr,
+    ) -> Result<Scalar, Error> {
         let parameters: Vec<Column> = x
             .parameter_list
             .iter()
             .map(|c| Column::reference(c.column.get()))
             .collect();
-        let subquery = self.any_resolved_scan(x.subquery.get());
+        let subquery = self.any_resolved_scan(x.subquery.get())?;
         let (join, scalar) = match x.subquery_type.get() {
             // Scalar
             0 => {
@@ -891,7 +3315,11 @@ impl Converter {
                 (join, scalar)
             }
             // AnyArray
-            1 => unimplemented!(),
+            1 => {
+                return Err(Error::NotSupported(
+                    "ARRAY subqueries are not supported".to_string(),
+                ))
+            }
             // Exists
             2 => {
                 let mark = Column::fresh("$exists", DataType::Bool);
@@ -905,7 +3333,7 @@ impl Converter {
                 let find = match x {
                     ResolvedSubqueryExprProto {
                         in_expr: Some(x), ..
-                    } => self.expr(x, outer),
+                    } => self.expr(x, outer)?,
                     other => panic!("{:?}", other),
                 };
                 let check = self.single_column(x.subquery.get());
@@ -919,7 +3347,7 @@ impl Converter {
         // Push join onto outer.
         *outer = self.create_dependent_join(parameters, join, subquery, outer.clone());
         // Return scalar that represents the entire query.
-        scalar
+        Ok(scalar)
     }
 
     fn create_dependent_join(
@@ -975,7 +3403,7 @@ impl Converter {
         //
         let rename_subquery_parameters: Vec<Column> = subquery_parameters
             .iter()
-            .map(|c| Column::fresh(&c.name, c.data_type))
+            .map(|c| Column::fresh(&c.name, c.data_type.clone()))
             .collect();
         let map_subquery_parameters: HashMap<Column, Column> = (0..subquery_parameters.len())
             .map(|i| {
@@ -1020,6 +3448,18 @@ impl Converter {
                 }
                 Join::Mark(mark.clone(), join_predicates)
             }
+            Join::Right(additional_predicates) => {
+                for p in additional_predicates {
+                    join_predicates.push(p.clone());
+                }
+                Join::Right(join_predicates)
+            }
+            Join::Inner(additional_predicates) => {
+                for p in additional_predicates {
+                    join_predicates.push(p.clone());
+                }
+                Join::Inner(join_predicates)
+            }
             _ => panic!("{}", join),
         };
         LogicalJoin {
@@ -1053,9 +3493,8 @@ fn parent(q: &AnyResolvedScanProto) -> &ResolvedScanProto {
         ResolvedTvfscanNode(q) => q.parent.get(),
         ResolvedRelationArgumentScanNode(q) => q.parent.get(),
         ResolvedAggregateScanBaseNode(q) => single_column_aggregate(q),
-        ResolvedRecursiveRefScanNode(_) | ResolvedRecursiveScanNode(_) => {
-            panic!("recursive queries not supported")
-        }
+        ResolvedRecursiveRefScanNode(q) => q.parent.get(),
+        ResolvedRecursiveScanNode(q) => q.parent.get(),
         ResolvedPivotScanNode(_) | ResolvedUnpivotScanNode(_) => panic!("PIVOT not supported"),
         ResolvedGroupRowsScanNode(_) => panic!("GROUP_ROWS not supported"),
     }
@@ -1068,25 +3507,443 @@ fn single_column_aggregate(q: &AnyResolvedAggregateScanBaseProto) -> &ResolvedSc
     }
 }
 
+// The maximum number of iterations of a recursive query, unless the statement sets the hint recursion_limit.
+const DEFAULT_RECURSION_LIMIT: usize = 500;
+
 fn function_name(name: &String) -> String {
     format!("${}", name.trim_start_matches("ZetaSQL:"))
 }
 
-fn literal(value: &ValueProto, data_type: &TypeProto) -> Value {
+/// Scan the visible rows of table, returning the scan, the requested columns and the $tid column.
+fn scan(table: &Table, columns: &Vec<(String, DataType)>) -> (Expr, Vec<Column>, Column) {
+    let columns: Vec<Column> = columns
+        .iter()
+        .map(|(name, data_type)| Column {
+            table: Some(table.clone()),
+            ..Column::fresh(name, data_type.clone())
+        })
+        .collect();
+    let xmin = Column::fresh("$xmin", DataType::I64);
+    let xmax = Column::fresh("$xmax", DataType::I64);
+    let tid = Column::fresh("$tid", DataType::I64);
+    let predicates = vec![
+        Scalar::Call(Box::new(F::LessOrEqual(
+            Scalar::Column(xmin.clone()),
+            Scalar::Call(Box::new(F::Xid)),
+        ))),
+        Scalar::Call(Box::new(F::Less(
+            Scalar::Call(Box::new(F::Xid)),
+            Scalar::Column(xmax.clone()),
+        ))),
+    ];
+    let mut projects = columns.clone();
+    projects.push(xmin);
+    projects.push(xmax);
+    projects.push(tid.clone());
+    let expr = LogicalGet {
+        projects,
+        predicates,
+        table: table.clone(),
+        sample: None,
+    };
+    (expr, columns, tid)
+}
+
+/// The names and types of the primary key columns of table, or nothing if it has no primary key.
+fn primary_key(user_table: &UserTable) -> Vec<(String, DataType)> {
+    user_table
+        .columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| (c.name.clone(), c.data_type.clone()))
+        .collect()
+}
+
+/// Find the columns that are written to each of names, or nothing if any of them is omitted.
+fn named_columns(names: &Vec<String>, columns: &Vec<(Column, String)>) -> Option<Vec<Column>> {
+    names
+        .iter()
+        .map(|name| {
+            columns
+                .iter()
+                .find(|(_, column_name)| column_name == name)
+                .map(|(c, _)| c.clone())
+        })
+        .collect()
+}
+
+/// Keep only the rows of input where none of columns is NULL.
+fn filter_not_null(input: Expr, columns: &Vec<Column>) -> Expr {
+    LogicalFilter {
+        predicates: columns
+            .iter()
+            .map(|c| {
+                Scalar::Call(Box::new(F::Not(Scalar::Call(Box::new(F::IsNull(
+                    Scalar::Column(c.clone()),
+                ))))))
+            })
+            .collect(),
+        input: Box::new(input),
+    }
+}
+
+/// Pair the names of columns in another table with the types of the matching columns in this table.
+fn typed_columns(names: &Vec<String>, columns: &Vec<Column>) -> Vec<(String, DataType)> {
+    names
+        .iter()
+        .zip(columns)
+        .map(|(name, c)| (name.clone(), c.data_type.clone()))
+        .collect()
+}
+
+fn equal_columns(left: &Vec<Column>, right: &Vec<Column>) -> Vec<Scalar> {
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| {
+            Scalar::Call(Box::new(F::Equal(
+                Scalar::Column(l.clone()),
+                Scalar::Column(r.clone()),
+            )))
+        })
+        .collect()
+}
+
+/// Analyze the SQL of a CHECK constraint of user_table, whose columns are matched by name.
+fn check_expression(
+    user_table: &UserTable,
+    constraint_name: &str,
+    sql: &str,
+    catalog: &SimpleCatalogProvider,
+) -> Result<Scalar, Error> {
+    let columns: Vec<(String, DataType)> = user_table
+        .columns
+        .iter()
+        .map(|c| (c.name.clone(), c.data_type.clone()))
+        .collect();
+    let check = crate::parser::analyze_expression(sql, &columns, catalog).map_err(|message| {
+        Error::Syntax(format!(
+            "Check constraint {} of table {} is invalid: {}",
+            constraint_name, user_table.name, message
+        ))
+    })?;
+    let mut converter = Converter {
+        catalog_id: catalog.id(),
+        catalog,
+        sql,
+        recursive: None,
+        recursion_limit: DEFAULT_RECURSION_LIMIT,
+        arguments: columns
+            .iter()
+            .map(|(name, data_type)| {
+                (
+                    name.to_lowercase(),
+                    Scalar::Column(Column::fresh(name, data_type.clone())),
+                )
+            })
+            .collect(),
+    };
+    let mut input = LogicalSingleGet;
+    let value = converter.expr(&check, &mut input)?;
+    Ok(inline_bindings(
+        value,
+        function_bindings(input).unwrap_or_default(),
+    ))
+}
+
+/// The range of the statement that x was parsed from, if ZetaSQL recorded it.
+fn parse_location(x: &AnyResolvedExprProto) -> Option<&ParseLocationRangeProto> {
+    let parent = match x.node.get() {
+        ResolvedLiteralNode(x) => x.parent.get(),
+        ResolvedParameterNode(x) => x.parent.get(),
+        ResolvedExpressionColumnNode(x) => x.parent.get(),
+        ResolvedColumnRefNode(x) => x.parent.get(),
+        ResolvedConstantNode(x) => x.parent.get(),
+        ResolvedFunctionCallBaseNode(x) => match x.node.get() {
+            ResolvedFunctionCallNode(x) => x.parent.get().parent.get(),
+            ResolvedNonScalarFunctionCallBaseNode(_) => return None,
+        },
+        // ZetaSQL doesn't record the location of the casts that it adds, like INT64 to DOUBLE.
+        ResolvedCastNode(x) => {
+            return x
+                .parent
+                .get()
+                .parent
+                .get()
+                .parse_location_range
+                .as_ref()
+                .or_else(|| parse_location(x.expr.get()))
+        }
+        ResolvedGetStructFieldNode(x) => x.parent.get(),
+        ResolvedGetJsonFieldNode(x) => x.parent.get(),
+        _ => return None,
+    };
+    parent.parent.get().parse_location_range.as_ref()
+}
+
+/// Count the rows produced by input, and fail with a constraint violation unless there are none.
+fn assert_empty(input: Expr, message: String) -> Expr {
+    assert_no_rows(input, Error::Constraint(message))
+}
+
+/// Count the rows produced by input, and fail with error unless there are none.
+fn assert_no_rows(input: Expr, error: Error) -> Expr {
+    let count = Column::fresh("$violations", DataType::I64);
+    let input = count_rows(input, vec![], count.clone());
+    LogicalCall {
+        procedure: Procedure::Assert(
+            Scalar::Call(Box::new(F::Equal(
+                Scalar::Column(count),
+                Scalar::Literal(Value::I64(Some(0))),
+            ))),
+            error,
+        ),
+        input: Box::new(input),
+    }
+}
+
+/// RENAME COLUMN is planned after the statement is analyzed, because ZetaSQL doesn't look up the table,
+/// so it isn't in the catalog that the statement was analyzed with.
+pub fn rename_column(
+    name: Name,
+    new_name: Name,
+    catalog: &SimpleCatalogProvider,
+) -> Result<Expr, Error> {
+    let table_path = &name.path[..name.path.len() - 1];
+    let old_column = name.path.last().unwrap();
+    let new_column = new_name.path.last().unwrap();
+    let user_table = match catalog.table_by_path(table_path) {
+        Some(user_table) if user_table.view.is_none() => user_table,
+        _ => {
+            return Err(Error::Syntax(format!(
+                "Table {} does not exist",
+                table_path.join(".")
+            )))
+        }
+    };
+    // Indexes, CHECK constraints and the distribution key are planned using the names of the columns.
+    let used_by_index = catalog
+        .indexes()
+        .iter()
+        .any(|index| index.table_id == user_table.id && index.columns.contains(old_column));
+    if used_by_index {
+        return Err(Error::NotSupported(format!(
+            "Column {} is used by an index",
+            old_column
+        )));
+    }
+    for (constraint_name, sql) in &user_table.checks {
+        let check = check_expression(user_table, constraint_name, sql, catalog)?;
+        if check.references().iter().any(|c| &c.name == old_column) {
+            return Err(Error::NotSupported(format!(
+                "Column {} is used by check constraint {}",
+                old_column, constraint_name
+            )));
+        }
+    }
+    if user_table.distribution_key.as_ref() == Some(old_column) {
+        return Err(Error::NotSupported(format!(
+            "Column {} is the distribution key of table {}",
+            old_column, user_table.name
+        )));
+    }
+    // The rows are stored by column name, so every row gets a new version with the column renamed.
+    let table = Table {
+        id: user_table.id,
+        name: user_table.name.clone(),
+    };
+    let columns: Vec<(String, DataType)> = user_table
+        .columns
+        .iter()
+        .map(|c| (c.name.clone(), c.data_type.clone()))
+        .collect();
+    let (input, columns, tid) = scan(&table, &columns);
+    let values = columns
+        .into_iter()
+        .map(|c| {
+            let name = if &c.name == old_column {
+                new_column.clone()
+            } else {
+                c.name.clone()
+            };
+            (Scalar::Column(c), name)
+        })
+        .collect();
+    Ok(LogicalScript {
+        stmts: vec![
+            LogicalRename {
+                object: ObjectType::Column,
+                name,
+                new_name,
+            },
+            rewrite_rows(table, input, tid, values),
+        ],
+    })
+}
+
+/// Replace every row of table with a new version, where values are the new columns of each row
+/// computed from the columns of input, so that a change to the schema of the table is versioned like any other write.
+fn rewrite_rows(table: Table, input: Expr, tid: Column, values: Vec<(Scalar, String)>) -> Expr {
+    let mut projects = vec![];
+    let mut columns = vec![];
+    for (value, name) in values {
+        let column = Column::fresh(&name, value.data_type());
+        projects.push((value, column.clone()));
+        columns.push((column, name));
+    }
+    projects.push((Scalar::Column(tid.clone()), tid.clone()));
+    LogicalUpdate {
+        table,
+        tid,
+        input: Box::new(LogicalMap {
+            include_existing: false,
+            projects,
+            input: Box::new(input),
+        }),
+        columns,
+    }
+}
+
+/// Assert that no two rows of input have the same values of keys.
+fn assert_at_most_once(input: Expr, keys: Vec<Column>, error: Error) -> Expr {
+    let count = Column::fresh("$count", DataType::I64);
+    let input = LogicalFilter {
+        predicates: vec![Scalar::Call(Box::new(F::Greater(
+            Scalar::Column(count.clone()),
+            Scalar::Literal(Value::I64(Some(1))),
+        )))],
+        input: Box::new(count_rows(input, keys, count)),
+    };
+    assert_no_rows(input, error)
+}
+
+/// Count the rows of input with each value of group_by, or all the rows of input if group_by is empty.
+fn count_rows(input: Expr, group_by: Vec<Column>, count: Column) -> Expr {
+    let one = Column::fresh("$one", DataType::I64);
+    LogicalAggregate {
+        group_by,
+        aggregate: vec![AggregateExpr::new(
+            AggregateFunction::Count,
+            one.clone(),
+            count,
+        )],
+        input: Box::new(LogicalMap {
+            include_existing: true,
+            projects: vec![(Scalar::Literal(Value::I64(Some(1))), one)],
+            input: Box::new(input),
+        }),
+    }
+}
+
+/// Bind the arguments of a call to a user-defined function that aren't columns or literals to fresh columns,
+/// so they are computed once, even if the body of the function uses them more than once.
+fn bind_arguments(
+    parameters: &Vec<(String, DataType)>,
+    arguments: Vec<Scalar>,
+) -> (Vec<(Scalar, Column)>, Vec<Scalar>) {
+    let mut bindings = vec![];
+    let arguments = parameters
+        .iter()
+        .zip(arguments)
+        .map(|((name, _), argument)| match argument {
+            Scalar::Literal(_) | Scalar::Column(_) | Scalar::Parameter(_, _) => argument,
+            argument => {
+                let column = Column::fresh(name, argument.data_type());
+                bindings.push((argument, column.clone()));
+                Scalar::Column(column)
+            }
+        })
+        .collect();
+    (bindings, arguments)
+}
+
+/// The bindings of function arguments that converting an expression added to an input that started as LogicalSingleGet,
+/// or None if it added a subquery.
+fn function_bindings(input: Expr) -> Option<Vec<(Scalar, Column)>> {
+    match input {
+        LogicalSingleGet => Some(vec![]),
+        LogicalMap {
+            include_existing: true,
+            projects,
+            input,
+        } => {
+            let mut bindings = function_bindings(*input)?;
+            bindings.extend(projects);
+            Some(bindings)
+        }
+        _ => None,
+    }
+}
+
+/// Substitute the bindings of function arguments back into an expression that is evaluated without an input row, like a DEFAULT.
+fn inline_bindings(value: Scalar, bindings: Vec<(Scalar, Column)>) -> Scalar {
+    // Later bindings can read earlier ones, so they are substituted first.
+    bindings
+        .iter()
+        .rev()
+        .fold(value, |value, (expr, column)| value.inline(expr, column))
+}
+
+/// Give fresh IDs to the columns of an expression that ZetaSQL analyzed separately from the enclosing query.
+fn fresh_ids(expr: &mut Expr) -> HashMap<Column, Column> {
+    let mut fresh = HashMap::new();
+    for expr in expr.pre_order() {
+        for c in expr.attributes() {
+            if c.id > 0 && !fresh.contains_key(&c) {
+                let f = Column::fresh(&c.name, c.data_type.clone());
+                fresh.insert(c, f);
+            }
+        }
+    }
+    expr.subst(&fresh);
+    fresh
+}
+
+/// Copy an expression that reads a temp table, renaming its columns so the copy can appear in the same plan.
+fn fresh_copy(expr: &Expr) -> (Expr, HashMap<Column, Column>) {
+    let rename: HashMap<Column, Column> = expr
+        .attributes()
+        .iter()
+        .map(|c| (c.clone(), Column::fresh(&c.name, c.data_type.clone())))
+        .collect();
+    let mut expr = expr.clone();
+    expr.subst(&rename);
+    (expr, rename)
+}
+
+fn literal(value: &ValueProto, data_type: &TypeProto) -> Result<Value, Error> {
     let value = match value {
         ValueProto { value: Some(value) } => value,
-        _ => return Value::null(DataType::from(data_type)),
+        _ => return Ok(Value::null(DataType::from(data_type))),
     };
-    match value {
+    let value = match value {
         Int64Value(x) => Value::I64(Some(*x)),
         BoolValue(x) => Value::Bool(Some(*x)),
         DoubleValue(x) => Value::F64(Some(*x)),
         DateValue(x) => Value::Date(Some(*x)),
         TimestampValue(x) => Value::Timestamp(Some(microseconds_since_epoch(x))),
         StringValue(x) => Value::String(Some(x.clone())),
+        BytesValue(x) => Value::Bytes(Some(x.clone())),
+        JsonValue(x) => Value::Json(Some(normalize_json(x)?)),
         EnumValue(i) => Value::EnumValue(*i),
-        other => panic!("{:?}", other),
-    }
+        ArrayValue(x) => {
+            let element_type = data_type.array_type.get().element_type.get();
+            let elements = x
+                .element
+                .iter()
+                .map(|element| literal(element, element_type))
+                .collect::<Result<Vec<_>, _>>()?;
+            Value::Array(DataType::from(element_type.as_ref()), Some(elements))
+        }
+        other => return not_supported(other),
+    };
+    Ok(value)
+}
+
+/// An error for a node that ZetaSQL accepts but we can't plan yet, named after its variant.
+fn not_supported<T>(node: &impl std::fmt::Debug) -> Result<T, Error> {
+    let debug = format!("{:?}", node);
+    let name = debug.split(&['(', ' ', '{'][..]).next().unwrap_or(&debug);
+    Err(Error::NotSupported(format!("{} is not supported", name)))
 }
 
 fn microseconds_since_epoch(time: &prost_types::Timestamp) -> i64 {
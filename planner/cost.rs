@@ -25,15 +25,22 @@ pub(crate) fn physical_cost(mid: MultiExprID, ss: &SearchSpace) -> Cost {
         | Out { .. }
         | Limit { .. }
         | Union { .. }
+        | Iterate { .. }
         | Values { .. }
         | Script { .. }
         | Call { .. }
         | Explain { .. } => 0.0,
-        SeqScan { table, .. } => {
+        SeqScan { table, sample, .. } => {
             let n = statistics::approx_cardinality(table.id);
-            n * SEQ_SCAN
+            match sample {
+                // SYSTEM sampling skips the pages it doesn't keep.
+                Some(sample) if sample.method == SampleMethod::System => {
+                    n * sample.fraction() * SEQ_SCAN
+                }
+                _ => n * SEQ_SCAN,
+            }
         }
-        IndexScan { .. } => {
+        IndexScan { .. } | IndexOrderScan { .. } => {
             let n = ss[parent].props.cardinality;
             n * INDEX_SCAN
         }
@@ -77,7 +84,7 @@ pub(crate) fn physical_cost(mid: MultiExprID, ss: &SearchSpace) -> Cost {
             let n = ss[leaf(input)].props.cardinality;
             n * MAP
         }
-        GroupByAggregate { input, .. } => {
+        GroupByAggregate { input, .. } | PartialAggregate { input, .. } => {
             let n = ss[leaf(input)].props.cardinality;
             n * HASH_BUILD
         }
@@ -109,6 +116,7 @@ pub(crate) fn physical_cost(mid: MultiExprID, ss: &SearchSpace) -> Cost {
         | LogicalWith { .. }
         | LogicalCreateTempTable { .. }
         | LogicalUnion { .. }
+        | LogicalIterate { .. }
         | LogicalFilter { .. }
         | LogicalOut { .. }
         | LogicalMap { .. }
@@ -120,11 +128,19 @@ pub(crate) fn physical_cost(mid: MultiExprID, ss: &SearchSpace) -> Cost {
         | LogicalUpdate { .. }
         | LogicalDelete { .. }
         | LogicalGet { .. }
+        | LogicalIndexGet { .. }
         | LogicalGetWith { .. }
         | LogicalCreateDatabase { .. }
         | LogicalCreateTable { .. }
+        | LogicalCreateTableAs { .. }
         | LogicalCreateIndex { .. }
+        | LogicalCreateSequence { .. }
         | LogicalDrop { .. }
+        | LogicalAddColumn { .. }
+        | LogicalRename { .. }
+        | LogicalCreateView { .. }
+        | LogicalCreateFunction { .. }
+        | LogicalRefresh { .. }
         | LogicalRewrite { .. }
         | LogicalScript { .. }
         | LogicalCall { .. }
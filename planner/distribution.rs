@@ -1,5 +1,106 @@
 use ast::*;
 
+/// Split each aggregation into a partial aggregation that runs on every worker, below the Gather or Exchange,
+/// and a final aggregation that combines the partial results, above it.
+pub fn split_aggregates(expr: &mut Expr) {
+    match expr {
+        SimpleAggregate { aggregate, input } if can_split(aggregate) => {
+            if let Gather { input, .. } = input.as_mut() {
+                let (partial, finals) = split(aggregate);
+                let inner = std::mem::take(input.as_mut());
+                **input = PartialAggregate {
+                    group_by: vec![],
+                    aggregate: partial,
+                    input: Box::new(inner),
+                };
+                *aggregate = finals;
+            }
+        }
+        GroupByAggregate {
+            partition_by,
+            group_by,
+            aggregate,
+            input,
+        } if can_split(aggregate) => {
+            if let Exchange { input, .. } = input.as_mut() {
+                let (partial, finals) = split(aggregate);
+                // The partial aggregation passes through the hash column, which is determined by the group-by columns.
+                let mut partial_group_by = group_by.clone();
+                partial_group_by.push(partition_by.clone());
+                let inner = std::mem::take(input.as_mut());
+                **input = PartialAggregate {
+                    group_by: partial_group_by,
+                    aggregate: partial,
+                    input: Box::new(inner),
+                };
+                *aggregate = finals;
+            }
+        }
+        _ => {}
+    }
+    for i in 0..expr.len() {
+        split_aggregates(&mut expr[i])
+    }
+}
+
+fn can_split(aggregate: &Vec<AggregateExpr>) -> bool {
+    !aggregate.is_empty()
+        && aggregate.iter().all(|a| {
+            // DISTINCT depends on seeing every input value in one place, unless the function ignores duplicates.
+            let distinct = !a.distinct || a.function.ignores_duplicates();
+            // IGNORE NULLS skips rows before HAVING MAX/MIN compares them, which the partial best value can't see.
+            // COUNT HAVING MAX/MIN is 0 when no row qualifies, but the final SUM of no partial counts is NULL.
+            let having =
+                a.having.is_none() || (!a.ignore_nulls && a.function != AggregateFunction::Count);
+            // ORDER BY and LIMIT depend on seeing every input value in order.
+            distinct && having && a.order_by.is_empty() && a.limit.is_none()
+        })
+}
+
+fn split(aggregate: &Vec<AggregateExpr>) -> (Vec<AggregateExpr>, Vec<AggregateExpr>) {
+    let mut partials = vec![];
+    let mut finals = vec![];
+    for a in aggregate {
+        let (partial_function, final_function) = a.function.split();
+        let partial_output = Column::fresh(
+            &format!("{}$partial", a.output.name),
+            partial_function.data_type(a.input.data_type.clone()),
+        );
+        partials.push(AggregateExpr {
+            ignore_nulls: a.ignore_nulls,
+            having: a.having.clone(),
+            ..AggregateExpr::new(partial_function, a.input.clone(), partial_output.clone())
+        });
+        // Each worker also reports the best value of the HAVING column it saw,
+        // and the final phase only combines the partial results of the workers that saw the overall best.
+        let having = match &a.having {
+            Some(having) => {
+                let column = having.column();
+                let best = Column::fresh(
+                    &format!("{}$partial", column.name),
+                    column.data_type.clone(),
+                );
+                let (function, modifier) = match having {
+                    HavingModifier::Max(_) => {
+                        (AggregateFunction::Max, HavingModifier::Max(best.clone()))
+                    }
+                    HavingModifier::Min(_) => {
+                        (AggregateFunction::Min, HavingModifier::Min(best.clone()))
+                    }
+                };
+                partials.push(AggregateExpr::new(function, column.clone(), best));
+                Some(modifier)
+            }
+            None => None,
+        };
+        finals.push(AggregateExpr {
+            having,
+            ..AggregateExpr::new(final_function, partial_output, a.output.clone())
+        });
+    }
+    (partials, finals)
+}
+
 pub fn set_hash_columns(expr: &mut Expr) {
     fn top_down_rewrite(expr: &mut Expr, column: Option<Column>) {
         match expr {
@@ -16,6 +117,13 @@ pub fn set_hash_columns(expr: &mut Expr) {
             } => {
                 top_down_rewrite(input, Some(partition_by.clone()));
             }
+            Insert {
+                partition_by: Some(partition_by),
+                input,
+                ..
+            } => {
+                top_down_rewrite(input, Some(partition_by.clone()));
+            }
             HashJoin {
                 broadcast: false,
                 partition_left,
@@ -39,7 +147,11 @@ pub fn set_hash_columns(expr: &mut Expr) {
 
 pub fn set_stages(expr: &mut Expr) {
     fn top_down_rewrite(expr: &mut Expr, next_stage: &mut i32) {
-        if let Broadcast { stage, .. } | Exchange { stage, .. } | Gather { stage, .. } = expr {
+        if let Broadcast { stage, .. }
+        | Exchange { stage, .. }
+        | Gather { stage, .. }
+        | Iterate { stage, .. } = expr
+        {
             *stage = Some(*next_stage);
             *next_stage += 1;
         }
@@ -2,9 +2,9 @@
 
 use std::{collections::HashMap, sync::Mutex};
 
-use ast::Expr;
+use ast::{Expr, Name, ObjectType};
 use catalog::SimpleCatalogProvider;
-use kernel::DataType;
+use kernel::{DataType, Error};
 use once_cell::sync::Lazy;
 
 mod bootstrap;
@@ -14,26 +14,160 @@ mod convert;
 mod cost;
 mod distribution;
 mod optimize;
+mod options;
+#[cfg(test)]
+mod options_tests;
 mod parser;
 #[cfg(test)]
 mod parser_tests;
 mod rewrite;
+#[cfg(test)]
+mod rewrite_tests;
 mod rule;
+mod script;
+#[cfg(test)]
+mod script_tests;
 mod search_space;
 mod unnest;
 
+pub use catalog::TempTable;
+pub use script::Statement;
+
+/// Plan a statement, where params are the types of the query parameters, like @x,
+/// and variables are the types of the variables of the enclosing script, which are referenced by name, like x.
 pub fn plan(
     sql: String,
     params: HashMap<String, DataType>,
+    variables: &HashMap<String, DataType>,
     catalog_id: i64,
+    session: Option<i64>,
     txn: i64,
-) -> Result<Expr, String> {
+) -> Result<Expr, Error> {
     // Calling ZetaSQL is expensive so we cache it.
     let table_names = cached_table_names(&sql);
     // This step is not cached because the catalog changes when a DDL statement is executed.
-    let catalog = crate::catalog::simple_catalog(table_names, catalog_id, txn);
+    let catalog =
+        crate::catalog::simple_catalog(table_names.clone(), catalog_id, session, false, txn)
+            .declare(variables);
     // Calling ZetaSQL and optimizing the expression is expensive so we cache it.
-    cached_analyze_optimize(sql, params, catalog)
+    let expr = match cached_analyze_optimize(sql.clone(), params.clone(), catalog) {
+        // Looking up functions and sequences costs a query, so we only do it for statements that use them.
+        Err(error) if crate::catalog::needs_functions(&error) => {
+            let catalog =
+                crate::catalog::simple_catalog(table_names, catalog_id, session, true, txn)
+                    .declare(variables);
+            cached_analyze_optimize(sql, params, catalog)?
+        }
+        result => result?,
+    };
+    match expr {
+        // The definition of a materialized view is not part of the catalog, so we look it up and plan the refresh separately.
+        Expr::LogicalRefresh { name } => {
+            crate::catalog::refresh_materialized_view(&name, session, txn)
+        }
+        // ZetaSQL doesn't look up the table of RENAME COLUMN, so we look it up and plan the rewrite of its rows separately.
+        Expr::LogicalRename {
+            object: ObjectType::Column,
+            name,
+            new_name,
+        } => {
+            let table_path = name.path[..name.path.len() - 1].to_vec();
+            let catalog =
+                crate::catalog::simple_catalog(vec![table_path], catalog_id, session, true, txn);
+            let expr = crate::convert::rename_column(name, new_name, &catalog)?;
+            Ok(crate::optimize::optimize(expr, catalog.indexes()))
+        }
+        // Temp tables belong to the session, so they are dropped outside the metadata tables.
+        // CREATE TEMP TABLE is returned as is, and the coordinator creates the table with create_temp_table.
+        Expr::LogicalDrop {
+            object: ObjectType::Table,
+            name,
+        } => match crate::catalog::drop_temp_table(session, &name) {
+            Some(expr) => Ok(expr),
+            None => Ok(crate::optimize::optimize(
+                Expr::LogicalDrop {
+                    object: ObjectType::Table,
+                    name,
+                },
+                vec![],
+            )),
+        },
+        expr => Ok(expr),
+    }
+}
+
+/// Split a request into the statements of a script, which is cached because the coordinator does it for every request.
+/// Scripts that fail to parse aren't cached, and the cache is emptied when it fills up.
+pub fn parse_script(sql: &str) -> Result<Vec<Statement>, Error> {
+    // TODO this should be an LRU cache.
+    static SCRIPT_CACHE: Lazy<Mutex<HashMap<String, Vec<Statement>>>> = Lazy::new(Default::default);
+    const SCRIPT_CACHE_SIZE: usize = 1000;
+    if let Some(script) = SCRIPT_CACHE.lock().unwrap().get(sql) {
+        return Ok(script.clone());
+    }
+    let script = crate::script::parse_script(sql)?;
+    // ZetaSQL's grammar decides which scripts are valid, but it doesn't know about sequences.
+    if !script.iter().all(Statement::is_sql) && !script.iter().any(Statement::has_sequences) {
+        crate::parser::check_script(sql)?;
+    }
+    let mut cache = SCRIPT_CACHE.lock().unwrap();
+    if cache.len() >= SCRIPT_CACHE_SIZE {
+        cache.clear();
+    }
+    cache.insert(sql.to_string(), script.clone());
+    Ok(script)
+}
+
+/// Plan CREATE TEMP TABLE, returning a plan that creates the heap of the table and returns its table id,
+/// and the table, which register_temp_table registers for the session once the plan has run.
+pub fn create_temp_table(session: Option<i64>, expr: Expr) -> Result<(Expr, TempTable), Error> {
+    match expr {
+        Expr::LogicalCreateTable {
+            name,
+            columns,
+            constraints,
+            temp: true,
+            options,
+        } => crate::catalog::create_temp_table(session, &name, columns, constraints, options)
+            .map_err(Error::Syntax),
+        other => panic!("{}", other.name()),
+    }
+}
+
+pub fn register_temp_table(session: i64, table: TempTable, table_id: i64) -> Result<(), Error> {
+    crate::catalog::register_temp_table(session, table, table_id).map_err(Error::Syntax)
+}
+
+/// Forget the temp tables of a session that has ended, returning a plan that truncates them.
+pub fn end_session(session: i64) -> Option<Expr> {
+    crate::catalog::end_session(session)
+}
+
+/// Plan a statement of a script that ZetaSQL can't analyze, such as CREATE SEQUENCE.
+pub fn plan_statement(statement: &Statement, catalog_id: i64) -> Expr {
+    let name = |name: &String| Name {
+        catalog_id,
+        path: vec![name.clone()],
+    };
+    let expr = match statement {
+        Statement::CreateSequence {
+            name: sequence_name,
+            start,
+            increment,
+        } => Expr::LogicalCreateSequence {
+            name: name(sequence_name),
+            start: *start,
+            increment: *increment,
+        },
+        Statement::DropSequence {
+            name: sequence_name,
+        } => Expr::LogicalDrop {
+            object: ObjectType::Sequence,
+            name: name(sequence_name),
+        },
+        other => panic!("{:?} should be run by the script", other),
+    };
+    crate::optimize::optimize(expr, vec![])
 }
 
 fn cached_table_names(sql: &str) -> Vec<Vec<String>> {
@@ -52,9 +186,9 @@ fn cached_analyze_optimize(
     sql: String,
     params: HashMap<String, DataType>,
     catalog: SimpleCatalogProvider,
-) -> Result<Expr, String> {
+) -> Result<Expr, Error> {
     // TODO this should be an LRU cache.
-    static ANALYZE_CACHE: Lazy<Mutex<HashMap<Key, Result<Expr, String>>>> =
+    static ANALYZE_CACHE: Lazy<Mutex<HashMap<Key, Result<Expr, Error>>>> =
         Lazy::new(Default::default);
     let mut key = Key {
         sql,
@@ -71,7 +205,21 @@ fn cached_analyze_optimize(
         .entry(key)
         .or_insert_with_key(|key| {
             let expr = crate::parser::analyze(&key.sql, &params, &key.catalog)?;
-            Ok(crate::optimize::optimize(expr, key.catalog.indexes()))
+            crate::options::check_options(&expr).map_err(Error::Syntax)?;
+            match expr {
+                expr @ Expr::LogicalRefresh { .. } => Ok(expr),
+                expr @ Expr::LogicalCreateTableAs { .. } => Ok(expr),
+                expr @ Expr::LogicalCreateTable { temp: true, .. } => Ok(expr),
+                expr @ Expr::LogicalRename {
+                    object: ObjectType::Column,
+                    ..
+                } => Ok(expr),
+                expr @ Expr::LogicalDrop {
+                    object: ObjectType::Table,
+                    ..
+                } => Ok(expr),
+                expr => Ok(crate::optimize::optimize(expr, key.catalog.indexes())),
+            }
         })
         .clone()
 }
@@ -7,7 +7,9 @@ const TRACE: bool = false;
 #[log::trace]
 pub fn optimize(expr: Expr, indexes: Vec<Index>) -> Expr {
     let expr = crate::rewrite::rewrite_plan(expr);
+    let expr = crate::rewrite::rewrite_index_order(expr, &indexes);
     let mut expr = search_for_best_plan(expr, indexes);
+    crate::distribution::split_aggregates(&mut expr);
     crate::distribution::set_hash_columns(&mut expr);
     crate::distribution::set_stages(&mut expr);
     expr
@@ -0,0 +1,127 @@
+use ast::*;
+
+// The largest page_size, which is the number of rows in a page of storage.
+const MAX_PAGE_SIZE: i64 = 1024;
+
+/// ZetaSQL accepts any name with any constant value in OPTIONS(...), so check that the options of CREATE TABLE and CREATE INDEX are ones we support.
+pub fn check_options(expr: &Expr) -> Result<(), String> {
+    match expr {
+        Expr::LogicalCreateTable {
+            name,
+            columns,
+            options,
+            ..
+        } => {
+            let table_name = name.path.last().unwrap();
+            for (option_name, value) in options {
+                match (option_name.as_str(), value) {
+                    ("description", Scalar::Literal(Value::String(Some(_)))) => {}
+                    ("page_size", Scalar::Literal(Value::I64(Some(page_size)))) => {
+                        if *page_size < 1 || *page_size > MAX_PAGE_SIZE {
+                            return Err(format!(
+                                "Option page_size of table {} must be between 1 and {}",
+                                table_name, MAX_PAGE_SIZE
+                            ));
+                        }
+                    }
+                    ("distribution_key", Scalar::Literal(Value::String(Some(column_name)))) => {
+                        if !columns.iter().any(|(name, _)| name == column_name) {
+                            return Err(format!(
+                                "Distribution key {} is not a column of table {}",
+                                column_name, table_name
+                            ));
+                        }
+                    }
+                    ("description", _) | ("distribution_key", _) => {
+                        return Err(format!(
+                            "Option {} of table {} must be a STRING literal",
+                            option_name, table_name
+                        ))
+                    }
+                    ("page_size", _) => {
+                        return Err(format!(
+                            "Option page_size of table {} must be an INT64 literal",
+                            table_name
+                        ))
+                    }
+                    // Pages hold uncompressed columns that scans read in place, so there is no codec to choose.
+                    ("compression", _) => {
+                        return Err(format!(
+                            "Option compression of table {} is not supported, because pages are not compressed",
+                            table_name
+                        ))
+                    }
+                    _ => {
+                        return Err(format!(
+                            "CREATE TABLE does not support option {}",
+                            option_name
+                        ))
+                    }
+                }
+            }
+            check_duplicates(options)
+        }
+        Expr::LogicalCreateIndex { name, options, .. } => {
+            // Indexes are radix trees with no pages, so there is no fill setting to apply.
+            let index_name = name.path.last().unwrap();
+            for (option_name, value) in options {
+                match (option_name.as_str(), value) {
+                    ("description", Scalar::Literal(Value::String(Some(_)))) => {}
+                    ("description", _) => {
+                        return Err(format!(
+                            "Option description of index {} must be a STRING literal",
+                            index_name
+                        ))
+                    }
+                    _ => {
+                        return Err(format!(
+                            "CREATE INDEX does not support option {}",
+                            option_name
+                        ))
+                    }
+                }
+            }
+            check_duplicates(options)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_duplicates(options: &Vec<(String, Scalar)>) -> Result<(), String> {
+    for (i, (option_name, _)) in options.iter().enumerate() {
+        if options[..i].iter().any(|(name, _)| name == option_name) {
+            return Err(format!(
+                "Option {} is specified more than once",
+                option_name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The value of an option as it is stored in the metadata catalog.
+/// Options have already been checked, so the value is a non-null literal.
+pub fn option_value(value: &Scalar) -> String {
+    match value {
+        Scalar::Literal(Value::String(Some(value))) => value.clone(),
+        Scalar::Literal(Value::I64(Some(value))) => value.to_string(),
+        other => panic!("{} is not a valid option value", other),
+    }
+}
+
+/// The number of rows in each page of the heap of a table.
+pub fn page_size(options: &Vec<(String, Scalar)>) -> i64 {
+    options
+        .iter()
+        .find(|(name, _)| name == "page_size")
+        .map(|(_, value)| option_value(value).parse().unwrap())
+        .unwrap_or(MAX_PAGE_SIZE)
+}
+
+/// The column that decides which worker each row of a table is stored on.
+pub fn distribution_key(options: &Vec<(String, Scalar)>) -> Option<String> {
+    options
+        .iter()
+        .find(|(name, _)| name == "distribution_key")
+        .map(|(_, value)| option_value(value))
+}
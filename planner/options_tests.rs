@@ -0,0 +1,90 @@
+use ast::*;
+use kernel::DataType;
+
+use crate::options::*;
+
+fn create_table(options: Vec<(&str, Value)>) -> Expr {
+    Expr::LogicalCreateTable {
+        name: Name {
+            catalog_id: 100,
+            path: vec!["t".to_string()],
+        },
+        columns: vec![("id".to_string(), DataType::I64)],
+        constraints: vec![],
+        temp: false,
+        options: options
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), Scalar::Literal(value)))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_table_options() {
+    let options = vec![
+        ("description", Value::String(Some("orders".to_string()))),
+        ("page_size", Value::I64(Some(256))),
+        ("distribution_key", Value::String(Some("id".to_string()))),
+    ];
+    assert_eq!(Ok(()), check_options(&create_table(options)));
+    assert_eq!(
+        Err(
+            "Option compression of table t is not supported, because pages are not compressed"
+                .to_string()
+        ),
+        check_options(&create_table(vec![(
+            "compression",
+            Value::String(Some("zstd".to_string()))
+        )]))
+    );
+    assert_eq!(
+        Err("CREATE TABLE does not support option fill_factor".to_string()),
+        check_options(&create_table(vec![("fill_factor", Value::I64(Some(90)))]))
+    );
+    assert!(check_options(&create_table(vec![("page_size", Value::I64(Some(4096)))])).is_err());
+    assert!(check_options(&create_table(vec![("page_size", Value::I64(None))])).is_err());
+    assert!(check_options(&create_table(vec![(
+        "distribution_key",
+        Value::String(Some("missing".to_string()))
+    )]))
+    .is_err());
+}
+
+fn create_index(options: Vec<(&str, Value)>) -> Expr {
+    Expr::LogicalCreateIndex {
+        name: Name {
+            catalog_id: 100,
+            path: vec!["t_id".to_string()],
+        },
+        table: Table {
+            id: 100,
+            name: "t".to_string(),
+        },
+        columns: vec!["id".to_string()],
+        descending: vec![false],
+        options: options
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), Scalar::Literal(value)))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_index_options() {
+    let options = vec![("description", Value::String(Some("by id".to_string())))];
+    assert_eq!(Ok(()), check_options(&create_index(options)));
+    assert_eq!(
+        Err("CREATE INDEX does not support option fill_factor".to_string()),
+        check_options(&create_index(vec![("fill_factor", Value::I64(Some(90)))]))
+    );
+}
+
+#[test]
+fn test_page_size() {
+    assert_eq!(1024, page_size(&vec![]));
+    let options = vec![(
+        "page_size".to_string(),
+        Scalar::Literal(Value::I64(Some(64))),
+    )];
+    assert_eq!(64, page_size(&options));
+}
@@ -1,13 +1,17 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, panic::AssertUnwindSafe};
 
 use ast::Expr;
 use defaults::{enabled_language_features, supported_statement_kinds};
-use kernel::DataType;
-use tonic::{transport::Channel, Request};
+use kernel::{panic_message, DataType, Error};
+use tonic::{transport::Channel, Code, Request, Status};
 use zetasql::{
-    analyze_request::Target::ParseResumeLocation,
+    analyze_request::Target::{ParseResumeLocation, SqlExpression},
     analyze_response::Result::{ResolvedExpression, ResolvedStatement},
-    analyzer_options_proto::QueryParameterProto,
+    analyzer_options_proto::{
+        parse_location_options_proto::FunctionCallRecordType, ParseLocationOptionsProto,
+        QueryParameterProto,
+    },
+    build_sql_request,
     zeta_sql_local_service_client::ZetaSqlLocalServiceClient,
     *,
 };
@@ -65,7 +69,7 @@ pub fn analyze(
     sql: &str,
     params: &HashMap<String, DataType>,
     catalog: &SimpleCatalogProvider,
-) -> Result<Expr, String> {
+) -> Result<Expr, Error> {
     // Parse each statement in the script, one at a time, in a loop.
     let simple_catalog = catalog.to_proto();
     let mut offset = 0;
@@ -80,7 +84,66 @@ pub fn analyze(
             break;
         }
     }
-    Ok(crate::convert::convert(&stmts, catalog.id()))
+    // The converter reports statements that it can't plan as errors, so a panic is a bug that we report instead of crashing the coordinator.
+    std::panic::catch_unwind(AssertUnwindSafe(|| {
+        crate::convert::convert(&stmts, sql, catalog)
+    }))
+    .map_err(|payload| Error::Internal(panic_message(payload)))?
+}
+
+/// Analyze the body of a user-defined function, where the arguments are in scope as columns.
+#[log::trace]
+pub fn analyze_expression(
+    sql: &str,
+    columns: &Vec<(String, DataType)>,
+    catalog: &SimpleCatalogProvider,
+) -> Result<AnyResolvedExprProto, String> {
+    let request = AnalyzeRequest {
+        simple_catalog: Some(catalog.to_proto()),
+        options: Some(AnalyzerOptionsProto {
+            default_timezone: Some("UTC".to_string()),
+            language_options: Some(language_options()),
+            // The output columns of a table-valued function body are not referenced by the expression that wraps it.
+            prune_unused_columns: Some(false),
+            expression_columns: columns
+                .iter()
+                .map(|(name, data_type)| QueryParameterProto {
+                    name: Some(name.clone()),
+                    r#type: Some(data_type.to_proto()),
+                })
+                .collect(),
+            ..Default::default()
+        }),
+        target: Some(SqlExpression(sql.to_string())),
+        ..Default::default()
+    };
+    let response = match log::rpc(async move { parser().await.analyze(request).await }) {
+        Ok(response) => response.into_inner(),
+        Err(status) => return Err(status.message().to_string()),
+    };
+    match response.result.unwrap() {
+        ResolvedExpression(expr) => Ok(expr),
+        ResolvedStatement(_) => {
+            panic!("expected expression but found statement")
+        }
+    }
+}
+
+/// Turn a resolved statement back into SQL, for statements that have to be planned in several steps.
+#[log::trace]
+pub fn build_sql(
+    stmt: AnyResolvedStatementProto,
+    catalog: &SimpleCatalogProvider,
+) -> Result<String, String> {
+    let request = BuildSqlRequest {
+        simple_catalog: Some(catalog.to_proto()),
+        target: Some(build_sql_request::Target::ResolvedStatement(stmt)),
+        ..Default::default()
+    };
+    match log::rpc(async move { parser().await.build_sql(request).await }) {
+        Ok(response) => Ok(response.into_inner().sql.unwrap()),
+        Err(status) => Err(status.message().to_string()),
+    }
 }
 
 #[log::trace]
@@ -105,19 +168,43 @@ pub fn extract_table_names_from_stmt(sql: &str) -> Vec<Vec<String>> {
     })
 }
 
+/// Check the syntax of a script with ZetaSQL's parser, which knows the grammar of scripts even though it can't analyze them.
+#[log::trace]
+pub fn check_script(sql: &str) -> Result<(), Error> {
+    let request = ExtractTableNamesFromStatementRequest {
+        sql_statement: Some(sql.to_string()),
+        allow_script: Some(true),
+        options: Some(language_options()),
+    };
+    match log::rpc(async move {
+        parser()
+            .await
+            .extract_table_names_from_statement(request)
+            .await
+    }) {
+        Ok(_) => Ok(()),
+        Err(status) => Err(analyzer_error(status)),
+    }
+}
+
 #[log::trace]
 fn analyze_next_statement(
     sql: &str,
     offset: i32,
     params: &HashMap<String, DataType>,
     simple_catalog: &SimpleCatalogProto,
-) -> Result<(AnyResolvedStatementProto, i32), String> {
+) -> Result<(AnyResolvedStatementProto, i32), Error> {
     let request = AnalyzeRequest {
         simple_catalog: Some(simple_catalog.clone()),
         options: Some(AnalyzerOptionsProto {
             default_timezone: Some("UTC".to_string()),
             language_options: Some(language_options()),
             prune_unused_columns: Some(true),
+            // DEFAULT and CHECK expressions are stored as the SQL they were parsed from.
+            parse_location_options: Some(ParseLocationOptionsProto {
+                record_parse_locations: Some(true),
+                function_call_record_type: Some(FunctionCallRecordType::FunctionCall as i32),
+            }),
             query_parameters: params
                 .iter()
                 .map(|(name, data_type)| QueryParameterProto {
@@ -136,7 +223,7 @@ fn analyze_next_statement(
     };
     let response = match log::rpc(async move { parser().await.analyze(request).await }) {
         Ok(response) => response.into_inner(),
-        Err(status) => return Err(status.message().to_string()),
+        Err(status) => return Err(analyzer_error(status)),
     };
     match response.result.unwrap() {
         ResolvedStatement(stmt) => Ok((stmt, response.resume_byte_position.unwrap())),
@@ -146,6 +233,15 @@ fn analyze_next_statement(
     }
 }
 
+/// ZetaSQL reports features that it doesn't implement as UNIMPLEMENTED, and every other problem
+/// with a statement as INVALID_ARGUMENT.
+fn analyzer_error(status: Status) -> Error {
+    match status.code() {
+        Code::Unimplemented => Error::NotSupported(status.message().to_string()),
+        _ => Error::Syntax(status.message().to_string()),
+    }
+}
+
 async fn parser() -> ZetaSqlLocalServiceClient<Channel> {
     ZetaSqlLocalServiceClient::connect("http://localhost:50051")
         .await
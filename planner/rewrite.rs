@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use ast::*;
 use chrono::{NaiveDate, TimeZone, Utc};
+use kernel::DataType;
 
 use crate::{catalog::SimpleCatalogProvider, unnest::unnest_dependent_joins};
 
@@ -27,6 +28,92 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
         }
         catalog_id
     }
+    fn table_id_query(name: &Name) -> String {
+        format!(
+            "(select table_id from table where catalog_id = {} and table_name = {:?})",
+            catalog_id_query(name),
+            name.path.last().unwrap()
+        )
+    }
+    fn insert_table(
+        name: &Name,
+        columns: &Vec<(String, DataType)>,
+        constraints: &Vec<Constraint>,
+        options: &Vec<(String, Scalar)>,
+    ) -> Vec<String> {
+        let mut lines = vec![];
+        let catalog_id = catalog_id_query(name);
+        let table_name = format!("{:?}", name.path.last().unwrap());
+        // Enforce UNIQUE (catalog_id, table_name).
+        lines.push(format!(
+            "assert 0 = (select count(*) from table where catalog_id = {catalog_id} and table_name = {table_name}) as 'Table {table_name} already exists in catalog {catalog_id}';",
+            catalog_id = catalog_id, table_name = table_name
+        ));
+        lines.push(format!(
+            "insert into table (catalog_id, table_id, table_name) select {}, next_table_id(), {};",
+            catalog_id, table_name
+        ));
+        for (column_id, (column_name, column_type)) in columns.iter().enumerate() {
+            let column_type = column_type.to_string();
+            let not_null = constraints
+                .iter()
+                .any(|c| matches!(c, Constraint::NotNull(column) if column == column_name));
+            let default_expr = constraints
+                .iter()
+                .find_map(|c| match c {
+                    Constraint::Default(column, sql) if column == column_name => {
+                        Some(format!("{:?}", sql))
+                    }
+                    _ => None,
+                })
+                .unwrap_or("null".to_string());
+            let primary_key = constraints.iter().any(
+                |c| matches!(c, Constraint::PrimaryKey(columns) if columns.contains(column_name)),
+            );
+            lines.push(format!("insert into column (table_id, column_id, column_name, column_type, not_null, default_expr, primary_key) select next_table_id(), {}, {:?}, {:?}, {}, {}, {};", column_id, column_name, column_type, not_null, default_expr, primary_key));
+        }
+        for constraint in constraints {
+            if let Constraint::Check(constraint_name, check_expr) = constraint {
+                lines.push(format!("insert into check_constraint (table_id, constraint_name, check_expr) select next_table_id(), {:?}, {:?};", constraint_name, check_expr));
+            }
+            if let Constraint::ForeignKey(constraint_name, columns, table, referenced_columns) =
+                constraint
+            {
+                for (key_order, (column_name, referenced_column_name)) in
+                    columns.iter().zip(referenced_columns).enumerate()
+                {
+                    lines.push(format!("insert into foreign_key (table_id, constraint_name, column_name, referenced_table_id, referenced_column_name, key_order) select next_table_id(), {:?}, {:?}, {}, {:?}, {};", constraint_name, column_name, table.id, referenced_column_name, key_order));
+                }
+            }
+            // The primary key is enforced by looking up new rows in an index, which is created with the table.
+            if let Constraint::PrimaryKey(column_names) = constraint {
+                let index_name = format!("{:?}", format!("{}_pkey", name.path.last().unwrap()));
+                lines.push(format!(
+                    "assert 0 = (select count(*) from index where catalog_id = {catalog_id} and index_name = {index_name}) as 'Index {index_name} already exists in catalog {catalog_id}';",
+                    catalog_id = catalog_id, index_name = index_name
+                ));
+                lines.push(format!("insert into index (catalog_id, index_id, table_id, index_name) select {}, next_index_id(), next_table_id(), {};", catalog_id, index_name));
+                for (index_order, column_name) in column_names.iter().enumerate() {
+                    let column_id = columns
+                        .iter()
+                        .position(|(name, _)| name == column_name)
+                        .unwrap();
+                    lines.push(format!("insert into index_column (index_id, column_id, index_order, descending) select next_index_id(), {}, {}, false;", column_id, index_order));
+                }
+                lines.push(format!("call create_index();"));
+            }
+            // Identity columns own an unnamed sequence, which is dropped with the column.
+            if let Constraint::Identity(column_name) = constraint {
+                lines.push(format!("insert into sequence (catalog_id, sequence_id, sequence_name, start_value, increment, table_id, column_name) select {}, next_sequence_id(), null, 1, 1, next_table_id(), {:?};", catalog_id, column_name));
+                lines.push(format!("call create_sequence(1, 1);"));
+            }
+        }
+        for (option_name, value) in options {
+            let option_value = crate::options::option_value(value);
+            lines.push(format!("insert into table_option (table_id, option_name, option_value) select next_table_id(), {:?}, {:?};", option_name, option_value));
+        }
+        lines
+    }
     match expr {
         LogicalCreateDatabase { name } => {
             let parent_catalog_id = catalog_id_query(&name);
@@ -43,24 +130,66 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
                 sql: lines.join("\n"),
             })
         }
-        LogicalCreateTable { name, columns } => {
-            let mut lines = vec![];
-            let catalog_id = catalog_id_query(&name);
-            let table_name = format!("{:?}", name.path.last().unwrap());
-            // Enforce UNIQUE (catalog_id, table_name).
+        LogicalCreateTable {
+            name,
+            columns,
+            constraints,
+            temp: false,
+            options,
+        } => {
+            let mut lines = insert_table(&name, &columns, &constraints, &options);
             lines.push(format!(
-                "assert 0 = (select count(*) from table where catalog_id = {catalog_id} and table_name = {table_name}) as 'Table {table_name} already exists in catalog {catalog_id}';",
-                catalog_id = catalog_id, table_name = table_name
+                "call create_table({});",
+                crate::options::page_size(&options)
             ));
+            Ok(LogicalRewrite {
+                sql: lines.join("\n"),
+            })
+        }
+        LogicalCreateView {
+            name,
+            columns,
+            sql,
+            materialized,
+        } => {
+            // Views are tables with a definition, so they share a namespace with tables.
+            // The heap of a plain view is always empty; the heap of a materialized view holds the results of the last refresh.
+            let mut lines = insert_table(&name, &columns, &vec![], &vec![]);
             lines.push(format!(
-                "insert into table (catalog_id, table_id, table_name) select {}, next_table_id(), {};",
-                catalog_id, table_name
+                "insert into view (table_id, view_sql, materialized) select next_table_id(), {:?}, {};",
+                sql, materialized
+            ));
+            lines.push(format!(
+                "call create_table({});",
+                crate::options::page_size(&vec![])
             ));
-            for (column_id, (column_name, column_type)) in columns.iter().enumerate() {
-                let column_type = column_type.to_string();
-                lines.push(format!("insert into column (table_id, column_id, column_name, column_type) select next_table_id(), {}, {:?}, {:?};", column_id, column_name, column_type));
-            }
-            lines.push(format!("call create_table();"));
+            Ok(LogicalRewrite {
+                sql: lines.join("\n"),
+            })
+        }
+        LogicalCreateFunction {
+            name,
+            arguments,
+            return_type,
+            columns,
+            sql,
+        } => {
+            let mut lines = vec![];
+            let catalog_id = catalog_id_query(&name);
+            let function_name = format!("{:?}", name.path.last().unwrap());
+            // Enforce UNIQUE (catalog_id, function_name). Scalar and table-valued functions share a namespace.
+            lines.push(format!(
+                "assert 0 = (select count(*) from function where catalog_id = {catalog_id} and function_name = {function_name}) as 'Function {function_name} already exists in catalog {catalog_id}';",
+                catalog_id = catalog_id, function_name = function_name
+            ));
+            // Signatures are stored as JSON and converted back into types by the catalog.
+            let arguments = serde_json::to_string(&arguments).unwrap();
+            let return_type = match return_type {
+                Some(data_type) => format!("{:?}", data_type.to_string()),
+                None => "null".to_string(),
+            };
+            let output_columns = serde_json::to_string(&columns).unwrap();
+            lines.push(format!("insert into function (catalog_id, function_name, function_sql, arguments, return_type, output_columns) select {}, {}, {:?}, {:?}, {}, {:?};", catalog_id, function_name, sql, arguments, return_type, output_columns));
             Ok(LogicalRewrite {
                 sql: lines.join("\n"),
             })
@@ -69,6 +198,8 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
             name,
             table,
             columns,
+            descending,
+            options,
         } => {
             let mut lines = vec![];
             let catalog_id = catalog_id_query(&name);
@@ -85,13 +216,36 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
                     "(select column_id from column where table_id = {} and column_name = {:?})",
                     table.id, column_name
                 );
-                lines.push(format!("insert into index_column (index_id, column_id, index_order) select next_index_id(), {}, {:?};", column_id, index_order));
+                lines.push(format!("insert into index_column (index_id, column_id, index_order, descending) select next_index_id(), {}, {:?}, {};", column_id, index_order, descending[index_order]));
+            }
+            for (option_name, value) in &options {
+                let option_value = crate::options::option_value(value);
+                lines.push(format!("insert into index_option (index_id, option_name, option_value) select next_index_id(), {:?}, {:?};", option_name, option_value));
             }
             lines.push(format!("call create_index();"));
             Ok(LogicalRewrite {
                 sql: lines.join("\n"),
             })
         }
+        LogicalCreateSequence {
+            name,
+            start,
+            increment,
+        } => {
+            let mut lines = vec![];
+            let catalog_id = catalog_id_query(&name);
+            let sequence_name = format!("{:?}", name.path.last().unwrap());
+            // Enforce UNIQUE (catalog_id, sequence_name).
+            lines.push(format!(
+                "assert 0 = (select count(*) from sequence where catalog_id = {catalog_id} and sequence_name = {sequence_name}) as 'Sequence {sequence_name} already exists in catalog {catalog_id}';",
+                catalog_id = catalog_id, sequence_name = sequence_name
+            ));
+            lines.push(format!("insert into sequence (catalog_id, sequence_id, sequence_name, start_value, increment) select {}, next_sequence_id(), {}, {}, {};", catalog_id, sequence_name, start, increment));
+            lines.push(format!("call create_sequence({}, {});", start, increment));
+            Ok(LogicalRewrite {
+                sql: lines.join("\n"),
+            })
+        }
         LogicalDrop { object, name } => {
             let mut lines = vec![];
             match object {
@@ -105,6 +259,39 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
                     // TODO delete tables and indexes once no more transactions are using them.
                 }
                 ObjectType::Table => {
+                    let table_id = table_id_query(&name);
+                    let table_name = format!("{:?}", name.path.last().unwrap());
+                    lines.push(format!(
+                        "assert 0 = (select count(*) from view where table_id = {table_id}) as '{table_name} is a view';",
+                        table_id = table_id, table_name = table_name
+                    ));
+                    // Tables that are referenced by a foreign key must be dropped after the referencing table.
+                    lines.push(format!(
+                        "assert 0 = (select count(*) from foreign_key where referenced_table_id = {table_id} and table_id <> {table_id}) as 'Table {table_name} is referenced by a foreign key';",
+                        table_id = table_id, table_name = table_name
+                    ));
+                    lines.push(format!(
+                        "delete from foreign_key where table_id = {};",
+                        table_id
+                    ));
+                    lines.push(format!(
+                        "delete from sequence_worker where sequence_id in (select sequence_id from sequence where table_id = {});",
+                        table_id
+                    ));
+                    lines.push(format!(
+                        "delete from sequence where table_id = {};",
+                        table_id
+                    ));
+                    lines.push(format!(
+                        "delete from table_option where table_id = {};",
+                        table_id
+                    ));
+                    // Indexes are dropped with their table, including the index of the primary key.
+                    lines.push(format!(
+                        "delete from index_option where index_id in (select index_id from index where table_id = {});",
+                        table_id
+                    ));
+                    lines.push(format!("delete from index where table_id = {};", table_id));
                     let catalog_id = catalog_id_query(&name);
                     lines.push(format!(
                         "delete from table where catalog_id = {} and table_name = {:?};",
@@ -115,6 +302,11 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
                 }
                 ObjectType::Index => {
                     let catalog_id = catalog_id_query(&name);
+                    lines.push(format!(
+                        "delete from index_option where index_id = (select index_id from index where catalog_id = {} and index_name = {:?});",
+                        catalog_id,
+                        name.path.last().unwrap()
+                    ));
                     lines.push(format!(
                         "delete from index where catalog_id = {} and index_name = {:?};",
                         catalog_id,
@@ -122,8 +314,193 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
                     ));
                     // TODO delete index once no more transactions are using it.
                 }
-                ObjectType::Column => todo!(),
+                ObjectType::View => {
+                    let table_id = table_id_query(&name);
+                    let view_name = format!("{:?}", name.path.last().unwrap());
+                    lines.push(format!(
+                        "assert 1 = (select count(*) from view where table_id = {table_id}) as 'View {view_name} does not exist';",
+                        table_id = table_id, view_name = view_name
+                    ));
+                    lines.push(format!("delete from view where table_id = {};", table_id));
+                    lines.push(format!(
+                        "delete from table where catalog_id = {} and table_name = {};",
+                        catalog_id_query(&name),
+                        view_name
+                    ));
+                    // TODO delete the heap of a materialized view once no more transactions are using it.
+                }
+                ObjectType::Function => {
+                    let function_name = format!("{:?}", name.path.last().unwrap());
+                    lines.push(format!(
+                        "delete from function where catalog_id = {} and function_name = {};",
+                        catalog_id_query(&name),
+                        function_name
+                    ));
+                }
+                ObjectType::Sequence => {
+                    lines.push(format!(
+                        "delete from sequence_worker where sequence_id in (select sequence_id from sequence where catalog_id = {} and sequence_name = {:?});",
+                        catalog_id_query(&name),
+                        name.path.last().unwrap()
+                    ));
+                    lines.push(format!(
+                        "delete from sequence where catalog_id = {} and sequence_name = {:?};",
+                        catalog_id_query(&name),
+                        name.path.last().unwrap()
+                    ));
+                    // TODO delete the sequence once no more transactions are using it.
+                }
+                ObjectType::Column => {
+                    let table = Name {
+                        catalog_id: name.catalog_id,
+                        path: name.path[0..name.path.len() - 1].to_vec(),
+                    };
+                    let table_id = table_id_query(&table);
+                    let column_name = format!("{:?}", name.path.last().unwrap());
+                    // Indexed columns must be dropped after the index.
+                    lines.push(format!(
+                        "assert 0 = (select count(*) from index join index_column using (index_id) join column using (table_id, column_id) where table_id = {table_id} and column_name = {column_name}) as 'Column {column_name} is used by an index';",
+                        table_id = table_id, column_name = column_name
+                    ));
+                    lines.push(format!(
+                        "assert 0 = (select count(*) from foreign_key where (table_id = {table_id} and column_name = {column_name}) or (referenced_table_id = {table_id} and referenced_column_name = {column_name})) as 'Column {column_name} is used by a foreign key';",
+                        table_id = table_id, column_name = column_name
+                    ));
+                    lines.push(format!(
+                        "assert 0 = (select count(*) from table_option where table_id = {table_id} and option_name = 'distribution_key' and option_value = {column_name}) as 'Column {column_name} is the distribution key';",
+                        table_id = table_id, column_name = column_name
+                    ));
+                    lines.push(format!(
+                        "delete from column where table_id = {} and column_name = {};",
+                        table_id, column_name
+                    ));
+                    lines.push(format!(
+                        "delete from sequence_worker where sequence_id in (select sequence_id from sequence where table_id = {} and column_name = {});",
+                        table_id, column_name
+                    ));
+                    lines.push(format!(
+                        "delete from sequence where table_id = {} and column_name = {};",
+                        table_id, column_name
+                    ));
+                }
+            };
+            Ok(LogicalRewrite {
+                sql: lines.join("\n"),
+            })
+        }
+        LogicalAddColumn {
+            name,
+            column: (column_name, column_type),
+            default,
+            not_null,
+            if_not_exists,
+        } => {
+            let mut lines = vec![];
+            let table_id = table_id_query(&name);
+            let table_name = format!("{:?}", name.path.last().unwrap());
+            let column_name = format!("{:?}", column_name);
+            let column_type = format!("{:?}", column_type.to_string());
+            lines.push(format!(
+                "assert 1 = (select count(*) from table where table_id = {table_id}) as 'Table {table_name} does not exist';",
+                table_id = table_id, table_name = table_name
+            ));
+            let column_exists = format!(
+                "(select count(*) from column where table_id = {} and column_name = {})",
+                table_id, column_name
+            );
+            if !if_not_exists {
+                // Enforce UNIQUE (table_id, column_name).
+                lines.push(format!(
+                    "assert 0 = {column_exists} as 'Column {column_name} already exists in table {table_name}';",
+                    column_exists = column_exists, column_name = column_name, table_name = table_name
+                ));
+            }
+            let default_expr = match default {
+                Some(sql) => format!("{:?}", sql),
+                None => "null".to_string(),
             };
+            // New columns go after existing columns.
+            lines.push(format!("insert into column (table_id, column_id, column_name, column_type, not_null, default_expr) select {table_id}, (select coalesce(max(column_id) + 1, 0) from column where table_id = {table_id}), {column_name}, {column_type}, {not_null}, {default_expr} from (select 1) where {column_exists} = 0;",
+                table_id = table_id, column_name = column_name, column_type = column_type, not_null = not_null, default_expr = default_expr, column_exists = column_exists));
+            Ok(LogicalRewrite {
+                sql: lines.join("\n"),
+            })
+        }
+        LogicalRename {
+            object: ObjectType::Column,
+            name,
+            new_name,
+        } => {
+            let mut lines = vec![];
+            let table = Name {
+                catalog_id: name.catalog_id,
+                path: name.path[0..name.path.len() - 1].to_vec(),
+            };
+            let table_id = table_id_query(&table);
+            let table_name = format!("{:?}", table.path.last().unwrap());
+            let old_name = format!("{:?}", name.path.last().unwrap());
+            let new_name = format!("{:?}", new_name.path.last().unwrap());
+            lines.push(format!(
+                "assert 1 = (select count(*) from column where table_id = {table_id} and column_name = {old_name}) as 'Column {old_name} does not exist in table {table_name}';",
+                table_id = table_id, old_name = old_name, table_name = table_name
+            ));
+            // Enforce UNIQUE (table_id, column_name).
+            lines.push(format!(
+                "assert 0 = (select count(*) from column where table_id = {table_id} and column_name = {new_name}) as 'Column {new_name} already exists in table {table_name}';",
+                table_id = table_id, new_name = new_name, table_name = table_name
+            ));
+            // Foreign keys and identity sequences refer to columns by name.
+            lines.push(format!(
+                "update column set column_name = {new_name} where table_id = {table_id} and column_name = {old_name};",
+                table_id = table_id, old_name = old_name, new_name = new_name
+            ));
+            lines.push(format!(
+                "update foreign_key set column_name = {new_name} where table_id = {table_id} and column_name = {old_name};",
+                table_id = table_id, old_name = old_name, new_name = new_name
+            ));
+            lines.push(format!(
+                "update foreign_key set referenced_column_name = {new_name} where referenced_table_id = {table_id} and referenced_column_name = {old_name};",
+                table_id = table_id, old_name = old_name, new_name = new_name
+            ));
+            lines.push(format!(
+                "update sequence set column_name = {new_name} where table_id = {table_id} and column_name = {old_name};",
+                table_id = table_id, old_name = old_name, new_name = new_name
+            ));
+            Ok(LogicalRewrite {
+                sql: lines.join("\n"),
+            })
+        }
+        LogicalRename {
+            object,
+            name,
+            new_name,
+        } => {
+            let mut lines = vec![];
+            let (table, id, name_column) = match object {
+                ObjectType::Database => ("catalog", "parent_catalog_id", "catalog_name"),
+                ObjectType::Table | ObjectType::View => ("table", "catalog_id", "table_name"),
+                ObjectType::Index => ("index", "catalog_id", "index_name"),
+                ObjectType::Function => ("function", "catalog_id", "function_name"),
+                ObjectType::Sequence => ("sequence", "catalog_id", "sequence_name"),
+                ObjectType::Column => unreachable!(),
+            };
+            let catalog_id = catalog_id_query(&name);
+            let old_name = format!("{:?}", name.path.last().unwrap());
+            let new_catalog_id = catalog_id_query(&new_name);
+            let new_name = format!("{:?}", new_name.path.last().unwrap());
+            lines.push(format!(
+                "assert 1 = (select count(*) from {table} where {id} = {catalog_id} and {name_column} = {old_name}) as '{old_name} does not exist in catalog {catalog_id}';",
+                table = table, id = id, name_column = name_column, catalog_id = catalog_id, old_name = old_name
+            ));
+            // Enforce UNIQUE (catalog_id, name).
+            lines.push(format!(
+                "assert 0 = (select count(*) from {table} where {id} = {new_catalog_id} and {name_column} = {new_name}) as '{new_name} already exists in catalog {new_catalog_id}';",
+                table = table, id = id, name_column = name_column, new_catalog_id = new_catalog_id, new_name = new_name
+            ));
+            lines.push(format!(
+                "update {table} set {id} = {new_catalog_id}, {name_column} = {new_name} where {id} = {catalog_id} and {name_column} = {old_name};",
+                table = table, id = id, name_column = name_column, catalog_id = catalog_id, old_name = old_name, new_catalog_id = new_catalog_id, new_name = new_name
+            ));
             Ok(LogicalRewrite {
                 sql: lines.join("\n"),
             })
@@ -137,6 +514,9 @@ fn rewrite_ddl(expr: Expr) -> Result<Expr, Expr> {
             table: table.clone(),
             input: Box::new(LogicalDelete { table, tid, input }),
             columns,
+            // UPDATE of the distribution key is converted to an insert that moves the rows,
+            // so the remaining updated rows stay on the worker where they were inserted.
+            partition_by: None,
         }),
         _ => Err(expr),
     }
@@ -210,10 +590,13 @@ pub fn rewrite_scalars(mut expr: Expr) -> Result<Expr, Expr> {
     };
     let visit_procedure = |procedure: &mut Procedure| match procedure {
         Procedure::Assert(x, _) => visit(x),
-        Procedure::CreateCatalog | Procedure::CreateTable | Procedure::CreateIndex => false,
+        Procedure::CreateTable(page_size) => visit(page_size),
+        Procedure::CreateSequence(start, increment) => visit(start) | visit(increment),
+        Procedure::CreateCatalog | Procedure::CreateIndex | Procedure::Truncate(_) => false,
     };
     let did_rewrite = match &mut expr {
         Expr::LogicalGet { predicates, .. }
+        | Expr::LogicalIndexGet { predicates, .. }
         | Expr::LogicalFilter { predicates, .. }
         | Expr::LogicalDependentJoin { predicates, .. } => visit_predicates(predicates),
         Expr::LogicalMap { projects, .. } => visit_projects(projects),
@@ -229,19 +612,28 @@ pub fn rewrite_scalars(mut expr: Expr) -> Result<Expr, Expr> {
         | Expr::LogicalLimit { .. }
         | Expr::LogicalSort { .. }
         | Expr::LogicalUnion { .. }
+        | Expr::LogicalIterate { .. }
         | Expr::LogicalInsert { .. }
         | Expr::LogicalValues { .. }
         | Expr::LogicalUpdate { .. }
         | Expr::LogicalDelete { .. }
         | Expr::LogicalCreateDatabase { .. }
         | Expr::LogicalCreateTable { .. }
+        | Expr::LogicalCreateTableAs { .. }
         | Expr::LogicalCreateIndex { .. }
+        | Expr::LogicalCreateSequence { .. }
         | Expr::LogicalDrop { .. }
+        | Expr::LogicalAddColumn { .. }
+        | Expr::LogicalRename { .. }
+        | Expr::LogicalCreateView { .. }
+        | Expr::LogicalCreateFunction { .. }
+        | Expr::LogicalRefresh { .. }
         | Expr::LogicalScript { .. }
         | Expr::LogicalExplain { .. }
         | Expr::LogicalRewrite { .. } => false,
         Expr::TableFreeScan { .. }
         | Expr::SeqScan { .. }
+        | Expr::IndexOrderScan { .. }
         | Expr::IndexScan { .. }
         | Expr::Filter { .. }
         | Expr::Out { .. }
@@ -252,9 +644,11 @@ pub fn rewrite_scalars(mut expr: Expr) -> Result<Expr, Expr> {
         | Expr::GetTempTable { .. }
         | Expr::SimpleAggregate { .. }
         | Expr::GroupByAggregate { .. }
+        | Expr::PartialAggregate { .. }
         | Expr::Limit { .. }
         | Expr::Sort { .. }
         | Expr::Union { .. }
+        | Expr::Iterate { .. }
         | Expr::Broadcast { .. }
         | Expr::Exchange { .. }
         | Expr::Gather { .. }
@@ -548,10 +942,12 @@ fn push_down_predicates(expr: Expr) -> Result<Expr, Expr> {
                 projects,
                 predicates: get_predicates,
                 table,
+                sample,
             } => Ok(LogicalGet {
                 projects,
                 predicates: combine_predicates(filter_predicates, get_predicates),
                 table,
+                sample,
             }),
             _ => Err(LogicalFilter {
                 predicates: filter_predicates,
@@ -680,6 +1076,7 @@ fn push_down_projections(expr: Expr) -> Result<Expr, Expr> {
                     projects: inner,
                     predicates,
                     table,
+                    sample,
                 } => {
                     let mut combined = inner.clone();
                     for (x, c) in &outer {
@@ -691,6 +1088,7 @@ fn push_down_projections(expr: Expr) -> Result<Expr, Expr> {
                                     projects: inner,
                                     predicates,
                                     table,
+                                    sample,
                                 }),
                             });
                         }
@@ -702,6 +1100,7 @@ fn push_down_projections(expr: Expr) -> Result<Expr, Expr> {
                         projects: combined,
                         predicates,
                         table,
+                        sample,
                     })
                 }
                 _ => Err(LogicalMap {
@@ -715,6 +1114,94 @@ fn push_down_projections(expr: Expr) -> Result<Expr, Expr> {
     }
 }
 
+/// Replace ORDER BY ... LIMIT over a table with a scan of an index that is already in the right order.
+#[log::trace]
+pub fn rewrite_index_order(expr: Expr, indexes: &Vec<Index>) -> Expr {
+    top_down_rewrite(expr, |expr| push_limit_into_index(expr, indexes))
+}
+
+fn push_limit_into_index(expr: Expr, indexes: &Vec<Index>) -> Result<Expr, Expr> {
+    match expr {
+        LogicalLimit {
+            limit,
+            offset,
+            input,
+        } => match *input {
+            LogicalSort { order_by, input } if !order_by.is_empty() => {
+                let limit_sort = |input: Expr| LogicalLimit {
+                    limit,
+                    offset,
+                    input: Box::new(LogicalSort {
+                        order_by: order_by.clone(),
+                        input: Box::new(input),
+                    }),
+                };
+                match index_get(&order_by, limit.saturating_add(offset), *input, indexes) {
+                    Ok(input) => Ok(limit_sort(input)),
+                    Err(input) => Err(limit_sort(input)),
+                }
+            }
+            input => Err(LogicalLimit {
+                limit,
+                offset,
+                input: Box::new(input),
+            }),
+        },
+        _ => Err(expr),
+    }
+}
+
+fn index_get(
+    order_by: &Vec<OrderBy>,
+    limit: usize,
+    input: Expr,
+    indexes: &Vec<Index>,
+) -> Result<Expr, Expr> {
+    match input {
+        LogicalGet {
+            predicates,
+            projects,
+            table,
+            sample: None,
+        } => match indexes
+            .iter()
+            .find(|index| index.table_id == table.id && index.sorts(order_by, &projects))
+        {
+            Some(index) => Ok(LogicalIndexGet {
+                predicates,
+                projects,
+                index: index.clone(),
+                table,
+                limit,
+            }),
+            None => Err(LogicalGet {
+                predicates,
+                projects,
+                table,
+                sample: None,
+            }),
+        },
+        // Map doesn't change the number of rows, so the limit can be pushed through it.
+        LogicalMap {
+            include_existing,
+            projects,
+            input,
+        } => match index_get(order_by, limit, *input, indexes) {
+            Ok(input) => Ok(LogicalMap {
+                include_existing,
+                projects,
+                input: Box::new(input),
+            }),
+            Err(input) => Err(LogicalMap {
+                include_existing,
+                projects,
+                input: Box::new(input),
+            }),
+        },
+        _ => Err(input),
+    }
+}
+
 fn rewrite_logical_rewrite(expr: Expr) -> Result<Expr, Expr> {
     match expr {
         LogicalRewrite { sql } => {
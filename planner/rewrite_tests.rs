@@ -0,0 +1,112 @@
+use ast::*;
+use kernel::DataType;
+
+use crate::rewrite::*;
+
+fn column(id: i64, name: &str) -> Column {
+    Column {
+        id,
+        name: name.to_string(),
+        table: Some(table()),
+        data_type: DataType::I64,
+        created_late: false,
+    }
+}
+
+fn table() -> Table {
+    Table {
+        id: 100,
+        name: "t".to_string(),
+    }
+}
+
+fn index(descending: bool) -> Index {
+    Index {
+        index_id: 1,
+        table_id: 100,
+        columns: vec!["x".to_string()],
+        descending: vec![descending],
+    }
+}
+
+fn order_by_limit(descending: bool, limit: usize, offset: usize, input: Expr) -> Expr {
+    Expr::LogicalLimit {
+        limit,
+        offset,
+        input: Box::new(Expr::LogicalSort {
+            order_by: vec![OrderBy {
+                column: column(1, "x"),
+                descending,
+            }],
+            input: Box::new(input),
+        }),
+    }
+}
+
+fn get() -> Expr {
+    Expr::LogicalGet {
+        predicates: vec![],
+        projects: vec![column(1, "x"), column(2, "y")],
+        table: table(),
+        sample: None,
+    }
+}
+
+/// The index scan under ORDER BY ... LIMIT, if the rewrite found one.
+fn index_get(expr: &Expr) -> Option<(Index, usize)> {
+    expr.pre_order().find_map(|expr| match expr {
+        Expr::LogicalIndexGet { index, limit, .. } => Some((index.clone(), *limit)),
+        _ => None,
+    })
+}
+
+#[test]
+fn test_index_order() {
+    let expr = rewrite_index_order(order_by_limit(false, 10, 5, get()), &vec![index(false)]);
+    assert_eq!(Some((index(false), 15)), index_get(&expr));
+    // The sort and limit stay above the index scan, which may return more rows than the limit.
+    assert!(matches!(
+        expr,
+        Expr::LogicalLimit {
+            limit: 10,
+            offset: 5,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_index_order_descending() {
+    let expr = rewrite_index_order(order_by_limit(true, 10, 0, get()), &vec![index(true)]);
+    assert_eq!(Some((index(true), 10)), index_get(&expr));
+    let expr = rewrite_index_order(order_by_limit(true, 10, 0, get()), &vec![index(false)]);
+    assert_eq!(None, index_get(&expr));
+}
+
+#[test]
+fn test_index_order_through_map() {
+    let map = Expr::LogicalMap {
+        include_existing: true,
+        projects: vec![],
+        input: Box::new(get()),
+    };
+    let expr = rewrite_index_order(order_by_limit(false, 3, 0, map), &vec![index(false)]);
+    assert_eq!(Some((index(false), 3)), index_get(&expr));
+}
+
+#[test]
+fn test_index_order_other_column() {
+    let expr = Expr::LogicalLimit {
+        limit: 10,
+        offset: 0,
+        input: Box::new(Expr::LogicalSort {
+            order_by: vec![OrderBy {
+                column: column(2, "y"),
+                descending: false,
+            }],
+            input: Box::new(get()),
+        }),
+    };
+    let expr = rewrite_index_order(expr, &vec![index(false)]);
+    assert_eq!(None, index_get(&expr));
+}
@@ -18,6 +18,7 @@ pub(crate) enum Rule {
     LogicalGetToTableFreeScan,
     LogicalGetToSeqScan,
     LogicalGetToIndexScan,
+    LogicalIndexGetToIndexOrderScan,
     LogicalFilterToFilter,
     LogicalOutToOut,
     LogicalMapToMap,
@@ -31,6 +32,7 @@ pub(crate) enum Rule {
     LogicallUnionToUnion,
     LogicalCreateTempTableToCreateTempTable,
     LogicalGetWithToGetTempTable,
+    LogicalIterateToIterate,
     LogicalInsertToInsert,
     LogicalValuesToValues,
     LogicalDeleteToDelete,
@@ -48,6 +50,7 @@ impl Rule {
             | Rule::LogicalGetToTableFreeScan
             | Rule::LogicalGetToSeqScan
             | Rule::LogicalGetToIndexScan
+            | Rule::LogicalIndexGetToIndexOrderScan
             | Rule::LogicalFilterToFilter
             | Rule::LogicalOutToOut
             | Rule::LogicalMapToMap
@@ -61,6 +64,7 @@ impl Rule {
             | Rule::LogicallUnionToUnion
             | Rule::LogicalCreateTempTableToCreateTempTable
             | Rule::LogicalGetWithToGetTempTable
+            | Rule::LogicalIterateToIterate
             | Rule::LogicalInsertToInsert
             | Rule::LogicalValuesToValues
             | Rule::LogicalDeleteToDelete
@@ -120,6 +124,7 @@ impl Rule {
             (Rule::LogicalGetToTableFreeScan, LogicalSingleGet)
             | (Rule::LogicalGetToSeqScan, LogicalGet { .. })
             | (Rule::LogicalGetToIndexScan, LogicalGet { .. })
+            | (Rule::LogicalIndexGetToIndexOrderScan, LogicalIndexGet { .. })
             | (Rule::LogicalFilterToFilter, LogicalFilter { .. })
             | (Rule::LogicalOutToOut, LogicalOut { .. })
             | (Rule::LogicalMapToMap, LogicalMap { .. })
@@ -129,6 +134,7 @@ impl Rule {
             | (Rule::LogicallUnionToUnion, LogicalUnion { .. })
             | (Rule::LogicalCreateTempTableToCreateTempTable, LogicalCreateTempTable { .. })
             | (Rule::LogicalGetWithToGetTempTable, LogicalGetWith { .. })
+            | (Rule::LogicalIterateToIterate, LogicalIterate { .. })
             | (Rule::LogicalInsertToInsert, LogicalInsert { .. })
             | (Rule::LogicalValuesToValues, LogicalValues { .. })
             | (Rule::LogicalDeleteToDelete, LogicalDelete { .. })
@@ -149,6 +155,7 @@ impl Rule {
             | Rule::LogicalGetToTableFreeScan
             | Rule::LogicalGetToSeqScan
             | Rule::LogicalGetToIndexScan
+            | Rule::LogicalIndexGetToIndexOrderScan
             | Rule::LogicalFilterToFilter
             | Rule::LogicalOutToOut
             | Rule::LogicalMapToMap
@@ -162,6 +169,7 @@ impl Rule {
             | Rule::LogicallUnionToUnion
             | Rule::LogicalCreateTempTableToCreateTempTable
             | Rule::LogicalGetWithToGetTempTable
+            | Rule::LogicalIterateToIterate
             | Rule::LogicalInsertToInsert
             | Rule::LogicalValuesToValues
             | Rule::LogicalDeleteToDelete
@@ -223,6 +231,7 @@ impl Rule {
                                 predicates,
                                 projects,
                                 table,
+                                sample: None,
                             } = &ss[*left].expr
                             {
                                 binds.push(LogicalJoin {
@@ -231,6 +240,7 @@ impl Rule {
                                         predicates: predicates.clone(),
                                         projects: projects.clone(),
                                         table: table.clone(),
+                                        sample: None,
                                     }),
                                     right: right.clone(),
                                 })
@@ -361,20 +371,24 @@ impl Rule {
                     projects,
                     predicates,
                     table,
+                    sample,
                 } = bind
                 {
                     return single(SeqScan {
                         projects,
                         predicates,
                         table,
+                        sample,
                     });
                 }
             }
             Rule::LogicalGetToIndexScan => {
+                // An index scan would read every matching row, so a sample can only be read by SeqScan.
                 if let LogicalGet {
                     projects,
                     predicates,
                     table,
+                    sample: None,
                 } = bind
                 {
                     let mut results = vec![];
@@ -396,6 +410,24 @@ impl Rule {
                     return results;
                 }
             }
+            Rule::LogicalIndexGetToIndexOrderScan => {
+                if let LogicalIndexGet {
+                    predicates,
+                    projects,
+                    index,
+                    table,
+                    limit,
+                } = bind
+                {
+                    return single(IndexOrderScan {
+                        projects,
+                        predicates,
+                        index,
+                        table,
+                        limit,
+                    });
+                }
+            }
             Rule::LogicalFilterToFilter => {
                 if let LogicalFilter { predicates, input } = bind {
                     return single(Filter { predicates, input });
@@ -440,6 +472,7 @@ impl Rule {
                         predicates: table_predicates,
                         projects,
                         table,
+                        sample: None,
                     } = *left
                     {
                         let mut results = vec![];
@@ -518,11 +551,31 @@ impl Rule {
                     return single(GetTempTable { name, columns });
                 }
             }
+            Rule::LogicalIterateToIterate => {
+                if let LogicalIterate {
+                    name,
+                    columns,
+                    recursion_limit,
+                    base,
+                    step,
+                } = bind
+                {
+                    return single(Iterate {
+                        name,
+                        columns,
+                        recursion_limit,
+                        stage: None,
+                        base,
+                        step,
+                    });
+                }
+            }
             Rule::LogicalInsertToInsert => {
                 if let LogicalInsert {
                     table,
                     input,
                     columns,
+                    partition_by,
                 } = bind
                 {
                     let indexes = ss
@@ -536,6 +589,7 @@ impl Rule {
                         indexes,
                         input,
                         columns,
+                        partition_by,
                     });
                 }
             }
@@ -587,6 +641,7 @@ impl Rule {
             Rule::LogicalGetToTableFreeScan,
             Rule::LogicalGetToSeqScan,
             Rule::LogicalGetToIndexScan,
+            Rule::LogicalIndexGetToIndexOrderScan,
             Rule::LogicalFilterToFilter,
             Rule::LogicalOutToOut,
             Rule::LogicalMapToMap,
@@ -600,6 +655,7 @@ impl Rule {
             Rule::LogicallUnionToUnion,
             Rule::LogicalCreateTempTableToCreateTempTable,
             Rule::LogicalGetWithToGetTempTable,
+            Rule::LogicalIterateToIterate,
             Rule::LogicalInsertToInsert,
             Rule::LogicalValuesToValues,
             Rule::LogicalDeleteToDelete,
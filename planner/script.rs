@@ -0,0 +1,618 @@
+use kernel::Error;
+
+/// A statement of a procedural script.
+/// ZetaSQL checks the syntax of a script, but it analyzes one statement at a time, so we find the control flow around those statements ourselves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// A statement that is planned by ZetaSQL. It refers to script variables by name, like x.
+    Sql(String),
+    /// DECLARE with the expression that computes the initial value, which is cast to the declared type if there is one.
+    Declare {
+        names: Vec<String>,
+        value: String,
+    },
+    Set {
+        name: String,
+        value: String,
+    },
+    If {
+        branches: Vec<(String, Vec<Statement>)>,
+        otherwise: Vec<Statement>,
+    },
+    While {
+        condition: String,
+        body: Vec<Statement>,
+    },
+    Loop {
+        body: Vec<Statement>,
+    },
+    Block {
+        body: Vec<Statement>,
+    },
+    Break,
+    Continue,
+    Raise {
+        message: Option<String>,
+    },
+    /// ZetaSQL doesn't know about sequences, so we parse CREATE SEQUENCE and DROP SEQUENCE ourselves.
+    CreateSequence {
+        name: String,
+        start: i64,
+        increment: i64,
+    },
+    DropSequence {
+        name: String,
+    },
+}
+
+impl Statement {
+    pub fn is_sql(&self) -> bool {
+        matches!(self, Statement::Sql(_))
+    }
+
+    /// ZetaSQL's grammar doesn't include sequences, so a script that creates or drops one can't be checked by ZetaSQL.
+    pub(crate) fn has_sequences(&self) -> bool {
+        let any = |statements: &Vec<Statement>| statements.iter().any(Statement::has_sequences);
+        match self {
+            Statement::CreateSequence { .. } | Statement::DropSequence { .. } => true,
+            Statement::If {
+                branches,
+                otherwise,
+            } => branches.iter().any(|(_, body)| any(body)) || any(otherwise),
+            Statement::While { body, .. }
+            | Statement::Loop { body }
+            | Statement::Block { body } => any(body),
+            _ => false,
+        }
+    }
+}
+
+/// Split a script into statements, parsing DECLARE, SET, IF, WHILE, LOOP, BEGIN...END, BREAK, CONTINUE, RAISE,
+/// CREATE SEQUENCE and DROP SEQUENCE. SHOW TABLES and DESCRIBE are expanded into queries of INFORMATION_SCHEMA.
+/// Expressions and SQL statements are returned as text, to be analyzed by ZetaSQL when they are executed.
+pub fn parse_script(sql: &str) -> Result<Vec<Statement>, Error> {
+    let mut parser = Parser {
+        sql,
+        tokens: tokenize(sql),
+        i: 0,
+    };
+    parser.statements(&[])
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Symbol(char),
+    Other,
+}
+
+struct Lexeme {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(sql: &str) -> Vec<Lexeme> {
+    let bytes = sql.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i] as char;
+        if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        // Comments.
+        if c == '#' || sql[i..].starts_with("--") {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if sql[i..].starts_with("/*") {
+            i = match sql[i + 2..].find("*/") {
+                Some(end) => i + 2 + end + 2,
+                None => bytes.len(),
+            };
+            continue;
+        }
+        // Strings and quoted identifiers, including raw and bytes literals like r'...', b"..." and rb'''...'''.
+        // A backslash keeps the quote after it from ending a raw string too, so raw strings are scanned the same way.
+        let prefix = sql[i..]
+            .bytes()
+            .take_while(|b| b.eq_ignore_ascii_case(&b'r') || b.eq_ignore_ascii_case(&b'b'))
+            .count();
+        let quoted =
+            sql[i + prefix..].starts_with(|c| c == '\'' || c == '"') || (prefix == 0 && c == '`');
+        if prefix <= 2 && quoted {
+            i += prefix;
+            let c = bytes[i] as char;
+            let quote = if sql[i..].starts_with(&c.to_string().repeat(3)) {
+                c.to_string().repeat(3)
+            } else {
+                c.to_string()
+            };
+            i += quote.len();
+            while i < bytes.len() && !bytes[i..].starts_with(quote.as_bytes()) {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + quote.len()).min(bytes.len());
+            tokens.push(Lexeme {
+                token: Token::Other,
+                start,
+                end: i,
+            });
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == '_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            tokens.push(Lexeme {
+                token: Token::Word(sql[start..i].to_uppercase()),
+                start,
+                end: i,
+            });
+            continue;
+        }
+        // Everything else is a single character, possibly part of a multi-byte character.
+        i += 1;
+        while !sql.is_char_boundary(i) {
+            i += 1;
+        }
+        let token = if c.is_ascii_punctuation() {
+            Token::Symbol(c)
+        } else {
+            Token::Other
+        };
+        tokens.push(Lexeme {
+            token,
+            start,
+            end: i,
+        });
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: Vec<Lexeme>,
+    i: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn statements(&mut self, terminators: &[&str]) -> Result<Vec<Statement>, Error> {
+        let mut statements = vec![];
+        loop {
+            while self.peek_symbol(';') {
+                self.i += 1;
+            }
+            if self.i == self.tokens.len() {
+                if terminators.is_empty() {
+                    return Ok(statements);
+                }
+                return Err(Error::Syntax(format!(
+                    "Expected {} but reached the end of the script",
+                    terminators.join(" or ")
+                )));
+            }
+            match self.peek_word() {
+                Some(word) if terminators.contains(&word) => return Ok(statements),
+                Some(word @ "END") | Some(word @ "ELSE") | Some(word @ "ELSEIF") => {
+                    return Err(Error::Syntax(format!("Unexpected {}", word)))
+                }
+                _ => statements.push(self.statement()?),
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, Error> {
+        match self.peek_word() {
+            Some("DECLARE") => self.declare(),
+            Some("SET") => self.set(),
+            Some("IF") => self.if_statement(),
+            Some("WHILE") => self.while_statement(),
+            Some("LOOP") => {
+                self.i += 1;
+                let body = self.statements(&["END"])?;
+                self.expect_word("END")?;
+                self.expect_word("LOOP")?;
+                Ok(Statement::Loop { body })
+            }
+            Some("BEGIN") if !matches!(self.word_at(self.i + 1), Some("TRANSACTION")) => {
+                self.i += 1;
+                let body = self.statements(&["END", "EXCEPTION"])?;
+                if self.peek_word() == Some("EXCEPTION") {
+                    return Err(Error::NotSupported(
+                        "BEGIN ... EXCEPTION is not supported".to_string(),
+                    ));
+                }
+                self.expect_word("END")?;
+                Ok(Statement::Block { body })
+            }
+            Some("BREAK") | Some("LEAVE") => {
+                self.i += 1;
+                Ok(Statement::Break)
+            }
+            Some("CONTINUE") | Some("ITERATE") => {
+                self.i += 1;
+                Ok(Statement::Continue)
+            }
+            Some("RAISE") => {
+                self.i += 1;
+                let message = if self.peek_word() == Some("USING") {
+                    self.i += 1;
+                    self.expect_word("MESSAGE")?;
+                    self.expect_symbol('=')?;
+                    Some(self.text_until(None)?)
+                } else {
+                    None
+                };
+                Ok(Statement::Raise { message })
+            }
+            Some("CREATE") if self.word_at(self.i + 1) == Some("SEQUENCE") => {
+                self.create_sequence()
+            }
+            Some("DROP") if self.word_at(self.i + 1) == Some("SEQUENCE") => {
+                self.i += 2;
+                if self.peek_word() == Some("IF") {
+                    self.i += 1;
+                    self.expect_word("EXISTS")?;
+                }
+                let name = self.sequence_name()?;
+                Ok(Statement::DropSequence { name })
+            }
+            Some("SHOW") if self.word_at(self.i + 1) == Some("TABLES") => self.show_tables(),
+            Some("DESCRIBE") => self.describe(),
+            _ => Ok(Statement::Sql(self.text_until(None)?)),
+        }
+    }
+
+    fn show_tables(&mut self) -> Result<Statement, Error> {
+        self.expect_word("SHOW")?;
+        self.expect_word("TABLES")?;
+        let schema = match self.peek_word() {
+            Some("FROM") | Some("IN") => {
+                self.i += 1;
+                self.path()?.join(".")
+            }
+            _ => "".to_string(),
+        };
+        let mut sql = format!(
+            "select table_name, table_type from information_schema.tables where table_schema = {:?}",
+            schema
+        );
+        if self.peek_word() == Some("LIKE") {
+            self.i += 1;
+            sql.push_str(&format!(" and table_name like {}", self.text_until(None)?));
+        }
+        if !self.at_semicolon() {
+            return Err(Error::Syntax(format!(
+                "Expected FROM or LIKE at {}",
+                self.position()
+            )));
+        }
+        sql.push_str(" order by table_name");
+        Ok(Statement::Sql(sql))
+    }
+
+    fn describe(&mut self) -> Result<Statement, Error> {
+        self.expect_word("DESCRIBE")?;
+        if self.peek_word() == Some("TABLE") {
+            self.i += 1;
+        }
+        let mut path = self.path()?;
+        if !self.at_semicolon() {
+            return Err(Error::Syntax(format!(
+                "Expected the end of DESCRIBE at {}",
+                self.position()
+            )));
+        }
+        let table_name = path.pop().unwrap();
+        let filter = format!(
+            "table_schema = {:?} and table_name = {:?}",
+            path.join("."),
+            table_name
+        );
+        // A table always has at least one column, so an empty description would mean the table doesn't exist.
+        Ok(Statement::Block {
+            body: vec![
+                Statement::Sql(format!(
+                    "assert exists (select 1 from information_schema.tables where {}) as {:?}",
+                    filter,
+                    format!("Table {} does not exist", table_name)
+                )),
+                Statement::Sql(format!(
+                    "select column_name, data_type, is_nullable from information_schema.columns where {} order by ordinal_position",
+                    filter
+                )),
+            ],
+        })
+    }
+
+    /// A name like a.b.c, whose parts may be quoted like `a`.
+    fn path(&mut self) -> Result<Vec<String>, Error> {
+        let mut path = vec![self.name_part()?];
+        while self.peek_symbol('.') {
+            self.i += 1;
+            path.push(self.name_part()?);
+        }
+        Ok(path)
+    }
+
+    fn name_part(&mut self) -> Result<String, Error> {
+        match self.tokens.get(self.i) {
+            Some(Lexeme {
+                token: Token::Word(_),
+                start,
+                end,
+            }) => {
+                self.i += 1;
+                Ok(self.sql[*start..*end].to_string())
+            }
+            Some(Lexeme {
+                token: Token::Other,
+                start,
+                end,
+            }) if self.sql[*start..*end].starts_with('`') => {
+                self.i += 1;
+                Ok(self.sql[*start..*end].trim_matches('`').to_string())
+            }
+            _ => Err(Error::Syntax(format!(
+                "Expected a name at {}",
+                self.position()
+            ))),
+        }
+    }
+
+    fn create_sequence(&mut self) -> Result<Statement, Error> {
+        self.expect_word("CREATE")?;
+        self.expect_word("SEQUENCE")?;
+        let name = self.sequence_name()?;
+        let mut start = 1;
+        let mut increment = 1;
+        loop {
+            match self.peek_word() {
+                Some("START") => {
+                    self.i += 1;
+                    if self.peek_word() == Some("WITH") {
+                        self.i += 1;
+                    }
+                    start = self.integer(Some("INCREMENT"))?;
+                }
+                Some("INCREMENT") => {
+                    self.i += 1;
+                    if self.peek_word() == Some("BY") {
+                        self.i += 1;
+                    }
+                    increment = self.integer(Some("START"))?;
+                }
+                _ => break,
+            }
+        }
+        if !self.at_semicolon() {
+            return Err(Error::Syntax(format!(
+                "Expected START WITH or INCREMENT BY at {}",
+                self.position()
+            )));
+        }
+        if increment == 0 {
+            return Err(Error::Syntax(format!(
+                "INCREMENT BY of sequence {} cannot be 0",
+                name
+            )));
+        }
+        Ok(Statement::CreateSequence {
+            name,
+            start,
+            increment,
+        })
+    }
+
+    fn sequence_name(&mut self) -> Result<String, Error> {
+        if self.peek_word().is_none() {
+            return Err(Error::Syntax(format!(
+                "Expected a sequence name at {}",
+                self.position()
+            )));
+        }
+        self.identifier()
+    }
+
+    fn integer(&mut self, stop: Option<&str>) -> Result<i64, Error> {
+        let text = self.text_until(stop)?;
+        let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        digits
+            .parse()
+            .map_err(|_| Error::Syntax(format!("Expected an integer but found {}", text)))
+    }
+
+    fn declare(&mut self) -> Result<Statement, Error> {
+        self.expect_word("DECLARE")?;
+        let mut names = vec![self.identifier()?];
+        while self.peek_symbol(',') {
+            self.i += 1;
+            names.push(self.identifier()?);
+        }
+        let data_type = if self.peek_word() == Some("DEFAULT") || self.at_semicolon() {
+            None
+        } else {
+            Some(self.text_until(Some("DEFAULT"))?)
+        };
+        let default = if self.peek_word() == Some("DEFAULT") {
+            self.i += 1;
+            Some(self.text_until(None)?)
+        } else {
+            None
+        };
+        let value = match (data_type, default) {
+            (Some(data_type), Some(default)) => format!("CAST(({}) AS {})", default, data_type),
+            (Some(data_type), None) => format!("CAST(NULL AS {})", data_type),
+            (None, Some(default)) => default,
+            (None, None) => {
+                return Err(Error::Syntax(format!(
+                    "DECLARE {} requires a type or a DEFAULT value",
+                    names.join(", ")
+                )))
+            }
+        };
+        Ok(Statement::Declare { names, value })
+    }
+
+    fn set(&mut self) -> Result<Statement, Error> {
+        self.expect_word("SET")?;
+        if self.peek_symbol('(') {
+            return Err(Error::NotSupported(
+                "SET with multiple variables is not supported".to_string(),
+            ));
+        }
+        let name = self.identifier()?;
+        self.expect_symbol('=')?;
+        let value = self.text_until(None)?;
+        Ok(Statement::Set { name, value })
+    }
+
+    fn if_statement(&mut self) -> Result<Statement, Error> {
+        self.expect_word("IF")?;
+        let mut branches = vec![];
+        let mut otherwise = vec![];
+        loop {
+            let condition = self.text_until(Some("THEN"))?;
+            self.expect_word("THEN")?;
+            let body = self.statements(&["ELSEIF", "ELSE", "END"])?;
+            branches.push((condition, body));
+            match self.peek_word() {
+                Some("ELSEIF") => self.i += 1,
+                Some("ELSE") => {
+                    self.i += 1;
+                    otherwise = self.statements(&["END"])?;
+                    break;
+                }
+                _ => break,
+            }
+        }
+        self.expect_word("END")?;
+        self.expect_word("IF")?;
+        Ok(Statement::If {
+            branches,
+            otherwise,
+        })
+    }
+
+    fn while_statement(&mut self) -> Result<Statement, Error> {
+        self.expect_word("WHILE")?;
+        let condition = self.text_until(Some("DO"))?;
+        self.expect_word("DO")?;
+        let body = self.statements(&["END"])?;
+        self.expect_word("END")?;
+        self.expect_word("WHILE")?;
+        Ok(Statement::While { condition, body })
+    }
+
+    /// The text of an expression or statement, which ends at a semicolon or at the stop word.
+    fn text_until(&mut self, stop: Option<&str>) -> Result<String, Error> {
+        let start = self.i;
+        let mut parens = 0;
+        let mut cases = 0;
+        while self.i < self.tokens.len() {
+            match &self.tokens[self.i].token {
+                Token::Symbol(';') => break,
+                Token::Symbol('(') | Token::Symbol('[') => parens += 1,
+                Token::Symbol(')') | Token::Symbol(']') => parens -= 1,
+                Token::Word(word) if word == "CASE" => cases += 1,
+                Token::Word(word) if word == "END" && cases > 0 => cases -= 1,
+                Token::Word(word) if parens == 0 && cases == 0 && Some(word.as_str()) == stop => {
+                    break
+                }
+                _ => {}
+            }
+            self.i += 1;
+        }
+        if self.i == start {
+            return Err(Error::Syntax(format!(
+                "Expected an expression at {}",
+                self.position()
+            )));
+        }
+        let start = self.tokens[start].start;
+        let end = self.tokens[self.i - 1].end;
+        Ok(self.sql[start..end].to_string())
+    }
+
+    fn identifier(&mut self) -> Result<String, Error> {
+        match self.tokens.get(self.i) {
+            Some(Lexeme {
+                token: Token::Word(_),
+                start,
+                end,
+            }) => {
+                self.i += 1;
+                Ok(self.sql[*start..*end].to_string())
+            }
+            _ => Err(Error::Syntax(format!(
+                "Expected a variable name at {}",
+                self.position()
+            ))),
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), Error> {
+        if self.peek_word() != Some(word) {
+            return Err(Error::Syntax(format!(
+                "Expected {} at {}",
+                word,
+                self.position()
+            )));
+        }
+        self.i += 1;
+        Ok(())
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), Error> {
+        if !self.peek_symbol(symbol) {
+            return Err(Error::Syntax(format!(
+                "Expected {} at {}",
+                symbol,
+                self.position()
+            )));
+        }
+        self.i += 1;
+        Ok(())
+    }
+
+    fn peek_word(&self) -> Option<&str> {
+        self.word_at(self.i)
+    }
+
+    fn word_at(&self, i: usize) -> Option<&str> {
+        match self.tokens.get(i) {
+            Some(Lexeme {
+                token: Token::Word(word),
+                ..
+            }) => Some(word.as_str()),
+            _ => None,
+        }
+    }
+
+    fn peek_symbol(&self, symbol: char) -> bool {
+        matches!(self.tokens.get(self.i), Some(Lexeme { token: Token::Symbol(c), .. }) if *c == symbol)
+    }
+
+    fn at_semicolon(&self) -> bool {
+        self.i == self.tokens.len() || self.peek_symbol(';')
+    }
+
+    fn position(&self) -> String {
+        match self.tokens.get(self.i) {
+            Some(lexeme) => {
+                let text: String = self.sql[lexeme.start..].chars().take(20).collect();
+                format!("{:?}", text)
+            }
+            None => "the end of the script".to_string(),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::script::*;
+
+#[test]
+fn test_plain_statements() {
+    assert_eq!(
+        vec![
+            Statement::Sql("select 1".to_string()),
+            Statement::Sql("select ';' -- end;\n from t".to_string()),
+        ],
+        parse_script("select 1; select ';' -- end;\n from t;").unwrap()
+    );
+}
+
+#[test]
+fn test_declare_set() {
+    assert_eq!(
+        vec![
+            Statement::Declare {
+                names: vec!["x".to_string(), "y".to_string()],
+                value: "CAST((1 + 1) AS INT64)".to_string(),
+            },
+            Statement::Declare {
+                names: vec!["z".to_string()],
+                value: "CAST(NULL AS STRING)".to_string(),
+            },
+            Statement::Set {
+                name: "x".to_string(),
+                value: "x * 2".to_string(),
+            },
+        ],
+        parse_script("DECLARE x, y INT64 DEFAULT 1 + 1; DECLARE z STRING; SET x = x * 2;").unwrap()
+    );
+    assert!(parse_script("declare x;").is_err());
+}
+
+#[test]
+fn test_control_flow() {
+    let sql = "
+        while i < 10 do
+            if case when i = 5 then true else false end then
+                break;
+            elseif i = 3 then
+                continue;
+            else
+                select i;
+            end if;
+        end while;
+        begin
+            loop raise using message = 'done'; end loop;
+        end";
+    assert_eq!(
+        vec![
+            Statement::While {
+                condition: "i < 10".to_string(),
+                body: vec![Statement::If {
+                    branches: vec![
+                        (
+                            "case when i = 5 then true else false end".to_string(),
+                            vec![Statement::Break]
+                        ),
+                        ("i = 3".to_string(), vec![Statement::Continue]),
+                    ],
+                    otherwise: vec![Statement::Sql("select i".to_string())],
+                }],
+            },
+            Statement::Block {
+                body: vec![Statement::Loop {
+                    body: vec![Statement::Raise {
+                        message: Some("'done'".to_string())
+                    }],
+                }],
+            },
+        ],
+        parse_script(sql).unwrap()
+    );
+    assert!(parse_script("if true then select 1;").is_err());
+    assert!(parse_script("end if;").is_err());
+}
+
+#[test]
+fn test_sequences() {
+    assert_eq!(
+        vec![
+            Statement::CreateSequence {
+                name: "s".to_string(),
+                start: 1,
+                increment: 1,
+            },
+            Statement::CreateSequence {
+                name: "t".to_string(),
+                start: -10,
+                increment: 5,
+            },
+            Statement::DropSequence {
+                name: "t".to_string(),
+            },
+        ],
+        parse_script(
+            "create sequence s; create sequence t increment by 5 start with -10; drop sequence if exists t;"
+        )
+        .unwrap()
+    );
+    assert!(parse_script("create sequence s increment by 0;").is_err());
+    assert!(parse_script("create sequence s start 1 cycle;").is_err());
+}
+
+#[test]
+fn test_show_describe() {
+    assert_eq!(
+        vec![Statement::Sql(
+            "select table_name, table_type from information_schema.tables where table_schema = \"db\" and table_name like 'a%' order by table_name".to_string()
+        )],
+        parse_script("show tables from db like 'a%';").unwrap()
+    );
+    match &parse_script("describe table db.`my table`").unwrap()[..] {
+        [Statement::Block { body }] => assert_eq!(
+            Statement::Sql("select column_name, data_type, is_nullable from information_schema.columns where table_schema = \"db\" and table_name = \"my table\" order by ordinal_position".to_string()),
+            body[1]
+        ),
+        other => panic!("{:?}", other),
+    }
+    assert!(parse_script("describe;").is_err());
+}
+
+#[test]
+fn test_string_literals() {
+    let statements = [
+        r#"select r'\d;' as pattern"#,
+        r#"select rb"end if; \" begin" from t"#,
+        r#"select '''it's; end''' as text"#,
+        r#"select """a "quoted"; loop""" as text"#,
+        r#"select r'''\'''; begin''' as text"#,
+        r#"select b'\''"#,
+    ];
+    assert_eq!(
+        statements
+            .iter()
+            .map(|sql| Statement::Sql(sql.to_string()))
+            .collect::<Vec<_>>(),
+        parse_script(&statements.join("; ")).unwrap()
+    );
+    assert_eq!(
+        vec![
+            Statement::Declare {
+                names: vec!["x".to_string()],
+                value: r"r'end; if\''".to_string(),
+            },
+            Statement::Set {
+                name: "x".to_string(),
+                value: "'''then; end if'''".to_string(),
+            },
+        ],
+        parse_script(r"declare x default r'end; if\''; set x = '''then; end if''';").unwrap()
+    );
+}
@@ -126,6 +126,11 @@ impl SearchSpace {
             // Recursively copy in the children.
             for i in 0..expr.len() {
                 self.copy_in_new(&mut expr[i]);
+                // Record the working table of a recursive query before copying in the step that reads it.
+                if let (LogicalIterate { name, base, .. }, 0) = (&*expr, i) {
+                    self.temp_tables
+                        .insert(name.clone(), self[leaf(base)].props.clone());
+                }
             }
             // Record temp tables.
             if let LogicalCreateTempTable { name, input, .. } = expr {
@@ -361,6 +366,13 @@ impl PhysicalProp {
             (NestedLoop { .. }, 0) => PhysicalProp::BroadcastDist,
             (SimpleAggregate { .. }, 0) => PhysicalProp::GatherDist,
             (GroupByAggregate { .. }, 0) => PhysicalProp::ExchangeDist,
+            (
+                Insert {
+                    partition_by: Some(_),
+                    ..
+                },
+                0,
+            ) => PhysicalProp::ExchangeDist,
             (Call { .. }, 0) => PhysicalProp::BroadcastDist,
             (Sort { .. }, 0) => PhysicalProp::GatherDist,
             (_, _) => PhysicalProp::None,
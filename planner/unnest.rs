@@ -253,7 +253,7 @@ fn push_both(
     // Substitute fresh column names for the left side subquery.
     let left_parameters: Vec<_> = parameters
         .iter()
-        .map(|c| Column::fresh(&c.name, c.data_type))
+        .map(|c| Column::fresh(&c.name, c.data_type.clone()))
         .collect();
     let left_parameters_map: HashMap<_, _> = (0..parameters.len())
         .map(|i| (parameters[i].clone(), left_parameters[i].clone()))
@@ -290,11 +290,8 @@ fn push_both(
 fn any_value(attributes: HashSet<Column>) -> Vec<AggregateExpr> {
     let mut attributes: Vec<_> = attributes
         .iter()
-        .map(|column| AggregateExpr {
-            function: AggregateFunction::AnyValue,
-            distinct: false,
-            input: column.clone(),
-            output: column.clone(),
+        .map(|column| {
+            AggregateExpr::new(AggregateFunction::AnyValue, column.clone(), column.clone())
         })
         .collect();
     attributes.sort_by(|a, b| a.input.cmp(&b.input));
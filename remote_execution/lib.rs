@@ -16,22 +16,37 @@ use tonic::{transport::Channel, Streaming};
 
 pub struct RecordStream {
     select: SelectAll<Streaming<Page>>,
+    rows_affected: i64,
 }
 
 impl RecordStream {
     fn new(streams: Vec<Streaming<Page>>) -> Self {
         Self {
             select: select_all(streams),
+            rows_affected: 0,
         }
     }
 
     pub fn next(&mut self) -> Next {
-        match log::rpc(self.select.next()) {
-            Some(Ok(page)) => Next::Page(unwrap_page(page)?),
-            Some(Err(status)) => Next::Error(status.message().to_string()),
-            None => Next::End,
+        loop {
+            match log::rpc(self.select.next()) {
+                Some(Ok(page)) => match page.part.unwrap() {
+                    Part::RecordBatch(bytes) => {
+                        return Next::Page(bincode::deserialize(&bytes).unwrap())
+                    }
+                    Part::Error(error) => return Next::Error(error.into()),
+                    Part::RowsAffected(count) => self.rows_affected += count,
+                },
+                Some(Err(status)) => return Next::Error(rpc::from_status(&status)),
+                None => return Next::End,
+            }
         }
     }
+
+    /// The number of rows inserted, updated or deleted by every worker, which is complete once next() returns End.
+    pub fn rows_affected(&self) -> i64 {
+        self.rows_affected
+    }
 }
 
 /// Submit a query to the coordinator.
@@ -50,6 +65,7 @@ pub fn submit(
                 .collect(),
             catalog_id,
             txn,
+            session: None,
         };
         let response = coordinator()
             .await
@@ -123,16 +139,6 @@ pub fn exchange(
     })
 }
 
-fn unwrap_page(page: Page) -> Result<RecordBatch, String> {
-    match page.part.unwrap() {
-        Part::RecordBatch(bytes) => {
-            let batch = bincode::deserialize(&bytes).unwrap();
-            Ok(batch)
-        }
-        Part::Error(error) => Err(error),
-    }
-}
-
 pub async fn coordinator() -> CoordinatorClient<Channel> {
     static COORDINATOR: OnceCell<String> = OnceCell::new();
     let url = COORDINATOR.get_or_init(|| std::env::var("COORDINATOR").unwrap());
@@ -0,0 +1,48 @@
+use kernel::Error;
+use tonic::{metadata::MetadataValue, Code, Status};
+
+use crate::QueryError;
+
+impl From<Error> for QueryError {
+    fn from(error: Error) -> Self {
+        QueryError {
+            sqlstate: error.sqlstate().to_string(),
+            message: error.message().to_string(),
+        }
+    }
+}
+
+impl From<QueryError> for Error {
+    fn from(error: QueryError) -> Self {
+        Error::from_sqlstate(&error.sqlstate, error.message)
+    }
+}
+
+/// The gRPC status that reports error to a client, with the SQLSTATE code in the sqlstate metadata entry.
+pub fn to_status(error: Error) -> Status {
+    let code = match &error {
+        Error::Syntax(_) | Error::DivisionByZero(_) | Error::DataException(_) => {
+            Code::InvalidArgument
+        }
+        Error::NotSupported(_) => Code::Unimplemented,
+        Error::Constraint(_) | Error::InvalidSession(_) => Code::FailedPrecondition,
+        Error::SerializationFailure(_) | Error::Raise(_) => Code::Aborted,
+        Error::Internal(_) => Code::Internal,
+    };
+    let mut status = Status::new(code, error.message());
+    status
+        .metadata_mut()
+        .insert("sqlstate", MetadataValue::from_static(error.sqlstate()));
+    status
+}
+
+/// The inverse of to_status(), for errors returned by another node.
+pub fn from_status(status: &Status) -> Error {
+    match status.metadata().get("sqlstate") {
+        Some(sqlstate) => Error::from_sqlstate(
+            sqlstate.to_str().unwrap_or_default(),
+            status.message().to_string(),
+        ),
+        None => Error::Internal(status.message().to_string()),
+    }
+}
@@ -1,6 +1,7 @@
+mod error;
 mod page_stream;
 mod rpc;
 mod runtime;
 
-pub use crate::{page_stream::*, rpc::*};
+pub use crate::{error::*, page_stream::*, rpc::*};
 pub use runtime::*;
@@ -24,7 +24,7 @@ use anyhow::{anyhow, bail};
 use chrono::{Date, DateTime, NaiveDate, TimeZone, Utc};
 use coordinator::CoordinatorNode;
 use defaults::{METADATA_CATALOG_ID, ROOT_CATALOG_ID};
-use kernel::{AnyArray, Array, RecordBatch};
+use kernel::{bytes_literal, AnyArray, Array, RecordBatch};
 use lazy_static::lazy_static;
 use md5::{Digest, Md5};
 use once_cell::sync::OnceCell;
@@ -32,7 +32,7 @@ use rand::Rng;
 use regex::Regex;
 use rpc::{
     coordinator_client::CoordinatorClient, coordinator_server::CoordinatorServer,
-    worker_server::WorkerServer, CheckRequest, QueryRequest,
+    worker_server::WorkerServer, CheckRequest, QueryRequest, StartSessionRequest,
 };
 use tonic::transport::{Channel, Endpoint, Server};
 use walkdir::WalkDir;
@@ -269,6 +269,7 @@ impl<'a> fmt::Display for OutcomesDisplay<'a> {
 
 pub(crate) struct Runner {
     catalog_id: i64,
+    session: i64,
     // Drop order matters for these fields.
     client: CoordinatorClient<Channel>,
 }
@@ -295,7 +296,17 @@ fn format_datum(column: &AnyArray, typ: &Type, row: usize, col: usize) -> Option
                 s
             }
         }
+        (Type::Text, AnyArray::Json(j)) => j.get(row)?,
+        (Type::Text, AnyArray::Bytes(b)) => bytes_literal(b.get_bytes(row)?),
+        (Type::Text, AnyArray::Array(a)) => {
+            let elements = a.get(row)?;
+            let formatted: Vec<String> = (0..elements.len())
+                .map(|i| format_datum(&elements, typ, i, col).unwrap_or("NULL".into()))
+                .collect();
+            format!("[{}]", formatted.join(", "))
+        }
         (Type::Text, AnyArray::Bool(b)) => b.get(row)?.to_string(),
+        (Type::Text, AnyArray::I64(i)) => i.get(row)?.to_string(),
         (Type::Text, AnyArray::F64(f)) => format!("{:.3}", f.get(row)?),
         (Type::Integer, AnyArray::Date(f)) | (Type::Text, AnyArray::Date(f)) => {
             date(f.get(row)?).format("%F").to_string()
@@ -328,7 +339,17 @@ impl Runner {
     pub async fn start() -> Self {
         let mut client = connect_to_cluster().await;
         let catalog_id = next_catalog(&mut client).await;
-        Runner { catalog_id, client }
+        let session = client
+            .start_session(StartSessionRequest {})
+            .await
+            .unwrap()
+            .into_inner()
+            .session;
+        Runner {
+            catalog_id,
+            session,
+            client,
+        }
     }
 
     async fn run_record<'a>(
@@ -555,10 +576,11 @@ impl Runner {
             sql: sql.to_string(),
             catalog_id: self.catalog_id,
             txn: None,
+            session: Some(self.session),
             params: HashMap::default(),
         };
-        let _response = self.client.statement(request).await?.into_inner();
-        Ok(0) // TODO
+        let response = self.client.statement(request).await?.into_inner();
+        Ok(response.rows_affected() as u64)
     }
 
     async fn query(&mut self, sql: &str) -> Result<RecordBatch, anyhow::Error> {
@@ -566,6 +588,7 @@ impl Runner {
             sql: sql.to_string(),
             catalog_id: self.catalog_id,
             txn: None,
+            session: Some(self.session),
             params: HashMap::default(),
         };
         let response = self.client.query(request).await?.into_inner();
@@ -905,6 +928,7 @@ async fn next_catalog(client: &mut CoordinatorClient<Channel>) -> i64 {
             sql: format!("create database test{:x}", next_catalog),
             catalog_id: ROOT_CATALOG_ID,
             txn: None,
+            session: None,
             params: HashMap::default(),
         })
         .await
@@ -918,6 +942,7 @@ async fn next_catalog(client: &mut CoordinatorClient<Channel>) -> i64 {
             ),
             catalog_id: METADATA_CATALOG_ID,
             txn: None,
+            session: None,
             params: HashMap::default(),
         })
         .await
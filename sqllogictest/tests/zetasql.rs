@@ -0,0 +1,123 @@
+use sqllogictest::runner::test;
+
+#[test]
+fn test_safe() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/safe/test_safe_cast.test"]));
+}
+
+#[test]
+fn test_errors() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/errors/test_not_supported.test"]));
+}
+
+#[test]
+fn test_merge() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/merge/test_merge.test"]));
+}
+
+#[test]
+fn test_alter() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/alter/test_alter_columns.test"]));
+}
+
+#[test]
+fn test_views() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/views/test_materialized_views.test",
+    ]));
+}
+
+#[test]
+fn test_constraints() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/constraints/test_constraints.test",
+    ]));
+}
+
+#[test]
+fn test_foreign_keys() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/foreign_keys/test_foreign_keys.test",
+    ]));
+}
+
+#[test]
+fn test_recursive() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/recursive/test_recursive.test"]));
+}
+
+#[test]
+fn test_aggregates() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/aggregates/test_aggregates.test",
+        "./tests/zetasql/aggregates/test_modifiers.test",
+        "./tests/zetasql/aggregates/test_arrays.test",
+    ]));
+}
+
+#[test]
+fn test_grouping() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/grouping/test_rollup.test"]));
+}
+
+#[test]
+fn test_functions() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/functions/test_functions.test",
+        "./tests/zetasql/functions/test_bytes.test",
+        "./tests/zetasql/functions/test_arrays.test",
+        "./tests/zetasql/functions/test_json.test",
+        "./tests/zetasql/functions/test_time_zones.test",
+    ]));
+}
+
+#[test]
+fn test_scripts() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/scripts/test_scripts.test"]));
+}
+
+#[test]
+fn test_dml() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/dml/test_rows_affected.test",
+        "./tests/zetasql/dml/test_truncate.test",
+        "./tests/zetasql/dml/test_create_table_as.test",
+    ]));
+}
+
+#[test]
+fn test_sequences() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/sequences/test_sequences.test"]));
+}
+
+#[test]
+fn test_information_schema() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/information_schema/test_information_schema.test",
+    ]));
+}
+
+#[test]
+fn test_joins() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/joins/test_join_subqueries.test",
+    ]));
+}
+
+#[test]
+fn test_indexes() {
+    rpc::runtime().block_on(test(vec![
+        "./tests/zetasql/indexes/test_index_order.test",
+        "./tests/zetasql/indexes/test_index_keys.test",
+    ]));
+}
+
+#[test]
+fn test_temp_tables() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/temp/test_temp_tables.test"]));
+}
+
+#[test]
+fn test_sample() {
+    rpc::runtime().block_on(test(vec!["./tests/zetasql/sample/test_tablesample.test"]));
+}
@@ -17,6 +17,11 @@ pub enum ColumnStatistics {
     Date(TypedColumnStatistics<i32>),
     Timestamp(TypedColumnStatistics<i64>),
     String(TypedColumnStatistics<String>),
+    Bytes(TypedColumnStatistics<Vec<u8>>),
+    Json(TypedColumnStatistics<String>),
+    /// ARRAY values have no order, so their statistics track the hash of each value,
+    /// which is still enough to count distinct values.
+    Array(DataType, TypedColumnStatistics<i64>),
 }
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -59,6 +64,11 @@ impl ColumnStatistics {
             DataType::Date => ColumnStatistics::Date(TypedColumnStatistics::default()),
             DataType::Timestamp => ColumnStatistics::Timestamp(TypedColumnStatistics::default()),
             DataType::String => ColumnStatistics::String(TypedColumnStatistics::default()),
+            DataType::Bytes => ColumnStatistics::Bytes(TypedColumnStatistics::default()),
+            DataType::Json => ColumnStatistics::Json(TypedColumnStatistics::default()),
+            DataType::Array(element_type) => {
+                ColumnStatistics::Array(*element_type, TypedColumnStatistics::default())
+            }
         }
     }
 
@@ -82,6 +92,19 @@ impl ColumnStatistics {
             (ColumnStatistics::String(left), ColumnStatistics::String(right)) => {
                 ColumnStatistics::String(TypedColumnStatistics::union(left, right))
             }
+            (ColumnStatistics::Bytes(left), ColumnStatistics::Bytes(right)) => {
+                ColumnStatistics::Bytes(TypedColumnStatistics::union(left, right))
+            }
+            (ColumnStatistics::Json(left), ColumnStatistics::Json(right)) => {
+                ColumnStatistics::Json(TypedColumnStatistics::union(left, right))
+            }
+            (
+                ColumnStatistics::Array(element_type, left),
+                ColumnStatistics::Array(other_type, right),
+            ) if element_type == other_type => ColumnStatistics::Array(
+                element_type.clone(),
+                TypedColumnStatistics::union(left, right),
+            ),
             (_, _) => panic!("{} does not match {}", left.data_type(), right.data_type()),
         }
     }
@@ -94,6 +117,11 @@ impl ColumnStatistics {
             ColumnStatistics::Date { .. } => DataType::Date,
             ColumnStatistics::Timestamp { .. } => DataType::Timestamp,
             ColumnStatistics::String { .. } => DataType::String,
+            ColumnStatistics::Bytes { .. } => DataType::Bytes,
+            ColumnStatistics::Json { .. } => DataType::Json,
+            ColumnStatistics::Array(element_type, _) => {
+                DataType::Array(Box::new(element_type.clone()))
+            }
         }
     }
 
@@ -105,6 +133,9 @@ impl ColumnStatistics {
             ColumnStatistics::Date(typed) => typed.count_distinct(),
             ColumnStatistics::Timestamp(typed) => typed.count_distinct(),
             ColumnStatistics::String(typed) => typed.count_distinct(),
+            ColumnStatistics::Bytes(typed) => typed.count_distinct(),
+            ColumnStatistics::Json(typed) => typed.count_distinct(),
+            ColumnStatistics::Array(_, typed) => typed.count_distinct(),
         }
     }
 
@@ -132,6 +163,17 @@ impl ColumnStatistics {
             (ColumnStatistics::String(typed), AnyArray::String(array)) => {
                 typed.insert(array, |x| Some(x.to_string()));
             }
+            (ColumnStatistics::Bytes(typed), AnyArray::Bytes(array)) => {
+                typed.insert(array, |x| Some(x));
+            }
+            (ColumnStatistics::Json(typed), AnyArray::Json(array)) => {
+                typed.insert(array, |x| Some(x.to_string()));
+            }
+            (ColumnStatistics::Array(_, typed), AnyArray::Array(array)) => {
+                let mut hashes = I64Array::from_values(vec![0].repeat(array.len()));
+                array.hash(&mut hashes);
+                typed.insert(&hashes.compress(&array.is_null().not()), |x| Some(x));
+            }
             (left, right) => panic!("{} does not match {}", left.data_type(), right.data_type()),
         }
     }
@@ -31,6 +31,45 @@ impl Art {
     }
 
     pub fn range<'a>(&self, bounds: impl RangeBounds<&'a [u8]>) -> Vec<i64> {
+        let mut acc = vec![];
+        self.visit(bounds, |_, value| {
+            acc.push(value);
+            true
+        });
+        acc
+    }
+
+    /// The first limit values whose keys come after the key `after`, or from the start if `after` is None,
+    /// along with the key of the last value if there may be more, so the next call can continue where this one stopped.
+    pub fn range_after(&self, after: Option<&[u8]>, limit: usize) -> (Vec<i64>, Option<Vec<u8>>) {
+        let mut acc = vec![];
+        let mut last = None;
+        if limit == 0 {
+            return (acc, last);
+        }
+        // The first key after `after` is `after` followed by the smallest digit.
+        let mut start = vec![];
+        if let Some(key) = after {
+            start.extend_from_slice(key);
+            start.push(0);
+        }
+        self.visit(start.as_slice().., |key, value| {
+            acc.push(value);
+            if acc.len() < limit {
+                true
+            } else {
+                last = Some(key.to_vec());
+                false
+            }
+        });
+        (acc, last)
+    }
+
+    fn visit<'a>(
+        &self,
+        bounds: impl RangeBounds<&'a [u8]>,
+        mut visit: impl FnMut(&[u8], i64) -> bool,
+    ) {
         fn inc(key: &[u8]) -> Vec<u8> {
             if key.is_empty() {
                 vec![0]
@@ -59,13 +98,12 @@ impl Art {
         } else {
             None
         };
-        let mut acc = vec![];
         self.root.range(
             LowerBound(&start_inclusive),
             UpperBound(end_exclusive),
-            &mut acc,
+            &mut vec![],
+            &mut visit,
         );
-        acc
     }
 }
 
@@ -221,97 +259,91 @@ impl Node {
         // TODO restore path compression.
     }
 
+    /// Visit the values in [start_inclusive, end_exclusive) in key order, until visit returns false.
+    /// key is the prefix of the keys in this node, and visit receives the full key of each value.
     fn range(
         &self,
         start_inclusive: LowerBound,
         end_exclusive: UpperBound,
-        acc: &mut Vec<i64>,
-    ) -> Option<()> {
-        match self {
-            Node::Null => Some(()),
+        key: &mut Vec<u8>,
+        visit: &mut impl FnMut(&[u8], i64) -> bool,
+    ) -> bool {
+        let (prefix, value) = match self {
+            Node::Null => return true,
             Node::Leaf(node) => {
-                if start_inclusive.le(&node.key[..]) && end_exclusive.gt(&node.key[..]) {
-                    acc.push(node.value);
+                if !start_inclusive.le(&node.key[..]) || !end_exclusive.gt(&node.key[..]) {
+                    return true;
+                }
+                let len = key.len();
+                key.extend_from_slice(&node.key);
+                let more = visit(key, node.value);
+                key.truncate(len);
+                return more;
+            }
+            Node::Node4(node) => (&node.key[..], node.value),
+            Node::Node16(node) => (&node.key[..], node.value),
+            Node::Node48(node) => (&node.key[..], node.value),
+            Node::Node256(node) => (&node.key[..], node.value),
+        };
+        let start_inclusive = match start_inclusive.drop_prefix(prefix) {
+            Some(start_inclusive) => start_inclusive,
+            None => return true,
+        };
+        let end_exclusive = match end_exclusive.drop_prefix(prefix) {
+            Some(end_exclusive) => end_exclusive,
+            None => return true,
+        };
+        let len = key.len();
+        key.extend_from_slice(prefix);
+        let mut more = true;
+        if start_inclusive.is_empty() {
+            if let Some(value) = value {
+                more = visit(key, value);
+            }
+        }
+        let mut range_child = |digit: u8, child: &Node| {
+            if let Some(start_inclusive) = start_inclusive.drop_prefix(&[digit]) {
+                if let Some(end_exclusive) = end_exclusive.drop_prefix(&[digit]) {
+                    key.push(digit);
+                    let more = child.range(start_inclusive, end_exclusive, key, visit);
+                    key.pop();
+                    return more;
                 }
-                Some(())
             }
+            true
+        };
+        // Node4 and Node16 keep their digits in the order they were inserted, so visit them in sorted order.
+        match self {
             Node::Node4(node) => {
-                let start_inclusive = start_inclusive.drop_prefix(&node.key[..])?;
-                let end_exclusive = end_exclusive.drop_prefix(&node.key[..])?;
-                if start_inclusive.is_empty() {
-                    if let Some(value) = node.value {
-                        acc.push(value);
-                    }
+                for i in digit_order(&node.digit[..node.count]) {
+                    more = more && range_child(node.digit[i], &node.child[i]);
                 }
-                for i in 0..node.count {
-                    let digit = &node.digit[i..=i];
-                    if let Some(start_inclusive) = start_inclusive.drop_prefix(digit) {
-                        if let Some(end_exclusive) = end_exclusive.drop_prefix(digit) {
-                            node.child[i].range(start_inclusive, end_exclusive, acc);
-                        }
-                    }
-                }
-                Some(())
             }
             Node::Node16(node) => {
-                let start_inclusive = start_inclusive.drop_prefix(&node.key[..])?;
-                let end_exclusive = end_exclusive.drop_prefix(&node.key[..])?;
-                if start_inclusive.is_empty() {
-                    if let Some(value) = node.value {
-                        acc.push(value);
-                    }
+                for i in digit_order(&node.digit[..node.count]) {
+                    more = more && range_child(node.digit[i], &node.child[i]);
                 }
-                for i in 0..node.count {
-                    let digit = &node.digit[i..=i];
-                    if let Some(start_inclusive) = start_inclusive.drop_prefix(digit) {
-                        if let Some(end_exclusive) = end_exclusive.drop_prefix(digit) {
-                            node.child[i].range(start_inclusive, end_exclusive, acc);
-                        }
-                    }
-                }
-                Some(())
             }
             Node::Node48(node) => {
-                let start_inclusive = start_inclusive.drop_prefix(&node.key[..])?;
-                let end_exclusive = end_exclusive.drop_prefix(&node.key[..])?;
-                if start_inclusive.is_empty() {
-                    if let Some(value) = node.value {
-                        acc.push(value);
-                    }
-                }
                 for digit in 0..node.child_index.len() {
                     if node.child_index[digit] != EMPTY {
-                        if let Some(start_inclusive) = start_inclusive.drop_prefix(&[digit as u8]) {
-                            if let Some(end_exclusive) = end_exclusive.drop_prefix(&[digit as u8]) {
-                                node.child[node.child_index[digit as usize] as usize].range(
-                                    start_inclusive,
-                                    end_exclusive,
-                                    acc,
-                                );
-                            }
-                        }
+                        more = more
+                            && range_child(
+                                digit as u8,
+                                &node.child[node.child_index[digit] as usize],
+                            );
                     }
                 }
-                Some(())
             }
             Node::Node256(node) => {
-                let start_inclusive = start_inclusive.drop_prefix(&node.key[..])?;
-                let end_exclusive = end_exclusive.drop_prefix(&node.key[..])?;
-                if start_inclusive.is_empty() {
-                    if let Some(value) = node.value {
-                        acc.push(value);
-                    }
-                }
                 for digit in 0..node.child.len() {
-                    if let Some(start_inclusive) = start_inclusive.drop_prefix(&[digit as u8]) {
-                        if let Some(end_exclusive) = end_exclusive.drop_prefix(&[digit as u8]) {
-                            node.child[digit as usize].range(start_inclusive, end_exclusive, acc);
-                        }
-                    }
+                    more = more && range_child(digit as u8, &node.child[digit]);
                 }
-                Some(())
             }
+            Node::Null | Node::Leaf(_) => unreachable!(),
         }
+        key.truncate(len);
+        more
     }
 
     fn insert(&mut self, key: &[u8], value: i64) -> Option<i64> {
@@ -650,6 +682,13 @@ impl<'a> UpperBound<'a> {
     }
 }
 
+/// The positions of digits, in the order of the digits.
+fn digit_order(digit: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..digit.len()).collect();
+    order.sort_unstable_by_key(|i| digit[*i]);
+    order
+}
+
 fn head(slice: &[u8], n: usize) -> &[u8] {
     if n < slice.len() {
         &slice[..n]
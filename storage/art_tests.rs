@@ -416,3 +416,42 @@ fn partial_range() {
         }
     }
 }
+
+#[test]
+fn range_in_key_order() {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut m = Art::empty();
+    let mut keys: Vec<u16> = (0..1000).collect();
+    for i in (1..keys.len()).rev() {
+        keys.swap(i, rng.gen_range(0..=i));
+    }
+    for key in &keys {
+        m.insert(format!("{:04X}", key).as_bytes(), *key as i64);
+    }
+    let expected: Vec<i64> = (0..1000).collect();
+    assert_eq!(expected, m.range(..));
+}
+
+#[test]
+fn range_after() {
+    let mut m = Art::empty();
+    for i in (0..100).rev() {
+        m.insert(format!("{:02}", i).as_bytes(), i as i64);
+    }
+    m.insert("1".as_bytes(), 100);
+    m.insert("10a".as_bytes(), 101);
+    let expected = m.range(..);
+    let mut found = vec![];
+    let mut after: Option<Vec<u8>> = None;
+    loop {
+        let (values, last) = m.range_after(after.as_deref(), 7);
+        assert!(values.len() <= 7);
+        found.extend(values);
+        match last {
+            Some(key) => after = Some(key),
+            None => break,
+        }
+    }
+    assert_eq!(expected, found);
+    assert_eq!((vec![], None), m.range_after(None, 0));
+}
@@ -21,6 +21,14 @@ pub fn byte_key_f64(value: f64) -> [u8; 8] {
     bits.to_be_bytes()
 }
 
+/// Complement every byte of a key, reversing its order relative to other complemented keys.
+/// Variable-length keys must be terminated so that no key is a prefix of another.
+pub fn byte_key_desc(key: &mut [u8]) {
+    for byte in key.iter_mut() {
+        *byte = !*byte;
+    }
+}
+
 // 0111111111111000000000000000000000000000000000000000000000000000        1111111111111000000000000000000000000000000000000000000000000000        f64::NAN                nan
 // 0111111111110000000000000000000000000000000000000000000000000000        1111111111110000000000000000000000000000000000000000000000000000        f64::INFINITY           positive infinity
 // 1111111111110000000000000000000000000000000000000000000000000000        0111111111110000000000000000000000000000000000000000000000000000        f64::NEG_INFINITY       negative infinity
@@ -45,6 +45,27 @@ fn test_f64() {
     ])
 }
 
+#[test]
+fn test_desc() {
+    let desc = |mut key: Vec<u8>| {
+        byte_key_desc(&mut key);
+        key
+    };
+    assert_ordered(vec![
+        desc(byte_key_i64(2i64).to_vec()),
+        desc(byte_key_i64(1i64).to_vec()),
+        desc(byte_key_i64(-1i64).to_vec()),
+        desc(byte_key_i64(i64::MIN).to_vec()),
+    ]);
+    // Terminated strings: a longer string sorts before its prefix.
+    assert_ordered(vec![
+        desc(b"b\0".to_vec()),
+        desc(b"abc\0".to_vec()),
+        desc(b"ab\0".to_vec()),
+        desc(b"a\0".to_vec()),
+    ]);
+}
+
 fn assert_ordered(examples: Vec<Vec<u8>>) {
     for i in 0..examples.len() {
         for j in 0..examples.len() {
@@ -1,6 +1,7 @@
 use std::{fmt, sync::Arc};
 
 use kernel::*;
+use rand::Rng;
 use statistics::TableStatistics;
 
 use crate::page::*;
@@ -8,16 +9,41 @@ use crate::page::*;
 // Heap represents a logical table as a list of pages.
 // New tuples are added to the end of the heap.
 // Deleted tuples are periodically garbage-collected and the heap is compacted.
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct Heap {
     pages: Vec<Arc<Page>>,
+    // The number of tuples in each page, set by the page_size option of CREATE TABLE.
+    page_size: usize,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::with_page_size(PAGE_SIZE)
+    }
 }
 
 impl Heap {
+    pub fn with_page_size(page_size: usize) -> Self {
+        Self {
+            pages: vec![],
+            page_size,
+        }
+    }
+
     pub fn scan(&self) -> Vec<Arc<Page>> {
         self.pages.clone()
     }
 
+    /// Scan a random subset of the pages, keeping each one with probability `fraction`.
+    /// TABLESAMPLE SYSTEM uses this so it doesn't have to read the pages it skips.
+    pub fn sample(&self, fraction: f64, rng: &mut impl Rng) -> Vec<Arc<Page>> {
+        self.pages
+            .iter()
+            .filter(|_| rng.gen_bool(fraction))
+            .cloned()
+            .collect()
+    }
+
     pub fn bitmap_scan(&self, sorted_tids: &Vec<i64>) -> Vec<Arc<Page>> {
         if sorted_tids.is_empty() {
             return vec![];
@@ -45,9 +71,16 @@ impl Heap {
     }
 
     pub fn insert(&mut self, records: &RecordBatch, txn: i64) -> I64Array {
-        if self.pages.is_empty() {
-            self.pages
-                .push(Arc::new(Page::empty(self.pages.len(), records.schema())));
+        // If the schema of the table has changed, or the last page was truncated, start a new page.
+        if self.pages.is_empty()
+            || !self.pages.last().unwrap().accepts(records)
+            || self.pages.last().unwrap().is_truncated()
+        {
+            self.pages.push(Arc::new(Page::with_capacity(
+                self.pages.len(),
+                records.schema(),
+                self.page_size,
+            )));
         }
         // Allocate arrays to keep track of where we insert the rows.
         let mut tids = I64Array::with_capacity(records.len());
@@ -70,8 +103,11 @@ impl Heap {
         last.insert(records, txn, tids, offset);
         // If there are leftover records, add a page and try again.
         if *offset < records.len() {
-            self.pages
-                .push(Arc::new(Page::empty(self.pages.len(), records.schema())));
+            self.pages.push(Arc::new(Page::with_capacity(
+                self.pages.len(),
+                records.schema(),
+                self.page_size,
+            )));
             self.insert_more(records, txn, tids, offset);
         }
     }
@@ -84,6 +120,18 @@ impl Heap {
         self.pages = vec![];
     }
 
+    /// Delete every row in the heap as of txn, like TRUNCATE TABLE, returning the number of rows that were deleted,
+    /// or None if a concurrent transaction changed the heap.
+    /// Unlike DELETE, this marks each page as truncated instead of setting the XMAX of every row,
+    /// so transactions that started earlier can still see the old rows.
+    pub fn truncate_as_of(&self, txn: i64) -> Option<usize> {
+        let mut count = 0;
+        for page in &self.pages {
+            count += page.truncate(txn)?;
+        }
+        Some(count)
+    }
+
     pub(crate) fn is_uninitialized(&self) -> bool {
         self.pages.is_empty()
     }
@@ -1,4 +1,5 @@
 use kernel::*;
+use rand::{rngs::SmallRng, SeedableRng};
 
 use crate::heap::*;
 
@@ -53,3 +54,111 @@ fn test_insert_new_page() {
         1000,
     );
 }
+
+#[test]
+fn test_alter_columns() {
+    let mut heap = Heap::default();
+    heap.insert(
+        &RecordBatch::new(vec![(
+            "a".to_string(),
+            AnyArray::I64(I64Array::from_options(vec![Some(1)])),
+        )]),
+        1000,
+    );
+    // Add a column b, which doesn't fit in the first page.
+    heap.insert(
+        &RecordBatch::new(vec![
+            (
+                "a".to_string(),
+                AnyArray::I64(I64Array::from_options(vec![Some(2)])),
+            ),
+            (
+                "b".to_string(),
+                AnyArray::I64(I64Array::from_options(vec![Some(20)])),
+            ),
+        ]),
+        2000,
+    );
+    let schema = vec![
+        ("a".to_string(), DataType::I64),
+        ("b".to_string(), DataType::I64),
+    ];
+    let found: Vec<String> = heap
+        .scan()
+        .iter()
+        .map(|page| format!("{:?}", page.select_or_null(&schema)))
+        .collect();
+    assert_eq!(
+        vec!["a b   \n1 NULL".to_string(), "a b \n2 20".to_string()],
+        found
+    );
+}
+
+#[test]
+fn test_truncate() {
+    let mut heap = Heap::default();
+    let batch = |value| {
+        RecordBatch::new(vec![(
+            "a".to_string(),
+            AnyArray::I64(I64Array::from_options(vec![Some(value)])),
+        )])
+    };
+    heap.insert(&batch(1), 1000);
+    assert_eq!(Some(1), heap.truncate_as_of(2000));
+    // Truncating again in the same transaction finds no rows, but a transaction that started earlier conflicts.
+    assert_eq!(Some(0), heap.truncate_as_of(2000));
+    assert_eq!(None, heap.truncate_as_of(1500));
+    heap.insert(&batch(2), 2000);
+    let found: Vec<String> = heap
+        .scan()
+        .iter()
+        .map(|page| {
+            format!(
+                "{:?}",
+                page.select(&vec!["a".to_string(), "$xmax".to_string()])
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "a $xmax\n1 2000 ".to_string(),
+            "a $xmax              \n2 9223372036854775807".to_string()
+        ],
+        found
+    );
+}
+
+#[test]
+fn test_page_size() {
+    let mut heap = Heap::with_page_size(4);
+    let tids = heap.insert(
+        &RecordBatch::new(vec![(
+            "a".to_string(),
+            AnyArray::I64(I64Array::from_values((0..10).collect())),
+        )]),
+        1000,
+    );
+    assert_eq!(3, heap.scan().len());
+    let page_size = crate::page::PAGE_SIZE as i64;
+    assert_eq!(
+        vec![0, 1, 2, 3, page_size, page_size + 1],
+        (0..6).map(|i| tids.get(i).unwrap()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_sample() {
+    let mut heap = Heap::with_page_size(4);
+    heap.insert(
+        &RecordBatch::new(vec![(
+            "a".to_string(),
+            AnyArray::I64(I64Array::from_values((0..400).collect())),
+        )]),
+        1000,
+    );
+    let mut rng = SmallRng::seed_from_u64(0);
+    assert_eq!(0, heap.sample(0.0, &mut rng).len());
+    assert_eq!(100, heap.sample(1.0, &mut rng).len());
+    let half = heap.sample(0.5, &mut rng).len();
+    assert!(25 < half && half < 75, "{}", half);
+}
@@ -19,66 +19,99 @@ pub const PAGE_SIZE: usize = 1024;
 pub struct Page {
     pid: usize,
     columns: Vec<(String, Data)>,
-    xmin: [AtomicI64; PAGE_SIZE],
-    xmax: [AtomicI64; PAGE_SIZE],
+    xmin: Vec<AtomicI64>,
+    xmax: Vec<AtomicI64>,
+    // TRUNCATE TABLE deletes every row of the page at once by lowering the XMAX of the whole page.
+    truncated: AtomicI64,
     len: AtomicUsize,
+    // Tables created with a smaller page_size option allocate only capacity slots in each page.
+    capacity: usize,
 }
 
 enum Data {
     Bool {
-        values: [u8; PAGE_SIZE / 8],
-        is_valid: [u8; PAGE_SIZE / 8],
+        values: Vec<u8>,
+        is_valid: Vec<u8>,
     },
     I64 {
-        values: [i64; PAGE_SIZE],
-        is_valid: [u8; PAGE_SIZE / 8],
+        values: Vec<i64>,
+        is_valid: Vec<u8>,
     },
     F64 {
-        values: [f64; PAGE_SIZE],
-        is_valid: [u8; PAGE_SIZE / 8],
+        values: Vec<f64>,
+        is_valid: Vec<u8>,
     },
     Date {
-        values: [i32; PAGE_SIZE],
-        is_valid: [u8; PAGE_SIZE / 8],
+        values: Vec<i32>,
+        is_valid: Vec<u8>,
     },
     Timestamp {
-        values: [i64; PAGE_SIZE],
-        is_valid: [u8; PAGE_SIZE / 8],
+        values: Vec<i64>,
+        is_valid: Vec<u8>,
     },
     String {
         buffer: String,
-        offsets: [i32; PAGE_SIZE + 1],
-        is_valid: [u8; PAGE_SIZE / 8],
+        offsets: Vec<i32>,
+        is_valid: Vec<u8>,
+    },
+    Bytes {
+        buffer: Vec<u8>,
+        offsets: Vec<i32>,
+        is_valid: Vec<u8>,
+    },
+    // JSON documents are stored like strings, in the compact form that was checked when they were parsed.
+    Json {
+        buffer: String,
+        offsets: Vec<i32>,
+        is_valid: Vec<u8>,
+    },
+    // ARRAY values are appended in order like strings, so the elements are kept in an ArrayArray that only grows.
+    Array {
+        values: ArrayArray,
     },
 }
 
 impl Data {
-    fn new(data_type: DataType) -> Self {
+    fn new(data_type: DataType, capacity: usize) -> Self {
+        let bitmask_len = (capacity + 7) / 8;
         match data_type {
             DataType::Bool => Data::Bool {
-                values: [0; PAGE_SIZE / 8],
-                is_valid: [0; PAGE_SIZE / 8],
+                values: vec![0; bitmask_len],
+                is_valid: vec![0; bitmask_len],
             },
             DataType::I64 => Data::I64 {
-                values: [0; PAGE_SIZE],
-                is_valid: [0; PAGE_SIZE / 8],
+                values: vec![0; capacity],
+                is_valid: vec![0; bitmask_len],
             },
             DataType::F64 => Data::F64 {
-                values: [0.0; PAGE_SIZE],
-                is_valid: [0; PAGE_SIZE / 8],
+                values: vec![0.0; capacity],
+                is_valid: vec![0; bitmask_len],
             },
             DataType::Date => Data::Date {
-                values: [0; PAGE_SIZE],
-                is_valid: [0; PAGE_SIZE / 8],
+                values: vec![0; capacity],
+                is_valid: vec![0; bitmask_len],
             },
             DataType::Timestamp => Data::Timestamp {
-                values: [0; PAGE_SIZE],
-                is_valid: [0; PAGE_SIZE / 8],
+                values: vec![0; capacity],
+                is_valid: vec![0; bitmask_len],
             },
             DataType::String => Data::String {
-                buffer: String::with_capacity(PAGE_SIZE),
-                offsets: [0; PAGE_SIZE + 1],
-                is_valid: [0; PAGE_SIZE / 8],
+                buffer: String::with_capacity(capacity),
+                offsets: vec![0; capacity + 1],
+                is_valid: vec![0; bitmask_len],
+            },
+            DataType::Bytes => Data::Bytes {
+                buffer: Vec::with_capacity(capacity),
+                offsets: vec![0; capacity + 1],
+                is_valid: vec![0; bitmask_len],
+            },
+            DataType::Json => Data::Json {
+                buffer: String::with_capacity(capacity),
+                offsets: vec![0; capacity + 1],
+                is_valid: vec![0; bitmask_len],
+            },
+            DataType::Array(element_type) => Data::Array {
+                values: ArrayArray::with_capacity(*element_type, capacity),
             },
         }
     }
@@ -91,6 +124,9 @@ impl Data {
             Data::Date { .. } => DataType::Date,
             Data::Timestamp { .. } => DataType::Timestamp,
             Data::String { .. } => DataType::String,
+            Data::Bytes { .. } => DataType::Bytes,
+            Data::Json { .. } => DataType::Json,
+            Data::Array { values } => values.data_type(),
         }
     }
 
@@ -124,6 +160,25 @@ impl Data {
                 &offsets[..len + 1],
                 BitSlice::from_slice(is_valid, 0..len),
             )),
+            Data::Bytes {
+                buffer,
+                offsets,
+                is_valid,
+            } => AnyArray::Bytes(BytesArray::from_slice(
+                &buffer[..offsets[len] as usize],
+                &offsets[..len + 1],
+                BitSlice::from_slice(is_valid, 0..len),
+            )),
+            Data::Json {
+                buffer,
+                offsets,
+                is_valid,
+            } => AnyArray::Json(JsonArray::from_slice(
+                &buffer[..offsets[len] as usize],
+                &offsets[..len + 1],
+                BitSlice::from_slice(is_valid, 0..len),
+            )),
+            Data::Array { values } => AnyArray::Array(values.slice(0..len)),
         }
     }
 
@@ -218,6 +273,52 @@ impl Data {
                     }
                 }
             }
+            (
+                Data::Bytes {
+                    buffer,
+                    offsets,
+                    is_valid,
+                },
+                AnyArray::Bytes(from),
+            ) => {
+                for i in 0..(end - start) {
+                    let src = offset + i;
+                    let dst = start + i;
+                    if let Some(value) = from.get_bytes(src) {
+                        buffer.extend_from_slice(value);
+                        offsets[dst + 1] = buffer.len() as i32;
+                        set_bit(is_valid, dst);
+                    } else {
+                        offsets[dst + 1] = buffer.len() as i32;
+                        unset_bit(is_valid, dst);
+                    }
+                }
+            }
+            (
+                Data::Json {
+                    buffer,
+                    offsets,
+                    is_valid,
+                },
+                AnyArray::Json(from),
+            ) => {
+                for i in 0..(end - start) {
+                    let src = offset + i;
+                    let dst = start + i;
+                    if let Some(value) = from.get_str(src) {
+                        buffer.push_str(value);
+                        offsets[dst + 1] = buffer.len() as i32;
+                        set_bit(is_valid, dst);
+                    } else {
+                        offsets[dst + 1] = buffer.len() as i32;
+                        unset_bit(is_valid, dst);
+                    }
+                }
+            }
+            (Data::Array { values }, AnyArray::Array(from)) => {
+                assert_eq!(start, values.len());
+                values.extend(&from.slice(offset..offset + (end - start)));
+            }
             (left, right) => panic!("{} does not match {}", left.data_type(), right.data_type()),
         }
     }
@@ -225,16 +326,24 @@ impl Data {
 
 impl Page {
     // Allocate a mutable page that can hold PAGE_SIZE tuples.
-    pub fn empty(pid: usize, mut schema: Vec<(String, DataType)>) -> Self {
+    pub fn empty(pid: usize, schema: Vec<(String, DataType)>) -> Self {
+        Self::with_capacity(pid, schema, PAGE_SIZE)
+    }
+
+    // Allocate a mutable page that holds at most capacity tuples.
+    pub fn with_capacity(pid: usize, mut schema: Vec<(String, DataType)>, capacity: usize) -> Self {
+        assert!(capacity <= PAGE_SIZE);
         Self {
             pid,
             columns: schema
                 .drain(..)
-                .map(|(name, data_type)| (name, Data::new(data_type)))
+                .map(|(name, data_type)| (name, Data::new(data_type, capacity)))
                 .collect(),
-            xmin: zeros(),
-            xmax: zeros(),
+            xmin: zeros(capacity),
+            xmax: zeros(capacity),
+            truncated: AtomicI64::new(i64::MAX),
             len: AtomicUsize::new(0),
+            capacity,
         }
     }
 
@@ -244,12 +353,7 @@ impl Page {
             .map(|find| {
                 let data_type = match find.as_str() {
                     "$xmin" | "$xmax" | "$tid" => DataType::I64,
-                    find => self
-                        .columns
-                        .iter()
-                        .find(|(name, _)| find == name)
-                        .map(|(_, data)| data.data_type())
-                        .expect(find),
+                    find => self.find(find).map(Data::data_type).expect(find),
                 };
                 (find.clone(), data_type)
             })
@@ -263,15 +367,27 @@ impl Page {
             .map(|find| {
                 let array = match find.as_str() {
                     "$xmin" => Self::xcolumn(&self.xmin[..len]),
-                    "$xmax" => Self::xcolumn(&self.xmax[..len]),
+                    "$xmax" => {
+                        let truncated = self.truncated.load(Ordering::Relaxed);
+                        if truncated == i64::MAX {
+                            Self::xcolumn(&self.xmax[..len])
+                        } else {
+                            // Rows that were deleted before the page was truncated keep their own XMAX.
+                            let mut array = I64Array::with_capacity(len);
+                            for value in &self.xmax[..len] {
+                                array.push(Some(value.load(Ordering::Relaxed).min(truncated)));
+                            }
+                            AnyArray::I64(array)
+                        }
+                    }
                     "$tid" => {
                         let start = (self.pid * PAGE_SIZE) as i64;
                         let end = start + len as i64;
                         let tids: Vec<i64> = (start..end).collect();
                         AnyArray::I64(I64Array::from_values(tids))
                     }
-                    find => match self.columns.iter().find(|(name, _)| find == name) {
-                        Some((_, data)) => data.slice(len),
+                    find => match self.find(find) {
+                        Some(data) => data.slice(len),
                         None => panic!("{} is not a column of {}", find, self.star().join(", ")),
                     },
                 };
@@ -281,6 +397,29 @@ impl Page {
         RecordBatch::new(columns)
     }
 
+    /// Select columns like select, but columns that were added to the table after this page was allocated are NULL.
+    pub fn select_or_null(&self, schema: &Vec<(String, DataType)>) -> RecordBatch {
+        let len = self.len.load(Ordering::Relaxed);
+        let present: Vec<String> = schema
+            .iter()
+            .filter(|(name, _)| name.starts_with('$') || self.find(name).is_some())
+            .map(|(name, _)| name.clone())
+            .collect();
+        if present.len() == schema.len() {
+            return self.select(&present);
+        }
+        let mut batch = self.select(&present);
+        let columns = schema
+            .iter()
+            .map(|(name, data_type)| match batch.find(name) {
+                Some(array) => (name.clone(), array.clone()),
+                None => (name.clone(), AnyArray::nulls(data_type.clone(), len)),
+            })
+            .collect();
+        batch.columns = columns;
+        batch
+    }
+
     pub fn with_names(&self, column_names: &Vec<String>) -> RecordBatch {
         assert_eq!(self.columns.len(), column_names.len());
         let len = self.len.load(Ordering::Relaxed);
@@ -304,6 +443,21 @@ impl Page {
         self.columns.iter().map(|(n, _)| n.clone()).collect()
     }
 
+    /// Can records be appended to this page, or does the page predate a change to the table schema?
+    pub fn accepts(&self, records: &RecordBatch) -> bool {
+        records
+            .columns
+            .iter()
+            .all(|(name, _)| self.find(name).is_some())
+    }
+
+    fn find(&self, name: &str) -> Option<&Data> {
+        self.columns
+            .iter()
+            .find(|(column_name, _)| column_name == name)
+            .map(|(_, data)| data)
+    }
+
     pub fn insert(&self, records: &RecordBatch, txn: i64, tids: &mut I64Array, offset: &mut usize) {
         let (start, end) = self.reserve(records.len() - *offset);
         // Write the new rows in the reserved slots.
@@ -332,13 +486,45 @@ impl Page {
             == Ok(i64::MAX)
     }
 
+    /// Delete every row of the page in a single step, returning the number of rows that were visible to txn,
+    /// or None if a concurrent transaction inserted, deleted or truncated rows of the page.
+    /// Rows can no longer be added to a truncated page, because they would be deleted too.
+    pub fn truncate(&self, txn: i64) -> Option<usize> {
+        match self.truncated.load(Ordering::Relaxed) {
+            i64::MAX => {}
+            // The page was truncated earlier by txn or by a transaction that txn can see, so it has no visible rows.
+            truncated if truncated <= txn => return Some(0),
+            _ => return None,
+        }
+        let len = self.len.load(Ordering::Relaxed);
+        let mut count = 0;
+        for row in 0..len {
+            let xmin = self.xmin[row].load(Ordering::Relaxed);
+            let xmax = self.xmax[row].load(Ordering::Relaxed);
+            if xmin > txn || (xmax != i64::MAX && xmax > txn) {
+                return None;
+            }
+            if txn < xmax {
+                count += 1;
+            }
+        }
+        self.truncated
+            .compare_exchange(i64::MAX, txn, Ordering::Relaxed, Ordering::Relaxed)
+            .ok()?;
+        Some(count)
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.load(Ordering::Relaxed) != i64::MAX
+    }
+
     fn reserve(&self, request: usize) -> (usize, usize) {
         let start = self.len.load(Ordering::Relaxed);
         let end = start + request;
         // If there's not enough space for request, take whatever is available.
-        if end > PAGE_SIZE {
-            let start = self.len.swap(PAGE_SIZE, Ordering::Relaxed);
-            return (start, PAGE_SIZE);
+        if end > self.capacity {
+            let start = self.len.swap(self.capacity, Ordering::Relaxed);
+            return (start, self.capacity);
         }
         // If someone else concurrently reserves rows, try again.
         if self
@@ -384,15 +570,6 @@ impl fmt::Debug for Page {
     }
 }
 
-fn zeros() -> [AtomicI64; PAGE_SIZE] {
-    let mut data: [std::mem::MaybeUninit<AtomicI64>; PAGE_SIZE] =
-        unsafe { std::mem::MaybeUninit::uninit().assume_init() };
-
-    for elem in &mut data[..] {
-        unsafe {
-            std::ptr::write(elem.as_mut_ptr(), AtomicI64::default());
-        }
-    }
-
-    unsafe { std::mem::transmute::<_, [AtomicI64; PAGE_SIZE]>(data) }
+fn zeros(capacity: usize) -> Vec<AtomicI64> {
+    (0..capacity).map(|_| AtomicI64::default()).collect()
 }
@@ -1,15 +1,33 @@
 use std::{collections::HashMap, fmt};
 
-use kernel::DataType;
+use kernel::{AnyArray, Array, DataType, I64Array, RecordBatch};
 use statistics::TableStatistics;
 
-use crate::{art::Art, heap::*};
+use crate::{art::Art, heap::*, page::PAGE_SIZE};
+
+// The metadata table that records how many values of each sequence each worker has reserved.
+const SEQUENCE_WORKER: usize = 12;
+// The number of values a worker reserves at a time, so it only records a new high-water mark once in a while.
+const SEQUENCE_BLOCK: i64 = 100;
+
+#[derive(Clone)]
+struct Sequence {
+    start: i64,
+    increment: i64,
+    // The number of values this worker has generated.
+    generated: i64,
+    // The number of values this worker has reserved, which is recorded in the sequence_worker table.
+    high_water: i64,
+    // The row of the sequence_worker table that records high_water.
+    high_water_tid: Option<i64>,
+}
 
 #[derive(Clone)]
 pub struct Storage {
     catalog_count: i64,
     tables: Vec<Heap>,
     indexes: Vec<Art>,
+    sequences: Vec<Sequence>,
     temp_tables: HashMap<(i64, String), Heap>,
 }
 
@@ -34,8 +52,9 @@ impl Storage {
         self.tables.len() as i64
     }
 
-    pub fn create_table(&mut self) {
-        self.tables.push(Heap::default())
+    pub fn create_table(&mut self, page_size: usize) -> i64 {
+        self.tables.push(Heap::with_page_size(page_size));
+        self.tables.len() as i64 - 1
     }
 
     pub fn temp_table(&self, txn: i64, name: String) -> &Heap {
@@ -50,6 +69,10 @@ impl Storage {
         self.tables[id as usize].truncate();
     }
 
+    pub fn truncate_table(&self, id: i64, txn: i64) -> Option<usize> {
+        self.tables[id as usize].truncate_as_of(txn)
+    }
+
     pub fn index(&self, id: i64) -> &Art {
         &self.indexes[id as usize]
     }
@@ -70,6 +93,56 @@ impl Storage {
         self.indexes[id as usize].truncate()
     }
 
+    pub fn next_sequence_id(&self) -> i64 {
+        self.sequences.len() as i64
+    }
+
+    pub fn create_sequence(&mut self, start: i64, increment: i64) {
+        self.sequences.push(Sequence {
+            start,
+            increment,
+            generated: 0,
+            high_water: 0,
+            high_water_tid: None,
+        })
+    }
+
+    /// Generate the next value of a sequence on worker, out of workers.
+    /// Each worker owns every workers-th value of the sequence, so workers never generate the same value.
+    /// Before a worker hands out values past its high-water mark, it records a new one in the sequence_worker table.
+    /// Returns None when the sequence has run out of values.
+    pub fn next_value(&mut self, id: i64, worker: i64, workers: i64, txn: i64) -> Option<i64> {
+        let sequence = &mut self.sequences[id as usize];
+        if sequence.generated == sequence.high_water {
+            let high_water = sequence.high_water + SEQUENCE_BLOCK;
+            let heap = &mut self.tables[SEQUENCE_WORKER];
+            // Sequences are not transactional, so the old high-water mark is replaced as soon as the new one is recorded.
+            if let Some(tid) = sequence.high_water_tid {
+                heap.page(tid as usize / PAGE_SIZE)
+                    .delete(tid as usize % PAGE_SIZE, txn);
+            }
+            let row = |value| AnyArray::I64(I64Array::from_values(vec![value]));
+            let tids = heap.insert(
+                &RecordBatch::new(vec![
+                    ("sequence_id".to_string(), row(id)),
+                    ("worker".to_string(), row(worker)),
+                    ("high_water".to_string(), row(high_water)),
+                ]),
+                txn,
+            );
+            sequence.high_water = high_water;
+            sequence.high_water_tid = tids.get(0);
+        }
+        let value = sequence
+            .generated
+            .checked_mul(workers)?
+            .checked_add(worker)?
+            .checked_mul(sequence.increment)?
+            .checked_add(sequence.start)?;
+        sequence.generated += 1;
+        Some(value)
+    }
+
     pub fn statistics(&self, table_id: i64) -> TableStatistics {
         self.table(table_id).statistics()
     }
@@ -82,8 +155,9 @@ impl Default for Storage {
         // First 100 tables are reserved for system use.
         let mut tables = Vec::with_capacity(0);
         tables.resize_with(100, Heap::default);
-        // Initially there are no indexes.
+        // Initially there are no indexes or sequences.
         let indexes = vec![];
+        let sequences = vec![];
         // Bootstrap statistics.
         let mut statistics = HashMap::new();
         for (table_id, columns) in bootstrap_statistics() {
@@ -97,6 +171,7 @@ impl Default for Storage {
             catalog_count,
             tables,
             indexes,
+            sequences,
             temp_tables: HashMap::default(),
         }
     }
@@ -140,6 +215,9 @@ fn bootstrap_statistics() -> Vec<(i64, Vec<(&'static str, DataType)>)> {
                 ("column_id", DataType::I64),
                 ("column_name", DataType::String),
                 ("column_type", DataType::String),
+                ("not_null", DataType::Bool),
+                ("default_expr", DataType::String),
+                ("primary_key", DataType::Bool),
             ],
         ),
         (
@@ -157,6 +235,82 @@ fn bootstrap_statistics() -> Vec<(i64, Vec<(&'static str, DataType)>)> {
                 ("index_id", DataType::I64),
                 ("column_id", DataType::I64),
                 ("index_order", DataType::I64),
+                ("descending", DataType::Bool),
+            ],
+        ),
+        (
+            5, // view
+            vec![
+                ("table_id", DataType::I64),
+                ("view_sql", DataType::String),
+                ("materialized", DataType::Bool),
+                ("refreshed_txn", DataType::I64),
+            ],
+        ),
+        (
+            6, // check_constraint
+            vec![
+                ("table_id", DataType::I64),
+                ("constraint_name", DataType::String),
+                ("check_expr", DataType::String),
+            ],
+        ),
+        (
+            7, // foreign_key
+            vec![
+                ("table_id", DataType::I64),
+                ("constraint_name", DataType::String),
+                ("column_name", DataType::String),
+                ("referenced_table_id", DataType::I64),
+                ("referenced_column_name", DataType::String),
+                ("key_order", DataType::I64),
+            ],
+        ),
+        (
+            8, // function
+            vec![
+                ("catalog_id", DataType::I64),
+                ("function_name", DataType::String),
+                ("function_sql", DataType::String),
+                ("arguments", DataType::String),
+                ("return_type", DataType::String),
+                ("output_columns", DataType::String),
+            ],
+        ),
+        (
+            9, // sequence
+            vec![
+                ("catalog_id", DataType::I64),
+                ("sequence_id", DataType::I64),
+                ("sequence_name", DataType::String),
+                ("start_value", DataType::I64),
+                ("increment", DataType::I64),
+                ("table_id", DataType::I64),
+                ("column_name", DataType::String),
+            ],
+        ),
+        (
+            10, // table_option
+            vec![
+                ("table_id", DataType::I64),
+                ("option_name", DataType::String),
+                ("option_value", DataType::String),
+            ],
+        ),
+        (
+            11, // index_option
+            vec![
+                ("index_id", DataType::I64),
+                ("option_name", DataType::String),
+                ("option_value", DataType::String),
+            ],
+        ),
+        (
+            12, // sequence_worker
+            vec![
+                ("sequence_id", DataType::I64),
+                ("worker", DataType::I64),
+                ("high_water", DataType::I64),
             ],
         ),
     ]
@@ -1,5 +1,6 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
+    panic::AssertUnwindSafe,
     sync::{Arc, Mutex},
     thread,
 };
@@ -7,7 +8,7 @@ use std::{
 use ast::Expr;
 use execute::Node;
 use globals::Global;
-use kernel::{AnyArray, Next, RecordBatch};
+use kernel::{panic_message, AnyArray, Error, Next, RecordBatch};
 use log::Session;
 use rpc::{
     page::Part, worker_server::Worker, BroadcastRequest, CheckRequest, CheckResponse,
@@ -227,12 +228,27 @@ fn gather(
     // Send each batch of records produced by expr to each worker node in the cluster.
     let mut query = Node::compile(expr.clone());
     loop {
-        let result = match query.next(storage, txn) {
-            Next::Page(batch) => Part::RecordBatch(bincode::serialize(&batch).unwrap()),
+        match next(&mut query, storage, txn) {
+            Next::Page(batch) => {
+                let part = Part::RecordBatch(bincode::serialize(&batch).unwrap());
+                listener.blocking_send(Page { part: Some(part) }).unwrap();
+            }
             Next::End => break,
-            Next::Error(message) => Part::Error(message),
-        };
-        listener.blocking_send(Page { part: Some(result) }).unwrap();
+            Next::Error(error) => {
+                let part = Part::Error(error.into());
+                listener.blocking_send(Page { part: Some(part) }).unwrap();
+                return;
+            }
+        }
+    }
+    // Report the number of rows modified by DML statements, which the coordinator sums across workers.
+    let rows_affected = query.rows_affected();
+    if rows_affected > 0 {
+        listener
+            .blocking_send(Page {
+                part: Some(Part::RowsAffected(rows_affected)),
+            })
+            .unwrap();
     }
 }
 
@@ -249,10 +265,13 @@ fn broadcast(
     // Send each batch of records produced by expr to each worker node in the cluster.
     let mut query = Node::compile(expr);
     loop {
-        let result = match query.next(storage, txn) {
-            Next::Page(batch) => Part::RecordBatch(bincode::serialize(&batch).unwrap()),
+        let (result, done) = match next(&mut query, storage, txn) {
+            Next::Page(batch) => (
+                Part::RecordBatch(bincode::serialize(&batch).unwrap()),
+                false,
+            ),
             Next::End => break,
-            Next::Error(message) => Part::Error(message),
+            Next::Error(error) => (Part::Error(error.into()), true),
         };
         for sink in &listeners {
             sink.blocking_send(Page {
@@ -260,6 +279,9 @@ fn broadcast(
             })
             .unwrap();
         }
+        if done {
+            break;
+        }
     }
 }
 
@@ -279,7 +301,7 @@ fn exchange(
     // Split up each batch of records produced by expr and send the splits to the worker nodes.
     let mut query = Node::compile(expr);
     loop {
-        match query.next(storage, txn) {
+        match next(&mut query, storage, txn) {
             Next::Page(batch) => {
                 let batches = partition(&batch, &hash_column, listeners.len());
                 for (hash_bucket, batch) in batches.iter().enumerate() {
@@ -291,18 +313,28 @@ fn exchange(
                 }
             }
             Next::End => break,
-            Next::Error(message) => {
+            Next::Error(error) => {
                 for (_, sink) in &listeners {
                     sink.blocking_send(Page {
-                        part: Some(Part::Error(message.clone())),
+                        part: Some(Part::Error(error.clone().into())),
                     })
                     .unwrap();
                 }
+                break;
             }
         }
     }
 }
 
+/// A panic fails the query with an internal error, instead of ending the stream as if the query
+/// had succeeded. The first error ends the query, so the node is not used again.
+fn next(query: &mut Node, storage: &Mutex<Storage>, txn: i64) -> Next {
+    match std::panic::catch_unwind(AssertUnwindSafe(|| query.next(storage, txn))) {
+        Ok(next) => next,
+        Err(payload) => Next::Error(Error::Internal(panic_message(payload))),
+    }
+}
+
 fn partition(batch: &RecordBatch, hash_column: &String, workers: usize) -> Vec<RecordBatch> {
     let (_, column) = batch
         .columns